@@ -114,6 +114,34 @@ fn criterion_benchmark(c: &mut Criterion) {
         );
     }
     group.finish();
+
+    // Prepare DataTree.clear() benchmark.
+    let mut group = c.benchmark_group("DataTree.clear() / tree size");
+    for size in &[1, 16, 256] {
+        // Create reusable data tree.
+        let mut dtree = DataTree::new(&ctx);
+
+        // Run benchmark.
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            size,
+            |b, size| {
+                b.iter(|| {
+                    for i in 1..=*size {
+                        let xpath = format!(
+                            "/ietf-interfaces:interfaces/interface[name='eth{}']/enabled",
+                            i
+                        );
+                        dtree
+                            .new_path(&xpath, Some("true"), false)
+                            .expect("Failed to edit data tree");
+                    }
+                    dtree.clear();
+                });
+            },
+        );
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
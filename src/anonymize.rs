@@ -0,0 +1,396 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Anonymization of identifying values (hostnames, IP addresses and MAC
+//! addresses) in data trees.
+
+use libyang3_sys as ffi;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::data::DataTree;
+use crate::schema::{SchemaLeafType, SchemaNodeKind};
+
+/// Maximum number of attempts to find an unused pseudonym in each range of
+/// pseudonyms.
+const MAX_ATTEMPTS: u32 = 64;
+
+/// Anonymizer of identifying values, replacing them with pseudonyms valid
+/// for their types (see [`DataTree::anonymize`]).
+///
+/// The identifying values are recognized by the types of the leaves and
+/// leaf-lists they're assigned to, namely the address, prefix and host
+/// types of the `ietf-inet-types` module, and the `mac-address` and
+/// `phys-address` types of the `ietf-yang-types` module (including the
+/// typedefs and leafrefs derived from them).
+///
+/// The same value is always replaced with the same pseudonym, within a data
+/// tree and across the data trees anonymized by the same anonymizer. Hence,
+/// list keys, leafrefs and references between configurations remain
+/// consistent. Pseudonyms are derived from the original values and the seed
+/// of the anonymizer, while making sure distinct values get distinct
+/// pseudonyms, so anonymizers created with the same seed give the same
+/// pseudonyms when given the same values in the same order.
+///
+/// Well-known addresses (unspecified, loopback, broadcast and multicast) and
+/// short prefixes are left as they are. IPv4 pseudonyms are taken from the
+/// 10.0.0.0/8 private range, IPv6 pseudonyms from the 2001:db8::/32
+/// documentation prefix, MAC address pseudonyms are locally administered
+/// unicast addresses, and domain name pseudonyms are subdomains of
+/// `example`. When these ranges run out of unused pseudonyms (e.g. for
+/// prefixes barely longer than the ranges themselves), pseudonyms are taken
+/// from the whole address space instead. Values for which no unused
+/// pseudonym can be found are kept as they are (see [`Anonymizer::kept`]).
+#[derive(Clone, Debug)]
+pub struct Anonymizer {
+    seed: u64,
+    pseudonyms: BTreeMap<(IdentifierKind, String), String>,
+    used: HashSet<String>,
+    kept: BTreeSet<(IdentifierKind, String)>,
+}
+
+/// Kind of identifying value.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum IdentifierKind {
+    Ipv4Address,
+    Ipv6Address,
+    Ipv4Prefix,
+    Ipv6Prefix,
+    DomainName,
+    MacAddress,
+}
+
+// Class of identifying values, as determined by the type of a leaf.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum TypeClass {
+    Address,
+    Prefix,
+    Host,
+    DomainName,
+    MacAddress,
+}
+
+// ===== impl Anonymizer =====
+
+impl Anonymizer {
+    /// Create a new anonymizer, deriving its pseudonyms from the given seed.
+    pub fn new(seed: u64) -> Anonymizer {
+        Anonymizer {
+            seed,
+            pseudonyms: BTreeMap::new(),
+            used: HashSet::new(),
+            kept: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the pseudonym of the given identifying value.
+    pub fn pseudonym(&mut self, kind: IdentifierKind, value: &str) -> String {
+        let key = (kind, value.to_owned());
+        if let Some(pseudonym) = self.pseudonyms.get(&key) {
+            return pseudonym.clone();
+        }
+
+        let pseudonym = match self.preserved(kind, value) {
+            true => value.to_owned(),
+            false => {
+                // Make sure distinct values get distinct pseudonyms, looking
+                // into the whole address space once the preferred range is
+                // exhausted.
+                let used = &self.used;
+                (0..2 * MAX_ATTEMPTS)
+                    .find_map(|attempt| {
+                        let hash = hash(self.seed, kind, value, attempt);
+                        let wide = attempt >= MAX_ATTEMPTS;
+                        generate(kind, value, hash, wide)
+                            .filter(|pseudonym| !used.contains(pseudonym))
+                    })
+                    .unwrap_or_else(|| {
+                        self.kept.insert(key.clone());
+                        value.to_owned()
+                    })
+            }
+        };
+        self.used.insert(pseudonym.clone());
+        self.pseudonyms.insert(key, pseudonym.clone());
+        pseudonym
+    }
+
+    /// Returns an iterator over the identifying values replaced so far,
+    /// along with their kinds and pseudonyms.
+    pub fn mappings(
+        &self,
+    ) -> impl Iterator<Item = (IdentifierKind, &str, &str)> {
+        self.pseudonyms.iter().map(|((kind, value), pseudonym)| {
+            (*kind, value.as_str(), pseudonym.as_str())
+        })
+    }
+
+    /// Returns an iterator over the identifying values kept as they are
+    /// because no unused pseudonym could be found for them.
+    pub fn kept(&self) -> impl Iterator<Item = (IdentifierKind, &str)> {
+        self.kept
+            .iter()
+            .map(|(kind, value)| (*kind, value.as_str()))
+    }
+
+    /// Returns the pseudonyms of the identifying values of the given data
+    /// tree, along with the data nodes they're assigned to.
+    pub(crate) fn tree_pseudonyms(
+        &mut self,
+        dtree: &DataTree<'_>,
+    ) -> Vec<(*mut ffi::lyd_node, String)> {
+        let nodes = dtree
+            .traverse()
+            .filter(|dnode| !dnode.is_opaque())
+            .filter(|dnode| {
+                matches!(
+                    dnode.schema().kind(),
+                    SchemaNodeKind::Leaf | SchemaNodeKind::LeafList
+                )
+            })
+            .filter_map(|dnode| {
+                let snode = dnode.schema();
+                let class = snode.leaf_type().and_then(|ltype| match ltype
+                    .leafref_real_type()
+                {
+                    Some(rtype) => type_class(&rtype),
+                    None => type_class(&ltype),
+                })?;
+                let value = dnode.value_canonical()?;
+                let kind = identifier_kind(class, &value)?;
+                Some((dnode.as_raw(), kind, value))
+            })
+            .collect::<Vec<_>>();
+
+        // Never use the original values as pseudonyms, so that no two
+        // instances of a list or leaf-list share the same key while the
+        // data tree is being anonymized.
+        for (_, _, value) in &nodes {
+            self.used.insert(value.clone());
+        }
+
+        nodes
+            .into_iter()
+            .filter_map(|(raw, kind, value)| {
+                let pseudonym = self.pseudonym(kind, &value);
+                (pseudonym != value).then_some((raw, pseudonym))
+            })
+            .collect()
+    }
+
+    // Returns whether the given value is left as it is.
+    fn preserved(&self, kind: IdentifierKind, value: &str) -> bool {
+        match kind {
+            IdentifierKind::Ipv4Address => {
+                let addr = match value.parse::<Ipv4Addr>() {
+                    Ok(addr) => addr,
+                    Err(_) => return true,
+                };
+                addr.is_unspecified()
+                    || addr.is_loopback()
+                    || addr.is_broadcast()
+                    || addr.is_multicast()
+            }
+            IdentifierKind::Ipv6Address => {
+                let (addr, _) = split_zone(value);
+                let addr = match addr.parse::<Ipv6Addr>() {
+                    Ok(addr) => addr,
+                    Err(_) => return true,
+                };
+                addr.is_unspecified()
+                    || addr.is_loopback()
+                    || addr.is_multicast()
+            }
+            IdentifierKind::Ipv4Prefix => {
+                prefix_length(value).map(|len| len < 8).unwrap_or(true)
+            }
+            IdentifierKind::Ipv6Prefix => {
+                prefix_length(value).map(|len| len < 32).unwrap_or(true)
+            }
+            IdentifierKind::DomainName => {
+                value.trim_end_matches('.').is_empty()
+            }
+            IdentifierKind::MacAddress => value.is_empty(),
+        }
+    }
+}
+
+// ===== helper functions =====
+
+// Returns the class of identifying values of the given type, if any.
+fn type_class(ltype: &SchemaLeafType<'_>) -> Option<TypeClass> {
+    ltype.typedef_chain().find_map(|tpdf| {
+        let class = match (tpdf.module().name(), tpdf.name()) {
+            (
+                "ietf-inet-types",
+                "ip-address"
+                | "ipv4-address"
+                | "ipv6-address"
+                | "ip-address-no-zone"
+                | "ipv4-address-no-zone"
+                | "ipv6-address-no-zone",
+            ) => TypeClass::Address,
+            (
+                "ietf-inet-types",
+                "ip-prefix" | "ipv4-prefix" | "ipv6-prefix",
+            ) => TypeClass::Prefix,
+            ("ietf-inet-types", "host") => TypeClass::Host,
+            ("ietf-inet-types", "domain-name") => TypeClass::DomainName,
+            ("ietf-yang-types", "mac-address" | "phys-address") => {
+                TypeClass::MacAddress
+            }
+            _ => return None,
+        };
+        Some(class)
+    })
+}
+
+// Returns the kind of the given identifying value.
+fn identifier_kind(class: TypeClass, value: &str) -> Option<IdentifierKind> {
+    let address_kind = |addr: &str| {
+        let (addr, _) = split_zone(addr);
+        if addr.parse::<Ipv4Addr>().is_ok() {
+            Some(IdentifierKind::Ipv4Address)
+        } else if addr.parse::<Ipv6Addr>().is_ok() {
+            Some(IdentifierKind::Ipv6Address)
+        } else {
+            None
+        }
+    };
+
+    match class {
+        TypeClass::Address => address_kind(value),
+        TypeClass::Prefix => {
+            let (addr, _) = value.split_once('/')?;
+            match address_kind(addr)? {
+                IdentifierKind::Ipv4Address => Some(IdentifierKind::Ipv4Prefix),
+                _ => Some(IdentifierKind::Ipv6Prefix),
+            }
+        }
+        TypeClass::Host => {
+            address_kind(value).or(Some(IdentifierKind::DomainName))
+        }
+        TypeClass::DomainName => Some(IdentifierKind::DomainName),
+        TypeClass::MacAddress => Some(IdentifierKind::MacAddress),
+    }
+}
+
+// Generate a pseudonym of the given value from the given hash, taken from
+// the preferred range or, if `wide` is set, from the whole address space.
+fn generate(
+    kind: IdentifierKind,
+    value: &str,
+    hash: u64,
+    wide: bool,
+) -> Option<String> {
+    let pseudonym = match kind {
+        IdentifierKind::Ipv4Address => ipv4_pseudonym(hash, wide).to_string(),
+        IdentifierKind::Ipv6Address => {
+            let (_, zone) = split_zone(value);
+            format!("{}{}", ipv6_pseudonym(hash, wide), zone)
+        }
+        IdentifierKind::Ipv4Prefix => {
+            let len = prefix_length(value).filter(|len| *len <= 32)?;
+            let addr = u32::from(ipv4_pseudonym(hash, wide));
+            let addr = match len {
+                0 => 0,
+                _ => addr & (!0u32 << (32 - len)),
+            };
+            format!("{}/{}", Ipv4Addr::from(addr), len)
+        }
+        IdentifierKind::Ipv6Prefix => {
+            let len = prefix_length(value).filter(|len| *len <= 128)?;
+            let addr = u128::from(ipv6_pseudonym(hash, wide));
+            let addr = match len {
+                128 => addr,
+                _ => addr & !(!0u128 >> len),
+            };
+            format!("{}/{}", Ipv6Addr::from(addr), len)
+        }
+        IdentifierKind::DomainName => {
+            let dot = if value.ends_with('.') { "." } else { "" };
+            match wide {
+                false => format!("host-{:08x}.example{}", hash as u32, dot),
+                true => format!("host-{:016x}.example{}", hash, dot),
+            }
+        }
+        IdentifierKind::MacAddress => {
+            // Locally administered unicast address, of the same length as
+            // the original one.
+            let octets = value.split(':').count();
+            let bytes = hash.to_be_bytes();
+            let first = match wide {
+                false => 0x02,
+                true => (bytes[7] | 0x02) & !0x01,
+            };
+            std::iter::once(first)
+                .chain(bytes.iter().cycle().copied())
+                .take(octets)
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<Vec<_>>()
+                .join(":")
+        }
+    };
+    Some(pseudonym)
+}
+
+// Returns an address of the 10.0.0.0/8 range, or of the whole address space
+// if `wide` is set.
+fn ipv4_pseudonym(hash: u64, wide: bool) -> Ipv4Addr {
+    match wide {
+        false => Ipv4Addr::from(0x0a00_0000 | (hash as u32 & 0x00ff_ffff)),
+        true => Ipv4Addr::from((hash >> 32) as u32 ^ hash as u32),
+    }
+}
+
+// Returns an address of the 2001:db8::/32 range, or of the whole address
+// space if `wide` is set.
+fn ipv6_pseudonym(hash: u64, wide: bool) -> Ipv6Addr {
+    let bits = ((mix(hash) as u128) << 64) | (hash as u128);
+    match wide {
+        false => Ipv6Addr::from((0x2001_0db8u128 << 96) | (bits >> 32)),
+        true => Ipv6Addr::from(bits),
+    }
+}
+
+// Split an address into the address itself and its zone suffix (including
+// the '%' separator).
+fn split_zone(value: &str) -> (&str, &str) {
+    match value.find('%') {
+        Some(pos) => value.split_at(pos),
+        None => (value, ""),
+    }
+}
+
+// Returns the length of an address prefix.
+fn prefix_length(value: &str) -> Option<u32> {
+    value.split_once('/')?.1.parse().ok()
+}
+
+// Hash an identifying value (FNV-1a, followed by a final mixing step).
+fn hash(seed: u64, kind: IdentifierKind, value: &str, attempt: u32) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    let bytes = seed
+        .to_le_bytes()
+        .iter()
+        .chain(&[kind as u8])
+        .chain(value.as_bytes())
+        .chain(&attempt.to_le_bytes())
+        .copied()
+        .collect::<Vec<_>>();
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    mix(hash)
+}
+
+// Mix the bits of the given value (SplitMix64 finalizer).
+fn mix(mut value: u64) -> u64 {
+    value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
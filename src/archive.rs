@@ -0,0 +1,184 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Configuration archives (rollback files).
+
+use libyang3_sys as ffi;
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+use crate::context::Context;
+use crate::data::{
+    Data, DataDiff, DataDiffFlags, DataFormat, DataParserFlags,
+    DataPrinterFlags, DataTree, DataValidationFlags,
+};
+use crate::error::{Error, Result};
+
+/// Archive of the last configurations committed, allowing to retrieve any of
+/// them or compare two of them on demand.
+///
+/// Configurations are stored in the LYB format, along with some metadata.
+/// By default, only the number of archived configurations is limited.
+#[derive(Clone, Debug)]
+pub struct ConfigArchive {
+    entries: VecDeque<ArchiveEntry>,
+    next_id: u64,
+    max_entries: usize,
+    max_age: Option<Duration>,
+}
+
+/// Archived configuration.
+///
+/// The fields are public so the entries can be persisted elsewhere and
+/// restored using [`ConfigArchive::restore`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArchiveEntry {
+    /// Revision identifier, unique within the archive.
+    pub id: u64,
+    /// Time when the configuration was archived.
+    pub timestamp: SystemTime,
+    /// Optional comment describing the configuration change.
+    pub comment: Option<String>,
+    /// Configuration, in the LYB format.
+    pub data: Vec<u8>,
+}
+
+// ===== impl ConfigArchive =====
+
+impl ConfigArchive {
+    /// Create a new empty archive, keeping at most the given number of
+    /// configurations.
+    pub fn new(max_entries: usize) -> ConfigArchive {
+        ConfigArchive {
+            entries: VecDeque::new(),
+            next_id: 1,
+            max_entries,
+            max_age: None,
+        }
+    }
+
+    /// Set the maximum age of the archived configurations. The most recent
+    /// configuration is always kept, regardless of its age.
+    pub fn set_max_age(&mut self, max_age: Option<Duration>) {
+        self.max_age = max_age;
+        self.prune();
+    }
+
+    /// Archive the given configuration, returning its revision identifier.
+    ///
+    /// The oldest configurations are pruned as necessary.
+    pub fn push(
+        &mut self,
+        dtree: &DataTree<'_>,
+        comment: Option<&str>,
+    ) -> Result<u64> {
+        let data = match dtree.reference() {
+            Some(_) => dtree.print_bytes(
+                DataFormat::LYB,
+                DataPrinterFlags::WITH_SIBLINGS,
+            )?,
+            None => Vec::new(),
+        };
+        let id = self.next_id;
+        self.restore(ArchiveEntry {
+            id,
+            timestamp: SystemTime::now(),
+            comment: comment.map(ToOwned::to_owned),
+            data,
+        })?;
+
+        Ok(id)
+    }
+
+    /// Add a previously archived configuration (e.g. loaded from persistent
+    /// storage). Entries should be restored from the oldest to the most
+    /// recent one.
+    ///
+    /// Fails if the revision of the entry is the highest possible one, as no
+    /// revision could follow it.
+    pub fn restore(&mut self, entry: ArchiveEntry) -> Result<()> {
+        let next_id = entry.id.checked_add(1).ok_or_else(|| Error {
+            errcode: ffi::LY_ERR::LY_EINVAL,
+            msg: Some("Archive revision out of range".to_owned()),
+            ..Default::default()
+        })?;
+        self.next_id = self.next_id.max(next_id);
+        self.entries.push_back(entry);
+        self.prune();
+
+        Ok(())
+    }
+
+    /// Returns an iterator over the archived configurations, from the oldest
+    /// to the most recent one.
+    pub fn entries(&self) -> impl Iterator<Item = &ArchiveEntry> {
+        self.entries.iter()
+    }
+
+    /// Returns the archived configuration of the given revision.
+    pub fn get<'a>(
+        &self,
+        context: &'a Context,
+        id: u64,
+    ) -> Result<DataTree<'a>> {
+        let entry = self.entry(id)?;
+        if entry.data.is_empty() {
+            return Ok(DataTree::new(context));
+        }
+
+        DataTree::parse_string(
+            context,
+            &entry.data,
+            DataFormat::LYB,
+            DataParserFlags::NO_VALIDATION,
+            DataValidationFlags::empty(),
+        )
+    }
+
+    /// Returns the changes between the archived configurations of the given
+    /// revisions.
+    pub fn diff<'a>(
+        &self,
+        context: &'a Context,
+        from: u64,
+        to: u64,
+    ) -> Result<DataDiff<'a>> {
+        let from = self.get(context, from)?;
+        let to = self.get(context, to)?;
+        from.diff(&to, DataDiffFlags::DEFAULTS)
+    }
+
+    /// Remove the configurations that don't comply with the pruning policies
+    /// anymore.
+    pub fn prune(&mut self) {
+        while self.entries.len() > self.max_entries.max(1) {
+            self.entries.pop_front();
+        }
+        if let Some(max_age) = self.max_age {
+            let now = SystemTime::now();
+            while self.entries.len() > 1
+                && self.entries.front().is_some_and(|entry| {
+                    now.duration_since(entry.timestamp)
+                        .is_ok_and(|age| age > max_age)
+                })
+            {
+                self.entries.pop_front();
+            }
+        }
+    }
+
+    // Returns the archived configuration of the given revision.
+    fn entry(&self, id: u64) -> Result<&ArchiveEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .ok_or_else(|| Error {
+                errcode: ffi::LY_ERR::LY_ENOTFOUND,
+                msg: Some(format!("Configuration revision {} not found", id)),
+                ..Default::default()
+            })
+    }
+}
@@ -0,0 +1,137 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Cached data tree lookups.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::data::{Data, DataNodeRef, DataTree};
+use crate::error::Result;
+
+/// Read-mostly view of a data tree, memoizing the results of XPath and path
+/// lookups.
+///
+/// The cache stores the paths of the nodes found, which are resolved again
+/// on every lookup, so nodes removed from the data tree are never returned.
+///
+/// The cache is invalidated whenever the data tree is edited through the
+/// view. Edits made by other means (e.g. using [`DataNodeRef`] methods) may
+/// leave outdated XPath results (e.g. nodes no longer matching a predicate)
+/// until [`CachedTreeView::invalidate`] is called.
+#[derive(Debug)]
+pub struct CachedTreeView<'a> {
+    tree: DataTree<'a>,
+    xpaths: Mutex<HashMap<String, Vec<String>>>,
+    paths: Mutex<HashMap<String, String>>,
+}
+
+// ===== impl CachedTreeView =====
+
+impl<'a> CachedTreeView<'a> {
+    /// Create a new view of the given data tree, with an empty cache.
+    pub fn new(tree: DataTree<'a>) -> CachedTreeView<'a> {
+        CachedTreeView {
+            tree,
+            xpaths: Default::default(),
+            paths: Default::default(),
+        }
+    }
+
+    /// Returns the underlying data tree.
+    pub fn tree(&self) -> &DataTree<'a> {
+        &self.tree
+    }
+
+    /// Returns the underlying data tree for editing, invalidating the cache.
+    pub fn tree_mut(&mut self) -> &mut DataTree<'a> {
+        self.invalidate();
+        &mut self.tree
+    }
+
+    /// Consumes the view, returning the underlying data tree.
+    pub fn into_tree(self) -> DataTree<'a> {
+        self.tree
+    }
+
+    /// Search in the data tree for the nodes matching the given XPath
+    /// expression (see [`Data::find_xpath`]).
+    pub fn find_xpath(&self, xpath: &str) -> Result<Vec<DataNodeRef<'_>>> {
+        let mut xpaths = self.xpaths.lock().unwrap();
+        if let Some(paths) = xpaths.get(xpath) {
+            return Ok(paths
+                .iter()
+                .filter_map(|path| self.tree.find_path(path).ok())
+                .collect());
+        }
+
+        let dnodes = self.tree.find_xpath(xpath)?.collect::<Vec<_>>();
+        let paths = dnodes.iter().map(|dnode| dnode.path()).collect();
+        xpaths.insert(xpath.to_owned(), paths);
+        Ok(dnodes)
+    }
+
+    /// Search in the data tree for the single node at the given path (see
+    /// [`Data::find_path`]).
+    ///
+    /// Failed lookups aren't cached.
+    pub fn find_path(&self, path: &str) -> Result<DataNodeRef<'_>> {
+        let mut paths = self.paths.lock().unwrap();
+        if let Some(dnode) = paths
+            .get(path)
+            .and_then(|cached| self.tree.find_path(cached).ok())
+        {
+            return Ok(dnode);
+        }
+
+        match self.tree.find_path(path) {
+            Ok(dnode) => {
+                paths.insert(path.to_owned(), dnode.path());
+                Ok(dnode)
+            }
+            Err(error) => {
+                paths.remove(path);
+                Err(error)
+            }
+        }
+    }
+
+    /// Create a new node or modify an existing one in the data tree (see
+    /// [`DataTree::new_path`]), invalidating the cache.
+    pub fn new_path(
+        &mut self,
+        path: &str,
+        value: Option<&str>,
+        output: bool,
+    ) -> Result<Option<DataNodeRef<'_>>> {
+        self.invalidate();
+        self.tree.new_path(path, value, output)
+    }
+
+    /// Remove a data node (see [`DataTree::remove`]), invalidating the cache.
+    pub fn remove(&mut self, path: &str) -> Result<()> {
+        self.invalidate();
+        self.tree.remove(path)
+    }
+
+    /// Merge the given data tree into the underlying one (see
+    /// [`DataTree::merge`]), invalidating the cache.
+    pub fn merge(&mut self, source: &DataTree<'_>) -> Result<()> {
+        self.invalidate();
+        self.tree.merge(source)
+    }
+
+    /// Clear the cached lookup results.
+    pub fn invalidate(&mut self) {
+        self.xpaths.get_mut().unwrap().clear();
+        self.paths.get_mut().unwrap().clear();
+    }
+
+    /// Returns the number of cached lookup results.
+    pub fn cached_count(&self) -> usize {
+        self.xpaths.lock().unwrap().len() + self.paths.lock().unwrap().len()
+    }
+}
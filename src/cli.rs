@@ -0,0 +1,312 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Generation of CLI command trees from schema subtrees.
+//!
+//! A command tree describes the commands and arguments of a configuration
+//! shell in a framework-agnostic way, so that it can be turned into `clap`
+//! commands or `reedline` completers by the application:
+//!
+//! - containers and lists map to commands, nested as in the schema;
+//! - list keys map to the arguments of their list command;
+//! - leaves and leaf-lists map to commands taking a value argument.
+//!
+//! Choices and cases are transparent, and actions and notifications are
+//! left out. Arguments carry their type and candidate values, and can
+//! validate user input against the schema.
+
+use bitflags::bitflags;
+use libyang3_sys as ffi;
+use std::ffi::CString;
+
+use crate::context::Context;
+use crate::error::{Error, Result};
+use crate::iter::IterSchemaFlags;
+use crate::schema::{
+    DataValueType, SchemaLeafType, SchemaModule, SchemaNode, SchemaNodeKind,
+};
+use crate::utils::*;
+
+bitflags! {
+    /// Command tree generation options.
+    #[derive(Debug)]
+    pub struct CliFlags: u32 {
+        /// Include state (config false) nodes.
+        const STATE = 0x01;
+        /// Leave out deprecated nodes (obsolete nodes are always left out).
+        const NO_DEPRECATED = 0x02;
+    }
+}
+
+/// CLI command mapped from a schema node.
+#[derive(Clone, Debug)]
+pub struct CliCommand<'a> {
+    /// Command name (the schema node name).
+    pub name: String,
+    /// Kind of the schema node.
+    pub kind: CliCommandKind,
+    /// Help text (the schema node description).
+    pub help: Option<String>,
+    /// Whether the schema node is mandatory.
+    pub mandatory: bool,
+    /// Positional arguments (list keys, or the value of a leaf or
+    /// leaf-list).
+    pub arguments: Vec<CliArgument<'a>>,
+    /// Nested commands.
+    pub subcommands: Vec<CliCommand<'a>>,
+    /// Schema node the command is mapped from.
+    pub snode: SchemaNode<'a>,
+}
+
+/// Kind of the schema node a CLI command is mapped from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CliCommandKind {
+    Container,
+    PresenceContainer,
+    List,
+    Leaf,
+    LeafList,
+}
+
+/// Positional argument of a CLI command.
+#[derive(Clone, Debug)]
+pub struct CliArgument<'a> {
+    /// Argument name (the name of the list key or of the leaf).
+    pub name: String,
+    /// Help text (the schema node description).
+    pub help: Option<String>,
+    /// Resolved base type of the value (leafrefs are resolved to their
+    /// target type).
+    pub value_type: DataValueType,
+    /// Complete set of accepted values, when known from the type
+    /// (booleans, enumerations, bits and identities).
+    pub candidates: Vec<String>,
+    /// Default value, in its canonical form.
+    pub default: Option<String>,
+    /// Schema node the argument is mapped from.
+    pub snode: SchemaNode<'a>,
+}
+
+// ===== impl CliCommand =====
+
+impl<'a> CliCommand<'a> {
+    /// Returns the nested command of the given name, if any.
+    pub fn subcommand(&self, name: &str) -> Option<&CliCommand<'a>> {
+        self.subcommands.iter().find(|command| command.name == name)
+    }
+
+    /// Returns an iterator over this command and all its nested commands
+    /// (depth-first search algorithm), along with their command paths.
+    pub fn traverse(
+        &self,
+    ) -> impl Iterator<Item = (Vec<&str>, &CliCommand<'a>)> {
+        let mut stack = vec![(vec![self.name.as_str()], self)];
+        std::iter::from_fn(move || {
+            let (path, command) = stack.pop()?;
+            for subcommand in command.subcommands.iter().rev() {
+                let mut path = path.clone();
+                path.push(subcommand.name.as_str());
+                stack.push((path, subcommand));
+            }
+            Some((path, command))
+        })
+    }
+}
+
+// ===== impl CliArgument =====
+
+impl CliArgument<'_> {
+    /// Validate the given value against the type of the argument, returning
+    /// its canonical form.
+    ///
+    /// Values that can only be fully validated against a data tree (e.g.
+    /// leafrefs and instance-identifiers) are checked against their type
+    /// only.
+    pub fn validate(&self, value: &str) -> Result<String> {
+        let context = self.snode.context;
+        let cvalue = CString::new(value).map_err(|_| Error {
+            errcode: ffi::LY_ERR::LY_EINVAL,
+            msg: Some("Value contains a NUL character".to_owned()),
+            ..Default::default()
+        })?;
+        let mut canonical = std::ptr::null();
+        let ret = unsafe {
+            ffi::lyd_value_validate(
+                context.raw,
+                self.snode.raw,
+                cvalue.as_ptr(),
+                value.len(),
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                &mut canonical,
+            )
+        };
+        let canonical = match canonical.is_null() {
+            true => value.to_owned(),
+            false => {
+                let string = char_ptr_to_string(canonical, false);
+                unsafe { ffi::lydict_remove(context.raw, canonical) };
+                string
+            }
+        };
+        match ret {
+            ffi::LY_ERR::LY_SUCCESS | ffi::LY_ERR::LY_EINCOMPLETE => {
+                Ok(canonical)
+            }
+            _ => Err(Error::new(context)),
+        }
+    }
+}
+
+/// Map the given schema subtree (a container, list, leaf or leaf-list) into
+/// a CLI command tree.
+///
+/// Returns `None` if the node can't be mapped into a command, or is left out
+/// by the given options.
+pub fn command_tree<'a>(
+    snode: &SchemaNode<'a>,
+    flags: CliFlags,
+) -> Option<CliCommand<'a>> {
+    command(snode, &flags)
+}
+
+/// Map the top-level data nodes of the given module into CLI command trees.
+pub fn module_commands<'a>(
+    module: &SchemaModule<'a>,
+    flags: CliFlags,
+) -> Vec<CliCommand<'a>> {
+    module
+        .top_level_nodes(IterSchemaFlags::empty())
+        .filter_map(|snode| command(&snode, &flags))
+        .collect()
+}
+
+// ===== helper functions =====
+
+// Map a schema node into a command, recursively.
+fn command<'a>(
+    snode: &SchemaNode<'a>,
+    flags: &CliFlags,
+) -> Option<CliCommand<'a>> {
+    if skips_node(snode, flags) {
+        return None;
+    }
+
+    let kind = match snode.kind() {
+        SchemaNodeKind::Container if snode.is_presence_container() => {
+            CliCommandKind::PresenceContainer
+        }
+        SchemaNodeKind::Container => CliCommandKind::Container,
+        SchemaNodeKind::List => CliCommandKind::List,
+        SchemaNodeKind::Leaf => CliCommandKind::Leaf,
+        SchemaNodeKind::LeafList => CliCommandKind::LeafList,
+        _ => return None,
+    };
+
+    let mut arguments = Vec::new();
+    let mut subcommands = Vec::new();
+    match kind {
+        CliCommandKind::Leaf | CliCommandKind::LeafList => {
+            arguments.extend(argument(snode));
+        }
+        _ => {
+            if kind == CliCommandKind::List {
+                arguments
+                    .extend(snode.list_keys().filter_map(|key| argument(&key)));
+            }
+            subcommands.extend(
+                snode
+                    .children2(IterSchemaFlags::empty())
+                    .filter(|child| !child.is_list_key())
+                    .filter_map(|child| command(&child, flags)),
+            );
+        }
+    }
+
+    Some(CliCommand {
+        name: snode.name().to_owned(),
+        kind,
+        help: snode.description().map(ToOwned::to_owned),
+        mandatory: snode.is_mandatory(),
+        arguments,
+        subcommands,
+        snode: snode.clone(),
+    })
+}
+
+// Returns whether the given node is left out by the given options.
+fn skips_node(snode: &SchemaNode<'_>, flags: &CliFlags) -> bool {
+    snode.is_status_obsolete()
+        || (flags.contains(CliFlags::NO_DEPRECATED)
+            && snode.is_status_deprecated())
+        || (!flags.contains(CliFlags::STATE) && snode.is_state())
+}
+
+// Map a leaf or leaf-list into a command argument. Leaves of the empty type
+// take no argument.
+fn argument<'a>(snode: &SchemaNode<'a>) -> Option<CliArgument<'a>> {
+    let ltype = snode.leaf_type()?;
+    let rtype = ltype.leafref_real_type().unwrap_or_else(|| ltype.clone());
+    let value_type = rtype.base_type();
+    if value_type == DataValueType::Empty {
+        return None;
+    }
+
+    let mut candidates = Vec::new();
+    type_candidates(snode.context, &rtype, &mut candidates);
+    Some(CliArgument {
+        name: snode.name().to_owned(),
+        help: snode.description().map(ToOwned::to_owned),
+        value_type,
+        candidates,
+        default: snode.default_value_canonical().map(ToOwned::to_owned),
+        snode: snode.clone(),
+    })
+}
+
+// Collect the values accepted by the given type, when they form a finite
+// set.
+fn type_candidates(
+    context: &Context,
+    ltype: &SchemaLeafType<'_>,
+    values: &mut Vec<String>,
+) {
+    match ltype.base_type() {
+        DataValueType::Bool => {
+            values.extend(["true", "false"].iter().map(|v| v.to_string()));
+        }
+        DataValueType::Enum => {
+            values.extend(ltype.enum_items().into_iter().map(|item| item.name));
+        }
+        DataValueType::Bits => {
+            values.extend(ltype.bits_items().into_iter().map(|item| item.name));
+        }
+        DataValueType::IdentityRef => {
+            for base in ltype.identity_bases() {
+                for ident in base.derived_all() {
+                    let value = ident.qualified_name();
+                    if !values.contains(&value) {
+                        values.push(value);
+                    }
+                }
+            }
+        }
+        DataValueType::Union => {
+            let rtype = ltype.as_raw() as *mut ffi::lysc_type_union;
+            for member in unsafe { ly_array_slice((*rtype).types) } {
+                let member =
+                    unsafe { SchemaLeafType::from_raw(context, *member) };
+                type_candidates(context, &member, values);
+            }
+        }
+        DataValueType::LeafRef => {
+            if let Some(rtype) = ltype.leafref_real_type() {
+                type_candidates(context, &rtype, values);
+            }
+        }
+        _ => (),
+    }
+}
@@ -0,0 +1,148 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Confirmed commits (RFC 6241, section 8.4).
+
+use libyang3_sys as ffi;
+use std::time::{Duration, SystemTime};
+
+use crate::data::{DataDiff, DataDiffFlags, DataTree};
+use crate::error::{Error, Result};
+
+/// Pending confirmed commit, which is rolled back unless confirmed before
+/// its deadline.
+///
+/// The changes of the commit are kept as a [`DataDiff`], so they can be
+/// reverted on the running configuration, or persisted (e.g. printed using
+/// [`Data::print_string`]) to survive a restart.
+///
+/// [`Data::print_string`]: crate::data::Data::print_string
+#[derive(Debug)]
+pub struct ConfirmedCommit<'a> {
+    diff: DataDiff<'a>,
+    deadline: SystemTime,
+    persist: Option<String>,
+}
+
+// ===== impl ConfirmedCommit =====
+
+impl<'a> ConfirmedCommit<'a> {
+    /// Start a confirmed commit that changed the running configuration from
+    /// `old` to `new`, to be rolled back after the given timeout.
+    ///
+    /// When a persist identifier is given, the commit can be confirmed or
+    /// canceled only by providing that same identifier.
+    pub fn new(
+        old: &DataTree<'a>,
+        new: &DataTree<'a>,
+        timeout: Duration,
+        persist: Option<&str>,
+    ) -> Result<ConfirmedCommit<'a>> {
+        let diff = old.diff(new, DataDiffFlags::DEFAULTS)?;
+        Ok(ConfirmedCommit::from_parts(
+            diff,
+            SystemTime::now() + timeout,
+            persist,
+        ))
+    }
+
+    /// Restore a pending confirmed commit from its parts (e.g. after a
+    /// restart).
+    pub fn from_parts(
+        diff: DataDiff<'a>,
+        deadline: SystemTime,
+        persist: Option<&str>,
+    ) -> ConfirmedCommit<'a> {
+        ConfirmedCommit {
+            diff,
+            deadline,
+            persist: persist.map(ToOwned::to_owned),
+        }
+    }
+
+    /// Returns the changes made by the commit.
+    pub fn diff(&self) -> &DataDiff<'a> {
+        &self.diff
+    }
+
+    /// Returns the time after which the commit must be rolled back.
+    pub fn deadline(&self) -> SystemTime {
+        self.deadline
+    }
+
+    /// Returns the persist identifier of the commit, if any.
+    pub fn persist(&self) -> Option<&str> {
+        self.persist.as_deref()
+    }
+
+    /// Returns whether the deadline of the commit has passed.
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() >= self.deadline
+    }
+
+    /// Handle a follow-up confirmed commit, which postpones the deadline and
+    /// accumulates the changes from `old` to `new` (RFC 6241, section 8.4.5.1).
+    pub fn extend(
+        &mut self,
+        old: &DataTree<'a>,
+        new: &DataTree<'a>,
+        timeout: Duration,
+        persist_id: Option<&str>,
+    ) -> Result<()> {
+        self.check_persist_id(persist_id)?;
+
+        // Compute the accumulated changes since the original configuration.
+        let mut orig = old.duplicate()?;
+        orig.revert(&self.diff)?;
+        self.diff = orig.diff(new, DataDiffFlags::DEFAULTS)?;
+        self.deadline = SystemTime::now() + timeout;
+
+        Ok(())
+    }
+
+    /// Confirm the commit, making its changes permanent.
+    ///
+    /// The commit is no longer pending once confirmed, and should be
+    /// dropped. On failure (i.e. persist identifier mismatch), it's still
+    /// pending.
+    pub fn confirm(&self, persist_id: Option<&str>) -> Result<()> {
+        self.check_persist_id(persist_id)
+    }
+
+    /// Cancel the commit, reverting its changes on the given running
+    /// configuration.
+    ///
+    /// The commit is no longer pending once canceled, and should be dropped.
+    /// On failure (e.g. persist identifier mismatch), it's still pending.
+    pub fn cancel(
+        &self,
+        running: &mut DataTree<'a>,
+        persist_id: Option<&str>,
+    ) -> Result<()> {
+        self.check_persist_id(persist_id)?;
+        running.revert(&self.diff)
+    }
+
+    /// Roll back the commit (e.g. once its deadline has passed), reverting
+    /// its changes on the given running configuration.
+    pub fn rollback(self, running: &mut DataTree<'a>) -> Result<()> {
+        running.revert(&self.diff)
+    }
+
+    // Check whether the given persist identifier matches the one of the
+    // commit.
+    fn check_persist_id(&self, persist_id: Option<&str>) -> Result<()> {
+        if self.persist.as_deref() != persist_id {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some("Persist identifier mismatch".to_owned()),
+                ..Default::default()
+            });
+        }
+
+        Ok(())
+    }
+}
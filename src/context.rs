@@ -7,18 +7,25 @@
 //! YANG context.
 
 use bitflags::bitflags;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ffi::CString;
 use std::mem::ManuallyDrop;
 use std::os::raw::{c_char, c_void};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::slice;
-use std::sync::Once;
-
-use crate::data::DataFormat;
-use crate::error::{Error, Result};
-use crate::iter::{SchemaModules, Set};
-use crate::schema::{SchemaModule, SchemaNode};
+use std::sync::{Arc, Once};
+
+use crate::data::{
+    DataFormat, DataParserFlags, DataPrinterFlags, DataTree,
+    DataValidationFlags,
+};
+use crate::error::{io_error, Error, Result};
+use crate::iter::{IterSchemaFlags, SchemaModules, Set};
+use crate::repository::ModuleRepository;
+use crate::schema::{
+    SchemaExtInstance, SchemaFindFlags, SchemaInputFormat, SchemaModule,
+    SchemaNode, SchemaNodeKind, SchemaOutputFormat, SchemaPrinterFlags,
+};
 use crate::{logging, utils::*};
 use libyang3_sys as ffi;
 
@@ -30,6 +37,7 @@ use libyang3_sys as ffi;
 #[derive(Debug, PartialEq)]
 pub struct Context {
     pub(crate) raw: *mut ffi::ly_ctx,
+    state: Arc<ContextState>,
 }
 
 bitflags! {
@@ -62,6 +70,31 @@ bitflags! {
 
         /// When searching for schema, prefer searchdirs instead of user callback.
         const PREFER_SEARCHDIRS = ffi::LY_CTX_PREFER_SEARCHDIRS as u16;
+
+        /// Allow extended leafref paths, using the XPath `deref()` function
+        /// and other constructs not allowed by RFC 7950.
+        const LEAFREF_EXTENDED = ffi::LY_CTX_LEAFREF_EXTENDED as u16;
+
+        /// Link the leafref nodes of validated data trees with their targets,
+        /// allowing to look up the nodes referring to a given data node (see
+        /// [`DataNodeRef::leafref_referrers`]).
+        ///
+        /// [`DataNodeRef::leafref_referrers`]: crate::data::DataNodeRef::leafref_referrers
+        const LEAFREF_LINKING = ffi::LY_CTX_LEAFREF_LINKING as u16;
+    }
+}
+
+bitflags! {
+    /// Options to filter the candidates of path completions (see
+    /// [`Context::complete_path`]).
+    #[derive(Debug)]
+    pub struct CompletionFlags: u32 {
+        /// Skip configuration nodes.
+        const NO_CONFIG = 0x01;
+        /// Skip state nodes.
+        const NO_STATE = 0x02;
+        /// Skip RPCs, actions and notifications.
+        const NO_OPERATIONS = 0x04;
     }
 }
 
@@ -78,6 +111,146 @@ pub struct EmbeddedModuleKey {
 /// A hashmap containing embedded YANG modules.
 pub type EmbeddedModules = HashMap<EmbeddedModuleKey, &'static str>;
 
+/// Module advertised by a capability URI of a NETCONF `<hello>` message
+/// (RFC 6020, section 5.6.4).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ModuleCapability {
+    /// Module namespace.
+    pub namespace: String,
+    /// Module name.
+    pub name: String,
+    /// Module revision, if any.
+    pub revision: Option<String>,
+    /// Enabled features.
+    pub features: Vec<String>,
+    /// Names of the modules containing deviations of the module.
+    pub deviations: Vec<String>,
+}
+
+/// Failure to load one of the modules of a batch (see
+/// [`Context::load_modules_batch`]).
+#[derive(Debug)]
+pub struct ModuleLoadError {
+    /// Module name.
+    pub name: String,
+    /// Error that prevented loading the module.
+    pub error: Error,
+}
+
+/// Candidate completion of a partially typed data path (see
+/// [`Context::complete_path`]).
+#[derive(Clone, Debug)]
+pub struct Completion<'a> {
+    /// Text completing the last segment of the path: a node name, qualified
+    /// by its module name when required, or a list key name.
+    pub text: String,
+    /// Kind of completion.
+    pub kind: CompletionKind,
+    /// Schema node of the completed node or key.
+    pub snode: SchemaNode<'a>,
+}
+
+/// Kind of path completion.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompletionKind {
+    /// Schema node name.
+    Node,
+    /// List key name, completing a predicate.
+    Key,
+}
+
+/// Builder of YANG contexts, collecting all the settings and modules needed
+/// to create a fully compiled context in a single step.
+#[derive(Debug)]
+pub struct ContextBuilder<'e> {
+    options: u16,
+    search_dirs: Vec<PathBuf>,
+    embedded_modules: Option<&'e EmbeddedModules>,
+    yang_library: Option<(String, DataFormat)>,
+    modules: Vec<(String, Option<String>, Vec<String>)>,
+}
+
+/// Event of a YANG context, reported to the hooks registered using
+/// [`Context::add_hook`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ContextEvent {
+    /// A new module was loaded into the context.
+    ModuleLoaded {
+        name: String,
+        revision: Option<String>,
+    },
+    /// A module became implemented.
+    ModuleImplemented {
+        name: String,
+        revision: Option<String>,
+    },
+    /// The set of enabled features of a module changed.
+    FeaturesChanged {
+        name: String,
+        revision: Option<String>,
+    },
+    /// The context was recompiled, invalidating all the compiled schema
+    /// nodes obtained before.
+    Recompiled,
+}
+
+/// Identifier of a context hook, used to unregister it (see
+/// [`Context::remove_hook`]).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ContextHookId(u64);
+
+/// Hook invoked on context events (see [`Context::add_hook`]).
+pub type ContextHook = Box<dyn Fn(&Context, &ContextEvent) + Send + Sync>;
+
+/// Callback providing the data required by extension instances, such as
+/// the YANG library and `schema-mounts` data describing the schemas mounted
+/// by `ietf-yang-schema-mount` mount points (see
+/// [`Context::set_ext_data_callback`]).
+pub type ExtDataCallback = Box<
+    dyn for<'a> Fn(&'a Context, &SchemaExtInstance<'a>) -> Result<DataTree<'a>>
+        + Send
+        + Sync,
+>;
+
+// State of a context kept on the Rust side. It's kept apart from the context
+// so that its address, passed to libyang, remains stable when the context is
+// moved.
+#[derive(Debug, PartialEq)]
+struct ContextState {
+    printer_flags: DataPrinterFlags,
+    parser_flags: DataParserFlags,
+    hooks: ContextHooks,
+    ext_data: ExtDataCallbackSlot,
+    repository: Option<Arc<ModuleRepository>>,
+}
+
+// Extension data callback registered in a context.
+#[derive(Default)]
+struct ExtDataCallbackSlot(Option<ExtDataCallback>);
+
+// Hooks registered in a context.
+#[derive(Default)]
+struct ContextHooks {
+    next_id: u64,
+    hooks: Vec<(ContextHookId, ContextHook)>,
+}
+
+// State of the context modules, used to detect the changes reported to the
+// context hooks.
+pub(crate) struct ContextSnapshot {
+    change_count: u16,
+    modules: BTreeMap<(String, Option<String>), (bool, Vec<String>)>,
+}
+
+/// String interned in the dictionary of a context (see [`Context::intern`]).
+///
+/// The string is kept in the dictionary as long as the handle is alive.
+pub struct InternedStr<'a> {
+    context: &'a Context,
+    raw: *const c_char,
+    len: usize,
+}
+
 /// Callback for retrieving missing included or imported models in a custom way.
 pub type ModuleImportCb = unsafe extern "C" fn(
     mod_name: *const c_char,
@@ -119,13 +292,14 @@ impl Context {
             // Need to construct error structure by hand.
             return Err(Error {
                 errcode: ret,
+                vecode: ffi::LY_VECODE::LYVE_SUCCESS,
                 msg: None,
                 path: None,
                 apptag: None,
             });
         }
 
-        Ok(Context { raw: context })
+        Ok(Context::from_raw_parts(context))
     }
 
     /// Creates libyang context from a YANG Library
@@ -135,6 +309,22 @@ impl Context {
         library_format: DataFormat,
         search_dir: P,
         options: ContextFlags,
+    ) -> Result<Context> {
+        Context::new_from_yang_library_str_opt(
+            yang_library_data,
+            library_format,
+            Some(search_dir.as_ref()),
+            options,
+        )
+    }
+
+    // Creates libyang context from a YANG Library, with an optional search
+    // directory.
+    fn new_from_yang_library_str_opt(
+        yang_library_data: &str,
+        library_format: DataFormat,
+        search_dir: Option<&Path>,
+        options: ContextFlags,
     ) -> Result<Context> {
         static INIT: Once = Once::new();
         let mut context = std::ptr::null_mut();
@@ -148,13 +338,17 @@ impl Context {
             unsafe { ffi::ly_log_options(ffi::LY_LOSTORE_LAST) };
         });
 
-        let search_dir =
-            CString::new(search_dir.as_ref().to_str().unwrap()).unwrap();
+        let search_dir = search_dir.map(|search_dir| {
+            CString::new(search_dir.to_str().unwrap()).unwrap()
+        });
+        let search_dir_ptr = search_dir
+            .as_ref()
+            .map_or(std::ptr::null(), |search_dir| search_dir.as_ptr());
         let yang_library = CString::new(yang_library_data).unwrap();
 
         let ret = unsafe {
             ffi::ly_ctx_new_ylmem(
-                search_dir.as_ptr(),
+                search_dir_ptr,
                 yang_library.as_ptr(),
                 library_format as u32,
                 options.bits() as i32,
@@ -165,13 +359,14 @@ impl Context {
             // Need to construct error structure by hand.
             return Err(Error {
                 errcode: ret,
+                vecode: ffi::LY_VECODE::LYVE_SUCCESS,
                 msg: None,
                 path: None,
                 apptag: None,
             });
         }
 
-        Ok(Context { raw: context })
+        Ok(Context::from_raw_parts(context))
     }
 
     /// Creates libyang context from a YANG Library
@@ -212,13 +407,14 @@ impl Context {
             // Need to construct error structure by hand.
             return Err(Error {
                 errcode: ret,
+                vecode: ffi::LY_VECODE::LYVE_SUCCESS,
                 msg: None,
                 path: None,
                 apptag: None,
             });
         }
 
-        Ok(Context { raw: context })
+        Ok(Context::from_raw_parts(context))
     }
 
     /// Set the log level to [`ffi::LY_LOG_LEVEL::LY_LLDBG`]
@@ -266,7 +462,28 @@ impl Context {
     /// Returns a mutable raw pointer to the underlying C library representation
     /// of the libyang context.
     pub fn into_raw(self) -> *mut ffi::ly_ctx {
-        ManuallyDrop::new(self).raw
+        let context = ManuallyDrop::new(self);
+        let state = unsafe { std::ptr::read(&context.state) };
+        if state.ext_data.0.is_some() {
+            unsafe {
+                ffi::ly_ctx_set_ext_data_clb(
+                    context.raw,
+                    None,
+                    std::ptr::null_mut(),
+                )
+            };
+        }
+        if state.repository.is_some() {
+            unsafe {
+                ffi::ly_ctx_set_module_imp_clb(
+                    context.raw,
+                    None,
+                    std::ptr::null_mut(),
+                )
+            };
+        }
+        drop(state);
+        context.raw
     }
 
     /// Add the search path into libyang context.
@@ -329,6 +546,22 @@ impl Context {
         Ok(())
     }
 
+    /// Returns the search paths configured in the libyang context, in the
+    /// order they're searched.
+    pub fn searchdirs(&self) -> Vec<PathBuf> {
+        let mut searchdirs = Vec::new();
+        let mut dirs = unsafe { ffi::ly_ctx_get_searchdirs(self.raw) };
+        if dirs.is_null() {
+            return searchdirs;
+        }
+
+        while !unsafe { *dirs }.is_null() {
+            searchdirs.push(PathBuf::from(char_ptr_to_str(unsafe { *dirs })));
+            dirs = unsafe { dirs.add(1) };
+        }
+        searchdirs
+    }
+
     /// Set hash map containing embedded YANG modules, which are loaded on
     /// demand.
     pub fn set_embedded_modules(&mut self, modules: &EmbeddedModules) {
@@ -339,6 +572,7 @@ impl Context {
                 modules as *const _ as *mut c_void,
             )
         };
+        self.state_mut().repository = None;
     }
 
     /// Remove all embedded modules from the libyang context.
@@ -346,6 +580,24 @@ impl Context {
         unsafe {
             ffi::ly_ctx_set_module_imp_clb(self.raw, None, std::ptr::null_mut())
         };
+        self.state_mut().repository = None;
+    }
+
+    /// Load the missing imported and included modules from the given
+    /// repository, replacing the embedded modules (if any).
+    ///
+    /// Unless the [`ContextFlags::PREFER_SEARCHDIRS`] option is set, the
+    /// repository is searched before the search directories. Imports
+    /// without a revision resolve to the latest indexed revision.
+    pub fn set_module_repository(&mut self, repository: Arc<ModuleRepository>) {
+        unsafe {
+            ffi::ly_ctx_set_module_imp_clb(
+                self.raw,
+                Some(ly_module_repository_cb),
+                Arc::as_ptr(&repository) as *mut c_void,
+            )
+        };
+        self.state_mut().repository = Some(repository);
     }
 
     /// Set missing include or import module callback. It is meant to be used
@@ -364,6 +616,41 @@ impl Context {
                 user_data,
             )
         };
+        self.state_mut().repository = None;
+    }
+
+    /// Set the callback providing the data required by extension instances.
+    ///
+    /// This is required by the `ietf-yang-schema-mount` extension, whose
+    /// mount points are resolved using the YANG library and `schema-mounts`
+    /// data returned by the callback (usually parsed using the given
+    /// context).
+    pub fn set_ext_data_callback<F>(&mut self, callback: F)
+    where
+        F: for<'a> Fn(
+                &'a Context,
+                &SchemaExtInstance<'a>,
+            ) -> Result<DataTree<'a>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.state_mut().ext_data.0 = Some(Box::new(callback));
+        unsafe {
+            ffi::ly_ctx_set_ext_data_clb(
+                self.raw,
+                Some(ly_ext_data_cb),
+                Arc::as_ptr(&self.state) as *mut c_void,
+            )
+        };
+    }
+
+    /// Remove the extension data callback.
+    pub fn unset_ext_data_callback(&mut self) {
+        unsafe {
+            ffi::ly_ctx_set_ext_data_clb(self.raw, None, std::ptr::null_mut())
+        };
+        self.state_mut().ext_data.0 = None;
     }
 
     /// Get the currently set context's options.
@@ -374,7 +661,9 @@ impl Context {
 
     /// Set some of the context's options.
     pub fn set_options(&mut self, options: ContextFlags) -> Result<()> {
+        let snapshot = self.snapshot();
         let ret = unsafe { ffi::ly_ctx_set_options(self.raw, options.bits()) };
+        self.notify_changes(snapshot);
         if ret != ffi::LY_ERR::LY_SUCCESS {
             return Err(Error::new(self));
         }
@@ -384,8 +673,10 @@ impl Context {
 
     /// Unset some of the context's options.
     pub fn unset_options(&mut self, options: ContextFlags) -> Result<()> {
+        let snapshot = self.snapshot();
         let ret =
             unsafe { ffi::ly_ctx_unset_options(self.raw, options.bits()) };
+        self.notify_changes(snapshot);
         if ret != ffi::LY_ERR::LY_SUCCESS {
             return Err(Error::new(self));
         }
@@ -393,6 +684,85 @@ impl Context {
         Ok(())
     }
 
+    /// Get the context-wide default data printer options.
+    pub fn get_default_printer_flags(&self) -> DataPrinterFlags {
+        self.state.printer_flags
+    }
+
+    /// Set the context-wide default data printer options, used by the data
+    /// printing methods that take no options (e.g.
+    /// [`Data::print_string_default`]).
+    ///
+    /// [`Data::print_string_default`]: crate::data::Data::print_string_default
+    pub fn set_default_printer_flags(&mut self, options: DataPrinterFlags) {
+        self.state_mut().printer_flags = options;
+    }
+
+    /// Get the context-wide default data parser options.
+    pub fn get_default_parser_flags(&self) -> DataParserFlags {
+        self.state.parser_flags
+    }
+
+    /// Set the context-wide default data parser options, used by the data
+    /// parsing methods that take no parser options (e.g.
+    /// [`DataTree::parse_string_default`]).
+    ///
+    /// [`DataTree::parse_string_default`]: crate::data::DataTree::parse_string_default
+    pub fn set_default_parser_flags(&mut self, options: DataParserFlags) {
+        self.state_mut().parser_flags = options;
+    }
+
+    /// Checks the given data printer options.
+    ///
+    /// Fails if a tagged with-defaults mode is requested but the
+    /// ietf-netconf-with-defaults module isn't implemented, since libyang
+    /// would silently omit the tags otherwise.
+    pub(crate) fn check_printer_flags(
+        &self,
+        options: DataPrinterFlags,
+    ) -> Result<DataPrinterFlags> {
+        if options.intersects(
+            DataPrinterFlags::WD_ALL_TAG | DataPrinterFlags::WD_IMPL_TAG,
+        ) && self
+            .get_module_implemented("ietf-netconf-with-defaults")
+            .is_none()
+        {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some(
+                    "Tagged with-defaults modes require the \
+                     ietf-netconf-with-defaults module"
+                        .to_owned(),
+                ),
+                ..Default::default()
+            });
+        }
+
+        Ok(options)
+    }
+
+    fn from_raw_parts(raw: *mut ffi::ly_ctx) -> Context {
+        Context {
+            raw,
+            state: Arc::new(ContextState {
+                printer_flags: DataPrinterFlags::empty(),
+                parser_flags: DataParserFlags::empty(),
+                hooks: Default::default(),
+                ext_data: Default::default(),
+                repository: None,
+            }),
+        }
+    }
+
+    // Returns the state of the context for modification.
+    //
+    // The state is only shared with the context handed to the extension data
+    // callback, which can't outlive a call into libyang, so it can't be
+    // shared while the context is mutably borrowed.
+    fn state_mut(&mut self) -> &mut ContextState {
+        Arc::get_mut(&mut self.state).expect("context state is shared")
+    }
+
     /// Get current ID of the modules set.
     pub fn get_module_set_id(&self) -> u16 {
         unsafe { ffi::ly_ctx_get_change_count(self.raw) }
@@ -521,10 +891,131 @@ impl Context {
     ///
     /// Internal modules (loaded during the context creation) can be skipped by
     /// setting "skip_internal" to true.
+    ///
+    /// The iterator walks the live list of modules of the context. Use
+    /// [`Context::modules_vec`] to walk a snapshot of the list instead.
     pub fn modules(&self, skip_internal: bool) -> SchemaModules<'_> {
         SchemaModules::new(self, skip_internal)
     }
 
+    /// Get a snapshot of the loaded modules.
+    ///
+    /// Unlike [`Context::modules`], the returned list isn't affected by
+    /// modules loaded afterwards, so it can be walked while loading modules
+    /// (e.g. from a module import callback). Internal modules can be skipped
+    /// by setting "skip_internal" to true.
+    pub fn modules_vec(&self, skip_internal: bool) -> Vec<SchemaModule<'_>> {
+        self.modules(skip_internal).collect()
+    }
+
+    /// Get the loaded module at the given index, in the order the modules
+    /// were added to the context (internal modules first).
+    ///
+    /// New modules are appended to the context, so the index of a module
+    /// remains valid while other modules are loaded. Indexes are only
+    /// invalidated when a module fails to load, in which case the modules
+    /// added by the failed attempt are removed, and when the context is
+    /// destroyed.
+    pub fn module_at(&self, index: u32) -> Option<SchemaModule<'_>> {
+        let mut next = index;
+        let module =
+            unsafe { ffi::ly_ctx_get_module_iter(self.raw, &mut next) };
+        unsafe { SchemaModule::from_raw_opt(self, module as *mut _) }
+    }
+
+    /// Print all the modules and submodules of the context (except the
+    /// libyang internal modules) to the given directory, returning the
+    /// number of written files.
+    ///
+    /// The files are named `name@revision.ext` (or `name.ext` when there's
+    /// no revision), with the `yin` extension for the YIN format, `tree` for
+    /// the tree format, and `yang` otherwise. With the compiled format,
+    /// submodules are left out, as their contents are part of the compiled
+    /// modules, and so are the modules that are only imported, as they have
+    /// no compiled form. The directory is created if it doesn't exist.
+    pub fn export_modules<P: AsRef<Path>>(
+        &self,
+        dir: P,
+        format: SchemaOutputFormat,
+    ) -> Result<usize> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir).map_err(io_error)?;
+
+        let extension = match format {
+            SchemaOutputFormat::YIN => "yin",
+            SchemaOutputFormat::TREE => "tree",
+            _ => "yang",
+        };
+        let filename = |name: &str, revision: Option<&str>| match revision {
+            Some(revision) => format!("{}@{}.{}", name, revision, extension),
+            None => format!("{}.{}", name, extension),
+        };
+
+        let mut count = 0;
+        for module in self.modules(true) {
+            if format == SchemaOutputFormat::YANG_COMPILED
+                && !module.is_implemented()
+            {
+                continue;
+            }
+            let output =
+                module.print_string(format, SchemaPrinterFlags::empty())?;
+            let path = dir.join(filename(module.name(), module.revision()));
+            std::fs::write(path, output).map_err(io_error)?;
+            count += 1;
+
+            if format == SchemaOutputFormat::YANG_COMPILED {
+                continue;
+            }
+            for submodule in module.submodules() {
+                let output = submodule
+                    .print_string(format, SchemaPrinterFlags::empty())?;
+                let path =
+                    dir.join(filename(submodule.name(), submodule.revision()));
+                std::fs::write(path, output).map_err(io_error)?;
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Generate the YANG Library
+    /// [RFC 8525](https://datatracker.ietf.org/doc/html/rfc8525) data
+    /// describing the modules of the context.
+    ///
+    /// The content-id is set to the current module set ID (see
+    /// [`Context::get_module_set_id`]). The `ietf-yang-library` module must
+    /// be loaded in the context (i.e. the context must not have been created
+    /// with [`ContextFlags::NO_YANGLIBRARY`]).
+    pub fn yang_library_data(&self) -> Result<DataTree<'_>> {
+        let mut rnode = std::ptr::null_mut();
+        let format = b"%u\0";
+        let ret = unsafe {
+            ffi::ly_ctx_get_yanglib_data(
+                self.raw,
+                &mut rnode,
+                format.as_ptr() as *const c_char,
+                self.get_module_set_id() as std::os::raw::c_uint,
+            )
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self));
+        }
+
+        Ok(unsafe { DataTree::from_raw(self, rnode) })
+    }
+
+    /// Returns the capability URIs of all implemented modules, as advertised
+    /// in the NETCONF `<hello>` message (see
+    /// [`SchemaModule::capability_urn`]).
+    pub fn hello_capabilities(&self) -> Vec<String> {
+        self.modules(false)
+            .filter(|module| module.is_implemented())
+            .map(|module| module.capability_urn())
+            .collect()
+    }
+
     /// Returns an iterator over all data nodes from all modules in the YANG
     /// context (depth-first search algorithm).
     pub fn traverse(&self) -> impl Iterator<Item = SchemaNode<'_>> {
@@ -558,6 +1049,243 @@ impl Context {
         revision: Option<&str>,
         features: &[&str],
     ) -> Result<SchemaModule<'_>> {
+        let snapshot = self.snapshot();
+        let module = self.load_module_raw(name, revision, features);
+        self.notify_changes(snapshot);
+        let module = module?;
+
+        Ok(unsafe { SchemaModule::from_raw(self, module) })
+    }
+
+    /// Load a batch of modules, given as (name, revision, features) tuples
+    /// with the same meaning as the [`Context::load_module`] parameters.
+    ///
+    /// All modules are parsed first, and then compiled at once, regardless
+    /// of their interdependencies. Modules that fail to be parsed (e.g.
+    /// missing or invalid ones) don't abort the batch, and are reported in
+    /// the returned list instead. An error is returned only if the final
+    /// compilation fails, in which case none of the modules are loaded.
+    pub fn load_modules_batch(
+        &mut self,
+        modules: &[(&str, Option<&str>, &[&str])],
+    ) -> Result<Vec<ModuleLoadError>> {
+        let snapshot = self.snapshot();
+
+        // Defer the compilation until all modules are parsed.
+        let options = unsafe { ffi::ly_ctx_get_options(self.raw) };
+        let explicit = ffi::LY_CTX_EXPLICIT_COMPILE as u16;
+        if options & explicit == 0 {
+            unsafe { ffi::ly_ctx_set_options(self.raw, explicit) };
+        }
+
+        let mut failures = Vec::new();
+        for (name, revision, features) in modules {
+            if let Err(error) = self.load_module_raw(name, *revision, features)
+            {
+                failures.push(ModuleLoadError {
+                    name: name.to_string(),
+                    error,
+                });
+            }
+        }
+        let ret = unsafe { ffi::ly_ctx_compile(self.raw) };
+
+        if options & explicit == 0 {
+            unsafe { ffi::ly_ctx_unset_options(self.raw, explicit) };
+        }
+        self.notify_changes(snapshot);
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self));
+        }
+
+        Ok(failures)
+    }
+
+    /// Load the module advertised by the given capability, enabling its
+    /// features, along with the modules containing its deviations.
+    pub fn load_capability(
+        &mut self,
+        capability: &ModuleCapability,
+    ) -> Result<SchemaModule<'_>> {
+        let features = capability
+            .features
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        self.load_module(
+            &capability.name,
+            capability.revision.as_deref(),
+            &features,
+        )?;
+        for deviation in &capability.deviations {
+            self.load_module(deviation, None, &[])?;
+        }
+
+        self.get_module_implemented(&capability.name)
+            .ok_or_else(|| Error::new(self))
+    }
+
+    /// Parse a module (YANG or YIN) from a string and load it into the
+    /// context, enabling the given features (see [`Context::load_module`]).
+    pub fn parse_module_string(
+        &mut self,
+        data: &str,
+        format: SchemaInputFormat,
+        features: &[&str],
+    ) -> Result<SchemaModule<'_>> {
+        let data = CString::new(data).unwrap();
+        let mut ly_in = std::ptr::null_mut();
+        let ret = unsafe { ffi::ly_in_new_memory(data.as_ptr(), &mut ly_in) };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self));
+        }
+
+        self.parse_module(ly_in, format, features)
+    }
+
+    /// Parse a module (YANG or YIN) from the given file and load it into the
+    /// context, enabling the given features (see [`Context::load_module`]).
+    #[cfg(not(target_os = "windows"))]
+    pub fn parse_module_file<F: std::os::unix::io::AsRawFd>(
+        &mut self,
+        fd: F,
+        format: SchemaInputFormat,
+        features: &[&str],
+    ) -> Result<SchemaModule<'_>> {
+        let mut ly_in = std::ptr::null_mut();
+        let ret = unsafe { ffi::ly_in_new_fd(fd.as_raw_fd(), &mut ly_in) };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self));
+        }
+
+        self.parse_module(ly_in, format, features)
+    }
+
+    /// Parse a module (YANG or YIN) from the given file and load it into the
+    /// context, enabling the given features (see [`Context::load_module`]).
+    #[cfg(target_os = "windows")]
+    pub fn parse_module_file(
+        &mut self,
+        file: impl std::os::windows::io::AsRawHandle,
+        format: SchemaInputFormat,
+        features: &[&str],
+    ) -> Result<SchemaModule<'_>> {
+        use libc::open_osfhandle;
+
+        let raw_handle = file.as_raw_handle();
+        let fd = unsafe { open_osfhandle(raw_handle as isize, 0) };
+        let mut ly_in = std::ptr::null_mut();
+        let ret = unsafe { ffi::ly_in_new_fd(fd, &mut ly_in) };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self));
+        }
+
+        self.parse_module(ly_in, format, features)
+    }
+
+    /// Load the modules missing to parse the given data, returning whether
+    /// any module was loaded.
+    ///
+    /// Whenever the input contains elements of unknown modules, the given
+    /// loader is called with the module name (JSON) or namespace (XML) of
+    /// these elements, and can return the source of the corresponding module
+    /// in the YANG format. The input is parsed again once the returned
+    /// modules are loaded, until no more modules can be loaded. The data can
+    /// then be parsed with [`DataTree::parse_string`].
+    pub fn load_missing_modules<F>(
+        &mut self,
+        data: impl AsRef<[u8]>,
+        format: DataFormat,
+        mut loader: F,
+    ) -> Result<bool>
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        let mut requested = HashSet::new();
+        let mut loaded_any = false;
+        loop {
+            // Parse the input keeping the unknown elements as opaque nodes.
+            let dtree = DataTree::parse_string(
+                self,
+                data.as_ref(),
+                format,
+                DataParserFlags::NO_VALIDATION | DataParserFlags::OPAQ,
+                DataValidationFlags::empty(),
+            )?;
+            let missing = dtree
+                .unknown_elements()
+                .into_iter()
+                .filter_map(|element| element.module)
+                .filter(|module| requested.insert(module.clone()))
+                .collect::<Vec<_>>();
+            drop(dtree);
+
+            let mut loaded = false;
+            for module in missing {
+                if let Some(source) = loader(&module) {
+                    self.parse_module_string(
+                        &source,
+                        SchemaInputFormat::YANG,
+                        &[],
+                    )?;
+                    loaded = true;
+                }
+            }
+            if !loaded {
+                break;
+            }
+            loaded_any = true;
+        }
+
+        Ok(loaded_any)
+    }
+
+    // Parse a module from the given input handler (freeing it) and load it
+    // into the context, enabling the given features.
+    fn parse_module(
+        &mut self,
+        ly_in: *mut ffi::ly_in,
+        format: SchemaInputFormat,
+        features: &[&str],
+    ) -> Result<SchemaModule<'_>> {
+        // Prepare features array.
+        let features_cstr = features
+            .iter()
+            .map(|feature| CString::new(*feature).unwrap())
+            .collect::<Vec<_>>();
+        let mut features_ptr = features_cstr
+            .iter()
+            .map(|feature| feature.as_ptr())
+            .collect::<Vec<_>>();
+        features_ptr.push(std::ptr::null());
+
+        let snapshot = self.snapshot();
+        let mut module = std::ptr::null_mut();
+        let ret = unsafe {
+            ffi::lys_parse(
+                self.raw,
+                ly_in,
+                format as u32,
+                features_ptr.as_mut_ptr(),
+                &mut module,
+            )
+        };
+        unsafe { ffi::ly_in_free(ly_in, 0) };
+        self.notify_changes(snapshot);
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self));
+        }
+
+        Ok(unsafe { SchemaModule::from_raw(self, module) })
+    }
+
+    // Load a module without notifying the context hooks.
+    fn load_module_raw(
+        &self,
+        name: &str,
+        revision: Option<&str>,
+        features: &[&str],
+    ) -> Result<*mut ffi::lys_module> {
         let name = CString::new(name).unwrap();
         let revision_cstr;
         let mut features_ptr;
@@ -594,22 +1322,136 @@ impl Context {
             return Err(Error::new(self));
         }
 
-        Ok(unsafe { SchemaModule::from_raw(self, module as *mut _) })
+        Ok(module as *mut _)
+    }
+
+    /// Register a hook invoked whenever modules are loaded or implemented,
+    /// their features change or the context is recompiled, allowing
+    /// dependent caches to be invalidated.
+    ///
+    /// Only the changes made using the methods of this crate are reported.
+    pub fn add_hook<F>(&mut self, hook: F) -> ContextHookId
+    where
+        F: Fn(&Context, &ContextEvent) + Send + Sync + 'static,
+    {
+        let hooks = &mut self.state_mut().hooks;
+        let id = ContextHookId(hooks.next_id);
+        hooks.next_id += 1;
+        hooks.hooks.push((id, Box::new(hook)));
+        id
+    }
+
+    /// Unregister a hook. Returns whether the hook was registered.
+    pub fn remove_hook(&mut self, id: ContextHookId) -> bool {
+        let hooks = &mut self.state_mut().hooks;
+        let count = hooks.hooks.len();
+        hooks.hooks.retain(|(hook_id, _)| *hook_id != id);
+        hooks.hooks.len() != count
+    }
+
+    // Take a snapshot of the context modules, unless no hook is registered.
+    pub(crate) fn snapshot(&self) -> Option<ContextSnapshot> {
+        if self.state.hooks.hooks.is_empty() {
+            return None;
+        }
+
+        let modules = self
+            .modules(false)
+            .map(|module| {
+                let key = (
+                    module.name().to_owned(),
+                    module.revision().map(ToOwned::to_owned),
+                );
+                let features = module
+                    .enabled_features()
+                    .into_iter()
+                    .map(ToOwned::to_owned)
+                    .collect();
+                (key, (module.is_implemented(), features))
+            })
+            .collect();
+        Some(ContextSnapshot {
+            change_count: self.get_module_set_id(),
+            modules,
+        })
+    }
+
+    // Invoke the registered hooks for the changes made since the given
+    // snapshot was taken.
+    pub(crate) fn notify_changes(&self, snapshot: Option<ContextSnapshot>) {
+        let old = match snapshot {
+            Some(old) => old,
+            None => return,
+        };
+        let new = match self.snapshot() {
+            Some(new) => new,
+            None => return,
+        };
+
+        let mut events = vec![];
+        for ((name, revision), (implemented, features)) in new.modules {
+            match old.modules.get(&(name.clone(), revision.clone())) {
+                None => {
+                    events.push(ContextEvent::ModuleLoaded {
+                        name: name.clone(),
+                        revision: revision.clone(),
+                    });
+                    if implemented {
+                        events.push(ContextEvent::ModuleImplemented {
+                            name,
+                            revision,
+                        });
+                    }
+                }
+                Some((old_implemented, old_features)) => {
+                    if implemented && !old_implemented {
+                        events.push(ContextEvent::ModuleImplemented {
+                            name: name.clone(),
+                            revision: revision.clone(),
+                        });
+                    }
+                    if features != *old_features {
+                        events.push(ContextEvent::FeaturesChanged {
+                            name,
+                            revision,
+                        });
+                    }
+                }
+            }
+        }
+        if new.change_count != old.change_count {
+            events.push(ContextEvent::Recompiled);
+        }
+
+        for event in &events {
+            for (_, hook) in &self.state.hooks.hooks {
+                hook(self, event);
+            }
+        }
     }
 
     /// Evaluate an xpath expression on schema nodes.
     pub fn find_xpath(&self, path: &str) -> Result<Set<'_, SchemaNode<'_>>> {
+        self.find_xpath_with(path, SchemaFindFlags::empty())
+    }
+
+    /// Evaluate an xpath expression on schema nodes, using the given lookup
+    /// options.
+    pub fn find_xpath_with(
+        &self,
+        path: &str,
+        options: SchemaFindFlags,
+    ) -> Result<Set<'_, SchemaNode<'_>>> {
         let path = CString::new(path).unwrap();
         let mut set = std::ptr::null_mut();
         let set_ptr = &mut set;
-        let options = 0u32;
 
         let ret = unsafe {
             ffi::lys_find_xpath(
                 self.raw,
                 std::ptr::null(),
                 path.as_ptr(),
-                options,
+                options.bits(),
                 set_ptr,
             )
         };
@@ -641,6 +1483,209 @@ impl Context {
 
         Ok(unsafe { SchemaNode::from_raw(self, rnode as *mut _) })
     }
+
+    /// Find all schema nodes matching the given namespace-qualified
+    /// identifier (`module:identifier`), regardless of their location in the
+    /// schema tree.
+    ///
+    /// The module name refers to the namespace of the nodes, hence nodes
+    /// instantiated from groupings defined in imported modules are qualified
+    /// by the module using the grouping, as in data paths. Nodes from all
+    /// modules are searched, including augmentations, RPCs, actions and
+    /// notifications.
+    pub fn find_qualified(&self, node_id: &str) -> Result<Vec<SchemaNode<'_>>> {
+        let (module_name, name) = match node_id.split_once(':') {
+            Some((module_name, name))
+                if !module_name.is_empty() && !name.is_empty() =>
+            {
+                (module_name, name)
+            }
+            _ => {
+                return Err(Error {
+                    errcode: ffi::LY_ERR::LY_EINVAL,
+                    msg: Some(format!(
+                        "Invalid qualified identifier \"{}\"",
+                        node_id
+                    )),
+                    ..Default::default()
+                })
+            }
+        };
+
+        // Depth-first search, in schema order.
+        let mut stack = self
+            .modules(false)
+            .flat_map(|module| {
+                module
+                    .data()
+                    .chain(module.rpcs())
+                    .chain(module.notifications())
+            })
+            .collect::<Vec<_>>();
+        stack.reverse();
+        let mut snodes = Vec::new();
+        while let Some(snode) = stack.pop() {
+            let children = snode.all_children().collect::<Vec<_>>();
+            stack.extend(children.into_iter().rev());
+            if snode.name() == name && snode.module().name() == module_name {
+                snodes.push(snode);
+            }
+        }
+
+        Ok(snodes)
+    }
+
+    /// Returns the candidate completions of a partially typed data path
+    /// (e.g. `/ietf-interfaces:interfaces/interface[name='eth0']/en`).
+    ///
+    /// The last segment of the path is completed with the names of the
+    /// matching child nodes or, inside an unterminated predicate (e.g.
+    /// `/ietf-interfaces:interfaces/interface[`), with the names of the
+    /// list keys not given yet. Choice and case nodes are transparent, as in
+    /// data paths. No completions are returned if the complete segments of
+    /// the path don't resolve to a schema node.
+    pub fn complete_path(
+        &self,
+        partial_path: &str,
+        flags: CompletionFlags,
+    ) -> Vec<Completion<'_>> {
+        let partial_path =
+            partial_path.strip_prefix('/').unwrap_or(partial_path);
+        let mut segments = split_path_segments(partial_path);
+        let last = segments.pop().unwrap_or("");
+
+        // Resolve the complete segments.
+        let mut parent: Option<SchemaNode<'_>> = None;
+        for segment in segments {
+            let name = segment.split('[').next().unwrap_or(segment);
+            let snode = self.path_children(parent.as_ref()).find(|snode| {
+                path_segment_matches(snode, parent.as_ref(), name)
+            });
+            match snode {
+                Some(snode) => parent = Some(snode),
+                None => return Vec::new(),
+            }
+        }
+
+        // Complete list keys inside an unterminated predicate.
+        if let Some((name, predicates)) = last.split_once('[') {
+            let partial = match predicates.rsplit_once('[') {
+                Some((_, partial)) => partial,
+                None => predicates,
+            };
+            if predicates.ends_with(']') || partial.contains('=') {
+                return Vec::new();
+            }
+            let snode =
+                match self.path_children(parent.as_ref()).find(|snode| {
+                    path_segment_matches(snode, parent.as_ref(), name)
+                }) {
+                    Some(snode) => snode,
+                    None => return Vec::new(),
+                };
+            // Keys already given in the previous predicates.
+            let given = predicates
+                .split('[')
+                .filter_map(|predicate| predicate.split_once('='))
+                .map(|(key, _)| key.trim())
+                .collect::<Vec<_>>();
+            return snode
+                .list_keys()
+                .filter(|key| !given.contains(&key.name()))
+                .filter(|key| key.name().starts_with(partial.trim_start()))
+                .map(|key| Completion {
+                    text: key.name().to_owned(),
+                    kind: CompletionKind::Key,
+                    snode: key,
+                })
+                .collect();
+        }
+
+        // Complete node names.
+        self.path_children(parent.as_ref())
+            .filter(|snode| match snode.kind() {
+                SchemaNodeKind::Rpc
+                | SchemaNodeKind::Action
+                | SchemaNodeKind::Notification => {
+                    !flags.contains(CompletionFlags::NO_OPERATIONS)
+                }
+                _ if snode.is_config() => {
+                    !flags.contains(CompletionFlags::NO_CONFIG)
+                }
+                _ if snode.is_state() => {
+                    !flags.contains(CompletionFlags::NO_STATE)
+                }
+                _ => true,
+            })
+            .filter_map(|snode| {
+                let module = snode.module().name().to_owned();
+                let qualified = match &parent {
+                    Some(parent) => {
+                        last.contains(':') || parent.module().name() != module
+                    }
+                    None => true,
+                };
+                let text = match qualified {
+                    true => format!("{}:{}", module, snode.name()),
+                    false => snode.name().to_owned(),
+                };
+                if !text.starts_with(last) {
+                    return None;
+                }
+                Some(Completion {
+                    text,
+                    kind: CompletionKind::Node,
+                    snode,
+                })
+            })
+            .collect()
+    }
+
+    // Returns the child nodes of the given schema node, as found in data
+    // paths, or the top-level nodes of the implemented modules.
+    fn path_children<'b>(
+        &'b self,
+        parent: Option<&SchemaNode<'b>>,
+    ) -> Box<dyn Iterator<Item = SchemaNode<'b>> + 'b> {
+        match parent {
+            Some(parent) => {
+                Box::new(parent.children2(IterSchemaFlags::empty()))
+            }
+            None => Box::new(
+                self.modules(false)
+                    .filter(|module| module.is_implemented())
+                    .flat_map(|module| {
+                        module.top_level_nodes(IterSchemaFlags::empty())
+                    }),
+            ),
+        }
+    }
+
+    /// Insert the given string into the dictionary of the context, returning
+    /// a handle to the shared copy.
+    ///
+    /// Interning strings that are used repeatedly (e.g. enumeration or
+    /// identity values) avoids allocating a new copy for each use.
+    pub fn intern(&self, value: &str) -> Result<InternedStr<'_>> {
+        // An empty length means the string is null-terminated.
+        let value_ptr = match value.is_empty() {
+            true => b"\0".as_ptr() as *const c_char,
+            false => value.as_ptr() as *const c_char,
+        };
+        let mut raw = std::ptr::null();
+        let ret = unsafe {
+            ffi::lydict_insert(self.raw, value_ptr, value.len(), &mut raw)
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self));
+        }
+
+        Ok(InternedStr {
+            context: self,
+            raw,
+            len: value.len(),
+        })
+    }
 }
 
 unsafe impl Send for Context {}
@@ -652,6 +1697,248 @@ impl Drop for Context {
     }
 }
 
+// ===== impl ContextBuilder =====
+
+impl<'e> ContextBuilder<'e> {
+    /// Create a new builder, with no options set.
+    pub fn new() -> ContextBuilder<'e> {
+        ContextBuilder {
+            options: 0,
+            search_dirs: Vec::new(),
+            embedded_modules: None,
+            yang_library: None,
+            modules: Vec::new(),
+        }
+    }
+
+    /// Set the context options.
+    pub fn options(mut self, options: ContextFlags) -> ContextBuilder<'e> {
+        self.options = options.bits();
+        self
+    }
+
+    /// Add a search directory.
+    pub fn search_dir<P: AsRef<Path>>(
+        mut self,
+        search_dir: P,
+    ) -> ContextBuilder<'e> {
+        self.search_dirs.push(search_dir.as_ref().to_owned());
+        self
+    }
+
+    /// Set the embedded modules (see [`Context::set_embedded_modules`]).
+    pub fn embedded_modules(
+        mut self,
+        modules: &'e EmbeddedModules,
+    ) -> ContextBuilder<'e> {
+        self.embedded_modules = Some(modules);
+        self
+    }
+
+    /// Load the modules listed by the given YANG Library
+    /// [RFC 8525](https://datatracker.ietf.org/doc/html/rfc8525) data.
+    ///
+    /// The modules of the YANG Library are loaded when the context is
+    /// created, so they can only be found in the first search directory (and
+    /// not in the embedded modules).
+    pub fn yang_library(
+        mut self,
+        data: &str,
+        format: DataFormat,
+    ) -> ContextBuilder<'e> {
+        self.yang_library = Some((data.to_owned(), format));
+        self
+    }
+
+    /// Add a module to load (see [`Context::load_module`]).
+    pub fn module(
+        mut self,
+        name: &str,
+        revision: Option<&str>,
+        features: &[&str],
+    ) -> ContextBuilder<'e> {
+        self.modules.push((
+            name.to_owned(),
+            revision.map(ToOwned::to_owned),
+            features.iter().map(|feature| feature.to_string()).collect(),
+        ));
+        self
+    }
+
+    /// Create the context, loading and compiling all the modules at once.
+    ///
+    /// An error is returned if any of the modules fails to be loaded.
+    pub fn build(&self) -> Result<Context> {
+        let options = ContextFlags::from_bits_truncate(self.options);
+        let mut search_dirs = self.search_dirs.iter();
+        let mut context = match &self.yang_library {
+            Some((data, format)) => Context::new_from_yang_library_str_opt(
+                data,
+                *format,
+                search_dirs.next().map(PathBuf::as_path),
+                options,
+            )?,
+            None => Context::new(options)?,
+        };
+        for search_dir in search_dirs {
+            context.set_searchdir(search_dir)?;
+        }
+        if let Some(modules) = self.embedded_modules {
+            context.set_embedded_modules(modules);
+        }
+
+        let features = self
+            .modules
+            .iter()
+            .map(|(_, _, features)| {
+                features.iter().map(String::as_str).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let modules = self
+            .modules
+            .iter()
+            .zip(&features)
+            .map(|((name, revision, _), features)| {
+                (name.as_str(), revision.as_deref(), features.as_slice())
+            })
+            .collect::<Vec<_>>();
+        if let Some(failure) =
+            context.load_modules_batch(&modules)?.into_iter().next()
+        {
+            return Err(failure.error);
+        }
+
+        Ok(context)
+    }
+}
+
+impl Default for ContextBuilder<'_> {
+    fn default() -> Self {
+        ContextBuilder::new()
+    }
+}
+
+// ===== impl ContextHooks =====
+
+impl std::fmt::Debug for ContextHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.hooks.iter().map(|(id, _)| id))
+            .finish()
+    }
+}
+
+// Hooks run after the module changes they report have been applied, over a
+// list of events computed beforehand. A panicking hook only prevents the
+// remaining events from being reported.
+impl std::panic::RefUnwindSafe for ContextHooks {}
+impl std::panic::UnwindSafe for ContextHooks {}
+
+// Hooks don't take part in the comparison of contexts.
+impl PartialEq for ContextHooks {
+    fn eq(&self, _other: &ContextHooks) -> bool {
+        true
+    }
+}
+
+// ===== impl ExtDataCallbackSlot =====
+
+impl std::fmt::Debug for ExtDataCallbackSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ExtDataCallbackSlot")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+// The callback is only invoked by libyang, through an `extern "C"` function
+// that a panic can't unwind out of (the process is aborted instead), so no
+// state is ever observed after a panicking callback.
+impl std::panic::RefUnwindSafe for ExtDataCallbackSlot {}
+impl std::panic::UnwindSafe for ExtDataCallbackSlot {}
+
+// The callback doesn't take part in the comparison of contexts.
+impl PartialEq for ExtDataCallbackSlot {
+    fn eq(&self, _other: &ExtDataCallbackSlot) -> bool {
+        true
+    }
+}
+
+// ===== impl InternedStr =====
+
+impl InternedStr<'_> {
+    /// Returns a raw pointer to the null-terminated dictionary string.
+    pub fn as_ptr(&self) -> *const c_char {
+        self.raw
+    }
+
+    /// Returns the interned string.
+    pub fn as_str(&self) -> &str {
+        let bytes = unsafe { slice::from_raw_parts(self.raw as _, self.len) };
+        unsafe { std::str::from_utf8_unchecked(bytes) }
+    }
+}
+
+impl std::ops::Deref for InternedStr<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for InternedStr<'_> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Clone for InternedStr<'_> {
+    fn clone(&self) -> Self {
+        let mut raw = std::ptr::null();
+        let ret =
+            unsafe { ffi::lydict_dup(self.context.raw, self.raw, &mut raw) };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            panic!("Failed to duplicate dictionary string");
+        }
+
+        InternedStr {
+            context: self.context,
+            raw,
+            len: self.len,
+        }
+    }
+}
+
+impl std::fmt::Debug for InternedStr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl std::fmt::Display for InternedStr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq for InternedStr<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for InternedStr<'_> {}
+
+unsafe impl Send for InternedStr<'_> {}
+unsafe impl Sync for InternedStr<'_> {}
+
+impl Drop for InternedStr<'_> {
+    fn drop(&mut self) {
+        unsafe { ffi::lydict_remove(self.context.raw, self.raw) };
+    }
+}
+
 // ===== impl EmbeddedModuleKey =====
 
 impl EmbeddedModuleKey {
@@ -670,17 +1957,178 @@ impl EmbeddedModuleKey {
     }
 }
 
+// ===== impl ModuleCapability =====
+
+impl ModuleCapability {
+    /// Parse a module capability URI (e.g.
+    /// `urn:ietf:params:xml:ns:yang:ietf-interfaces?module=ietf-interfaces&revision=2018-02-20`).
+    ///
+    /// The parameters can be separated either by `&` or by its XML-escaped
+    /// form `&amp;`. Unknown parameters are ignored.
+    pub fn parse(uri: &str) -> Result<ModuleCapability> {
+        ModuleCapability::parse_opt(uri)?.ok_or_else(|| Error {
+            errcode: ffi::LY_ERR::LY_EINVAL,
+            msg: Some(format!("Missing module parameter in \"{}\"", uri)),
+            ..Default::default()
+        })
+    }
+
+    /// Parse the capability URIs of a `<hello>` message, skipping the ones
+    /// that don't advertise a module (e.g.
+    /// `urn:ietf:params:netconf:base:1.1`).
+    pub fn parse_hello<'s>(
+        capabilities: impl IntoIterator<Item = &'s str>,
+    ) -> Result<Vec<ModuleCapability>> {
+        let mut modules = Vec::new();
+        for uri in capabilities {
+            if let Some(module) = ModuleCapability::parse_opt(uri)? {
+                modules.push(module);
+            }
+        }
+        Ok(modules)
+    }
+
+    /// Returns the capability of the given module, as advertised in the
+    /// NETCONF `<hello>` message.
+    pub fn from_module(module: &SchemaModule<'_>) -> ModuleCapability {
+        ModuleCapability {
+            namespace: module.namespace().to_owned(),
+            name: module.name().to_owned(),
+            revision: module.revision().map(ToOwned::to_owned),
+            features: module
+                .enabled_features()
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect(),
+            deviations: module
+                .deviated_by()
+                .iter()
+                .map(|module| module.name().to_owned())
+                .collect(),
+        }
+    }
+
+    /// Returns the capability URI of the module (the inverse of
+    /// [`ModuleCapability::parse`]).
+    pub fn urn(&self) -> String {
+        let mut urn = format!("{}?module={}", self.namespace, self.name);
+        if let Some(revision) = &self.revision {
+            urn.push_str("&revision=");
+            urn.push_str(revision);
+        }
+        if !self.features.is_empty() {
+            urn.push_str("&features=");
+            urn.push_str(&self.features.join(","));
+        }
+        if !self.deviations.is_empty() {
+            urn.push_str("&deviations=");
+            urn.push_str(&self.deviations.join(","));
+        }
+        urn
+    }
+
+    // Parse a capability URI, returning `None` if it doesn't advertise a
+    // module.
+    fn parse_opt(uri: &str) -> Result<Option<ModuleCapability>> {
+        let uri = uri.trim();
+        let (namespace, query) = match uri.split_once('?') {
+            Some(split) => split,
+            None => return Ok(None),
+        };
+
+        let mut capability = ModuleCapability {
+            namespace: namespace.to_owned(),
+            ..Default::default()
+        };
+        let list = |value: &str| {
+            value
+                .split(',')
+                .filter(|item| !item.is_empty())
+                .map(ToOwned::to_owned)
+                .collect()
+        };
+        for param in query.replace("&amp;", "&").split('&') {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            match key {
+                "module" => capability.name = value.to_owned(),
+                "revision" => capability.revision = Some(value.to_owned()),
+                "features" => capability.features = list(value),
+                "deviations" => capability.deviations = list(value),
+                _ => (),
+            }
+        }
+        if capability.name.is_empty() {
+            return Ok(None);
+        }
+        if capability.namespace.is_empty() {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some(format!("Missing namespace in \"{}\"", uri)),
+                ..Default::default()
+            });
+        }
+
+        Ok(Some(capability))
+    }
+}
+
 unsafe impl<'a> Binding<'a> for Context {
     type CType = ffi::ly_ctx;
     type Container = ();
 
     unsafe fn from_raw(_: &'a Self::Container, raw: *mut Self::CType) -> Self {
-        Self { raw }
+        Context::from_raw_parts(raw)
     }
 }
 
 // ===== helper functions =====
 
+// Split a data path into its segments, ignoring the slashes inside
+// predicates.
+fn split_path_segments(path: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    let mut quote = None;
+    for (pos, c) in path.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => (),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            (None, '/') if depth == 0 => {
+                segments.push(&path[start..pos]);
+                start = pos + 1;
+            }
+            _ => (),
+        }
+    }
+    segments.push(&path[start..]);
+    segments
+}
+
+// Returns whether the given schema node matches the name of a data path
+// segment, which must be qualified by the module name unless the node
+// belongs to the same module as its parent.
+fn path_segment_matches(
+    snode: &SchemaNode<'_>,
+    parent: Option<&SchemaNode<'_>>,
+    name: &str,
+) -> bool {
+    match name.split_once(':') {
+        Some((module, name)) => {
+            snode.name() == name && snode.module().name() == module
+        }
+        None => {
+            snode.name() == name
+                && parent.is_some_and(|parent| {
+                    parent.module().name() == snode.module().name()
+                })
+        }
+    }
+}
+
 fn find_embedded_module<'a>(
     modules: &'a EmbeddedModules,
     mod_name: &'a str,
@@ -702,6 +2150,82 @@ fn find_embedded_module<'a>(
     })
 }
 
+// Look up the requested module or submodule in the repository attached to the
+// context.
+unsafe extern "C" fn ly_module_repository_cb(
+    mod_name: *const c_char,
+    mod_rev: *const c_char,
+    submod_name: *const c_char,
+    submod_rev: *const c_char,
+    user_data: *mut c_void,
+    format: *mut ffi::LYS_INFORMAT::Type,
+    module_data: *mut *const c_char,
+    free_module_data: *mut ffi::ly_module_imp_data_free_clb,
+) -> ffi::LY_ERR::Type {
+    let repository = &*(user_data as *const ModuleRepository);
+    let module = match char_ptr_to_opt_str(submod_name) {
+        Some(submod_name) => {
+            repository.get(submod_name, char_ptr_to_opt_str(submod_rev))
+        }
+        None => repository
+            .get(char_ptr_to_str(mod_name), char_ptr_to_opt_str(mod_rev)),
+    };
+    let source = match module.map(|module| (module.format(), module.source())) {
+        Some((module_format, Ok(source))) => {
+            *format = module_format as ffi::LYS_INFORMAT::Type;
+            source
+        }
+        _ => return ffi::LY_ERR::LY_ENOTFOUND,
+    };
+    let data = match CString::new(source) {
+        Ok(data) => data,
+        Err(_) => return ffi::LY_ERR::LY_EINVAL,
+    };
+
+    *module_data = data.into_raw();
+    *free_module_data = Some(ly_module_repository_free_cb);
+    ffi::LY_ERR::LY_SUCCESS
+}
+
+// Free the module source returned by `ly_module_repository_cb()`.
+unsafe extern "C" fn ly_module_repository_free_cb(
+    module_data: *mut c_void,
+    _user_data: *mut c_void,
+) {
+    drop(CString::from_raw(module_data as *mut c_char));
+}
+
+// Invoke the extension data callback registered in the context, handing the
+// returned data tree over to libyang.
+unsafe extern "C" fn ly_ext_data_cb(
+    ext: *const ffi::lysc_ext_instance,
+    user_data: *mut c_void,
+    ext_data: *mut *mut c_void,
+    ext_data_free: *mut ffi::ly_bool,
+) -> ffi::LY_ERR::Type {
+    // Borrow the context, along with its state, without taking ownership of
+    // them. The context may have been moved since the callback was set, but
+    // its state wasn't.
+    let context = ManuallyDrop::new(Context {
+        raw: (*(*ext).module).ctx,
+        state: Arc::from_raw(user_data as *const ContextState),
+    });
+    let callback = match &context.state.ext_data.0 {
+        Some(callback) => callback,
+        None => return ffi::LY_ERR::LY_ENOTFOUND,
+    };
+    let ext = SchemaExtInstance::from_raw(&context, ext as *mut _);
+    let result = callback(&context, &ext);
+    match result {
+        Ok(dtree) => {
+            *ext_data = dtree.into_raw() as *mut c_void;
+            *ext_data_free = 1;
+            ffi::LY_ERR::LY_SUCCESS
+        }
+        Err(error) => error.errcode,
+    }
+}
+
 unsafe extern "C" fn ly_module_import_cb(
     mod_name: *const c_char,
     mod_rev: *const c_char,
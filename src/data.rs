@@ -8,32 +8,125 @@
 
 use bitflags::bitflags;
 use core::ffi::{c_char, c_void};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ffi::CStr;
 use std::ffi::CString;
 use std::mem::ManuallyDrop;
 use std::slice;
-
-use crate::context::Context;
-use crate::error::{Error, Result};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::anonymize::Anonymizer;
+use crate::context::{Context, ModuleCapability};
+use crate::error::{io_error, Error, Result};
+use crate::input::InputProvider;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use crate::input::ReadInput;
 use crate::iter::{
     Ancestors, MetadataList, NodeIterable, Set, Siblings, Traverse,
 };
-use crate::schema::SchemaExtInstance;
-use crate::schema::{DataValue, SchemaModule, SchemaNode, SchemaNodeKind};
+use crate::limits::{LimitedParseError, ParserLimits};
+use crate::schema::{
+    DataValue, DataValueType, SchemaModule, SchemaNode, SchemaNodeKind,
+};
+use crate::schema::{SchemaExtInstance, SchemaIdentity, StatusFilter};
 use crate::utils::*;
+use crate::validation::{
+    find_constraint, ValidationConstraint, ValidationReport,
+};
 use libyang3_sys as ffi;
 
+/// Placeholder used by [`DataTree::redact`] to replace redacted values.
+pub const REDACTED_VALUE: &str = "<redacted>";
+
 /// YANG data tree.
 #[derive(Debug)]
 pub struct DataTree<'a> {
     context: &'a Context,
     raw: *mut ffi::lyd_node,
+    indexes: Vec<DataIndex>,
+    observers: DataObservers,
+    profiler: XPathProfilerSlot,
+}
+
+/// Edit made to a data tree through its mutation methods, reported to the
+/// observers registered using [`DataTree::add_observer`].
+#[derive(Clone, Debug)]
+pub enum DataEdit<'a> {
+    /// Root of a subtree created by [`DataTree::new_path`], added by
+    /// [`DataTree::add_implicit`] (or one of its scoped variants), or parsed
+    /// by [`DataTree::parse_mounted_string`].
+    Created(DataNodeRef<'a>),
+    /// Node modified by [`DataTree::new_path`].
+    Modified(DataNodeRef<'a>),
+    /// Root of a subtree about to be removed by [`DataTree::remove`].
+    Removed(DataNodeRef<'a>),
+    /// Node created or updated by [`DataTree::merge`].
+    Merged(DataNodeRef<'a>),
+    /// Change applied by [`DataTree::diff_apply`], as a node of the diff.
+    DiffApplied(DataDiffOp, DataNodeRef<'a>),
+}
+
+/// Identifier of a data tree observer, used to unregister it (see
+/// [`DataTree::remove_observer`]).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DataObserverId(u64);
+
+/// Observer invoked on data tree edits (see [`DataTree::add_observer`]).
+pub type DataObserver = Box<dyn Fn(&DataEdit<'_>) + Send + Sync>;
+
+/// Profile of an XPath evaluation over a data tree (see
+/// [`DataTree::explain_xpath`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct XPathProfile {
+    /// Evaluated XPath expression.
+    pub xpath: String,
+    /// Evaluation time.
+    pub duration: Duration,
+    /// Number of matching nodes.
+    pub matches: usize,
+    /// Upper bound of the number of data nodes visited by the evaluation.
+    ///
+    /// libyang doesn't report the exact count, so this is the number of
+    /// data nodes instantiating the schema nodes accessed by the
+    /// expression, whether or not the evaluation visited them.
+    pub max_nodes_visited: usize,
+    /// Whether the expression is eligible for list instance lookups by keys
+    /// (hashes), which is the case when it's a plain data path with
+    /// predicates for all lists and leaf-lists (see [`CompiledPath`]).
+    ///
+    /// This is derived from the expression alone; whether libyang actually
+    /// used hash lookups isn't reported.
+    pub key_hash_lookup_possible: bool,
+}
+
+/// Profiler invoked on XPath evaluations (see
+/// [`DataTree::set_xpath_profiler`]).
+pub type XPathProfiler = Box<dyn Fn(&XPathProfile) + Send + Sync>;
+
+// XPath profiler registered in a data tree.
+#[derive(Default)]
+struct XPathProfilerSlot(Option<XPathProfiler>);
+
+// Observers registered in a data tree.
+#[derive(Default)]
+struct DataObservers {
+    next_id: u64,
+    observers: Vec<(DataObserverId, DataObserver)>,
+}
+
+/// Secondary index mapping the values of a list leaf to the paths of the list
+/// instances containing them (see [`DataTree::add_index`]).
+#[derive(Debug)]
+struct DataIndex {
+    leaf: *const ffi::lysc_node,
+    values: HashMap<String, BTreeSet<String>>,
+    instances: HashMap<String, String>,
 }
 
 /// YANG data tree with an associated inner node reference.
 #[derive(Debug)]
 pub struct DataTreeOwningRef<'a> {
-    pub tree: DataTree<'a>,
+    tree: DataTree<'a>,
     raw: *mut ffi::lyd_node,
 }
 
@@ -56,6 +149,69 @@ pub struct Metadata<'a> {
     raw: *mut ffi::lyd_meta,
 }
 
+/// Data element without a schema definition, stored as an opaque node.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownElement {
+    /// Path of the opaque node.
+    pub path: String,
+    /// Name of the element.
+    pub name: String,
+    /// Module name (JSON) or namespace (XML) of the element, if any.
+    pub module: Option<String>,
+}
+
+/// Node set or changed differently in two data trees (see
+/// [`DataTree::merge_check`] and [`DataTree::merge3`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MergeConflict {
+    /// Path of the node.
+    pub path: String,
+    /// Canonical value of the node in our data tree, if any.
+    pub ours: Option<String>,
+    /// Canonical value of the node in their data tree, if any.
+    pub theirs: Option<String>,
+}
+
+/// Change of a numeric leaf between two data trees (see
+/// [`DataTree::counter_deltas`]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct CounterDelta {
+    /// Path of the leaf.
+    pub path: String,
+    /// Value of the leaf in the older data tree.
+    pub old: DataValue,
+    /// Value of the leaf in the newer data tree.
+    pub new: DataValue,
+    /// Difference between the new and old values. For unsigned integers,
+    /// a new value lower than the old one is considered a counter wrap
+    /// according to the width of the type.
+    pub delta: i128,
+}
+
+/// Data path compiled against the schema, allowing the same path to be
+/// resolved repeatedly in different data trees without parsing it again (see
+/// [`DataTree::collect_values`]).
+#[derive(Clone, Debug)]
+pub struct CompiledPath<'a> {
+    context: &'a Context,
+    path: String,
+    steps: Vec<CompiledPathStep>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct CompiledPathStep {
+    snode: *const ffi::lysc_node,
+    // List key predicates or leaf-list value.
+    key_or_value: Option<CString>,
+}
+
+/// Opaque envelope of an operation parsed from NETCONF (e.g. `<rpc>`,
+/// `<rpc-reply>` or `<notification>`).
+#[derive(Debug)]
+pub struct NetconfEnvelope<'a> {
+    tree: DataTree<'a>,
+}
+
 /// YANG data tree diff.
 #[derive(Debug)]
 pub struct DataDiff<'a> {
@@ -70,6 +226,22 @@ pub enum DataDiffOp {
     Replace,
 }
 
+bitflags! {
+    /// Data diff text representation options.
+    ///
+    /// Default behavior:
+    /// - Only the root of each created or deleted subtree is listed.
+    /// - No terminal colors are used.
+    pub struct DataDiffTextFlags: u32 {
+        /// List all the nodes of created and deleted subtrees instead of only
+        /// their roots.
+        const FULL_SUBTREES = 0x01;
+        /// Use ANSI escape sequences to color the changes according to their
+        /// operations.
+        const COLOR = 0x02;
+    }
+}
+
 /// Data input/output formats supported by libyang.
 #[allow(clippy::upper_case_acronyms)]
 #[repr(u32)]
@@ -83,6 +255,18 @@ pub enum DataFormat {
     LYB = ffi::LYD_FORMAT::LYD_LYB,
 }
 
+/// Compression codecs of data files (see [`DataTree::print_compressed`]).
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompressionCodec {
+    /// gzip (RFC 1952).
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// Zstandard (RFC 8878).
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
 /// Data operation type.
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -128,6 +312,7 @@ bitflags! {
     /// - invalid multiple data instances/data from several cases cause a
     ///   validation error.
     /// - implicit nodes (NP containers and default values) are added.
+    #[derive(Clone, Copy, Debug, PartialEq)]
     pub struct DataParserFlags: u32 {
         /// Data will be only parsed and no validation will be performed. When
         /// statements are kept unevaluated, union types may not be fully
@@ -139,6 +324,43 @@ bitflags! {
         const STRICT = ffi::LYD_PARSE_STRICT;
         /// Forbid state data in the parsed data.
         const NO_STATE = ffi::LYD_PARSE_NO_STATE;
+        /// Instead of silently ignoring data without schema definition, store
+        /// them as opaque nodes (see [`DataTree::unknown_elements`]). Opaque
+        /// nodes fail validation, so this flag is meant to be used together
+        /// with [`DataParserFlags::NO_VALIDATION`]. Do not combine with
+        /// [`DataParserFlags::STRICT`].
+        const OPAQ = ffi::LYD_PARSE_OPAQ;
+        /// Only for LYB format, allow parsing data printed using a specific
+        /// module revision to be loaded even with a module with the same name
+        /// but newer revision.
+        const LYB_MOD_UPDATE = ffi::LYD_PARSE_LYB_MOD_UPDATE;
+        /// Do not search for the correct place of each node but instead expect
+        /// that the nodes are sorted according to the schema (and with their
+        /// user-ordered instances in the correct order). Improves parsing
+        /// performance, but unordered data result in an error.
+        const ORDERED = ffi::LYD_PARSE_ORDERED;
+        /// Parse only the first child item along with any descendants, but no
+        /// siblings. Useful for parsing data one top-level node at a time.
+        const SUBTREE = ffi::LYD_PARSE_SUBTREE;
+        /// Mark all the parsed nodes dependent on a when condition with the
+        /// flag that means the condition was satisfied before. This allows
+        /// for auto-deletion of these nodes during validation.
+        const WHEN_TRUE = ffi::LYD_PARSE_WHEN_TRUE;
+        /// Do not set the new flag on the parsed nodes, so they are not
+        /// considered during validation. Only to be used when the parsed data
+        /// are known to be valid already.
+        const NO_NEW = ffi::LYD_PARSE_NO_NEW;
+        /// Perform only storing of the values, without any type validation
+        /// (pattern, range, length, leafref targets, etc.). Implies
+        /// [`DataParserFlags::NO_VALIDATION`].
+        const STORE_ONLY = ffi::LYD_PARSE_STORE_ONLY;
+        /// Allow using JSON empty value `null` within JSON input. Such nodes
+        /// are silently skipped and treated as non-existent.
+        const JSON_NULL = ffi::LYD_PARSE_JSON_NULL;
+        /// Allow the values of all data types to be encoded as JSON strings,
+        /// instead of requiring the native JSON type (number, boolean) where
+        /// applicable.
+        const JSON_STRING_DATATYPES = ffi::LYD_PARSE_JSON_STRING_DATATYPES;
     }
 }
 
@@ -152,6 +374,19 @@ bitflags! {
         const NO_STATE = ffi::LYD_VALIDATE_NO_STATE;
         /// Validate only modules whose data actually exist.
         const PRESENT = ffi::LYD_VALIDATE_PRESENT;
+        /// Do not stop validation on the first error but generate all the
+        /// detected errors.
+        const MULTI_ERROR = ffi::LYD_VALIDATE_MULTI_ERROR;
+        /// Semantic constraint violations are reported only as warnings
+        /// instead of errors (see RFC 8342 sec. 5.3).
+        const OPERATIONAL = ffi::LYD_VALIDATE_OPERATIONAL;
+        /// Do not add any default nodes during validation, other implicit
+        /// nodes (non-presence containers) are still added.
+        const NO_DEFAULTS = ffi::LYD_VALIDATE_NO_DEFAULTS;
+        /// Skip final validation tasks that require for all the data nodes to
+        /// either exist or not, based on the YANG constraints. Once the data
+        /// are complete, a full validation must be performed.
+        const NOT_FINAL = ffi::LYD_VALIDATE_NOT_FINAL;
     }
 }
 
@@ -160,6 +395,7 @@ bitflags! {
     ///
     /// Various options to change data validation behaviour, both for the parser
     /// and separate validation.
+    #[derive(Clone, Copy, Debug, PartialEq)]
     pub struct DataPrinterFlags: u32 {
         /// Flag for printing also the (following) sibling nodes of the data
         /// node.
@@ -178,6 +414,19 @@ bitflags! {
         const WD_TRIM = ffi::LYD_PRINT_WD_TRIM;
         /// Include implicit default nodes.
         const WD_ALL = ffi::LYD_PRINT_WD_ALL;
+        /// Same as WD_ALL, but also adds the "default" attribute (from the
+        /// ietf-netconf-with-defaults module) to all the nodes with their
+        /// default value, explicit or implicit (RFC 6243 report-all-tagged
+        /// mode). The ietf-netconf-with-defaults module must be implemented
+        /// in the context (e.g. loaded using [`Context::load_module`]).
+        ///
+        /// [`Context::load_module`]: crate::context::Context::load_module
+        const WD_ALL_TAG = ffi::LYD_PRINT_WD_ALL_TAG;
+        /// Same as WD_ALL, but also adds the "default" attribute (from the
+        /// ietf-netconf-with-defaults module) to the implicit default nodes
+        /// only. The ietf-netconf-with-defaults module must be implemented in
+        /// the context.
+        const WD_IMPL_TAG = ffi::LYD_PRINT_WD_IMPL_TAG;
     }
 }
 
@@ -212,6 +461,10 @@ bitflags! {
         /// explicit nodes. Also, leaves and leaf-lists are added into diff even
         /// in case only their default flag (state) was changed.
         const DEFAULTS = ffi::LYD_DIFF_DEFAULTS as u16;
+        /// Metadata of the nodes are also compared, and any changes are
+        /// recorded in the diff using special 'meta-create', 'meta-delete'
+        /// and 'meta-replace' metadata.
+        const META = ffi::LYD_DIFF_META as u16;
     }
 }
 
@@ -242,17 +495,29 @@ pub trait Data<'a> {
     /// with constant (*O(1)*) complexity (unless they are defined in
     /// top-level). Other predicates can still follow the aforementioned ones.
     fn find_xpath(&'a self, xpath: &str) -> Result<Set<'a, DataNodeRef<'a>>> {
-        let xpath = CString::new(xpath).unwrap();
+        let xpath_cstr = CString::new(xpath).unwrap();
         let mut set = std::ptr::null_mut();
         let set_ptr = &mut set;
 
-        let ret =
-            unsafe { ffi::lyd_find_xpath(self.raw(), xpath.as_ptr(), set_ptr) };
+        let start = Instant::now();
+        let ret = unsafe {
+            ffi::lyd_find_xpath(self.raw(), xpath_cstr.as_ptr(), set_ptr)
+        };
         if ret != ffi::LY_ERR::LY_SUCCESS {
             return Err(Error::new(self.context()));
         }
 
         let rnodes_count = unsafe { (*set).count } as usize;
+        if let Some(profiler) = &self.tree().profiler.0 {
+            let profile = xpath_profile(
+                self.tree(),
+                self.raw(),
+                xpath,
+                start.elapsed(),
+                rnodes_count,
+            );
+            profiler(&profile);
+        }
         let slice = if rnodes_count == 0 {
             &[]
         } else {
@@ -317,7 +582,7 @@ pub trait Data<'a> {
                 fd.as_raw_fd(),
                 self.raw(),
                 format as u32,
-                options.bits(),
+                self.context().check_printer_flags(options)?.bits(),
             )
         };
         if ret != ffi::LY_ERR::LY_SUCCESS {
@@ -341,7 +606,12 @@ pub trait Data<'a> {
         let fd = unsafe { open_osfhandle(raw_handle as isize, 0) };
 
         let ret = unsafe {
-            ffi::lyd_print_fd(fd, self.raw(), format as u32, options.bits())
+            ffi::lyd_print_fd(
+                fd,
+                self.raw(),
+                format as u32,
+                self.context().check_printer_flags(options)?.bits(),
+            )
         };
         if ret != ffi::LY_ERR::LY_SUCCESS {
             return Err(Error::new(self.context()));
@@ -371,7 +641,7 @@ pub trait Data<'a> {
                 cstr_ptr,
                 self.raw(),
                 format as u32,
-                options.bits(),
+                self.context().check_printer_flags(options)?.bits(),
             )
         };
         if ret != ffi::LY_ERR::LY_SUCCESS {
@@ -395,7 +665,7 @@ pub trait Data<'a> {
                 cstr_ptr,
                 self.raw(),
                 format as u32,
-                options.bits(),
+                self.context().check_printer_flags(options)?.bits(),
             )
         };
         if ret != ffi::LY_ERR::LY_SUCCESS {
@@ -423,6 +693,22 @@ pub trait Data<'a> {
         };
         Ok(bytes)
     }
+
+    /// Print data tree in the specified format to a `String`, using the
+    /// context-wide default printer options (see
+    /// [`Context::set_default_printer_flags`]).
+    fn print_string_default(&self, format: DataFormat) -> Result<String> {
+        let options = self.context().get_default_printer_flags();
+        self.print_string(format, options)
+    }
+
+    /// Print data tree in the specified format to a bytes vector, using the
+    /// context-wide default printer options (see
+    /// [`Context::set_default_printer_flags`]).
+    fn print_bytes_default(&self, format: DataFormat) -> Result<Vec<u8>> {
+        let options = self.context().get_default_printer_flags();
+        self.print_bytes(format, options)
+    }
 }
 
 // ===== impl DataTree =====
@@ -438,6 +724,9 @@ impl<'a> DataTree<'a> {
         DataTree {
             context,
             raw: std::ptr::null_mut(),
+            indexes: Vec::new(),
+            observers: Default::default(),
+            profiler: Default::default(),
         }
     }
 
@@ -598,6 +887,185 @@ impl<'a> DataTree<'a> {
         )
     }
 
+    /// Parse (and validate) input data as a YANG data tree, using the
+    /// context-wide default parser options (see
+    /// [`Context::set_default_parser_flags`]).
+    pub fn parse_string_default(
+        context: &'a Context,
+        data: impl AsRef<[u8]>,
+        format: DataFormat,
+        validation_options: DataValidationFlags,
+    ) -> Result<DataTree<'a>> {
+        DataTree::parse_string(
+            context,
+            data,
+            format,
+            context.get_default_parser_flags(),
+            validation_options,
+        )
+    }
+
+    /// Parse (and validate) untrusted input data as a YANG data tree,
+    /// enforcing the given limits (see [`ParserLimits`]).
+    pub fn parse_string_limited(
+        context: &'a Context,
+        data: impl AsRef<[u8]>,
+        format: DataFormat,
+        parser_options: DataParserFlags,
+        validation_options: DataValidationFlags,
+        limits: &ParserLimits,
+    ) -> std::result::Result<DataTree<'a>, LimitedParseError> {
+        let data = data.as_ref();
+        limits.check_input(data, format, false)?;
+        let dtree = DataTree::parse_string(
+            context,
+            data,
+            format,
+            parser_options,
+            validation_options,
+        )?;
+        limits.check_tree(&dtree)?;
+
+        Ok(dtree)
+    }
+
+    /// Parse (and validate) untrusted XML or JSON input data as a YANG data
+    /// tree. This is the entry point meant for data received from untrusted
+    /// peers (e.g. RESTCONF or NETCONF clients).
+    ///
+    /// On top of the given limits (see [`ParserLimits`]), the following is
+    /// enforced:
+    /// * LYB input is rejected, since it isn't meant to be exchanged with
+    ///   untrusted peers.
+    /// * XML document type declarations (and thus external entities) and
+    ///   processing instructions are rejected. Only the leading XML
+    ///   declaration is allowed.
+    /// * The [`DataParserFlags::STRICT`] flag is always set, so unknown data
+    ///   is rejected. The [`DataParserFlags::OPAQ`],
+    ///   [`DataParserFlags::NO_NEW`] and [`DataParserFlags::STORE_ONLY`]
+    ///   flags, which would let unvalidated data through, are cleared.
+    pub fn parse_untrusted(
+        context: &'a Context,
+        data: impl AsRef<[u8]>,
+        format: DataFormat,
+        parser_options: DataParserFlags,
+        validation_options: DataValidationFlags,
+        limits: &ParserLimits,
+    ) -> std::result::Result<DataTree<'a>, LimitedParseError> {
+        if format == DataFormat::LYB {
+            return Err(LimitedParseError::Parse(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some("LYB input can't be parsed as untrusted".to_owned()),
+                ..Default::default()
+            }));
+        }
+
+        let data = data.as_ref();
+        limits.check_input(data, format, true)?;
+        // STORE_ONLY includes the NO_VALIDATION bit, which is kept.
+        let mut parser_options = parser_options;
+        let no_validation =
+            parser_options.contains(DataParserFlags::NO_VALIDATION);
+        parser_options.remove(
+            DataParserFlags::OPAQ
+                | DataParserFlags::NO_NEW
+                | DataParserFlags::STORE_ONLY,
+        );
+        parser_options.set(DataParserFlags::NO_VALIDATION, no_validation);
+        parser_options.insert(DataParserFlags::STRICT);
+        let dtree = DataTree::parse_string(
+            context,
+            data,
+            format,
+            parser_options,
+            validation_options,
+        )?;
+        limits.check_tree(&dtree)?;
+
+        Ok(dtree)
+    }
+
+    /// Parse (and validate) input data read from the given input provider
+    /// as a YANG data tree.
+    ///
+    /// Returns an error if reading the input fails.
+    pub fn parse_input(
+        context: &'a Context,
+        input: &mut impl InputProvider,
+        format: DataFormat,
+        parser_options: DataParserFlags,
+        validation_options: DataValidationFlags,
+    ) -> Result<DataTree<'a>> {
+        DataTree::parse_string(
+            context,
+            input.read_all()?,
+            format,
+            parser_options,
+            validation_options,
+        )
+    }
+
+    /// Parse (and validate) a compressed data file as a YANG data tree.
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    pub fn parse_compressed(
+        context: &'a Context,
+        path: impl AsRef<std::path::Path>,
+        format: DataFormat,
+        codec: CompressionCodec,
+        parser_options: DataParserFlags,
+        validation_options: DataValidationFlags,
+    ) -> Result<DataTree<'a>> {
+        let file = std::fs::File::open(path).map_err(io_error)?;
+        let reader: Box<dyn std::io::Read> = match codec {
+            #[cfg(feature = "gzip")]
+            CompressionCodec::Gzip => {
+                Box::new(flate2::read::GzDecoder::new(file))
+            }
+            #[cfg(feature = "zstd")]
+            CompressionCodec::Zstd => Box::new(
+                zstd::stream::read::Decoder::new(file).map_err(io_error)?,
+            ),
+        };
+        let data = ReadInput::new(reader).read_all()?;
+
+        DataTree::parse_string(
+            context,
+            data,
+            format,
+            parser_options,
+            validation_options,
+        )
+    }
+
+    /// Convert YANG data from one format to another.
+    ///
+    /// The data is parsed without validation, so that no implicit nodes are
+    /// added and all nodes present in the input are kept as they are. Data
+    /// without a schema definition is preserved as opaque nodes, as are the
+    /// metadata (e.g. NETCONF operation attributes) that can be represented
+    /// in the target format.
+    pub fn convert(
+        context: &'a Context,
+        data: impl AsRef<[u8]>,
+        from_format: DataFormat,
+        to_format: DataFormat,
+    ) -> Result<Vec<u8>> {
+        let dtree = DataTree::parse_string(
+            context,
+            data,
+            from_format,
+            DataParserFlags::NO_VALIDATION | DataParserFlags::OPAQ,
+            DataValidationFlags::empty(),
+        )?;
+        let options = DataPrinterFlags::WITH_SIBLINGS;
+        match to_format {
+            DataFormat::XML | DataFormat::JSON => dtree
+                .print_string(to_format, options)
+                .map(String::into_bytes),
+            DataFormat::LYB => dtree.print_bytes(to_format, options),
+        }
+    }
+
     /// Parse input data as an extension data tree using the given schema
     /// extension.
     pub fn parse_ext_string(
@@ -616,14 +1084,78 @@ impl<'a> DataTree<'a> {
         )
     }
 
+    /// Parse the data mounted at the given `ietf-yang-schema-mount` mount
+    /// point, adding it to the data tree.
+    ///
+    /// The schema of the mounted data is resolved using the extension data
+    /// callback of the context (see [`Context::set_ext_data_callback`]).
+    pub fn parse_mounted_string(
+        &mut self,
+        path: &str,
+        data: impl AsRef<[u8]>,
+        format: DataFormat,
+        parser_options: DataParserFlags,
+        validation_options: DataValidationFlags,
+    ) -> Result<()> {
+        let parent = self.find_path(path)?;
+        if !parent.schema().is_mount_point() {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some(format!("Node \"{}\" is not a mount point", path)),
+                ..Default::default()
+            });
+        }
+        let parent = parent.raw;
+        let existing = match self.observers.observers.is_empty() {
+            true => HashSet::new(),
+            false => self.traverse().map(|dnode| dnode.raw).collect(),
+        };
+
+        // Create input handler.
+        let cdata;
+        let mut ly_in = std::ptr::null_mut();
+        let ret = match format {
+            DataFormat::XML | DataFormat::JSON => unsafe {
+                cdata = CString::new(data.as_ref()).unwrap();
+                ffi::ly_in_new_memory(cdata.as_ptr() as _, &mut ly_in)
+            },
+            DataFormat::LYB => unsafe {
+                ffi::ly_in_new_memory(data.as_ref().as_ptr() as _, &mut ly_in)
+            },
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.context));
+        }
+
+        let mut rnode = std::ptr::null_mut();
+        let ret = unsafe {
+            ffi::lyd_parse_data(
+                self.context.raw,
+                parent,
+                ly_in,
+                format as u32,
+                parser_options.bits(),
+                validation_options.bits(),
+                &mut rnode,
+            )
+        };
+        unsafe { ffi::ly_in_free(ly_in, 0) };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.context));
+        }
+
+        self.reindex();
+        self.notify_added(&existing);
+
+        Ok(())
+    }
+
     fn _parse_op_string(
         ctx_or_ext: CtxOrExt<'a>,
         data: impl AsRef<[u8]>,
         format: DataFormat,
         op: DataOperation,
-    ) -> Result<DataTree<'a>> {
-        let mut rnode = std::ptr::null_mut();
-        let rnode_ptr = &mut rnode;
+    ) -> Result<(DataTree<'a>, *mut ffi::lyd_node)> {
         let context = match ctx_or_ext {
             CtxOrExt::C(c) => c,
             CtxOrExt::E(e) => e.context,
@@ -645,38 +1177,77 @@ impl<'a> DataTree<'a> {
             return Err(Error::new(context));
         }
 
-        let ret = unsafe {
-            match ctx_or_ext {
-                CtxOrExt::C(c) => ffi::lyd_parse_op(
-                    c.raw,
-                    std::ptr::null_mut(),
-                    ly_in,
-                    format as u32,
-                    op as u32,
-                    rnode_ptr,
-                    std::ptr::null_mut(),
-                ),
-                CtxOrExt::E(e) => ffi::lyd_parse_ext_op(
-                    e.raw,
-                    std::ptr::null_mut(),
-                    ly_in,
-                    format as u32,
-                    op as u32,
-                    rnode_ptr,
-                    std::ptr::null_mut(),
-                ),
-            }
+        unsafe { DataTree::_parse_op_in(ctx_or_ext, ly_in, format, op) }
+    }
+
+    fn _parse_op_fd(
+        context: &'a Context,
+        fd: std::os::raw::c_int,
+        format: DataFormat,
+        op: DataOperation,
+    ) -> Result<(DataTree<'a>, *mut ffi::lyd_node)> {
+        // Create input handler.
+        let mut ly_in = std::ptr::null_mut();
+        let ret = unsafe { ffi::ly_in_new_fd(fd, &mut ly_in) };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(context));
+        }
+
+        unsafe {
+            DataTree::_parse_op_in(CtxOrExt::C(context), ly_in, format, op)
+        }
+    }
+
+    // Parse an operation from the given input handler, freeing it afterwards.
+    unsafe fn _parse_op_in(
+        ctx_or_ext: CtxOrExt<'a>,
+        ly_in: *mut ffi::ly_in,
+        format: DataFormat,
+        op: DataOperation,
+    ) -> Result<(DataTree<'a>, *mut ffi::lyd_node)> {
+        let mut rnode = std::ptr::null_mut();
+        let rnode_ptr = &mut rnode;
+        let mut op_node = std::ptr::null_mut();
+        let op_node_ptr = &mut op_node;
+        let context = match ctx_or_ext {
+            CtxOrExt::C(c) => c,
+            CtxOrExt::E(e) => e.context,
         };
-        unsafe { ffi::ly_in_free(ly_in, 0) };
+
+        let ret = match ctx_or_ext {
+            CtxOrExt::C(c) => ffi::lyd_parse_op(
+                c.raw,
+                std::ptr::null_mut(),
+                ly_in,
+                format as u32,
+                op as u32,
+                rnode_ptr,
+                op_node_ptr,
+            ),
+            CtxOrExt::E(e) => ffi::lyd_parse_ext_op(
+                e.raw,
+                std::ptr::null_mut(),
+                ly_in,
+                format as u32,
+                op as u32,
+                rnode_ptr,
+                op_node_ptr,
+            ),
+        };
+        ffi::ly_in_free(ly_in, 0);
 
         if ret != ffi::LY_ERR::LY_SUCCESS {
             return Err(Error::new(context));
         }
 
-        Ok(unsafe { DataTree::from_raw(context, rnode) })
+        Ok((DataTree::from_raw(context, rnode), op_node))
     }
 
     /// Parse YANG data into an operation data tree.
+    ///
+    /// Use [`DataTree::op_node`] to retrieve the operation node, or
+    /// [`DataTreeOwningRef::parse_op_string`] to get a reference to it
+    /// directly.
     pub fn parse_op_string(
         context: &'a Context,
         data: impl AsRef<[u8]>,
@@ -684,10 +1255,51 @@ impl<'a> DataTree<'a> {
         op: DataOperation,
     ) -> Result<DataTree<'a>> {
         DataTree::_parse_op_string(CtxOrExt::C(context), data, format, op)
+            .map(|(tree, _)| tree)
     }
 
-    /// Parse op data as an extension data tree using the given schema
-    /// extension. Parse input data into an operation data tree.
+    /// Parse YANG data read from the given input provider into an operation
+    /// data tree.
+    ///
+    /// Returns an error if reading the input fails.
+    pub fn parse_op_input(
+        context: &'a Context,
+        input: &mut impl InputProvider,
+        format: DataFormat,
+        op: DataOperation,
+    ) -> Result<DataTree<'a>> {
+        DataTree::parse_op_string(context, input.read_all()?, format, op)
+    }
+
+    /// Parse YANG data from a file into an operation data tree.
+    #[cfg(not(target_os = "windows"))]
+    pub fn parse_op_file<F: std::os::unix::io::AsRawFd>(
+        context: &'a Context,
+        fd: F,
+        format: DataFormat,
+        op: DataOperation,
+    ) -> Result<DataTree<'a>> {
+        DataTree::_parse_op_fd(context, fd.as_raw_fd(), format, op)
+            .map(|(tree, _)| tree)
+    }
+    #[cfg(target_os = "windows")]
+    pub fn parse_op_file(
+        context: &'a Context,
+        file: impl std::os::windows::io::AsRawHandle,
+        format: DataFormat,
+        op: DataOperation,
+    ) -> Result<DataTree<'a>> {
+        use libc::open_osfhandle;
+
+        let raw_handle = file.as_raw_handle();
+
+        let fd = unsafe { open_osfhandle(raw_handle as isize, 0) };
+
+        DataTree::_parse_op_fd(context, fd, format, op).map(|(tree, _)| tree)
+    }
+
+    /// Parse op data as an extension data tree using the given schema
+    /// extension. Parse input data into an operation data tree.
     pub fn parse_op_ext_string(
         ext: &'a SchemaExtInstance<'a>,
         data: impl AsRef<[u8]>,
@@ -695,6 +1307,7 @@ impl<'a> DataTree<'a> {
         op: DataOperation,
     ) -> Result<DataTree<'a>> {
         DataTree::_parse_op_string(CtxOrExt::E(ext), data, format, op)
+            .map(|(tree, _)| tree)
     }
 
     /// Returns a reference to the fist top-level data node, unless the data
@@ -775,16 +1388,482 @@ impl<'a> DataTree<'a> {
             self.raw = unsafe { ffi::lyd_first_sibling(self.raw) };
         }
 
+        // Update secondary indexes.
+        if !self.indexes.is_empty() {
+            for raw in [rnode_root, rnode] {
+                if !raw.is_null() {
+                    self.index_subtree(raw, true);
+                }
+            }
+        }
+
+        // Notify observers.
+        if !self.observers.observers.is_empty() {
+            if !rnode_root.is_null() {
+                let dnode = unsafe { DataNodeRef::from_raw(self, rnode_root) };
+                self.notify_observers(&DataEdit::Created(dnode));
+            } else if !rnode.is_null() {
+                let dnode = unsafe { DataNodeRef::from_raw(self, rnode) };
+                self.notify_observers(&DataEdit::Modified(dnode));
+            }
+        }
+
         Ok(unsafe { DataNodeRef::from_raw_opt(self.tree(), rnode) })
     }
 
+    /// Make sure the empty-typed leaf or presence container at the given path
+    /// exists, creating it (and its parents) if necessary.
+    pub fn touch(&mut self, path: &str) -> Result<()> {
+        let snode = self.context().find_path(path)?;
+        let value = match snode.kind() {
+            SchemaNodeKind::Leaf => Some(""),
+            _ => None,
+        };
+        if !is_empty_node(&snode) {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some(format!(
+                    "{} is not an empty leaf or presence container",
+                    path
+                )),
+                ..Default::default()
+            });
+        }
+
+        self.new_path(path, value, false)?;
+        Ok(())
+    }
+
     /// Remove a data node.
     pub fn remove(&mut self, path: &str) -> Result<()> {
+        let raw = self.find_path(path)?.raw;
+        if !self.observers.observers.is_empty() {
+            let dnode = unsafe { DataNodeRef::from_raw(self, raw) };
+            self.notify_observers(&DataEdit::Removed(dnode));
+        }
+        if !self.indexes.is_empty() {
+            self.index_subtree(raw, false);
+        }
+        // Update top-level sibling.
+        if raw == self.raw {
+            self.raw = unsafe { (*raw).next };
+        }
+        unsafe { ffi::lyd_free_tree(raw) };
+        Ok(())
+    }
+
+    /// Remove all data nodes, keeping the data tree itself for reuse (e.g.
+    /// when building many short-lived data trees in a row).
+    ///
+    /// The secondary indexes are kept and emptied without releasing their
+    /// memory. The data nodes themselves are freed, as libyang doesn't
+    /// support pooling their allocations.
+    pub fn clear(&mut self) {
+        unsafe { ffi::lyd_free_all(self.raw) };
+        self.raw = std::ptr::null_mut();
+        for index in &mut self.indexes {
+            index.values.clear();
+            index.instances.clear();
+        }
+    }
+
+    /// Replace the values of the leaves and leaf-lists whose schema nodes
+    /// match the given filter (e.g. passwords or keys) with the
+    /// [`REDACTED_VALUE`] placeholder, preserving the structure of the data
+    /// tree. List keys are never redacted.
+    ///
+    /// Since the placeholder might not be a valid value for the redacted
+    /// nodes, they are replaced with opaque nodes. Hence, the redacted data
+    /// tree is only meant to be printed (e.g. when exporting configurations
+    /// for troubleshooting purposes).
+    pub fn redact<F>(&mut self, filter: F) -> Result<()>
+    where
+        F: Fn(&SchemaNode<'_>) -> bool,
+    {
+        let nodes = self
+            .traverse()
+            .filter(|dnode| !dnode.is_opaque())
+            .filter(|dnode| {
+                let snode = dnode.schema();
+                matches!(
+                    snode.kind(),
+                    SchemaNodeKind::Leaf | SchemaNodeKind::LeafList
+                ) && !snode.is_list_key()
+                    && filter(&snode)
+            })
+            .map(|dnode| dnode.raw)
+            .collect::<Vec<_>>();
+
+        let value = CString::new(REDACTED_VALUE).unwrap();
+        for raw in nodes {
+            let snode = unsafe { (*raw).schema };
+            let module = unsafe { (*(*snode).module).name };
+            let mut ropaq = std::ptr::null_mut();
+            let ret = unsafe {
+                ffi::lyd_new_opaq(
+                    std::ptr::null_mut(),
+                    self.context.raw,
+                    (*snode).name,
+                    value.as_ptr(),
+                    std::ptr::null(),
+                    module,
+                    &mut ropaq,
+                )
+            };
+            if ret != ffi::LY_ERR::LY_SUCCESS {
+                return Err(Error::new(self.context));
+            }
+            let ret = unsafe { ffi::lyd_insert_after(raw, ropaq) };
+            if ret != ffi::LY_ERR::LY_SUCCESS {
+                unsafe { ffi::lyd_free_tree(ropaq) };
+                return Err(Error::new(self.context));
+            }
+
+            // Update top-level sibling.
+            if raw == self.raw {
+                self.raw = unsafe { (*raw).next };
+            }
+            unsafe { ffi::lyd_free_tree(raw) };
+        }
+        self.reindex();
+
+        Ok(())
+    }
+
+    /// Replace the identifying values of the data tree (hostnames, IP
+    /// addresses and MAC addresses) with pseudonyms valid for their types,
+    /// returning the number of replaced values.
+    ///
+    /// The same anonymizer can be used for several data trees, so that their
+    /// values are replaced consistently (see [`Anonymizer`]). Unlike
+    /// [`DataTree::redact`], the anonymized data tree remains valid.
+    pub fn anonymize(&mut self, anonymizer: &mut Anonymizer) -> Result<usize> {
+        let pseudonyms = anonymizer.tree_pseudonyms(self);
+        for (raw, pseudonym) in &pseudonyms {
+            let value = CString::new(pseudonym.as_str()).unwrap();
+            let ret = unsafe { ffi::lyd_change_term(*raw, value.as_ptr()) };
+            match ret {
+                ffi::LY_ERR::LY_SUCCESS
+                | ffi::LY_ERR::LY_EEXIST
+                | ffi::LY_ERR::LY_ENOT => (),
+                _ => return Err(Error::new(self.context)),
+            }
+        }
+        self.reindex();
+
+        Ok(pseudonyms.len())
+    }
+
+    /// Link all leafref nodes of the data tree with their targets, which is
+    /// otherwise done when the data tree is validated.
+    ///
+    /// Requires the [`ContextFlags::LEAFREF_LINKING`] context option.
+    ///
+    /// [`ContextFlags::LEAFREF_LINKING`]: crate::context::ContextFlags::LEAFREF_LINKING
+    pub fn link_leafrefs(&self) -> Result<()> {
+        let mut raw = self.raw;
+        while !raw.is_null() {
+            let ret = unsafe { ffi::lyd_leafref_link_node_tree(raw) };
+            if ret != ffi::LY_ERR::LY_SUCCESS {
+                return Err(Error::new(self.context));
+            }
+            raw = unsafe { (*raw).next };
+        }
+
+        Ok(())
+    }
+
+    /// Returns the leafref nodes that would be left dangling if the data node
+    /// identified by the given path was removed, i.e. the nodes outside its
+    /// subtree referring to nodes inside it.
+    ///
+    /// Requires leafref linking (see [`DataNodeRef::leafref_referrers`]).
+    pub fn dangling_on_remove(
+        &self,
+        path: &str,
+    ) -> Result<Vec<DataNodeRef<'_>>> {
         let dnode = self.find_path(path)?;
-        unsafe { ffi::lyd_free_tree(dnode.raw) };
+        let in_subtree = |node: &DataNodeRef<'_>| {
+            node.inclusive_ancestors()
+                .any(|ancestor| ancestor.raw == dnode.raw)
+        };
+
+        let mut referrers = Vec::new();
+        for node in dnode.traverse() {
+            for referrer in node.leafref_referrers() {
+                if !in_subtree(&referrer)
+                    && !referrers
+                        .iter()
+                        .any(|r: &DataNodeRef<'_>| r.raw == referrer.raw)
+                {
+                    referrers.push(referrer);
+                }
+            }
+        }
+
+        Ok(referrers)
+    }
+
+    /// Compute the deltas of the numeric leaves (e.g. counters) present in
+    /// both this data tree and the given newer data tree, typically two
+    /// snapshots of the same operational state.
+    ///
+    /// Leaves are matched by their data paths. Leaves present in only one of
+    /// the data trees are ignored.
+    pub fn counter_deltas(&self, newer: &DataTree<'a>) -> Vec<CounterDelta> {
+        self.traverse()
+            .filter(|dnode| {
+                !dnode.is_opaque()
+                    && dnode.schema().kind() == SchemaNodeKind::Leaf
+            })
+            .filter_map(|dnode| {
+                let old = dnode.value()?;
+                let path = dnode.path();
+                let new = newer.find_path(&path).ok()?.value()?;
+                let delta = counter_delta(&old, &new)?;
+                Some(CounterDelta {
+                    path,
+                    old,
+                    new,
+                    delta,
+                })
+            })
+            .collect()
+    }
+
+    /// Resolve many compiled paths in one pass, returning the values of the
+    /// corresponding data nodes, in the same order as the paths (`None` for
+    /// the paths that don't exist, don't identify a leaf or leaf-list, or
+    /// were compiled using another context).
+    ///
+    /// Paths sharing a common prefix (e.g. the counters of the same
+    /// interface) are resolved only once up to that prefix, using hash-based
+    /// sibling lookups.
+    pub fn collect_values(
+        &self,
+        paths: &[CompiledPath<'_>],
+    ) -> Vec<Option<DataValue>> {
+        let mut values = vec![None; paths.len()];
+
+        // Sort the paths to maximize the shared prefixes.
+        let mut order = (0..paths.len()).collect::<Vec<_>>();
+        order.sort_by(|a, b| paths[*a].path.cmp(&paths[*b].path));
+
+        let mut chain: Vec<DataNodeRef<'_>> = Vec::new();
+        let mut prev: Option<&CompiledPath<'_>> = None;
+        for idx in order {
+            let path = &paths[idx];
+            if !std::ptr::eq(path.context, self.context) {
+                continue;
+            }
+            let common = prev
+                .map(|prev| {
+                    prev.steps
+                        .iter()
+                        .zip(path.steps.iter())
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                })
+                .unwrap_or(0);
+            chain.truncate(common);
+            prev = Some(path);
+
+            for step in &path.steps[chain.len()..] {
+                let siblings = match chain.last() {
+                    Some(parent) => match parent.first_child() {
+                        Some(child) => child.raw,
+                        None => break,
+                    },
+                    None => self.raw,
+                };
+                if siblings.is_null() {
+                    break;
+                }
+
+                let (key_or_value, len) = match &step.key_or_value {
+                    Some(value) => (value.as_ptr(), value.as_bytes().len()),
+                    None => (std::ptr::null(), 0),
+                };
+                let mut rnode = std::ptr::null_mut();
+                let ret = unsafe {
+                    ffi::lyd_find_sibling_val(
+                        siblings,
+                        step.snode,
+                        key_or_value,
+                        len,
+                        &mut rnode,
+                    )
+                };
+                if ret != ffi::LY_ERR::LY_SUCCESS {
+                    break;
+                }
+                chain.push(unsafe { DataNodeRef::from_raw(self, rnode) });
+            }
+
+            if chain.len() == path.steps.len() {
+                values[idx] = chain.last().and_then(|dnode| dnode.value());
+            }
+        }
+
+        values
+    }
+
+    /// Add a secondary index of the instances of a list, keyed by the value
+    /// of one of its child leaves (e.g.
+    /// `/ietf-interfaces:interfaces/interface/type`), to speed up lookups
+    /// using [`DataTree::find_indexed`].
+    ///
+    /// Indexes are updated incrementally by [`DataTree::new_path`] and
+    /// [`DataTree::remove`], and rebuilt by the other editing methods of the
+    /// data tree. Changes made through other APIs (e.g. [`DataNodeRef`]
+    /// editing methods) require calling [`DataTree::reindex`], as the list
+    /// instances they add aren't found until then (indexed instances that
+    /// were removed or changed are never returned).
+    pub fn add_index(&mut self, leaf_path: &str) -> Result<()> {
+        let leaf = self.index_leaf(leaf_path)?;
+        if self.indexes.iter().any(|index| index.leaf == leaf) {
+            return Ok(());
+        }
+
+        self.indexes.push(DataIndex {
+            leaf,
+            values: Default::default(),
+            instances: Default::default(),
+        });
+        self.reindex();
+        Ok(())
+    }
+
+    /// Remove the secondary index of the given leaf, if any.
+    pub fn remove_index(&mut self, leaf_path: &str) -> Result<()> {
+        let leaf = self.index_leaf(leaf_path)?;
+        self.indexes.retain(|index| index.leaf != leaf);
         Ok(())
     }
 
+    /// Rebuild all secondary indexes of the data tree.
+    pub fn reindex(&mut self) {
+        if self.indexes.is_empty() {
+            return;
+        }
+
+        for index in &mut self.indexes {
+            index.values.clear();
+            index.instances.clear();
+        }
+        let mut raw = self.raw;
+        while !raw.is_null() {
+            self.index_subtree(raw, true);
+            raw = unsafe { (*raw).next };
+        }
+    }
+
+    /// Returns an iterator over the list instances whose indexed leaf has the
+    /// given canonical value, in the order of their paths.
+    ///
+    /// The index stores the paths of the list instances, which are resolved
+    /// and checked against the given value on lookup, so instances removed
+    /// or changed since the index was last updated are skipped.
+    ///
+    /// Returns an error if no index was added for the given leaf.
+    pub fn find_indexed(
+        &self,
+        leaf_path: &str,
+        value: &str,
+    ) -> Result<impl Iterator<Item = DataNodeRef<'_>>> {
+        let leaf = self.index_leaf(leaf_path)?;
+        let index = self
+            .indexes
+            .iter()
+            .find(|index| index.leaf == leaf)
+            .ok_or_else(|| Error {
+                errcode: ffi::LY_ERR::LY_ENOTFOUND,
+                msg: Some(format!("No index found for {}", leaf_path)),
+                ..Default::default()
+            })?;
+
+        let instances = index
+            .values
+            .get(value)
+            .into_iter()
+            .flatten()
+            .filter_map(|path| self.find_path(path).ok())
+            .filter(|dnode| {
+                dnode.children().any(|child| {
+                    !child.is_opaque()
+                        && unsafe { (*child.raw).schema } == leaf
+                        && child.value_canonical().as_deref() == Some(value)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(instances.into_iter())
+    }
+
+    // Resolve the schema node of an indexed leaf.
+    fn index_leaf(&self, leaf_path: &str) -> Result<*const ffi::lysc_node> {
+        let snode = self.context.find_path(leaf_path)?;
+        let is_list_leaf = snode.kind() == SchemaNodeKind::Leaf
+            && snode
+                .ancestors()
+                .find(|snode| {
+                    !matches!(
+                        snode.kind(),
+                        SchemaNodeKind::Choice | SchemaNodeKind::Case
+                    )
+                })
+                .is_some_and(|snode| snode.kind() == SchemaNodeKind::List);
+        if !is_list_leaf {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some(format!("{} is not a list leaf", leaf_path)),
+                ..Default::default()
+            });
+        }
+
+        Ok(snode.raw)
+    }
+
+    // Add (or remove) the indexed leaves of the given subtree to (or from)
+    // the secondary indexes.
+    fn index_subtree(&mut self, raw: *mut ffi::lyd_node, add: bool) {
+        let tree = DataTree::new(self.context);
+        let dnode = unsafe { DataNodeRef::from_raw(&tree, raw) };
+        for dnode in dnode.traverse().filter(|dnode| !dnode.is_opaque()) {
+            let schema = unsafe { (*dnode.raw).schema };
+            let Some(index) =
+                self.indexes.iter_mut().find(|index| index.leaf == schema)
+            else {
+                continue;
+            };
+            let Some(instance) = dnode.parent().map(|parent| parent.path())
+            else {
+                continue;
+            };
+
+            // Remove the previous value of the list instance.
+            if let Some(value) = index.instances.remove(&instance) {
+                if let Some(instances) = index.values.get_mut(&value) {
+                    instances.remove(&instance);
+                    if instances.is_empty() {
+                        index.values.remove(&value);
+                    }
+                }
+            }
+
+            if add {
+                if let Some(value) = dnode.value_canonical() {
+                    index
+                        .values
+                        .entry(value.clone())
+                        .or_default()
+                        .insert(instance.clone());
+                    index.instances.insert(instance, value);
+                }
+            }
+        }
+    }
+
     /// Fully validate the data tree.
     pub fn validate(&mut self, options: DataValidationFlags) -> Result<()> {
         let ret = unsafe {
@@ -799,9 +1878,131 @@ impl<'a> DataTree<'a> {
             return Err(Error::new(self.context));
         }
 
+        self.reindex();
+
+        Ok(())
+    }
+
+    /// Fully validate the data tree, like [`DataTree::validate`], returning
+    /// the changes made by the validation: the created default nodes and the
+    /// deleted nodes whose "when" condition is false.
+    ///
+    /// Default nodes can be stripped again afterwards using
+    /// [`DataTree::strip_defaults`].
+    pub fn validate_diff(
+        &mut self,
+        options: DataValidationFlags,
+    ) -> Result<DataDiff<'a>> {
+        let mut rdiff = std::ptr::null_mut();
+        let ret = unsafe {
+            ffi::lyd_validate_all(
+                &mut self.raw,
+                self.context.raw,
+                options.bits(),
+                &mut rdiff,
+            )
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            unsafe { ffi::lyd_free_all(rdiff) };
+            return Err(Error::new(self.context));
+        }
+
+        self.reindex();
+
+        Ok(DataDiff {
+            tree: unsafe { DataTree::from_raw(self.context, rdiff) },
+        })
+    }
+
+    /// Remove the default nodes created by the validation that produced the
+    /// given diff (see [`DataTree::validate_diff`]), keeping the ones that
+    /// were explicitly set since then.
+    pub fn strip_defaults(&mut self, diff: &DataDiff<'a>) -> Result<()> {
+        for (op, dnode) in diff.iter() {
+            if op != DataDiffOp::Create {
+                continue;
+            }
+            let path = dnode.path();
+            let is_default = match self.find_path(&path) {
+                Ok(dnode) => {
+                    (unsafe { (*dnode.raw).flags } & ffi::LYD_DEFAULT) != 0
+                }
+                // Already removed along with one of its ancestors.
+                Err(_) => false,
+            };
+            if is_default {
+                self.remove(&path)?;
+            }
+        }
+
         Ok(())
     }
 
+    /// Fully validate the data tree, like [`DataTree::validate`], but
+    /// collect all the validation errors instead of stopping at the first
+    /// one.
+    ///
+    /// Each issue of the returned report is resolved to the must or when
+    /// statement that caused it, if any. Warnings logged while validating
+    /// are reported along with the errors, and are returned in an `Ok`
+    /// report when the data tree is valid (e.g. semantic constraint
+    /// violations under [`DataValidationFlags::OPERATIONAL`]).
+    pub fn validate_report(
+        &mut self,
+        options: DataValidationFlags,
+    ) -> std::result::Result<ValidationReport, ValidationReport> {
+        // Temporarily store all errors instead of only the last one.
+        let mut log_options = ffi::LY_LOSTORE;
+        unsafe { ffi::ly_temp_log_options(&mut log_options) };
+        unsafe { ffi::ly_err_clean(self.context.raw, std::ptr::null_mut()) };
+
+        let options = options | DataValidationFlags::MULTI_ERROR;
+        let ret = unsafe {
+            ffi::lyd_validate_all(
+                &mut self.raw,
+                self.context.raw,
+                options.bits(),
+                std::ptr::null_mut(),
+            )
+        };
+        let report = ValidationReport::collect(self);
+        unsafe { ffi::ly_err_clean(self.context.raw, std::ptr::null_mut()) };
+        unsafe { ffi::ly_temp_log_options(std::ptr::null_mut()) };
+
+        self.reindex();
+
+        match ret {
+            ffi::LY_ERR::LY_SUCCESS => Ok(report),
+            _ => Err(report),
+        }
+    }
+
+    /// Validate a partial data tree (e.g. a shard of a larger data tree),
+    /// like [`DataTree::validate_report`], but without requiring the targets
+    /// of references (leafref and instance-identifier values) to exist.
+    ///
+    /// All the other constraints are validated as usual (the values are
+    /// type-checked when parsed). Partial data trees are usually parsed
+    /// using [`DataParserFlags::NO_VALIDATION`], validated using this
+    /// method, and then fully validated once assembled.
+    pub fn validate_partial(
+        &mut self,
+        options: DataValidationFlags,
+    ) -> std::result::Result<ValidationReport, ValidationReport> {
+        let mut report = match self.validate_report(options) {
+            Ok(report) => return Ok(report),
+            Err(report) => report,
+        };
+
+        report
+            .issues
+            .retain(|issue| !issue.is_unresolved_reference());
+        match report.has_errors() {
+            true => Err(report),
+            false => Ok(report),
+        }
+    }
+
     /// Create a copy of the data tree.
     pub fn duplicate<'b>(&'b self) -> Result<DataTree<'a>> {
         let mut dup = std::ptr::null_mut();
@@ -834,31 +2035,319 @@ impl<'a> DataTree<'a> {
     /// complete until validation is called on the resulting data tree (data
     /// from more cases may be present, default and non-default values).
     pub fn merge(&mut self, source: &DataTree<'_>) -> Result<()> {
+        let mut merged = Vec::new();
+
         // Special handling for empty data trees.
         if self.raw.is_null() {
             let mut new_tree = source.duplicate()?;
             self.raw = new_tree.raw;
             new_tree.raw = std::ptr::null_mut();
+            if !self.observers.observers.is_empty() {
+                merged = self.traverse().map(|dnode| dnode.raw).collect();
+            }
         } else {
             let options = 0u16;
+            let ret = if self.observers.observers.is_empty() {
+                unsafe {
+                    ffi::lyd_merge_siblings(&mut self.raw, source.raw, options)
+                }
+            } else {
+                unsafe {
+                    ffi::lyd_merge_module(
+                        &mut self.raw,
+                        source.raw,
+                        std::ptr::null(),
+                        Some(merge_collect_cb),
+                        &mut merged as *mut _ as *mut c_void,
+                        options,
+                    )
+                }
+            };
+            if ret != ffi::LY_ERR::LY_SUCCESS {
+                return Err(Error::new(self.context));
+            }
+        }
+
+        self.reindex();
+
+        // Notify observers.
+        for raw in merged {
+            let dnode = unsafe { DataNodeRef::from_raw(self, raw) };
+            self.notify_observers(&DataEdit::Merged(dnode));
+        }
+
+        Ok(())
+    }
+
+    /// Find the leaves that the given data tree sets to a different value
+    /// than this one, which [`DataTree::merge`] would silently overwrite.
+    ///
+    /// List instances are matched by their keys. Implicit default nodes
+    /// never conflict, and neither do nodes present in one tree only.
+    pub fn merge_check(&self, other: &DataTree<'a>) -> Vec<MergeConflict> {
+        let mut conflicts = Vec::new();
+        merge_check_siblings(
+            self.reference(),
+            other.reference(),
+            &mut conflicts,
+        );
+        conflicts
+    }
+
+    /// Three-way merge of the changes made to a common base data tree by two
+    /// other data trees (e.g. site overrides and operator edits on top of a
+    /// golden configuration).
+    ///
+    /// Changes made by only one side, or identically by both, are merged.
+    /// Changes made differently by both sides to the same node, or to a node
+    /// and one of its ancestors, are reported as conflicts and resolved in
+    /// favor of `ours`.
+    pub fn merge3(
+        base: &DataTree<'a>,
+        ours: &DataTree<'a>,
+        theirs: &DataTree<'a>,
+    ) -> Result<(DataTree<'a>, Vec<MergeConflict>)> {
+        let ours_diff = base.diff(ours, DataDiffFlags::DEFAULTS)?;
+        let theirs_diff = base.diff(theirs, DataDiffFlags::DEFAULTS)?;
+        let ours_changes = ours_diff
+            .iter()
+            .map(|(op, dnode)| (op, dnode.path(), dnode))
+            .collect::<Vec<_>>();
+
+        let mut merged = ours.duplicate()?;
+        let mut conflicts = Vec::new();
+        for (op, dnode) in theirs_diff.iter() {
+            let path = dnode.path();
+            let overlapping = ours_changes.iter().find(|(_, ours_path, _)| {
+                *ours_path == path
+                    || path.starts_with(&format!("{}/", ours_path))
+                    || ours_path.starts_with(&format!("{}/", path))
+            });
+            match overlapping {
+                None => {
+                    let change = theirs_diff.filter(&path)?;
+                    merged.diff_apply(&change)?;
+                }
+                Some((ours_op, ours_path, ours_dnode)) => {
+                    let identical = *ours_op == op
+                        && *ours_path == path
+                        && same_change(ours_dnode, &dnode);
+                    if identical {
+                        continue;
+                    }
+
+                    // Values are reported only for changes to the same node.
+                    let same_node = *ours_path == path;
+                    let value = |op, dnode: &DataNodeRef<'_>| match op {
+                        DataDiffOp::Delete => None,
+                        _ if !same_node => None,
+                        _ => dnode.value_canonical(),
+                    };
+                    conflicts.push(MergeConflict {
+                        path,
+                        ours: value(*ours_op, ours_dnode),
+                        theirs: value(op, &dnode),
+                    });
+                }
+            }
+        }
+
+        Ok((merged, conflicts))
+    }
+
+    /// Returns the modules required by the data tree, sorted by name.
+    ///
+    /// These include the modules of the data nodes, of the identities
+    /// referenced by identityref values and of the metadata annotations.
+    /// Compiled schema nodes don't retain their if-feature statements, hence
+    /// all the features enabled in the context are reported for each module.
+    pub fn required_modules(&self) -> Vec<ModuleCapability> {
+        let mut modules = BTreeMap::new();
+        for dnode in self.traverse() {
+            let mut raw_modules = Vec::new();
+            if !dnode.is_opaque() {
+                raw_modules.push(unsafe { (*(*dnode.raw).schema).module });
+            }
+            if let Some(identity) = dnode.identity() {
+                raw_modules.push(unsafe { (*identity.as_raw()).module });
+            }
+            for meta in dnode.meta() {
+                let annotation = unsafe { (*meta.raw).annotation };
+                if !annotation.is_null() {
+                    raw_modules.push(unsafe { (*annotation).module });
+                }
+            }
+
+            for raw in raw_modules {
+                let module =
+                    unsafe { SchemaModule::from_raw(self.context, raw) };
+                modules
+                    .entry(module.name().to_owned())
+                    .or_insert_with(|| ModuleCapability::from_module(&module));
+            }
+        }
+
+        modules.into_values().collect()
+    }
+
+    /// Split the data tree into one data tree per module.
+    ///
+    /// The top-level nodes are grouped by their owner module (see
+    /// [`DataNodeRef::owner_module`]) and duplicated into separate data trees,
+    /// keyed by module name. Top-level opaque nodes whose module can't be
+    /// resolved are skipped.
+    pub fn split_by_module(&self) -> Result<BTreeMap<String, DataTree<'a>>> {
+        let mut trees = BTreeMap::<String, DataTree<'a>>::new();
+
+        for dnode in Siblings::new(self.reference()) {
+            let module = unsafe { ffi::lyd_owner_module(dnode.raw) };
+            if module.is_null() {
+                continue;
+            }
+            let name = char_ptr_to_string(unsafe { (*module).name }, false);
+
+            let mut dup = std::ptr::null_mut();
+            let options = ffi::LYD_DUP_RECURSIVE | ffi::LYD_DUP_WITH_FLAGS;
             let ret = unsafe {
-                ffi::lyd_merge_siblings(&mut self.raw, source.raw, options)
+                ffi::lyd_dup_single(
+                    dnode.raw,
+                    std::ptr::null_mut(),
+                    options,
+                    &mut dup,
+                )
             };
             if ret != ffi::LY_ERR::LY_SUCCESS {
                 return Err(Error::new(self.context));
             }
+
+            let tree = trees
+                .entry(name)
+                .or_insert_with(|| DataTree::new(self.context));
+            unsafe { tree.insert_sibling(dup)? };
+        }
+
+        Ok(trees)
+    }
+
+    /// Move all top-level nodes of the given data trees into this data tree.
+    ///
+    /// This is the inverse operation of [`DataTree::split_by_module`]. Unlike
+    /// [`DataTree::merge`], the nodes are moved instead of duplicated, so the
+    /// source trees are expected not to overlap with each other or with this
+    /// data tree.
+    pub fn extend(
+        &mut self,
+        trees: impl IntoIterator<Item = DataTree<'a>>,
+    ) -> Result<()> {
+        for tree in trees {
+            let mut raw = tree.into_raw();
+            while !raw.is_null() {
+                let next = unsafe { (*raw).next };
+                unsafe { ffi::lyd_unlink_tree(raw) };
+                if let Err(error) = unsafe { self.insert_sibling(raw) } {
+                    unsafe { ffi::lyd_free_tree(raw) };
+                    unsafe { ffi::lyd_free_all(next) };
+                    return Err(error);
+                }
+                raw = next;
+            }
+        }
+
+        self.reindex();
+
+        Ok(())
+    }
+
+    // Insert an unlinked node as a top-level sibling of this data tree.
+    unsafe fn insert_sibling(&mut self, raw: *mut ffi::lyd_node) -> Result<()> {
+        let mut first = std::ptr::null_mut();
+        let ret = ffi::lyd_insert_sibling(self.raw, raw, &mut first);
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.context));
+        }
+        self.raw = first;
+
+        Ok(())
+    }
+
+    /// Add any missing implicit nodes. Default nodes with a false "when" are
+    /// not added.
+    pub fn add_implicit(&mut self, options: DataImplicitFlags) -> Result<()> {
+        self.add_implicit2(options, StatusFilter::empty())
+    }
+
+    /// Add any missing implicit nodes, leaving out the nodes of the given
+    /// statuses. Default nodes with a false "when" are not added.
+    pub fn add_implicit2(
+        &mut self,
+        options: DataImplicitFlags,
+        status: StatusFilter,
+    ) -> Result<()> {
+        let existing = self.existing_nodes(status);
+        let ret = unsafe {
+            ffi::lyd_new_implicit_all(
+                &mut self.raw,
+                self.context.raw,
+                options.bits(),
+                std::ptr::null_mut(),
+            )
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.context));
+        }
+
+        // Update top-level sibling.
+        self.raw = unsafe { ffi::lyd_first_sibling(self.raw) };
+
+        self.finish_implicit(&existing, status);
+
+        Ok(())
+    }
+
+    /// Add any missing implicit nodes of the given module only, leaving out
+    /// the nodes of the given statuses. Default nodes with a false "when" are
+    /// not added.
+    pub fn add_implicit_module(
+        &mut self,
+        module: &SchemaModule<'_>,
+        options: DataImplicitFlags,
+        status: StatusFilter,
+    ) -> Result<()> {
+        let existing = self.existing_nodes(status);
+        let ret = unsafe {
+            ffi::lyd_new_implicit_module(
+                &mut self.raw,
+                module.as_raw(),
+                options.bits(),
+                std::ptr::null_mut(),
+            )
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.context));
         }
 
+        // Update top-level sibling.
+        self.raw = unsafe { ffi::lyd_first_sibling(self.raw) };
+
+        self.finish_implicit(&existing, status);
+
         Ok(())
     }
 
-    /// Add any missing implicit nodes. Default nodes with a false "when" are
-    /// not added.
-    pub fn add_implicit(&mut self, options: DataImplicitFlags) -> Result<()> {
+    /// Add any missing implicit nodes in the subtree of the given data node
+    /// only, leaving out the nodes of the given statuses. Default nodes with
+    /// a false "when" are not added.
+    pub fn add_implicit_subtree(
+        &mut self,
+        path: &str,
+        options: DataImplicitFlags,
+        status: StatusFilter,
+    ) -> Result<()> {
+        let raw = self.find_path(path)?.raw;
+        let existing = self.existing_nodes(status);
         let ret = unsafe {
-            ffi::lyd_new_implicit_all(
-                &mut self.raw,
-                self.context.raw,
+            ffi::lyd_new_implicit_tree(
+                raw,
                 options.bits(),
                 std::ptr::null_mut(),
             )
@@ -867,12 +2356,70 @@ impl<'a> DataTree<'a> {
             return Err(Error::new(self.context));
         }
 
-        // Update top-level sibling.
-        self.raw = unsafe { ffi::lyd_first_sibling(self.raw) };
+        self.finish_implicit(&existing, status);
 
         Ok(())
     }
 
+    // Returns the nodes existing before adding implicit nodes, if needed to
+    // remove the implicit nodes of the filtered out statuses or to notify the
+    // observers of the added ones.
+    fn existing_nodes(
+        &self,
+        status: StatusFilter,
+    ) -> HashSet<*mut ffi::lyd_node> {
+        if status.is_empty() && self.observers.observers.is_empty() {
+            return HashSet::new();
+        }
+        self.traverse().map(|dnode| dnode.raw).collect()
+    }
+
+    // Remove the implicit nodes of the filtered out statuses, then update the
+    // secondary indexes and notify the observers of the added nodes.
+    fn finish_implicit(
+        &mut self,
+        existing: &HashSet<*mut ffi::lyd_node>,
+        status: StatusFilter,
+    ) {
+        self.remove_excluded_implicit(existing, status);
+        self.reindex();
+        self.notify_added(existing);
+    }
+
+    // Notify the observers of the roots of the subtrees that aren't part of
+    // the given nodes (collected before the edit).
+    fn notify_added(&self, existing: &HashSet<*mut ffi::lyd_node>) {
+        if self.observers.observers.is_empty() {
+            return;
+        }
+        for dnode in self.traverse() {
+            if existing.contains(&dnode.raw) {
+                continue;
+            }
+            let parent = unsafe { (*dnode.raw).parent } as *mut ffi::lyd_node;
+            if parent.is_null() || existing.contains(&parent) {
+                self.notify_observers(&DataEdit::Created(dnode));
+            }
+        }
+    }
+
+    // Remove the implicit nodes of the filtered out statuses.
+    fn remove_excluded_implicit(
+        &mut self,
+        existing: &HashSet<*mut ffi::lyd_node>,
+        status: StatusFilter,
+    ) {
+        let excluded =
+            excluded_implicit_nodes(self.traverse(), existing, status);
+        for raw in excluded {
+            // Update top-level sibling.
+            if raw == self.raw {
+                self.raw = unsafe { (*raw).next };
+            }
+            unsafe { ffi::lyd_free_tree(raw) };
+        }
+    }
+
     /// Learn the differences between 2 data trees.
     ///
     /// The resulting diff is represented as a data tree with specific metadata
@@ -916,15 +2463,254 @@ impl<'a> DataTree<'a> {
             return Err(Error::new(self.context));
         }
 
+        self.reindex();
+
+        // Notify observers.
+        if !self.observers.observers.is_empty() {
+            for (op, dnode) in diff.iter() {
+                self.notify_observers(&DataEdit::DiffApplied(op, dnode));
+            }
+        }
+
         Ok(())
     }
 
+    /// Revert the changes of the given diff, which was previously applied to
+    /// the data tree.
+    ///
+    /// This is equivalent to reversing the diff using [`DataDiff::reverse`]
+    /// and applying the result using [`DataTree::diff_apply`].
+    pub fn revert(&mut self, diff: &DataDiff<'a>) -> Result<()> {
+        let rdiff = diff.reverse()?;
+        self.diff_apply(&rdiff)
+    }
+
+    /// Evaluate the given XPath expression over the data tree, reporting the
+    /// evaluation time along with some hints on its cost (e.g. to find out
+    /// why a filter is slow).
+    pub fn explain_xpath(&self, xpath: &str) -> Result<XPathProfile> {
+        let xpath_cstr = CString::new(xpath).unwrap();
+        let mut set = std::ptr::null_mut();
+
+        let start = Instant::now();
+        let ret = unsafe {
+            ffi::lyd_find_xpath(self.raw, xpath_cstr.as_ptr(), &mut set)
+        };
+        let duration = start.elapsed();
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.context));
+        }
+        let matches = unsafe { (*set).count } as usize;
+        unsafe { ffi::ly_set_free(set, None) };
+
+        Ok(xpath_profile(self, self.raw, xpath, duration, matches))
+    }
+
+    /// Set a profiler invoked after every XPath evaluation over the data tree
+    /// (see [`Data::find_xpath`]), or remove it.
+    ///
+    /// This is a debugging facility: estimating the cost of the evaluations
+    /// requires traversing the whole data tree.
+    pub fn set_xpath_profiler<F>(&mut self, profiler: Option<F>)
+    where
+        F: Fn(&XPathProfile) + Send + Sync + 'static,
+    {
+        self.profiler.0 =
+            profiler.map(|profiler| Box::new(profiler) as XPathProfiler);
+    }
+
+    /// Register an observer invoked whenever the data tree is edited using
+    /// [`DataTree::new_path`], [`DataTree::remove`], [`DataTree::merge`] or
+    /// [`DataTree::diff_apply`] (and the methods built on top of them), with
+    /// the affected nodes.
+    ///
+    /// Edits made by other means (e.g. using [`DataNodeRef`] methods) aren't
+    /// reported.
+    pub fn add_observer<F>(&mut self, observer: F) -> DataObserverId
+    where
+        F: Fn(&DataEdit<'_>) + Send + Sync + 'static,
+    {
+        let id = DataObserverId(self.observers.next_id);
+        self.observers.next_id += 1;
+        self.observers.observers.push((id, Box::new(observer)));
+        id
+    }
+
+    /// Unregister an observer. Returns whether the observer was registered.
+    pub fn remove_observer(&mut self, id: DataObserverId) -> bool {
+        let count = self.observers.observers.len();
+        self.observers
+            .observers
+            .retain(|(observer_id, _)| *observer_id != id);
+        self.observers.observers.len() != count
+    }
+
+    // Invoke the registered observers for the given edit.
+    fn notify_observers(&self, edit: &DataEdit<'_>) {
+        for (_, observer) in &self.observers.observers {
+            observer(edit);
+        }
+    }
+
     /// Returns an iterator over all elements in the data tree and its sibling
     /// trees (depth-first search algorithm).
     pub fn traverse(&self) -> impl Iterator<Item = DataNodeRef<'_>> {
         let top = Siblings::new(self.reference());
         top.flat_map(|dnode| dnode.traverse())
     }
+
+    /// Returns all data nodes defined as `ietf-yang-schema-mount` mount
+    /// points.
+    pub fn mount_points(&self) -> Vec<DataNodeRef<'_>> {
+        self.traverse()
+            .filter(|dnode| {
+                !dnode.is_opaque() && dnode.schema().is_mount_point()
+            })
+            .collect()
+    }
+
+    /// Returns all data nodes instantiating the given schema node.
+    ///
+    /// Only the subtrees leading to the schema node are traversed, making
+    /// this more efficient than a full traversal or an equivalent XPath
+    /// query. Choices, cases, and RPC/action inputs and outputs have no
+    /// instances of their own, so none are returned for them.
+    pub fn instances_of(&self, snode: &SchemaNode<'_>) -> Vec<DataNodeRef<'_>> {
+        let is_instantiated = |snode: &SchemaNode<'_>| {
+            !matches!(
+                snode.kind(),
+                SchemaNodeKind::Choice
+                    | SchemaNodeKind::Case
+                    | SchemaNodeKind::Input
+                    | SchemaNodeKind::Output
+            )
+        };
+        if !is_instantiated(snode) {
+            return Vec::new();
+        }
+
+        // Schema nodes from the top-level one down to the given one,
+        // excluding the nodes that aren't instantiated in data trees.
+        let mut chain = snode
+            .inclusive_ancestors()
+            .filter(is_instantiated)
+            .map(|snode| snode.raw as *const ffi::lysc_node)
+            .collect::<Vec<_>>();
+        chain.reverse();
+        let Some((first, chain)) = chain.split_first() else {
+            return Vec::new();
+        };
+
+        let is_instance = |dnode: &DataNodeRef<'_>, snode| unsafe {
+            std::ptr::eq((*dnode.raw).schema, snode)
+        };
+        let mut instances = match self.reference() {
+            Some(top) => top
+                .inclusive_siblings()
+                .filter(|dnode| is_instance(dnode, *first))
+                .collect::<Vec<_>>(),
+            None => return Vec::new(),
+        };
+        for snode in chain {
+            instances = instances
+                .iter()
+                .flat_map(|dnode| dnode.children())
+                .filter(|dnode| is_instance(dnode, *snode))
+                .collect();
+        }
+
+        instances
+    }
+
+    /// Returns the operation node (RPC, action or notification) of an
+    /// operation data tree, unless there's none.
+    ///
+    /// For nested operations (actions and nested notifications), the
+    /// returned node is the operation itself, not the top-level ancestor.
+    pub fn op_node(&self) -> Option<DataNodeRef<'_>> {
+        self.traverse().find(|dnode| {
+            !dnode.is_opaque()
+                && matches!(
+                    dnode.schema().kind(),
+                    SchemaNodeKind::Rpc
+                        | SchemaNodeKind::Action
+                        | SchemaNodeKind::Notification
+                )
+        })
+    }
+
+    /// Print the data tree in a human-readable tree format, showing the
+    /// hierarchy of the data nodes along with their values. Implicit default
+    /// nodes are marked as such.
+    ///
+    /// This format is meant for debugging and display purposes only, and
+    /// can't be parsed back.
+    pub fn print_tree_string(&self) -> String {
+        let mut output = String::new();
+        for dnode in Siblings::new(self.reference()) {
+            output.push_str(&tree_label(&dnode));
+            output.push('\n');
+            print_tree_children(&mut output, &dnode, "");
+        }
+        output
+    }
+
+    /// Print the data tree (along with its siblings) to a file, compressed
+    /// using the given codec.
+    #[cfg(any(feature = "gzip", feature = "zstd"))]
+    pub fn print_compressed(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        format: DataFormat,
+        codec: CompressionCodec,
+    ) -> Result<()> {
+        let options = DataPrinterFlags::WITH_SIBLINGS;
+        let data = match format {
+            DataFormat::XML | DataFormat::JSON => {
+                self.print_string(format, options)?.into_bytes()
+            }
+            DataFormat::LYB => self.print_bytes(format, options)?,
+        };
+
+        let file = std::fs::File::create(path).map_err(io_error)?;
+        match codec {
+            #[cfg(feature = "gzip")]
+            CompressionCodec::Gzip => {
+                use std::io::Write;
+
+                let level = flate2::Compression::default();
+                let mut encoder = flate2::write::GzEncoder::new(file, level);
+                encoder.write_all(&data).map_err(io_error)?;
+                encoder.finish().map_err(io_error)?;
+            }
+            #[cfg(feature = "zstd")]
+            CompressionCodec::Zstd => {
+                zstd::stream::copy_encode(data.as_slice(), file, 0)
+                    .map_err(io_error)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns all data elements that were stored as opaque nodes due to
+    /// missing schema definitions (see [`DataParserFlags::OPAQ`]).
+    ///
+    /// Only the outermost opaque nodes are reported; their descendants are
+    /// opaque as well and are kept as part of the reported subtrees.
+    pub fn unknown_elements(&self) -> Vec<UnknownElement> {
+        self.traverse()
+            .filter(|dnode| dnode.is_opaque())
+            .filter(|dnode| {
+                !dnode.parent().is_some_and(|parent| parent.is_opaque())
+            })
+            .map(|dnode| UnknownElement {
+                path: dnode.path(),
+                name: dnode.opaque_name().unwrap().to_owned(),
+                module: dnode.opaque_module().map(str::to_owned),
+            })
+            .collect()
+    }
 }
 
 impl<'a> Data<'a> for DataTree<'a> {
@@ -945,7 +2731,13 @@ unsafe impl<'a> Binding<'a> for DataTree<'a> {
         context: &'a Context,
         raw: *mut ffi::lyd_node,
     ) -> DataTree<'a> {
-        DataTree { context, raw }
+        DataTree {
+            context,
+            raw,
+            indexes: Vec::new(),
+            observers: Default::default(),
+            profiler: Default::default(),
+        }
     }
 }
 
@@ -958,6 +2750,39 @@ impl Drop for DataTree<'_> {
     }
 }
 
+// ===== impl DataObservers =====
+
+impl std::fmt::Debug for DataObservers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.observers.iter().map(|(id, _)| id))
+            .finish()
+    }
+}
+
+// Observers are notified before a removal starts or once an edit has been
+// fully applied (secondary indexes included), and the observer list isn't
+// modified while they run. A panicking observer can only cut the remaining
+// notifications short.
+impl std::panic::RefUnwindSafe for DataObservers {}
+impl std::panic::UnwindSafe for DataObservers {}
+
+// ===== impl XPathProfilerSlot =====
+
+impl std::fmt::Debug for XPathProfilerSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("XPathProfilerSlot")
+            .field(&self.0.is_some())
+            .finish()
+    }
+}
+
+// The profiler runs once the XPath evaluation has completed, and only borrows
+// the data tree. A panicking profiler leaks the result set of the evaluation,
+// but leaves the data tree untouched.
+impl std::panic::RefUnwindSafe for XPathProfilerSlot {}
+impl std::panic::UnwindSafe for XPathProfilerSlot {}
+
 // ===== impl DataTreeOwningRef =====
 
 impl<'a> DataTreeOwningRef<'a> {
@@ -1022,14 +2847,208 @@ impl<'a> DataTreeOwningRef<'a> {
         Ok(unsafe { DataTreeOwningRef::from_raw(tree, raw) })
     }
 
+    /// Create a new action (or RPC) node based on its data path, also
+    /// creating all of its ancestor data nodes (e.g.
+    /// `/ietf-routing:routing/ribs/rib[name='default']/active-route`).
+    ///
+    /// Returns a reference to the action node, to which input or output
+    /// parameters can be added using relative paths.
+    pub fn new_action_path(context: &'a Context, path: &str) -> Result<Self> {
+        let tree = DataTreeOwningRef::new_path(context, path, None, false)?;
+        if !matches!(
+            tree.noderef().schema().kind(),
+            SchemaNodeKind::Rpc | SchemaNodeKind::Action
+        ) {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some(format!("{} is not an RPC or action", path)),
+                ..Default::default()
+            });
+        }
+
+        Ok(tree)
+    }
+
+    /// Create the reply skeleton of the referenced RPC or action: a new data
+    /// tree containing the RPC/action node and, in the case of an action, all
+    /// of its parents (including list keys).
+    ///
+    /// Returns a reference to the RPC/action node, to which output parameters
+    /// can be added using relative paths.
+    pub fn reply_template(&self) -> Result<DataTreeOwningRef<'a>> {
+        let dnode = self.noderef();
+        if dnode.is_opaque()
+            || !matches!(
+                self.schema().kind(),
+                SchemaNodeKind::Rpc | SchemaNodeKind::Action
+            )
+        {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some(format!("{} is not an RPC or action", dnode.path())),
+                ..Default::default()
+            });
+        }
+
+        DataTreeOwningRef::new_path(
+            self.tree.context,
+            &dnode.path(),
+            None,
+            true,
+        )
+    }
+
+    /// Validate the operation (RPC/action request or reply, or notification)
+    /// referenced by this node.
+    ///
+    /// References from the operation subtree to nodes outside of it (e.g.
+    /// leafrefs, instance-identifiers and must/when conditions) are resolved
+    /// in the given dependency tree (usually the running datastore), without
+    /// merging it into the operation tree.
+    pub fn validate_op(
+        &mut self,
+        dep_tree: Option<&DataTree<'a>>,
+        op: DataOperation,
+    ) -> Result<()> {
+        let dep_tree = dep_tree.map_or(std::ptr::null(), |dtree| dtree.raw);
+        let ret = unsafe {
+            ffi::lyd_validate_op(
+                self.raw,
+                dep_tree,
+                op as ffi::lyd_type::Type,
+                std::ptr::null_mut(),
+            )
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.tree.context));
+        }
+
+        Ok(())
+    }
+
+    /// Parse YANG data into an operation data tree, returning a reference
+    /// to the operation node (RPC, action or notification).
+    ///
+    /// Returns an error if the data contains no operation node.
+    pub fn parse_op_string(
+        context: &'a Context,
+        data: impl AsRef<[u8]>,
+        format: DataFormat,
+        op: DataOperation,
+    ) -> Result<DataTreeOwningRef<'a>> {
+        let (tree, raw) =
+            DataTree::_parse_op_string(CtxOrExt::C(context), data, format, op)?;
+        DataTreeOwningRef::from_op_node(tree, raw)
+    }
+
+    /// Parse YANG data from a file into an operation data tree, returning a
+    /// reference to the operation node (RPC, action or notification).
+    ///
+    /// Returns an error if the data contains no operation node.
+    #[cfg(not(target_os = "windows"))]
+    pub fn parse_op_file<F: std::os::unix::io::AsRawFd>(
+        context: &'a Context,
+        fd: F,
+        format: DataFormat,
+        op: DataOperation,
+    ) -> Result<DataTreeOwningRef<'a>> {
+        let (tree, raw) =
+            DataTree::_parse_op_fd(context, fd.as_raw_fd(), format, op)?;
+        DataTreeOwningRef::from_op_node(tree, raw)
+    }
+    #[cfg(target_os = "windows")]
+    pub fn parse_op_file(
+        context: &'a Context,
+        file: impl std::os::windows::io::AsRawHandle,
+        format: DataFormat,
+        op: DataOperation,
+    ) -> Result<DataTreeOwningRef<'a>> {
+        use libc::open_osfhandle;
+
+        let raw_handle = file.as_raw_handle();
+
+        let fd = unsafe { open_osfhandle(raw_handle as isize, 0) };
+
+        let (tree, raw) = DataTree::_parse_op_fd(context, fd, format, op)?;
+        DataTreeOwningRef::from_op_node(tree, raw)
+    }
+
+    // Returns a reference to the operation node of a parsed operation data
+    // tree, which libyang leaves unset when the data contains no operation.
+    fn from_op_node(
+        tree: DataTree<'a>,
+        op_node: *mut ffi::lyd_node,
+    ) -> Result<DataTreeOwningRef<'a>> {
+        if op_node.is_null() {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_ENOTFOUND,
+                msg: Some("No operation node found in the data".to_owned()),
+                ..Default::default()
+            });
+        }
+
+        Ok(unsafe { DataTreeOwningRef::from_raw(tree, op_node) })
+    }
+
     /// Obtain a DataNodeRef that the DataTreeOwningRef is referencing.
-    pub fn noderef(&'a self) -> DataNodeRef<'a> {
+    pub fn noderef(&self) -> DataNodeRef<'_> {
         DataNodeRef {
             tree: &self.tree,
             raw: self.raw,
         }
     }
 
+    /// Returns the data tree owning the referenced node.
+    pub fn tree(&self) -> &DataTree<'a> {
+        &self.tree
+    }
+
+    /// Returns a mutable reference to the data tree owning the referenced
+    /// node.
+    ///
+    /// # Safety
+    ///
+    /// The caller must not remove the referenced node (or any of its
+    /// ancestors) from the tree, e.g. using [`DataTree::remove`] or
+    /// [`DataTree::clear`], as the DataTreeOwningRef would be left with a
+    /// dangling reference.
+    pub unsafe fn tree_mut(&mut self) -> &mut DataTree<'a> {
+        &mut self.tree
+    }
+
+    /// Consumes the DataTreeOwningRef, returning the data tree owning the
+    /// referenced node.
+    pub fn into_tree(self) -> DataTree<'a> {
+        self.tree
+    }
+
+    /// Returns the schema node of the referenced node.
+    pub fn schema(&self) -> SchemaNode<'a> {
+        let raw = unsafe { (*self.raw).schema };
+        unsafe { SchemaNode::from_raw(self.tree.context, raw as *mut _) }
+    }
+
+    /// Generate the path of the referenced node.
+    pub fn path(&self) -> String {
+        self.noderef().path()
+    }
+
+    /// Returns the parent of the referenced node.
+    pub fn parent(&self) -> Option<DataNodeRef<'_>> {
+        self.noderef().parent()
+    }
+
+    /// Returns an iterator over the child nodes of the referenced node.
+    pub fn children(&self) -> Siblings<'_, DataNodeRef<'_>> {
+        self.noderef().children()
+    }
+
+    /// Returns an iterator over all elements in the subtree of the referenced
+    /// node (depth-first search algorithm).
+    pub fn traverse(&self) -> Traverse<'_, DataNodeRef<'_>> {
+        self.noderef().traverse()
+    }
+
     fn _parse_op(
         context: &'a Context,
         raw: *mut ffi::lyd_node,
@@ -1037,6 +3056,7 @@ impl<'a> DataTreeOwningRef<'a> {
         format: DataFormat,
         op_type: ffi::lyd_type::Type,
         op_node_ptr: *mut *mut ffi::lyd_node,
+        envelope_ptr: Option<&mut *mut ffi::lyd_node>,
     ) -> Result<()> {
         let mut opaque = std::ptr::null_mut();
         let opaque_ptr = &mut opaque;
@@ -1069,12 +3089,17 @@ impl<'a> DataTreeOwningRef<'a> {
         };
 
         unsafe { ffi::ly_in_free(ly_in, 0) };
-        unsafe { ffi::lyd_free_all(opaque) }; // Can be set on error.
 
         if ret != ffi::LY_ERR::LY_SUCCESS {
+            unsafe { ffi::lyd_free_all(opaque) }; // Can be set on error.
             return Err(Error::new(context));
         }
 
+        match envelope_ptr {
+            Some(envelope_ptr) => *envelope_ptr = opaque,
+            None => unsafe { ffi::lyd_free_all(opaque) },
+        }
+
         Ok(())
     }
 
@@ -1083,10 +3108,22 @@ impl<'a> DataTreeOwningRef<'a> {
         context: &'a Context,
         data: impl AsRef<[u8]>,
     ) -> Result<DataTreeOwningRef<'a>> {
+        Self::parse_netconf_rpc_op_envelope(context, data).map(|(tree, _)| tree)
+    }
+
+    /// Parse RPC with input args from NETCONF (i.e. in XML), also returning
+    /// its `<rpc>` envelope.
+    pub fn parse_netconf_rpc_op_envelope(
+        context: &'a Context,
+        data: impl AsRef<[u8]>,
+    ) -> Result<(DataTreeOwningRef<'a>, NetconfEnvelope<'a>)> {
         let mut tree = DataTreeOwningRef {
             tree: DataTree::new(context),
             raw: std::ptr::null_mut(),
         };
+        let mut envelope = NetconfEnvelope {
+            tree: DataTree::new(context),
+        };
 
         Self::_parse_op(
             context,
@@ -1095,9 +3132,10 @@ impl<'a> DataTreeOwningRef<'a> {
             DataFormat::XML,
             ffi::lyd_type::LYD_TYPE_RPC_NETCONF,
             &mut tree.raw,
+            Some(&mut envelope.tree.raw),
         )?;
         unsafe { tree.tree.reroot(tree.raw) };
-        Ok(tree)
+        Ok((tree, envelope))
     }
 
     /// Parse RPC REPLY with output args from NETCONF (in XML)
@@ -1105,6 +3143,19 @@ impl<'a> DataTreeOwningRef<'a> {
         &mut self,
         data: impl AsRef<[u8]>,
     ) -> Result<()> {
+        self.parse_netconf_reply_op_envelope(data).map(|_| ())
+    }
+
+    /// Parse RPC REPLY with output args from NETCONF (in XML), returning its
+    /// `<rpc-reply>` envelope.
+    pub fn parse_netconf_reply_op_envelope(
+        &mut self,
+        data: impl AsRef<[u8]>,
+    ) -> Result<NetconfEnvelope<'a>> {
+        let mut envelope = NetconfEnvelope {
+            tree: DataTree::new(self.tree.context),
+        };
+
         Self::_parse_op(
             self.tree.context,
             self.raw,
@@ -1112,7 +3163,9 @@ impl<'a> DataTreeOwningRef<'a> {
             DataFormat::XML,
             ffi::lyd_type::LYD_TYPE_REPLY_NETCONF,
             std::ptr::null_mut(),
-        )
+            Some(&mut envelope.tree.raw),
+        )?;
+        Ok(envelope)
     }
 
     /// Parse NOTIFICATION with args from NETCONF (i.e. in XML)
@@ -1120,10 +3173,23 @@ impl<'a> DataTreeOwningRef<'a> {
         context: &'a Context,
         data: impl AsRef<[u8]>,
     ) -> Result<DataTreeOwningRef<'a>> {
+        Self::parse_netconf_notif_op_envelope(context, data)
+            .map(|(tree, _)| tree)
+    }
+
+    /// Parse NOTIFICATION with args from NETCONF (i.e. in XML), also
+    /// returning its `<notification>` envelope.
+    pub fn parse_netconf_notif_op_envelope(
+        context: &'a Context,
+        data: impl AsRef<[u8]>,
+    ) -> Result<(DataTreeOwningRef<'a>, NetconfEnvelope<'a>)> {
         let mut tree = DataTreeOwningRef {
             tree: DataTree::new(context),
             raw: std::ptr::null_mut(),
         };
+        let mut envelope = NetconfEnvelope {
+            tree: DataTree::new(context),
+        };
 
         Self::_parse_op(
             context,
@@ -1132,9 +3198,10 @@ impl<'a> DataTreeOwningRef<'a> {
             DataFormat::XML,
             ffi::lyd_type::LYD_TYPE_NOTIF_NETCONF,
             &mut tree.raw,
+            Some(&mut envelope.tree.raw),
         )?;
         unsafe { tree.tree.reroot(tree.raw) };
-        Ok(tree)
+        Ok((tree, envelope))
     }
 
     /// Parse RPC with input args from RESTCONF (in JSON or XML)
@@ -1150,6 +3217,7 @@ impl<'a> DataTreeOwningRef<'a> {
             format,
             ffi::lyd_type::LYD_TYPE_RPC_RESTCONF,
             std::ptr::null_mut(),
+            None,
         )
     }
 
@@ -1166,6 +3234,7 @@ impl<'a> DataTreeOwningRef<'a> {
             format,
             ffi::lyd_type::LYD_TYPE_REPLY_RESTCONF,
             std::ptr::null_mut(),
+            None,
         )
     }
 
@@ -1188,6 +3257,7 @@ impl<'a> DataTreeOwningRef<'a> {
                 DataFormat::XML,
                 ffi::lyd_type::LYD_TYPE_NOTIF_NETCONF,
                 &mut tree.raw,
+                None,
             )?;
         } else {
             Self::_parse_op(
@@ -1197,11 +3267,69 @@ impl<'a> DataTreeOwningRef<'a> {
                 DataFormat::JSON,
                 ffi::lyd_type::LYD_TYPE_NOTIF_RESTCONF,
                 &mut tree.raw,
+                None,
             )?;
         }
         unsafe { tree.tree.reroot(tree.raw) };
         Ok(tree)
     }
+
+    /// Parse RPC with input args from NETCONF (i.e. in XML), reading the
+    /// message from the given reader until EOF.
+    pub fn parse_netconf_rpc_op_reader(
+        context: &'a Context,
+        reader: impl std::io::Read,
+    ) -> Result<DataTreeOwningRef<'a>> {
+        Self::parse_netconf_rpc_op(context, read_message(reader)?)
+    }
+
+    /// Parse RPC REPLY with output args from NETCONF (in XML), reading the
+    /// message from the given reader until EOF.
+    pub fn parse_netconf_reply_op_reader(
+        &mut self,
+        reader: impl std::io::Read,
+    ) -> Result<()> {
+        self.parse_netconf_reply_op(read_message(reader)?)
+    }
+
+    /// Parse NOTIFICATION with args from NETCONF (i.e. in XML), reading the
+    /// message from the given reader until EOF.
+    pub fn parse_netconf_notif_op_reader(
+        context: &'a Context,
+        reader: impl std::io::Read,
+    ) -> Result<DataTreeOwningRef<'a>> {
+        Self::parse_netconf_notif_op(context, read_message(reader)?)
+    }
+
+    /// Parse RPC with input args from RESTCONF (in JSON or XML), reading the
+    /// message from the given reader until EOF.
+    pub fn parse_restconf_rpc_op_reader(
+        &mut self,
+        reader: impl std::io::Read,
+        format: DataFormat,
+    ) -> Result<()> {
+        self.parse_restconf_rpc_op(read_message(reader)?, format)
+    }
+
+    /// Parse RPC REPLY with output args from RESTCONF (in JSON or XML),
+    /// reading the message from the given reader until EOF.
+    pub fn parse_restconf_reply_op_reader(
+        &mut self,
+        reader: impl std::io::Read,
+        format: DataFormat,
+    ) -> Result<()> {
+        self.parse_restconf_reply_op(read_message(reader)?, format)
+    }
+
+    /// Parse NOTIFICATION with args from RESTCONF (in either JSON or XML),
+    /// reading the message from the given reader until EOF.
+    pub fn parse_restconf_notif_op_reader(
+        context: &'a Context,
+        reader: impl std::io::Read,
+        format: DataFormat,
+    ) -> Result<DataTreeOwningRef<'a>> {
+        Self::parse_restconf_notif_op(context, read_message(reader)?, format)
+    }
 }
 
 impl<'a> From<DataTree<'a>> for DataTreeOwningRef<'a> {
@@ -1242,6 +3370,61 @@ impl<'a> DataNodeRef<'a> {
         self.raw
     }
 
+    /// Returns whether this is an opaque node, i.e. a node without a schema
+    /// definition.
+    ///
+    /// Opaque nodes have no schema, so [`DataNodeRef::schema`] must not be
+    /// called on them.
+    pub fn is_opaque(&self) -> bool {
+        unsafe { (*self.raw).schema.is_null() }
+    }
+
+    /// Name of the opaque node.
+    pub fn opaque_name(&self) -> Option<&str> {
+        if !self.is_opaque() {
+            return None;
+        }
+        let ropaq = self.raw as *mut ffi::lyd_node_opaq;
+        char_ptr_to_opt_str(unsafe { (*ropaq).name.name })
+    }
+
+    /// Module of the opaque node. Depending on the format the node was parsed
+    /// from, this is either the module name (JSON) or its namespace (XML).
+    pub fn opaque_module(&self) -> Option<&str> {
+        if !self.is_opaque() {
+            return None;
+        }
+        let ropaq = self.raw as *mut ffi::lyd_node_opaq;
+        let rmodule = unsafe { (*ropaq).name.__bindgen_anon_1.module_name };
+        char_ptr_to_opt_str(rmodule)
+    }
+
+    /// Value of the opaque node.
+    pub fn opaque_value(&self) -> Option<&str> {
+        if !self.is_opaque() {
+            return None;
+        }
+        let ropaq = self.raw as *mut ffi::lyd_node_opaq;
+        char_ptr_to_opt_str(unsafe { (*ropaq).value })
+    }
+
+    /// Value of the given attribute of the opaque node, if present.
+    pub fn opaque_attr(&self, name: &str) -> Option<&str> {
+        if !self.is_opaque() {
+            return None;
+        }
+        let ropaq = self.raw as *mut ffi::lyd_node_opaq;
+        let mut rattr = unsafe { (*ropaq).attr };
+        while !rattr.is_null() {
+            let attr_name = char_ptr_to_opt_str(unsafe { (*rattr).name.name });
+            if attr_name == Some(name) {
+                return char_ptr_to_opt_str(unsafe { (*rattr).value });
+            }
+            rattr = unsafe { (*rattr).next };
+        }
+        None
+    }
+
     /// Schema definition of this node.
     pub fn schema(&self) -> SchemaNode<'_> {
         let raw = unsafe { (*self.raw).schema };
@@ -1267,6 +3450,11 @@ impl<'a> DataNodeRef<'a> {
         Ancestors::new(Some(self.clone()))
     }
 
+    /// Returns the data tree this node belongs to.
+    pub(crate) fn owner(&self) -> &'a DataTree<'a> {
+        self.tree
+    }
+
     /// Returns an iterator over the sibling data nodes.
     pub fn siblings(&self) -> Siblings<'a, DataNodeRef<'a>> {
         let sibling = self.next_sibling();
@@ -1295,6 +3483,242 @@ impl<'a> DataNodeRef<'a> {
         self.children().filter(|dnode| dnode.schema().is_list_key())
     }
 
+    /// Returns an iterator over all instances of the list this node is an
+    /// instance of, including itself.
+    pub fn list_instances(&self) -> impl Iterator<Item = DataNodeRef<'a>> {
+        let schema = unsafe { (*self.raw).schema };
+        let first = unsafe { ffi::lyd_first_sibling(self.raw) };
+        let first = unsafe { DataNodeRef::from_raw(self.tree, first) };
+        first
+            .inclusive_siblings()
+            .filter(move |dnode| unsafe { (*dnode.raw).schema } == schema)
+    }
+
+    /// Print each instance of the list this node is an instance of as an
+    /// independent document.
+    ///
+    /// JSON instances are printed in the JSON Lines (NDJSON) format, one
+    /// instance per line. XML instances are printed as XML fragments, also
+    /// separated by newlines. The LYB format, whose documents can't be told
+    /// apart once concatenated, isn't supported.
+    pub fn print_list_entries(
+        &self,
+        mut writer: impl std::io::Write,
+        format: DataFormat,
+    ) -> Result<()> {
+        if self.is_opaque() || self.schema().kind() != SchemaNodeKind::List {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some("Data node is not a list instance".to_owned()),
+                ..Default::default()
+            });
+        }
+        if format == DataFormat::LYB {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some(
+                    "List entries can't be printed in the LYB format"
+                        .to_owned(),
+                ),
+                ..Default::default()
+            });
+        }
+
+        for dnode in self.list_instances() {
+            let options = match format {
+                DataFormat::JSON => DataPrinterFlags::SHRINK,
+                _ => DataPrinterFlags::empty(),
+            };
+            let mut bytes = dnode.print_bytes(format, options)?;
+            // Remove the null terminator and separate the documents.
+            bytes.pop();
+            if bytes.last() != Some(&b'\n') {
+                bytes.push(b'\n');
+            }
+            writer.write_all(&bytes).map_err(io_error)?;
+        }
+
+        Ok(())
+    }
+
+    /// Duplicate this node (along with its parents) keeping only a window of
+    /// the instances of the given child list.
+    ///
+    /// The list instances are optionally sorted by the canonical value of the
+    /// `sort_key` child leaf (compared as strings) before `offset` instances
+    /// are skipped and at most `limit` instances are kept. Other children of
+    /// this node are not duplicated.
+    pub fn list_page(
+        &self,
+        schema_list: &SchemaNode<'_>,
+        offset: usize,
+        limit: Option<usize>,
+        sort_key: Option<&str>,
+    ) -> Result<DataTree<'a>> {
+        let mut instances = self
+            .children()
+            .filter(|dnode| {
+                std::ptr::eq(unsafe { (*dnode.raw).schema }, schema_list.raw)
+            })
+            .collect::<Vec<_>>();
+        if let Some(sort_key) = sort_key {
+            instances.sort_by_cached_key(|dnode| {
+                dnode
+                    .children()
+                    .find(|child| {
+                        !child.is_opaque() && child.schema().name() == sort_key
+                    })
+                    .and_then(|child| child.value_canonical())
+            });
+        }
+
+        // Duplicate this node and its parents, without children.
+        let mut dup = std::ptr::null_mut();
+        let options = ffi::LYD_DUP_WITH_PARENTS | ffi::LYD_DUP_WITH_FLAGS;
+        let ret = unsafe {
+            ffi::lyd_dup_single(
+                self.raw,
+                std::ptr::null_mut(),
+                options,
+                &mut dup,
+            )
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.context()));
+        }
+        let mut tree = DataTree::new(self.tree.context);
+        unsafe { tree.reroot(dup) };
+
+        // Duplicate the requested list instances.
+        let options = ffi::LYD_DUP_RECURSIVE | ffi::LYD_DUP_WITH_FLAGS;
+        for dnode in instances
+            .iter()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+        {
+            let ret = unsafe {
+                ffi::lyd_dup_single(
+                    dnode.raw,
+                    dup as *mut ffi::lyd_node_inner,
+                    options,
+                    std::ptr::null_mut(),
+                )
+            };
+            if ret != ffi::LY_ERR::LY_SUCCESS {
+                return Err(Error::new(self.context()));
+            }
+        }
+
+        Ok(tree)
+    }
+
+    /// Returns the leafref nodes referring to this node.
+    ///
+    /// Requires the [`ContextFlags::LEAFREF_LINKING`] context option, and the
+    /// data tree to be validated (or linked using
+    /// [`DataTree::link_leafrefs`]).
+    ///
+    /// [`ContextFlags::LEAFREF_LINKING`]: crate::context::ContextFlags::LEAFREF_LINKING
+    pub fn leafref_referrers(&self) -> Vec<DataNodeRef<'a>> {
+        self.leafref_links(|rec| rec.leafref_nodes)
+    }
+
+    /// Returns the target nodes of this leafref node.
+    ///
+    /// Requires the [`ContextFlags::LEAFREF_LINKING`] context option, and the
+    /// data tree to be validated (or linked using
+    /// [`DataTree::link_leafrefs`]).
+    ///
+    /// [`ContextFlags::LEAFREF_LINKING`]: crate::context::ContextFlags::LEAFREF_LINKING
+    pub fn leafref_targets(&self) -> Vec<DataNodeRef<'a>> {
+        self.leafref_links(|rec| rec.target_nodes)
+    }
+
+    /// Returns the first target node of this leafref node, if any.
+    ///
+    /// Same requirements as [`DataNodeRef::leafref_targets`].
+    pub fn leafref_target(&self) -> Option<DataNodeRef<'a>> {
+        self.leafref_targets().into_iter().next()
+    }
+
+    /// Link all leafref nodes of this subtree with their targets, e.g. after
+    /// editing part of a large data tree, without relinking the whole tree.
+    ///
+    /// Requires the [`ContextFlags::LEAFREF_LINKING`] context option.
+    ///
+    /// [`ContextFlags::LEAFREF_LINKING`]: crate::context::ContextFlags::LEAFREF_LINKING
+    pub fn link_leafrefs(&self) -> Result<()> {
+        let ret = unsafe { ffi::lyd_leafref_link_node_tree(self.raw) };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.context()));
+        }
+
+        Ok(())
+    }
+
+    fn leafref_links(
+        &self,
+        nodes: impl Fn(
+            &ffi::lyd_leafref_links_rec,
+        ) -> *mut *const ffi::lyd_node_term,
+    ) -> Vec<DataNodeRef<'a>> {
+        if self.is_opaque() {
+            return Vec::new();
+        }
+
+        let mut rec = std::ptr::null();
+        let ret = unsafe {
+            ffi::lyd_leafref_get_links(self.raw as *const _, &mut rec)
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS || rec.is_null() {
+            return Vec::new();
+        }
+
+        let array = nodes(unsafe { &*rec });
+        unsafe { ly_array_slice(array) }
+            .iter()
+            .map(|dnode| unsafe {
+                DataNodeRef::from_raw(self.tree, *dnode as *mut _)
+            })
+            .collect()
+    }
+
+    /// Returns the must and when constraints of this node that failed the
+    /// last validation, along with their error-message and error-app-tag.
+    ///
+    /// The failures are correlated to the node using the errors stored in
+    /// the context, so only the last error is taken into account unless
+    /// libyang was set to store all of them (e.g. with
+    /// [`DataValidationFlags::MULTI_ERROR`] and the `LY_LOSTORE` log option).
+    pub fn failed_constraints(&self) -> Vec<ValidationConstraint> {
+        if self.is_opaque() {
+            return Vec::new();
+        }
+
+        let path = self.path();
+        let snode = self.schema();
+        let mut constraints = Vec::new();
+        let mut error = unsafe { ffi::ly_err_first(self.context().raw) };
+        while !error.is_null() {
+            let item = unsafe { &*error };
+            error = item.next;
+            if item.level != ffi::LY_LOG_LEVEL::LY_LLERR
+                || char_ptr_to_opt_str(item.data_path) != Some(path.as_str())
+            {
+                continue;
+            }
+
+            let msg = char_ptr_to_opt_str(item.msg).unwrap_or_default();
+            if let Some(constraint) = find_constraint(&snode, msg) {
+                if !constraints.contains(&constraint) {
+                    constraints.push(constraint);
+                }
+            }
+        }
+
+        constraints
+    }
+
     /// Returns an iterator over all metadata associated to this node.
     pub fn meta(&self) -> MetadataList<'_> {
         let rmeta = unsafe { (*self.raw).meta };
@@ -1317,6 +3741,21 @@ impl<'a> DataNodeRef<'a> {
         char_ptr_to_string(buf.as_ptr(), false)
     }
 
+    /// Write the path of the node to the given writer, in the same format as
+    /// [`DataNodeRef::path`] but without any intermediate allocation.
+    pub fn write_path(
+        &self,
+        writer: &mut impl std::fmt::Write,
+    ) -> std::fmt::Result {
+        // Opaque nodes have no schema information, so let libyang handle
+        // them.
+        if self.inclusive_ancestors().any(|dnode| dnode.is_opaque()) {
+            return writer.write_str(&self.path());
+        }
+
+        write_path_segments(self, writer)
+    }
+
     /// Node's value (canonical string representation).
     pub fn value_canonical(&self) -> Option<String> {
         match self.schema().kind() {
@@ -1351,6 +3790,142 @@ impl<'a> DataNodeRef<'a> {
         }
     }
 
+    /// Returns the value of a boolean leaf or leaf-list.
+    ///
+    /// An error is returned if the node isn't of the boolean type.
+    pub fn as_bool(&self) -> Result<bool> {
+        match self.member_value() {
+            Some(DataValue::Bool(value)) => Ok(value),
+            _ => Err(self.type_mismatch("boolean")),
+        }
+    }
+
+    /// Returns the value of an unsigned integer leaf or leaf-list, of any
+    /// size.
+    ///
+    /// An error is returned if the node isn't of an unsigned integer type.
+    pub fn as_u64(&self) -> Result<u64> {
+        match self.member_value() {
+            Some(DataValue::Uint8(value)) => Ok(value.into()),
+            Some(DataValue::Uint16(value)) => Ok(value.into()),
+            Some(DataValue::Uint32(value)) => Ok(value.into()),
+            Some(DataValue::Uint64(value)) => Ok(value),
+            _ => Err(self.type_mismatch("unsigned integer")),
+        }
+    }
+
+    /// Returns the value of an integer leaf or leaf-list, of any size.
+    /// Unsigned integers are accepted as long as their type fits in an
+    /// `i64`.
+    ///
+    /// An error is returned if the node isn't of an integer type.
+    pub fn as_i64(&self) -> Result<i64> {
+        match self.member_value() {
+            Some(DataValue::Int8(value)) => Ok(value.into()),
+            Some(DataValue::Int16(value)) => Ok(value.into()),
+            Some(DataValue::Int32(value)) => Ok(value.into()),
+            Some(DataValue::Int64(value)) => Ok(value),
+            Some(DataValue::Uint8(value)) => Ok(value.into()),
+            Some(DataValue::Uint16(value)) => Ok(value.into()),
+            Some(DataValue::Uint32(value)) => Ok(value.into()),
+            _ => Err(self.type_mismatch("integer")),
+        }
+    }
+
+    /// Returns the value of a string leaf or leaf-list.
+    ///
+    /// An error is returned if the node isn't of the string type.
+    pub fn as_str(&self) -> Result<&str> {
+        let is_string = self.term_value().is_some_and(|value| unsafe {
+            (*(*value).realtype).basetype == ffi::LY_DATA_TYPE::LY_TYPE_STRING
+        });
+        if !is_string {
+            return Err(self.type_mismatch("string"));
+        }
+
+        let rnode = self.raw as *mut ffi::lyd_node_term;
+        let mut value = unsafe { (*rnode).value._canonical };
+        if value.is_null() {
+            value = unsafe {
+                ffi::lyd_value_get_canonical(
+                    self.context().raw,
+                    &(*rnode).value,
+                )
+            };
+        }
+        Ok(char_ptr_to_str(value))
+    }
+
+    /// Returns the identity referenced by the value of an identityref leaf
+    /// or leaf-list (also when part of a union).
+    pub fn identity(&self) -> Option<SchemaIdentity<'a>> {
+        let value = self.term_value()?;
+        unsafe {
+            if (*(*value).realtype).basetype != ffi::LY_DATA_TYPE::LY_TYPE_IDENT
+            {
+                return None;
+            }
+            let ident = (*value).__bindgen_anon_1.ident;
+            Some(SchemaIdentity::from_raw(self.tree.context, ident))
+        }
+    }
+
+    /// Set the value of an identityref leaf or leaf-list to the given
+    /// identity.
+    pub fn set_identity(
+        &mut self,
+        identity: &SchemaIdentity<'_>,
+    ) -> Result<()> {
+        self.change_term(&identity.qualified_name())
+    }
+
+    /// Returns the names of the bits set in the value of a bits leaf or
+    /// leaf-list (also when part of a union), in canonical (position) order.
+    pub fn bits_set(&self) -> Option<Vec<&str>> {
+        let value = self.term_value()?;
+        unsafe {
+            if (*(*value).realtype).basetype != ffi::LY_DATA_TYPE::LY_TYPE_BITS
+            {
+                return None;
+            }
+            let bits = (*value).__bindgen_anon_1.fixed_mem.as_ptr()
+                as *const ffi::lyd_value_bits;
+            let bits = ly_array_slice((*bits).items)
+                .iter()
+                .map(|item| char_ptr_to_str((**item).name))
+                .collect();
+            Some(bits)
+        }
+    }
+
+    /// Set the value of a bits leaf or leaf-list to the given set of bits,
+    /// given in any order.
+    pub fn set_bits(&mut self, bits: &[&str]) -> Result<()> {
+        self.change_term(&bits.join(" "))
+    }
+
+    /// Returns the decoded value of a binary leaf or leaf-list (also when part
+    /// of a union).
+    pub fn value_bytes(&self) -> Option<&[u8]> {
+        let value = self.term_value()?;
+        unsafe {
+            if (*(*value).realtype).basetype
+                != ffi::LY_DATA_TYPE::LY_TYPE_BINARY
+            {
+                return None;
+            }
+            let binary = (*value).__bindgen_anon_1.fixed_mem.as_ptr()
+                as *const ffi::lyd_value_binary;
+            if (*binary).size == 0 {
+                return Some(&[]);
+            }
+            Some(slice::from_raw_parts(
+                (*binary).data as *const u8,
+                (*binary).size,
+            ))
+        }
+    }
+
     /// Check whether a node value equals to its default one.
     pub fn is_default(&self) -> bool {
         match self.schema().kind() {
@@ -1537,33 +4112,162 @@ impl<'a> DataNodeRef<'a> {
         Ok(unsafe { DataNodeRef::from_raw(self.tree, rnode) })
     }
 
-    /// Create a new term node in the data tree.
-    pub fn new_term(
+    /// Create a new term node in the data tree.
+    pub fn new_term(
+        &mut self,
+        module: Option<&SchemaModule<'_>>,
+        name: &str,
+        value: Option<&str>,
+    ) -> Result<()> {
+        let name_cstr = CString::new(name).unwrap();
+        let value_cstr;
+        let options = 0;
+
+        let value_ptr = match value {
+            Some(value) => {
+                value_cstr = CString::new(value).unwrap();
+                value_cstr.as_ptr()
+            }
+            None => std::ptr::null(),
+        };
+
+        let ret = unsafe {
+            ffi::lyd_new_term(
+                self.raw(),
+                module
+                    .map(|module| module.as_raw())
+                    .unwrap_or(std::ptr::null_mut()),
+                name_cstr.as_ptr(),
+                value_ptr,
+                options,
+                std::ptr::null_mut(),
+            )
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(self.context()));
+        }
+
+        Ok(())
+    }
+
+    // Returns the value of a leaf or leaf-list, resolving union values to the
+    // value of their matching member type.
+    fn term_value(&self) -> Option<*const ffi::lyd_value> {
+        if self.is_opaque()
+            || !matches!(
+                self.schema().kind(),
+                SchemaNodeKind::Leaf | SchemaNodeKind::LeafList
+            )
+        {
+            return None;
+        }
+
+        let rnode = self.raw as *const ffi::lyd_node_term;
+        let mut value = unsafe { &(*rnode).value as *const ffi::lyd_value };
+        while unsafe { (*(*value).realtype).basetype }
+            == ffi::LY_DATA_TYPE::LY_TYPE_UNION
+        {
+            value = unsafe { &(*(*value).__bindgen_anon_1.subvalue).value };
+        }
+        Some(value)
+    }
+
+    // Returns the typed value of a leaf or leaf-list, resolving union values to
+    // the value of their matching member type.
+    fn member_value(&self) -> Option<DataValue> {
+        let value = self.term_value()?;
+        Some(unsafe { DataValue::from_raw(self.tree.context, value) })
+    }
+
+    fn type_mismatch(&self, expected: &str) -> Error {
+        Error {
+            errcode: ffi::LY_ERR::LY_EINVAL,
+            msg: Some(format!(
+                "{} is not of the {} type",
+                self.path(),
+                expected
+            )),
+            path: Some(self.path()),
+            ..Default::default()
+        }
+    }
+
+    // Change the value of a leaf or leaf-list (JSON format).
+    fn change_term(&mut self, value: &str) -> Result<()> {
+        let value = CString::new(value).unwrap();
+        let ret = unsafe { ffi::lyd_change_term(self.raw, value.as_ptr()) };
+        match ret {
+            // The value didn't change or only its default flag was cleared.
+            ffi::LY_ERR::LY_SUCCESS
+            | ffi::LY_ERR::LY_EEXIST
+            | ffi::LY_ERR::LY_ENOT => Ok(()),
+            _ => Err(Error::new(self.context())),
+        }
+    }
+
+    /// Make sure the given empty-typed leaf or presence container exists in
+    /// the current context node, creating it if necessary.
+    pub fn set_empty(
+        &mut self,
+        module: Option<&SchemaModule<'_>>,
+        name: &str,
+    ) -> Result<()> {
+        let snode = match self.schema().child(module, name) {
+            Some(snode) if is_empty_node(&snode) => snode,
+            _ => {
+                return Err(Error {
+                    errcode: ffi::LY_ERR::LY_EINVAL,
+                    msg: Some(format!(
+                        "{} is not an empty leaf or presence container",
+                        name
+                    )),
+                    ..Default::default()
+                })
+            }
+        };
+        if self
+            .children()
+            .any(|dnode| unsafe { (*dnode.raw).schema } == snode.raw)
+        {
+            return Ok(());
+        }
+
+        match snode.kind() {
+            SchemaNodeKind::Leaf => self.new_term(module, name, None),
+            _ => self.new_inner(module, name).map(|_| ()),
+        }
+    }
+
+    /// Create a new binary term node in the current context node, encoding the
+    /// given value in base64.
+    pub fn new_term_bytes(
+        &mut self,
+        module: Option<&SchemaModule<'_>>,
+        name: &str,
+        value: &[u8],
+    ) -> Result<()> {
+        self.new_term(module, name, Some(&base64_encode(value)))
+    }
+
+    /// Create a new metadata instance of the given annotation and attach it
+    /// to the data node.
+    pub fn new_meta(
         &mut self,
-        module: Option<&SchemaModule<'_>>,
+        module: &SchemaModule<'_>,
         name: &str,
-        value: Option<&str>,
+        value: &str,
     ) -> Result<()> {
         let name_cstr = CString::new(name).unwrap();
-        let value_cstr;
+        let value_cstr = CString::new(value).unwrap();
         let options = 0;
 
-        let value_ptr = match value {
-            Some(value) => {
-                value_cstr = CString::new(value).unwrap();
-                value_cstr.as_ptr()
-            }
-            None => std::ptr::null(),
-        };
-
         let ret = unsafe {
-            ffi::lyd_new_term(
+            ffi::lyd_new_meta(
+                std::ptr::null(),
                 self.raw(),
-                module
-                    .map(|module| module.as_raw())
-                    .unwrap_or(std::ptr::null_mut()),
+                module.as_raw(),
                 name_cstr.as_ptr(),
-                value_ptr,
+                value_cstr.as_ptr(),
                 options,
                 std::ptr::null_mut(),
             )
@@ -1575,6 +4279,71 @@ impl<'a> DataNodeRef<'a> {
         Ok(())
     }
 
+    /// Find the metadata instance of the given annotation.
+    pub fn find_meta(
+        &self,
+        module: &SchemaModule<'_>,
+        name: &str,
+    ) -> Option<Metadata<'_>> {
+        let name_cstr = CString::new(name).unwrap();
+        let rmeta = unsafe {
+            ffi::lyd_find_meta(
+                (*self.raw).meta,
+                module.as_raw(),
+                name_cstr.as_ptr(),
+            )
+        };
+        unsafe { Metadata::from_raw_opt(self, rmeta) }
+    }
+
+    /// Set the metadata of the given annotation, looking up the module
+    /// defining it by name (e.g. `ietf-origin`), and replacing any existing
+    /// metadata instance of the same annotation.
+    pub fn annotate(
+        &mut self,
+        module_name: &str,
+        name: &str,
+        value: &str,
+    ) -> Result<()> {
+        let module = self.annotation_module(module_name)?;
+        if let Some(meta) = self.find_meta(&module, name) {
+            unsafe { ffi::lyd_free_meta_single(meta.raw) };
+        }
+        self.new_meta(&module, name, value)
+    }
+
+    /// Set the `last-modified` metadata defined by the given module to the
+    /// given time, formatted as a `date-and-time` value in UTC.
+    pub fn annotate_last_modified(
+        &mut self,
+        module_name: &str,
+        now: SystemTime,
+    ) -> Result<()> {
+        self.annotate(module_name, "last-modified", &date_and_time(now))
+    }
+
+    /// Returns the value of the `last-modified` metadata defined by the given
+    /// module, if present.
+    pub fn last_modified(&self, module_name: &str) -> Option<String> {
+        let module = self.annotation_module(module_name).ok()?;
+        self.find_meta(&module, "last-modified")
+            .map(|meta| meta.value().to_owned())
+    }
+
+    fn annotation_module(&self, module_name: &str) -> Result<SchemaModule<'a>> {
+        let context = self.tree.context;
+        context
+            .get_module_implemented(module_name)
+            .ok_or_else(|| Error {
+                errcode: ffi::LY_ERR::LY_ENOTFOUND,
+                msg: Some(format!(
+                    "Module \"{}\" not implemented",
+                    module_name
+                )),
+                ..Default::default()
+            })
+    }
+
     /// Remove the data node.
     pub fn remove(&mut self) {
         unsafe { ffi::lyd_unlink_tree(self.raw()) };
@@ -1715,6 +4484,544 @@ impl PartialEq for Metadata<'_> {
 unsafe impl Send for Metadata<'_> {}
 unsafe impl Sync for Metadata<'_> {}
 
+// ===== impl NetconfEnvelope =====
+
+impl<'a> NetconfEnvelope<'a> {
+    /// Returns the opaque data tree of the envelope.
+    pub fn tree(&self) -> &DataTree<'a> {
+        &self.tree
+    }
+
+    /// Returns the `message-id` attribute of the `<rpc>` or `<rpc-reply>`
+    /// envelope, if present.
+    pub fn message_id(&self) -> Option<String> {
+        self.tree
+            .reference()
+            .and_then(|dnode| dnode.opaque_attr("message-id").map(String::from))
+    }
+
+    /// Returns the `eventTime` of the `<notification>` envelope, if present.
+    pub fn event_time(&self) -> Option<String> {
+        self.tree.reference().and_then(|dnode| {
+            dnode
+                .children()
+                .find(|dnode| dnode.opaque_name() == Some("eventTime"))
+                .and_then(|dnode| dnode.opaque_value().map(String::from))
+        })
+    }
+}
+
+// Build the profile of an XPath evaluation, bounding the number of visited
+// nodes by the instances of the schema nodes accessed by the expression.
+fn xpath_profile(
+    dtree: &DataTree<'_>,
+    ctx_node: *mut ffi::lyd_node,
+    xpath: &str,
+    duration: Duration,
+    matches: usize,
+) -> XPathProfile {
+    let xpath_cstr = CString::new(xpath).unwrap();
+    let ctx_snode = match ctx_node.is_null() {
+        true => std::ptr::null(),
+        false => unsafe { (*ctx_node).schema },
+    };
+
+    let mut atoms = HashSet::new();
+    let mut set = std::ptr::null_mut();
+    let ret = unsafe {
+        ffi::lys_find_xpath_atoms(
+            dtree.context.raw,
+            ctx_snode,
+            xpath_cstr.as_ptr(),
+            0,
+            &mut set,
+        )
+    };
+    if ret == ffi::LY_ERR::LY_SUCCESS {
+        let count = unsafe { (*set).count } as usize;
+        if count > 0 {
+            let snodes = unsafe {
+                slice::from_raw_parts((*set).__bindgen_anon_1.snodes, count)
+            };
+            atoms.extend(snodes.iter().map(|snode| *snode as *const _));
+        }
+        unsafe { ffi::ly_set_free(set, None) };
+    }
+    let max_nodes_visited = dtree
+        .traverse()
+        .filter(|dnode| {
+            atoms.contains(&(unsafe { (*dnode.raw).schema } as *const _))
+        })
+        .count();
+
+    XPathProfile {
+        xpath: xpath.to_owned(),
+        duration,
+        matches,
+        max_nodes_visited,
+        key_hash_lookup_possible: CompiledPath::new(dtree.context, xpath)
+            .is_ok(),
+    }
+}
+
+// Collect the target nodes reported by `lyd_merge_module()`.
+unsafe extern "C" fn merge_collect_cb(
+    trg_node: *mut ffi::lyd_node,
+    _src_node: *const ffi::lyd_node,
+    cb_data: *mut c_void,
+) -> ffi::LY_ERR::Type {
+    let merged = &mut *(cb_data as *mut Vec<*mut ffi::lyd_node>);
+    merged.push(trg_node);
+    ffi::LY_ERR::LY_SUCCESS
+}
+
+// Write the path of the given (non-opaque) data node, starting from its
+// top-level ancestor. Mirrors `lyd_path()` with the `LYD_PATH_STD` type.
+fn write_path_segments(
+    dnode: &DataNodeRef<'_>,
+    writer: &mut impl std::fmt::Write,
+) -> std::fmt::Result {
+    let parent = dnode.parent();
+    if let Some(parent) = &parent {
+        write_path_segments(parent, writer)?;
+    }
+
+    let snode = dnode.schema();
+    let module = snode.module();
+    let same_module = parent.is_some_and(|parent| {
+        parent.schema().module().as_raw() == module.as_raw()
+    });
+    if same_module {
+        write!(writer, "/{}", snode.name())?;
+    } else {
+        write!(writer, "/{}:{}", module.name(), snode.name())?;
+    }
+
+    match snode.kind() {
+        SchemaNodeKind::List if !snode.is_keyless_list() => {
+            for key in dnode.children().take_while(|child| {
+                !child.is_opaque() && child.schema().is_list_key()
+            }) {
+                write!(writer, "[{}=", key.schema().name())?;
+                write_path_value(&key, writer)?;
+                writer.write_char(']')?;
+            }
+        }
+        SchemaNodeKind::LeafList if snode.is_config() => {
+            writer.write_str("[.=")?;
+            write_path_value(dnode, writer)?;
+            writer.write_char(']')?;
+        }
+        SchemaNodeKind::List | SchemaNodeKind::LeafList => {
+            // Position among the instances of the same list or leaf-list.
+            let first = unsafe { ffi::lyd_first_sibling(dnode.raw) };
+            let first = unsafe { DataNodeRef::from_raw(dnode.tree, first) };
+            let position = first
+                .inclusive_siblings()
+                .take_while(|sibling| sibling.raw != dnode.raw)
+                .filter(|sibling| unsafe { (*sibling.raw).schema } == snode.as_raw())
+                .count()
+                + 1;
+            write!(writer, "[{}]", position)?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// Write the quoted canonical value of the given term node.
+fn write_path_value(
+    dnode: &DataNodeRef<'_>,
+    writer: &mut impl std::fmt::Write,
+) -> std::fmt::Result {
+    let rnode = dnode.raw as *mut ffi::lyd_node_term;
+    let mut value = unsafe { (*rnode).value._canonical };
+    if value.is_null() {
+        value = unsafe {
+            ffi::lyd_value_get_canonical(dnode.context().raw, &(*rnode).value)
+        };
+    }
+    let value = char_ptr_to_str(value);
+    let quote = if value.contains('\'') { '"' } else { '\'' };
+    write!(writer, "{}{}{}", quote, value, quote)
+}
+
+// Returns the outermost nodes that were added (i.e. not in `existing`) and
+// whose status is filtered out.
+fn excluded_implicit_nodes<'a>(
+    nodes: impl Iterator<Item = DataNodeRef<'a>>,
+    existing: &HashSet<*mut ffi::lyd_node>,
+    status: StatusFilter,
+) -> Vec<*mut ffi::lyd_node> {
+    if status.is_empty() {
+        return Vec::new();
+    }
+
+    let mut excluded = Vec::new();
+    for dnode in nodes {
+        if existing.contains(&dnode.raw)
+            || dnode.is_opaque()
+            || !status.excludes(&dnode.schema())
+        {
+            continue;
+        }
+        let nested = dnode
+            .ancestors()
+            .any(|ancestor| excluded.contains(&ancestor.raw));
+        if !nested {
+            excluded.push(dnode.raw);
+        }
+    }
+    excluded
+}
+
+// Read a whole message from the given reader.
+fn read_message(mut reader: impl std::io::Read) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data).map_err(io_error)?;
+    Ok(data)
+}
+
+// Check whether two diff nodes describe the same change.
+fn same_change(ours: &DataNodeRef<'_>, theirs: &DataNodeRef<'_>) -> bool {
+    let print = |dnode: &DataNodeRef<'_>| {
+        dnode
+            .print_string(DataFormat::JSON, DataPrinterFlags::SHRINK)
+            .ok()
+    };
+    ours.value_canonical() == theirs.value_canonical()
+        && print(ours) == print(theirs)
+}
+
+// Find the leaves set to different values in the given sibling lists.
+fn merge_check_siblings(
+    ours: Option<DataNodeRef<'_>>,
+    theirs: Option<DataNodeRef<'_>>,
+    conflicts: &mut Vec<MergeConflict>,
+) {
+    let ours = match ours {
+        Some(ours) => ours,
+        None => return,
+    };
+    for theirs in Siblings::new(theirs).filter(|dnode| !dnode.is_opaque()) {
+        // Look up the matching instance (using the schema node, key and
+        // value hashes).
+        let mut rmatch = std::ptr::null_mut();
+        let ret = unsafe {
+            ffi::lyd_find_sibling_first(ours.raw, theirs.raw, &mut rmatch)
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            continue;
+        }
+        let ours = unsafe { DataNodeRef::from_raw(ours.tree, rmatch) };
+
+        match theirs.schema().kind() {
+            SchemaNodeKind::Leaf => {
+                let is_default = |dnode: &DataNodeRef<'_>| {
+                    (unsafe { (*dnode.raw).flags } & ffi::LYD_DEFAULT) != 0
+                };
+                if is_default(&ours) || is_default(&theirs) {
+                    continue;
+                }
+                let (ours_value, theirs_value) =
+                    (ours.value_canonical(), theirs.value_canonical());
+                if ours_value != theirs_value {
+                    conflicts.push(MergeConflict {
+                        path: ours.path(),
+                        ours: ours_value,
+                        theirs: theirs_value,
+                    });
+                }
+            }
+            _ => merge_check_siblings(
+                ours.first_child(),
+                theirs.first_child(),
+                conflicts,
+            ),
+        }
+    }
+}
+
+// Print the children of the given data node in the tree format.
+fn print_tree_children(
+    output: &mut String,
+    dnode: &DataNodeRef<'_>,
+    indent: &str,
+) {
+    let mut children = dnode.children().peekable();
+    while let Some(child) = children.next() {
+        let last = children.peek().is_none();
+        output.push_str(indent);
+        output.push_str(if last { "└── " } else { "├── " });
+        output.push_str(&tree_label(&child));
+        output.push('\n');
+        let indent =
+            format!("{}{}", indent, if last { "    " } else { "│   " });
+        print_tree_children(output, &child, &indent);
+    }
+}
+
+// Returns the label of the given data node in the tree format.
+fn tree_label(dnode: &DataNodeRef<'_>) -> String {
+    if dnode.is_opaque() {
+        let mut label = dnode.opaque_name().unwrap_or_default().to_owned();
+        if let Some(value) = dnode.opaque_value().filter(|v| !v.is_empty()) {
+            label = format!("{}: {}", label, value);
+        }
+        return format!("{} (opaque)", label);
+    }
+
+    // Prefix the node name with its module name, unless it's the same as
+    // the parent's one.
+    let snode = dnode.schema();
+    let mut label = match dnode.parent() {
+        Some(parent)
+            if !parent.is_opaque()
+                && parent.schema().module() == snode.module() =>
+        {
+            snode.name().to_owned()
+        }
+        _ => format!("{}:{}", snode.module().name(), snode.name()),
+    };
+    match snode.kind() {
+        SchemaNodeKind::List => {
+            for key in dnode.list_keys() {
+                label.push_str(&format!(
+                    "[{}='{}']",
+                    key.schema().name(),
+                    key.value_canonical().unwrap_or_default()
+                ));
+            }
+        }
+        SchemaNodeKind::Leaf | SchemaNodeKind::LeafList => {
+            if let Some(value) = dnode.value_canonical() {
+                label.push_str(": ");
+                label.push_str(&value);
+            }
+        }
+        _ => (),
+    }
+    if (unsafe { (*dnode.raw).flags } & ffi::LYD_DEFAULT) != 0 {
+        label.push_str(" (default)");
+    }
+    label
+}
+
+// Check whether the schema node is an empty-typed leaf or a presence container.
+fn is_empty_node(snode: &SchemaNode<'_>) -> bool {
+    match snode.kind() {
+        SchemaNodeKind::Leaf => snode
+            .leaf_type()
+            .is_some_and(|ltype| ltype.base_type() == DataValueType::Empty),
+        SchemaNodeKind::Container => !snode.is_np_container(),
+        _ => false,
+    }
+}
+
+// Encode the given data in base64 (RFC 4648), as used by the YANG binary type.
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut output = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (n >> (18 - 6 * i)) & 0x3f;
+                output.push(ALPHABET[index as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+// Format the given time as a YANG `date-and-time` value in UTC.
+fn date_and_time(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (days, secs) = (secs / 86400, secs % 86400);
+
+    // Convert the number of days since the epoch to a civil date.
+    let days = days as i64 + 719468;
+    let era = days / 146097;
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+// Compute the difference between two numeric values of the same type,
+// handling the wrap of unsigned counters.
+fn counter_delta(old: &DataValue, new: &DataValue) -> Option<i128> {
+    fn wrapping(old: u64, new: u64, max: u64) -> i128 {
+        if new >= old {
+            (new - old) as i128
+        } else {
+            (max - old) as i128 + new as i128 + 1
+        }
+    }
+
+    let delta = match (old, new) {
+        (DataValue::Uint8(old), DataValue::Uint8(new)) => {
+            wrapping(*old as u64, *new as u64, u8::MAX as u64)
+        }
+        (DataValue::Uint16(old), DataValue::Uint16(new)) => {
+            wrapping(*old as u64, *new as u64, u16::MAX as u64)
+        }
+        (DataValue::Uint32(old), DataValue::Uint32(new)) => {
+            wrapping(*old as u64, *new as u64, u32::MAX as u64)
+        }
+        (DataValue::Uint64(old), DataValue::Uint64(new)) => {
+            wrapping(*old, *new, u64::MAX)
+        }
+        (DataValue::Int8(old), DataValue::Int8(new)) => {
+            *new as i128 - *old as i128
+        }
+        (DataValue::Int16(old), DataValue::Int16(new)) => {
+            *new as i128 - *old as i128
+        }
+        (DataValue::Int32(old), DataValue::Int32(new)) => {
+            *new as i128 - *old as i128
+        }
+        (DataValue::Int64(old), DataValue::Int64(new)) => {
+            *new as i128 - *old as i128
+        }
+        _ => return None,
+    };
+
+    Some(delta)
+}
+
+// ===== impl CompiledPath =====
+
+impl<'a> CompiledPath<'a> {
+    /// Compile the given data path (e.g.
+    /// `/ietf-interfaces:interfaces/interface[name='eth0']/statistics/in-octets`).
+    ///
+    /// Only list key predicates and leaf-list value predicates (`[.='value']`)
+    /// are supported.
+    pub fn new(context: &'a Context, path: &str) -> Result<CompiledPath<'a>> {
+        let invalid = |msg: &str| Error {
+            errcode: ffi::LY_ERR::LY_EINVAL,
+            msg: Some(format!("Invalid path \"{}\": {}", path, msg)),
+            ..Default::default()
+        };
+
+        let mut steps = Vec::new();
+        let mut schema_path = String::new();
+        for segment in
+            split_path(path).ok_or_else(|| invalid("syntax error"))?
+        {
+            let (name, predicates) = match segment.find('[') {
+                Some(pos) => segment.split_at(pos),
+                None => (segment, ""),
+            };
+            schema_path.push('/');
+            schema_path.push_str(name);
+            let snode = context.find_path(&schema_path)?;
+
+            let key_or_value = match (snode.kind(), predicates) {
+                (SchemaNodeKind::List | SchemaNodeKind::LeafList, "") => {
+                    return Err(invalid("missing predicate"));
+                }
+                (_, "") => None,
+                (SchemaNodeKind::List, _) => Some(predicates.to_owned()),
+                (SchemaNodeKind::LeafList, _) => Some(
+                    predicates
+                        .strip_prefix("[.=")
+                        .and_then(|value| value.strip_suffix(']'))
+                        .and_then(|value| {
+                            value
+                                .strip_prefix('\'')
+                                .and_then(|value| value.strip_suffix('\''))
+                                .or_else(|| {
+                                    value.strip_prefix('"').and_then(|value| {
+                                        value.strip_suffix('"')
+                                    })
+                                })
+                        })
+                        .ok_or_else(|| invalid("unsupported predicate"))?
+                        .to_owned(),
+                ),
+                _ => return Err(invalid("unexpected predicate")),
+            };
+            steps.push(CompiledPathStep {
+                snode: snode.raw,
+                key_or_value: key_or_value
+                    .map(|value| CString::new(value).unwrap()),
+            });
+        }
+
+        Ok(CompiledPath {
+            context,
+            path: path.to_owned(),
+            steps,
+        })
+    }
+
+    /// Returns the path this object was compiled from.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+unsafe impl Send for CompiledPath<'_> {}
+unsafe impl Sync for CompiledPath<'_> {}
+
+// Split a data path into its node segments (e.g. `interface[name='eth0']`),
+// ignoring the slashes inside predicates.
+fn split_path(path: &str) -> Option<Vec<&str>> {
+    let path = path.strip_prefix('/')?;
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    let mut quote = None;
+    for (pos, c) in path.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => (),
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            (None, '/') if depth == 0 => {
+                segments.push(&path[start..pos]);
+                start = pos + 1;
+            }
+            _ => (),
+        }
+    }
+    segments.push(&path[start..]);
+
+    if quote.is_some() || depth != 0 || segments.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+    Some(segments)
+}
+
 // ===== impl DataDiff =====
 
 impl<'a> DataDiff<'a> {
@@ -1753,6 +5060,189 @@ impl<'a> DataDiff<'a> {
         })
     }
 
+    /// Returns a compact and human-readable summary of the data changes, one
+    /// change per line:
+    /// * `+ <path>[: <value>]` for created nodes;
+    /// * `- <path>[: <value>]` for deleted nodes;
+    /// * `~ <path>[: <old value> -> <new value>]` for replaced nodes.
+    pub fn to_text(&self, options: DataDiffTextFlags) -> String {
+        let mut text = String::new();
+
+        for (op, dnode) in self.iter() {
+            let (symbol, color) = match op {
+                DataDiffOp::Create => ('+', "\x1b[32m"),
+                DataDiffOp::Delete => ('-', "\x1b[31m"),
+                DataDiffOp::Replace => ('~', "\x1b[33m"),
+            };
+            let dnodes = match op {
+                DataDiffOp::Create | DataDiffOp::Delete
+                    if options.contains(DataDiffTextFlags::FULL_SUBTREES) =>
+                {
+                    dnode.traverse().collect()
+                }
+                _ => vec![dnode],
+            };
+
+            for dnode in dnodes {
+                let mut line = format!("{} {}", symbol, dnode.path());
+                if let Some(value) = dnode.value_canonical() {
+                    let orig_value = dnode
+                        .meta()
+                        .find(|meta| meta.name() == "orig-value")
+                        .map(|meta| meta.value().to_owned());
+                    match orig_value {
+                        Some(orig_value) if op == DataDiffOp::Replace => {
+                            line += &format!(": {} -> {}", orig_value, value);
+                        }
+                        _ => line += &format!(": {}", value),
+                    }
+                }
+
+                if options.contains(DataDiffTextFlags::COLOR) {
+                    text += &format!("{}{}\x1b[0m\n", color, line);
+                } else {
+                    text += &format!("{}\n", line);
+                }
+            }
+        }
+
+        text
+    }
+
+    /// Returns a unified-diff-style rendering of the data changes, comparing
+    /// the given base data tree with the result of applying the diff to it,
+    /// both printed in the given (text) format.
+    ///
+    /// Removed and added lines are prefixed with `-` and `+` respectively,
+    /// and surrounded by up to three lines of unchanged context from the base
+    /// data tree, grouped in `@@ -l,s +l,s @@` hunks.
+    pub fn unified_text(
+        &self,
+        base: &DataTree<'a>,
+        format: DataFormat,
+    ) -> Result<String> {
+        let mut changed = base.duplicate()?;
+        changed.diff_apply(self)?;
+
+        let print = |dtree: &DataTree<'a>| {
+            if dtree.reference().is_none() {
+                return Ok(String::new());
+            }
+            dtree.print_string(format, DataPrinterFlags::WITH_SIBLINGS)
+        };
+        let old_text = print(base)?;
+        let new_text = print(&changed)?;
+        let old = old_text.lines().collect::<Vec<_>>();
+        let new = new_text.lines().collect::<Vec<_>>();
+
+        Ok(unified_hunks(&old, &new, &line_diff(&old, &new), 3))
+    }
+
+    /// Returns a new diff restricted to the subtrees matching the given XPath.
+    ///
+    /// The parents of the matching nodes are preserved, along with their
+    /// operation metadata.
+    pub fn filter(&self, xpath: &str) -> Result<DataDiff<'a>> {
+        let context = self.tree.context;
+        let mut filtered = DataTree::new(context);
+
+        // Special handling for empty diffs.
+        if self.tree.raw.is_null() {
+            return Ok(DataDiff { tree: filtered });
+        }
+
+        let xpath = CString::new(xpath).unwrap();
+        let mut set = std::ptr::null_mut();
+        let ret = unsafe {
+            ffi::lyd_find_xpath(self.tree.raw, xpath.as_ptr(), &mut set)
+        };
+        if ret != ffi::LY_ERR::LY_SUCCESS {
+            return Err(Error::new(context));
+        }
+
+        let rnodes_count = unsafe { (*set).count } as usize;
+        let rnodes = if rnodes_count == 0 {
+            &[]
+        } else {
+            let rnodes = unsafe { (*set).__bindgen_anon_1.dnodes };
+            unsafe { slice::from_raw_parts(rnodes, rnodes_count) }
+        };
+        let result = rnodes.iter().try_for_each(|rnode| {
+            let mut dup = std::ptr::null_mut();
+            let options = ffi::LYD_DUP_RECURSIVE
+                | ffi::LYD_DUP_WITH_FLAGS
+                | ffi::LYD_DUP_WITH_PARENTS;
+            let ret = unsafe {
+                ffi::lyd_dup_single(
+                    *rnode,
+                    std::ptr::null_mut(),
+                    options,
+                    &mut dup,
+                )
+            };
+            if ret != ffi::LY_ERR::LY_SUCCESS {
+                return Err(Error::new(context));
+            }
+
+            // Merge the duplicated branch into the filtered diff.
+            let mut branch = DataTree::new(context);
+            unsafe { branch.reroot(dup) };
+            if filtered.raw.is_null() {
+                filtered.raw = branch.into_raw();
+            } else {
+                let ret = unsafe {
+                    ffi::lyd_merge_siblings(
+                        &mut filtered.raw,
+                        branch.into_raw(),
+                        ffi::LYD_MERGE_DESTRUCT as u16,
+                    )
+                };
+                if ret != ffi::LY_ERR::LY_SUCCESS {
+                    return Err(Error::new(context));
+                }
+            }
+
+            Ok(())
+        });
+        unsafe { ffi::ly_set_free(set, None) };
+        result?;
+
+        Ok(DataDiff { tree: filtered })
+    }
+
+    /// Returns a new diff restricted to the data of the given module.
+    pub fn filter_module(
+        &self,
+        module: &SchemaModule<'_>,
+    ) -> Result<DataDiff<'a>> {
+        let context = self.tree.context;
+        let mut filtered = DataTree::new(context);
+
+        for dnode in Siblings::new(self.tree.reference()) {
+            let owner = unsafe { ffi::lyd_owner_module(dnode.raw) };
+            if !std::ptr::eq(owner, module.raw) {
+                continue;
+            }
+
+            let mut dup = std::ptr::null_mut();
+            let options = ffi::LYD_DUP_RECURSIVE | ffi::LYD_DUP_WITH_FLAGS;
+            let ret = unsafe {
+                ffi::lyd_dup_single(
+                    dnode.raw,
+                    std::ptr::null_mut(),
+                    options,
+                    &mut dup,
+                )
+            };
+            if ret != ffi::LY_ERR::LY_SUCCESS {
+                return Err(Error::new(context));
+            }
+            unsafe { filtered.insert_sibling(dup)? };
+        }
+
+        Ok(DataDiff { tree: filtered })
+    }
+
     /// Reverse a diff and make the opposite changes. Meaning change create to
     /// delete, delete to create, or move from place A to B to move from B
     /// to A and so on.
@@ -1772,6 +5262,132 @@ impl<'a> DataDiff<'a> {
     }
 }
 
+// Compute the shortest edit script between two sequences of lines (Myers'
+// algorithm), as a list of (old line, new line) index pairs, one of which is
+// `None` for removed and added lines.
+fn line_diff(
+    old: &[&str],
+    new: &[&str],
+) -> Vec<(Option<usize>, Option<usize>)> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let offset = n + m;
+    let idx = |k: isize| (k + offset) as usize;
+
+    // Find the furthest reaching paths, keeping the state of each step.
+    let mut v = vec![0isize; 2 * (n + m) as usize + 2];
+    let mut trace = Vec::new();
+    'search: for d in 0..=(n + m) {
+        trace.push(v.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)])
+            {
+                v[idx(k + 1)]
+            } else {
+                v[idx(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Backtrack to build the edit script.
+    let mut script = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)]) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_x = v[idx(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            script.push((Some(x as usize), Some(y as usize)));
+        }
+        if d > 0 {
+            if x == prev_x {
+                y -= 1;
+                script.push((None, Some(y as usize)));
+            } else {
+                x -= 1;
+                script.push((Some(x as usize), None));
+            }
+        }
+    }
+    script.reverse();
+    script
+}
+
+// Render the given edit script in the unified diff format, with the given
+// number of context lines around the changes.
+fn unified_hunks(
+    old: &[&str],
+    new: &[&str],
+    script: &[(Option<usize>, Option<usize>)],
+    context: usize,
+) -> String {
+    let changes = script
+        .iter()
+        .enumerate()
+        .filter(|(_, (o, n))| o.is_none() || n.is_none())
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    let range = |start: usize, count: usize| match count {
+        0 => format!("{},0", start),
+        _ => format!("{},{}", start + 1, count),
+    };
+
+    let mut output = String::new();
+    let mut i = 0;
+    while i < changes.len() {
+        // Merge the changes whose context lines overlap.
+        let mut j = i;
+        while j + 1 < changes.len()
+            && changes[j + 1] - changes[j] - 1 <= 2 * context
+        {
+            j += 1;
+        }
+        let start = changes[i].saturating_sub(context);
+        let end = (changes[j] + context + 1).min(script.len());
+        let hunk = &script[start..end];
+
+        let old_start = script[..start].iter().filter(|(o, _)| o.is_some());
+        let new_start = script[..start].iter().filter(|(_, n)| n.is_some());
+        let old_count = hunk.iter().filter(|(o, _)| o.is_some()).count();
+        let new_count = hunk.iter().filter(|(_, n)| n.is_some()).count();
+        output.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_start.count(), old_count),
+            range(new_start.count(), new_count)
+        ));
+        for entry in hunk {
+            let line = match *entry {
+                (Some(o), Some(_)) => format!(" {}", old[o]),
+                (Some(o), None) => format!("-{}", old[o]),
+                (None, Some(n)) => format!("+{}", new[n]),
+                (None, None) => unreachable!(),
+            };
+            output.push_str(&line);
+            output.push('\n');
+        }
+
+        i = j + 1;
+    }
+    output
+}
+
 impl<'a> Data<'a> for DataDiff<'a> {
     fn tree(&self) -> &DataTree<'a> {
         &self.tree
@@ -7,19 +7,85 @@
 use crate::context::Context;
 use crate::utils::*;
 use libyang3_sys as ffi;
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
 
 /// A convenience wrapper around `Result` for `yang3::Error`.
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Enum listing possible errors from yang3.
+///
+/// More fields may be added in the future, so errors can only be created
+/// outside of this crate using [`Error::with_message`] or [`Error::default`],
+/// setting the public fields afterwards.
 #[derive(Debug, Eq, PartialEq)]
+#[non_exhaustive]
 pub struct Error {
     pub errcode: ffi::LY_ERR::Type,
+    pub vecode: ffi::LY_VECODE::Type,
     pub msg: Option<String>,
     pub path: Option<String>,
     pub apptag: Option<String>,
 }
 
+/// Category of a libyang error (`LY_ERR` code).
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, FromPrimitive)]
+pub enum ErrorKind {
+    /// Memory allocation failure.
+    Memory = ffi::LY_ERR::LY_EMEM,
+    /// System call failure.
+    System = ffi::LY_ERR::LY_ESYS,
+    /// Invalid value.
+    Invalid = ffi::LY_ERR::LY_EINVAL,
+    /// Item already exists.
+    Exists = ffi::LY_ERR::LY_EEXIST,
+    /// Item does not exist.
+    NotFound = ffi::LY_ERR::LY_ENOTFOUND,
+    /// Internal error.
+    Internal = ffi::LY_ERR::LY_EINT,
+    /// Validation failure.
+    Validation = ffi::LY_ERR::LY_EVALID,
+    /// Operation is not allowed.
+    Denied = ffi::LY_ERR::LY_EDENIED,
+    /// Operation is incomplete due to unresolved dependencies.
+    Incomplete = ffi::LY_ERR::LY_EINCOMPLETE,
+    /// Compiled schema must be recompiled.
+    Recompile = ffi::LY_ERR::LY_ERECOMPILE,
+    /// Negative result.
+    Not = ffi::LY_ERR::LY_ENOT,
+    /// Unknown error.
+    Other = ffi::LY_ERR::LY_EOTHER,
+    /// Error reported by a plugin.
+    Plugin = ffi::LY_ERR::LY_EPLUGIN,
+}
+
+/// Validation error code (`LY_VECODE` code), detailing validation failures.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, FromPrimitive)]
+pub enum ValidationErrorKind {
+    /// Generic syntax error.
+    Syntax = ffi::LY_VECODE::LYVE_SYNTAX,
+    /// YANG syntax error.
+    SyntaxYang = ffi::LY_VECODE::LYVE_SYNTAX_YANG,
+    /// YIN syntax error.
+    SyntaxYin = ffi::LY_VECODE::LYVE_SYNTAX_YIN,
+    /// Invalid reference to another node.
+    Reference = ffi::LY_VECODE::LYVE_REFERENCE,
+    /// Invalid XPath expression.
+    XPath = ffi::LY_VECODE::LYVE_XPATH,
+    /// Generic semantic error.
+    Semantics = ffi::LY_VECODE::LYVE_SEMANTICS,
+    /// XML syntax error.
+    SyntaxXml = ffi::LY_VECODE::LYVE_SYNTAX_XML,
+    /// JSON syntax error.
+    SyntaxJson = ffi::LY_VECODE::LYVE_SYNTAX_JSON,
+    /// YANG data does not reflect some of the module restrictions.
+    Data = ffi::LY_VECODE::LYVE_DATA,
+    /// Unknown validation error.
+    Other = ffi::LY_VECODE::LYVE_OTHER,
+}
+
 impl Error {
     pub fn new(ctx: &Context) -> Error {
         let error = unsafe { ffi::ly_err_last(ctx.raw) };
@@ -30,23 +96,75 @@ impl Error {
         }
 
         let errcode = unsafe { (*error).err };
+        let vecode = unsafe { (*error).vecode };
         let msg = unsafe { char_ptr_to_opt_string((*error).msg, false) };
         let path = unsafe { char_ptr_to_opt_string((*error).data_path, false) };
         let apptag = unsafe { char_ptr_to_opt_string((*error).apptag, false) };
 
         Self {
             errcode,
+            vecode,
             msg,
             path,
             apptag,
         }
     }
+
+    /// Create an error with the given code and message.
+    pub fn with_message(errcode: ffi::LY_ERR::Type, msg: &str) -> Error {
+        Error {
+            errcode,
+            msg: Some(msg.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::from_u32(self.errcode).unwrap_or(ErrorKind::Other)
+    }
+
+    /// Returns the validation error code, if any.
+    pub fn validation_kind(&self) -> Option<ValidationErrorKind> {
+        ValidationErrorKind::from_u32(self.vecode)
+    }
+
+    /// Returns whether the error is a validation failure.
+    pub fn is_validation(&self) -> bool {
+        self.kind() == ErrorKind::Validation
+    }
+
+    /// Returns whether the error is caused by a missing item.
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == ErrorKind::NotFound
+    }
+
+    /// Returns whether the error is caused by an already existing item.
+    pub fn is_exists(&self) -> bool {
+        self.kind() == ErrorKind::Exists
+    }
+
+    /// Returns whether the error is caused by an invalid value.
+    pub fn is_invalid(&self) -> bool {
+        self.kind() == ErrorKind::Invalid
+    }
+
+    /// Returns whether the operation was denied.
+    pub fn is_denied(&self) -> bool {
+        self.kind() == ErrorKind::Denied
+    }
+
+    /// Returns whether the error is caused by a system call failure.
+    pub fn is_system(&self) -> bool {
+        self.kind() == ErrorKind::System
+    }
 }
 
 impl Default for Error {
     fn default() -> Self {
         Self {
             errcode: ffi::LY_ERR::LY_EOTHER,
+            vecode: ffi::LY_VECODE::LYVE_SUCCESS,
             msg: None,
             path: None,
             apptag: None,
@@ -66,3 +184,14 @@ impl std::fmt::Display for Error {
 }
 
 impl std::error::Error for Error {}
+
+// ===== helper functions =====
+
+// Convert an I/O error.
+pub(crate) fn io_error(error: std::io::Error) -> Error {
+    Error {
+        errcode: ffi::LY_ERR::LY_ESYS,
+        msg: Some(error.to_string()),
+        ..Default::default()
+    }
+}
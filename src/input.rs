@@ -0,0 +1,92 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! User-provided input sources.
+
+use std::io::Read;
+
+use crate::error::{io_error, Result};
+
+/// Source of input data, provided in chunks (e.g. decompressed on the fly).
+///
+/// libyang has no callback-based input handler, so the chunks are collected
+/// into a single buffer before being handed to the parser. The buffer
+/// returned by [`InputProvider::read_all`] can be passed to any parse
+/// function taking the input data (e.g. [`DataDiff::parse_string`] or
+/// [`Context::parse_module_string`]), and [`DataTree::parse_input`] and
+/// [`DataTree::parse_op_input`] do that directly.
+///
+/// [`Context::parse_module_string`]: crate::context::Context::parse_module_string
+/// [`DataDiff::parse_string`]: crate::data::DataDiff::parse_string
+/// [`DataTree::parse_input`]: crate::data::DataTree::parse_input
+/// [`DataTree::parse_op_input`]: crate::data::DataTree::parse_op_input
+pub trait InputProvider {
+    /// Returns the next chunk of input data, or `None` once the input is
+    /// exhausted.
+    fn read_chunk(&mut self) -> std::io::Result<Option<&[u8]>>;
+
+    /// Read all the remaining input data.
+    ///
+    /// Returns an error if reading any of the chunks fails.
+    fn read_all(&mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        while let Some(chunk) = self.read_chunk().map_err(io_error)? {
+            data.extend_from_slice(chunk);
+        }
+        Ok(data)
+    }
+}
+
+/// Input provider reading chunks from any [`Read`] implementation (e.g. a
+/// gzip decoder).
+#[derive(Debug)]
+pub struct ReadInput<R: Read> {
+    reader: R,
+    buf: Vec<u8>,
+}
+
+// ===== impl ReadInput =====
+
+impl<R: Read> ReadInput<R> {
+    /// Default size of the chunks.
+    pub const CHUNK_SIZE: usize = 8192;
+
+    /// Create a new input provider reading from the given reader.
+    pub fn new(reader: R) -> ReadInput<R> {
+        ReadInput::with_chunk_size(reader, ReadInput::<R>::CHUNK_SIZE)
+    }
+
+    /// Create a new input provider reading chunks of at most the given size
+    /// from the given reader.
+    pub fn with_chunk_size(reader: R, chunk_size: usize) -> ReadInput<R> {
+        ReadInput {
+            reader,
+            buf: vec![0; chunk_size.max(1)],
+        }
+    }
+
+    /// Consumes the input provider, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> InputProvider for ReadInput<R> {
+    fn read_chunk(&mut self) -> std::io::Result<Option<&[u8]>> {
+        loop {
+            match self.reader.read(&mut self.buf) {
+                Ok(0) => return Ok(None),
+                Ok(len) => return Ok(Some(&self.buf[..len])),
+                Err(error)
+                    if error.kind() == std::io::ErrorKind::Interrupted =>
+                {
+                    continue
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
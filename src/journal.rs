@@ -0,0 +1,303 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Edit journals (write-ahead logs).
+
+use libyang3_sys as ffi;
+use std::convert::{TryFrom, TryInto};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::context::Context;
+use crate::data::{
+    Data, DataDiff, DataFormat, DataParserFlags, DataPrinterFlags, DataTree,
+    DataValidationFlags,
+};
+use crate::error::{io_error, Error, Result};
+
+/// Size of the header of each journal record (length, checksum and snapshot
+/// generation).
+const RECORD_HEADER_LEN: usize = 16;
+
+/// Size of the header of snapshot files (snapshot generation).
+const SNAPSHOT_HEADER_LEN: usize = 8;
+
+/// Write-ahead log of the diffs applied to a data tree, allowing to recover
+/// the data tree after a crash by replaying the diffs onto the last saved
+/// snapshot.
+///
+/// Each diff is appended as a record consisting of its length, checksum and
+/// snapshot generation followed by the diff itself, in the LYB format. A
+/// record that was only partially written (e.g. due to a crash while
+/// appending it) ends the journal, and is discarded when the journal is
+/// replayed.
+///
+/// Every snapshot saved by [`EditJournal::checkpoint`] gets a new generation
+/// number, stored along with it. Records of older generations, left behind
+/// by a crash between saving a snapshot and clearing the journal, are
+/// already part of the snapshot and are skipped when the journal is
+/// replayed.
+///
+/// The generation of the snapshot is only known once it's loaded, so the
+/// journal must be recovered (see [`EditJournal::recover`]) before any diff
+/// is appended or replayed, or a new snapshot is saved.
+#[derive(Debug)]
+pub struct EditJournal {
+    file: File,
+    path: PathBuf,
+    generation: Option<u64>,
+}
+
+// ===== impl EditJournal =====
+
+impl EditJournal {
+    /// Open the journal stored in the given file, creating it if necessary.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<EditJournal> {
+        let path = path.as_ref();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(io_error)?;
+
+        Ok(EditJournal {
+            file,
+            path: path.to_owned(),
+            generation: None,
+        })
+    }
+
+    /// Returns the path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the generation of the snapshot the journal applies to, unless
+    /// the journal wasn't recovered yet.
+    pub fn generation(&self) -> Option<u64> {
+        self.generation
+    }
+
+    /// Append the given diff to the journal, flushing it to the disk before
+    /// returning. Empty diffs aren't recorded.
+    pub fn append(&mut self, diff: &DataDiff<'_>) -> Result<()> {
+        let generation = self.current_generation()?.to_le_bytes();
+        if diff.raw().is_null() {
+            return Ok(());
+        }
+
+        let data =
+            diff.print_bytes(DataFormat::LYB, DataPrinterFlags::WITH_SIBLINGS)?;
+        let len = u32::try_from(data.len()).map_err(|_| Error {
+            errcode: ffi::LY_ERR::LY_EINVAL,
+            msg: Some("Diff too large for a journal record".to_owned()),
+            ..Default::default()
+        })?;
+        let mut record = Vec::with_capacity(RECORD_HEADER_LEN + data.len());
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(&checksum(&generation, &data).to_le_bytes());
+        record.extend_from_slice(&generation);
+        record.extend_from_slice(&data);
+
+        self.file.seek(SeekFrom::End(0)).map_err(io_error)?;
+        self.file.write_all(&record).map_err(io_error)?;
+        self.file.sync_data().map_err(io_error)
+    }
+
+    /// Apply all the diffs recorded in the journal for the current snapshot
+    /// generation onto the given data tree (usually the last saved
+    /// snapshot), returning the number of diffs applied.
+    ///
+    /// A trailing partially written record is removed from the journal, so
+    /// that new diffs can be appended after the last complete one.
+    pub fn replay(&mut self, dtree: &mut DataTree<'_>) -> Result<usize> {
+        let current = self.current_generation()?;
+        let data = self.read_all()?;
+
+        let mut offset = 0;
+        let mut count = 0;
+        while let Some((generation, record)) = read_record(&data[offset..]) {
+            offset += RECORD_HEADER_LEN + record.len();
+            if generation != current {
+                continue;
+            }
+            let diff = DataDiff::parse_string(
+                dtree.context(),
+                record,
+                DataFormat::LYB,
+                DataParserFlags::NO_VALIDATION,
+                DataValidationFlags::empty(),
+            )?;
+            dtree.diff_apply(&diff)?;
+            count += 1;
+        }
+
+        // Discard the partially written record, if any, and the stale
+        // records when there are no others, so that new diffs aren't
+        // appended after them.
+        if count == 0 {
+            offset = 0;
+        }
+        if offset < data.len() {
+            self.file.set_len(offset as u64).map_err(io_error)?;
+            self.file.sync_data().map_err(io_error)?;
+        }
+
+        Ok(count)
+    }
+
+    /// Save the given data tree as the new snapshot, with a new generation
+    /// number, and clear the journal.
+    ///
+    /// The snapshot consists of its generation number followed by the data
+    /// tree in the LYB format. It's written to a temporary file first, and
+    /// then renamed, so a crash never leaves a partially written snapshot
+    /// behind.
+    pub fn checkpoint<P: AsRef<Path>>(
+        &mut self,
+        dtree: &DataTree<'_>,
+        snapshot: P,
+    ) -> Result<()> {
+        let snapshot = snapshot.as_ref();
+        let generation = self.current_generation()? + 1;
+        let mut data = generation.to_le_bytes().to_vec();
+        if dtree.reference().is_some() {
+            data.extend(dtree.print_bytes(
+                DataFormat::LYB,
+                DataPrinterFlags::WITH_SIBLINGS,
+            )?);
+        }
+
+        let mut tmp_path = snapshot.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let mut file = File::create(&tmp_path).map_err(io_error)?;
+        file.write_all(&data).map_err(io_error)?;
+        file.sync_all().map_err(io_error)?;
+        std::fs::rename(&tmp_path, snapshot).map_err(io_error)?;
+        sync_parent_dir(snapshot)?;
+
+        // From now on, the records of the previous generation are stale.
+        self.generation = Some(generation);
+        self.clear()
+    }
+
+    /// Load the snapshot saved in the given file (an empty data tree if it
+    /// doesn't exist), and replay the journal records of its generation onto
+    /// it. From then on, diffs are recorded for that generation.
+    pub fn recover<'a, P: AsRef<Path>>(
+        &mut self,
+        context: &'a Context,
+        snapshot: P,
+    ) -> Result<DataTree<'a>> {
+        let data = match std::fs::read(snapshot) {
+            Ok(data) => data,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                Vec::new()
+            }
+            Err(error) => return Err(io_error(error)),
+        };
+        let (generation, data) = match data.is_empty() {
+            true => (0, &data[..]),
+            false => {
+                let header =
+                    data.get(..SNAPSHOT_HEADER_LEN).ok_or_else(|| Error {
+                        errcode: ffi::LY_ERR::LY_EINVAL,
+                        msg: Some("Truncated snapshot".to_owned()),
+                        ..Default::default()
+                    })?;
+                let generation = u64::from_le_bytes(header.try_into().unwrap());
+                (generation, &data[SNAPSHOT_HEADER_LEN..])
+            }
+        };
+        let mut dtree = match data.is_empty() {
+            true => DataTree::new(context),
+            false => DataTree::parse_string(
+                context,
+                data,
+                DataFormat::LYB,
+                DataParserFlags::NO_VALIDATION,
+                DataValidationFlags::empty(),
+            )?,
+        };
+        self.generation = Some(generation);
+        self.replay(&mut dtree)?;
+
+        Ok(dtree)
+    }
+
+    /// Remove all the records from the journal.
+    pub fn clear(&mut self) -> Result<()> {
+        self.file.set_len(0).map_err(io_error)?;
+        self.file.sync_data().map_err(io_error)
+    }
+
+    // Returns the generation of the snapshot the journal applies to, failing
+    // if the journal wasn't recovered yet.
+    fn current_generation(&self) -> Result<u64> {
+        self.generation.ok_or_else(|| Error {
+            errcode: ffi::LY_ERR::LY_EINVAL,
+            msg: Some("Journal not recovered yet".to_owned()),
+            ..Default::default()
+        })
+    }
+
+    // Read the whole journal file.
+    fn read_all(&mut self) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.file.seek(SeekFrom::Start(0)).map_err(io_error)?;
+        self.file.read_to_end(&mut data).map_err(io_error)?;
+        Ok(data)
+    }
+}
+
+// ===== helper functions =====
+
+// Returns the generation and payload of the record at the start of the given
+// buffer, unless it's incomplete or corrupted.
+fn read_record(data: &[u8]) -> Option<(u64, &[u8])> {
+    if data.len() < RECORD_HEADER_LEN {
+        return None;
+    }
+    let len = u32::from_le_bytes(data[0..4].try_into().unwrap()) as usize;
+    let sum = u32::from_le_bytes(data[4..8].try_into().unwrap());
+    let generation = &data[8..RECORD_HEADER_LEN];
+    let record = data[RECORD_HEADER_LEN..].get(..len)?;
+    (checksum(generation, record) == sum)
+        .then(|| (u64::from_le_bytes(generation.try_into().unwrap()), record))
+}
+
+// FNV-1a hash, used to detect partially written records.
+fn checksum(generation: &[u8], data: &[u8]) -> u32 {
+    generation
+        .iter()
+        .chain(data)
+        .fold(0x811c_9dc5, |hash, byte| {
+            (hash ^ *byte as u32).wrapping_mul(0x0100_0193)
+        })
+}
+
+// Flush the directory entry of the given file to the disk, so that a rename
+// survives a crash.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    File::open(dir)
+        .and_then(|dir| dir.sync_all())
+        .map_err(io_error)
+}
+
+// Directories can't be opened as files on this platform, and renames are
+// flushed along with the file itself.
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> Result<()> {
+    Ok(())
+}
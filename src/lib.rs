@@ -10,6 +10,7 @@
 //!
 //! [libyang3]: https://github.com/CESNET/libyang/tree/master
 //! [libyang3-sys]: https://github.com/holo-routing/yang-rs/tree/master/libyang3-sys
+//! [proptest]: https://crates.io/crates/proptest
 //!
 //! ## Design Goals
 //! * Provide high-level bindings for libyang3 using idiomatic Rust
@@ -32,6 +33,11 @@
 //!   the pre-generated ones. Useful when updating this crate to use newer
 //!   libyang3 versions.
 //!   * Additional build requirements: *bindgen 0.68.0*
+//! * **proptest**: provide [proptest] strategies generating random data trees
+//!   for a given schema, useful for fuzzing code that consumes YANG data (see
+//!   `testdata::DataGenerator::strategy`).
+//! * **gzip**, **zstd**: print and parse data files compressed using the
+//!   corresponding codec (see `data::DataTree::print_compressed`).
 //!
 //! ## Examples
 //!
@@ -39,14 +45,32 @@
 
 mod error;
 
+pub mod anonymize;
+pub mod archive;
+pub mod cache;
+pub mod cli;
+pub mod commit;
 pub mod context;
 pub mod data;
+pub mod input;
 pub mod iter;
+pub mod journal;
+pub mod limits;
 pub mod logging;
+pub mod nacm;
+pub mod peek;
+pub mod repository;
+pub mod rpc;
 pub mod schema;
+pub mod session;
+pub mod tags;
+pub mod template;
+pub mod testdata;
+pub mod testing;
 pub mod utils;
+pub mod validation;
 
-pub use crate::error::Error;
+pub use crate::error::{Error, ErrorKind, ValidationErrorKind};
 
 // Re-export the raw FFI bindings for convenience.
 pub use libyang3_sys as ffi;
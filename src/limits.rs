@@ -0,0 +1,320 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Parser limits for untrusted input.
+
+use crate::data::{DataFormat, DataTree};
+use crate::error::Error;
+
+/// Limits enforced when parsing untrusted data (see
+/// [`DataTree::parse_string_limited`] and [`DataTree::parse_untrusted`]).
+///
+/// The input size and the nesting depth of XML and JSON input are checked
+/// before the input is handed to libyang, so excessively large or deeply
+/// nested input is rejected without being parsed. The depth and node count
+/// of the parsed data tree are checked once it's built, its memory usage
+/// being bounded by the input size limit.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ParserLimits {
+    /// Maximum depth of the data tree (top-level nodes have depth 1).
+    pub max_depth: Option<usize>,
+    /// Maximum number of nodes of the data tree.
+    pub max_nodes: Option<usize>,
+    /// Maximum size of the input, in bytes.
+    pub max_input_size: Option<usize>,
+    /// Maximum number of attributes (including namespace declarations) of
+    /// each XML element.
+    pub max_attributes: Option<usize>,
+}
+
+/// Error returned when parsing data with limits.
+#[derive(Debug, Eq, PartialEq)]
+pub enum LimitedParseError {
+    /// The input is larger than the maximum input size.
+    InputSize { size: usize, limit: usize },
+    /// The data is nested deeper than the maximum depth.
+    Depth { limit: usize },
+    /// The data has more nodes than the maximum node count.
+    NodeCount { limit: usize },
+    /// An XML element has more attributes than the maximum attribute count.
+    AttributeCount { limit: usize },
+    /// The XML input contains a document type declaration.
+    Doctype,
+    /// The XML input contains a processing instruction.
+    ProcessingInstruction,
+    /// The input couldn't be parsed or validated.
+    Parse(Error),
+}
+
+// ===== impl ParserLimits =====
+
+impl ParserLimits {
+    /// Create a new set of limits, with no limit set.
+    pub fn new() -> ParserLimits {
+        Default::default()
+    }
+
+    /// Set the maximum depth of the data tree.
+    pub fn max_depth(mut self, limit: usize) -> ParserLimits {
+        self.max_depth = Some(limit);
+        self
+    }
+
+    /// Set the maximum number of nodes of the data tree.
+    pub fn max_nodes(mut self, limit: usize) -> ParserLimits {
+        self.max_nodes = Some(limit);
+        self
+    }
+
+    /// Set the maximum size of the input, in bytes.
+    pub fn max_input_size(mut self, limit: usize) -> ParserLimits {
+        self.max_input_size = Some(limit);
+        self
+    }
+
+    /// Set the maximum number of attributes of each XML element.
+    pub fn max_attributes(mut self, limit: usize) -> ParserLimits {
+        self.max_attributes = Some(limit);
+        self
+    }
+
+    /// Check the raw input against the input size, depth and attribute
+    /// count limits.
+    pub(crate) fn check_input(
+        &self,
+        data: &[u8],
+        format: DataFormat,
+        untrusted: bool,
+    ) -> Result<(), LimitedParseError> {
+        if let Some(limit) = self.max_input_size {
+            if data.len() > limit {
+                return Err(LimitedParseError::InputSize {
+                    size: data.len(),
+                    limit,
+                });
+            }
+        }
+
+        match format {
+            DataFormat::XML => check_xml(data, self, untrusted)?,
+            DataFormat::JSON => {
+                if let Some(limit) = self.max_depth {
+                    if json_depth_exceeds(data, limit) {
+                        return Err(LimitedParseError::Depth { limit });
+                    }
+                }
+            }
+            DataFormat::LYB => (),
+        }
+
+        Ok(())
+    }
+
+    /// Check the parsed data tree against the depth and node count limits.
+    pub(crate) fn check_tree(
+        &self,
+        dtree: &DataTree<'_>,
+    ) -> Result<(), LimitedParseError> {
+        if self.max_depth.is_none() && self.max_nodes.is_none() {
+            return Ok(());
+        }
+
+        for (count, dnode) in dtree.traverse().enumerate() {
+            if let Some(limit) = self.max_nodes {
+                if count + 1 > limit {
+                    return Err(LimitedParseError::NodeCount { limit });
+                }
+            }
+            if let Some(limit) = self.max_depth {
+                if dnode.inclusive_ancestors().count() > limit {
+                    return Err(LimitedParseError::Depth { limit });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ===== impl LimitedParseError =====
+
+impl From<Error> for LimitedParseError {
+    fn from(error: Error) -> LimitedParseError {
+        LimitedParseError::Parse(error)
+    }
+}
+
+impl std::fmt::Display for LimitedParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitedParseError::InputSize { size, limit } => write!(
+                f,
+                "Input size ({} bytes) exceeds the limit ({} bytes)",
+                size, limit
+            ),
+            LimitedParseError::Depth { limit } => {
+                write!(f, "Data depth exceeds the limit ({})", limit)
+            }
+            LimitedParseError::NodeCount { limit } => {
+                write!(f, "Number of data nodes exceeds the limit ({})", limit)
+            }
+            LimitedParseError::AttributeCount { limit } => write!(
+                f,
+                "Number of XML attributes exceeds the limit ({})",
+                limit
+            ),
+            LimitedParseError::Doctype => {
+                write!(f, "XML document type declarations are not allowed")
+            }
+            LimitedParseError::ProcessingInstruction => {
+                write!(f, "XML processing instructions are not allowed")
+            }
+            LimitedParseError::Parse(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LimitedParseError {}
+
+// ===== helper functions =====
+
+// Check the XML input against the depth and attribute count limits. When
+// the input is untrusted, document type declarations and processing
+// instructions (other than the leading XML declaration) are rejected as well.
+fn check_xml(
+    data: &[u8],
+    limits: &ParserLimits,
+    untrusted: bool,
+) -> Result<(), LimitedParseError> {
+    let mut depth = 0usize;
+    let mut pos = 0;
+    while let Some(offset) = data[pos..].iter().position(|c| *c == b'<') {
+        let leading =
+            data[..pos + offset].iter().all(|c| c.is_ascii_whitespace());
+        pos += offset + 1;
+        let rest = &data[pos..];
+        if rest.starts_with(b"!--") {
+            // Comment.
+            pos += find(rest, b"-->").unwrap_or(rest.len());
+        } else if rest.starts_with(b"![CDATA[") {
+            pos += find(rest, b"]]>").unwrap_or(rest.len());
+        } else if rest.starts_with(b"?") {
+            // Processing instruction or XML declaration.
+            if untrusted && !(leading && rest.starts_with(b"?xml ")) {
+                return Err(LimitedParseError::ProcessingInstruction);
+            }
+            pos += find(rest, b"?>").unwrap_or(rest.len());
+        } else if rest.starts_with(b"!") {
+            // Document type declaration.
+            if untrusted {
+                return Err(LimitedParseError::Doctype);
+            }
+            pos += find(rest, b">").unwrap_or(rest.len());
+        } else if rest.starts_with(b"/") {
+            depth = depth.saturating_sub(1);
+        } else {
+            depth += 1;
+            if let Some(limit) = limits.max_depth {
+                if depth > limit {
+                    return Err(LimitedParseError::Depth { limit });
+                }
+            }
+
+            // Skip the start tag, taking quoted attribute values into
+            // account, and check whether it's self-closing.
+            let mut quote = None;
+            let mut prev = 0;
+            let mut attributes = 0;
+            for c in rest {
+                pos += 1;
+                match quote {
+                    Some(q) if *c == q => quote = None,
+                    Some(_) => (),
+                    None if *c == b'"' || *c == b'\'' => {
+                        quote = Some(*c);
+                        attributes += 1;
+                        if let Some(limit) = limits.max_attributes {
+                            if attributes > limit {
+                                return Err(
+                                    LimitedParseError::AttributeCount { limit },
+                                );
+                            }
+                        }
+                    }
+                    None if *c == b'>' => {
+                        if prev == b'/' {
+                            depth -= 1;
+                        }
+                        break;
+                    }
+                    None => (),
+                }
+                prev = *c;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Returns whether the JSON objects and arrays are nested deeper than the
+// given limit.
+//
+// The top-level object holds the top-level data nodes, each nested object
+// holds the children of a data node, and each array holds the instances of a
+// list or leaf-list. Objects that are array elements (list instances) don't
+// add a level of their own, so the nesting level is an upper bound of the
+// data tree depth. Arrays nested in arrays still count, so that they can't
+// be used to get around the limit.
+fn json_depth_exceeds(data: &[u8], limit: usize) -> bool {
+    // Open objects and arrays: whether each is an array, and whether it adds
+    // a nesting level.
+    let mut stack: Vec<(bool, bool)> = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escape = false;
+    for c in data {
+        if in_string {
+            match *c {
+                _ if escape => escape = false,
+                b'\\' => escape = true,
+                b'"' => in_string = false,
+                _ => (),
+            }
+            continue;
+        }
+        match *c {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                let array = *c == b'[';
+                let counted = array || !matches!(stack.last(), Some((true, _)));
+                stack.push((array, counted));
+                if counted {
+                    depth += 1;
+                    if depth > limit {
+                        return true;
+                    }
+                }
+            }
+            b'}' | b']' => {
+                if let Some((_, true)) = stack.pop() {
+                    depth -= 1;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    false
+}
+
+// Returns the position following the first occurrence of the given needle.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|pos| pos + needle.len())
+}
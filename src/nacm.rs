@@ -0,0 +1,592 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Network Configuration Access Control Model (RFC 8341).
+
+use libyang3_sys as ffi;
+use std::collections::{HashMap, HashSet};
+
+use crate::data::{Data, DataDiff, DataDiffOp, DataNodeRef, DataTree};
+use crate::error::{Error, Result};
+use crate::iter::NodeIterable;
+use crate::schema::SchemaNode;
+
+/// Path of the NACM container defined by the `ietf-netconf-acm` module.
+const NACM_PATH: &str = "/ietf-netconf-acm:nacm";
+
+/// NACM access operation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum NacmOperation {
+    Create,
+    Read,
+    Update,
+    Delete,
+    Exec,
+}
+
+/// NACM rule action.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NacmAction {
+    Permit,
+    Deny,
+}
+
+/// NACM access control rules, as configured in the `ietf-netconf-acm` module.
+#[derive(Clone, Debug)]
+pub struct Nacm {
+    enabled: bool,
+    read_default: NacmAction,
+    write_default: NacmAction,
+    exec_default: NacmAction,
+    external_groups: bool,
+    groups: Vec<NacmGroup>,
+    rule_lists: Vec<NacmRuleList>,
+}
+
+/// Access control session of a user, whose requests are checked against the
+/// NACM rules (see [`Nacm::session`]).
+#[derive(Clone, Debug)]
+pub struct NacmSession<'n> {
+    nacm: &'n Nacm,
+    user: String,
+    groups: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+struct NacmGroup {
+    name: String,
+    users: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+struct NacmRuleList {
+    groups: Vec<String>,
+    rules: Vec<NacmRule>,
+}
+
+#[derive(Clone, Debug)]
+struct NacmRule {
+    module_name: String,
+    rule_type: NacmRuleType,
+    // `None` matches all operations.
+    operations: Option<HashSet<NacmOperation>>,
+    action: NacmAction,
+}
+
+#[derive(Clone, Debug)]
+enum NacmRuleType {
+    Any,
+    ProtocolOperation(String),
+    Notification(String),
+    DataNode(String),
+}
+
+// Cache of the data nodes selected by the paths of the data node rules.
+type PathCache = HashMap<String, HashSet<*mut ffi::lyd_node>>;
+
+// ===== impl NacmOperation =====
+
+impl NacmOperation {
+    // Parse the name of an access operation.
+    fn parse(name: &str) -> Option<NacmOperation> {
+        match name {
+            "create" => Some(NacmOperation::Create),
+            "read" => Some(NacmOperation::Read),
+            "update" => Some(NacmOperation::Update),
+            "delete" => Some(NacmOperation::Delete),
+            "exec" => Some(NacmOperation::Exec),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the operation writes data.
+    pub fn is_write(&self) -> bool {
+        matches!(
+            self,
+            NacmOperation::Create
+                | NacmOperation::Update
+                | NacmOperation::Delete
+        )
+    }
+}
+
+// ===== impl NacmAction =====
+
+impl NacmAction {
+    // Parse the name of a rule action.
+    fn parse(name: &str) -> NacmAction {
+        match name {
+            "permit" => NacmAction::Permit,
+            _ => NacmAction::Deny,
+        }
+    }
+}
+
+// ===== impl Nacm =====
+
+impl Nacm {
+    /// Read the NACM rules from the given data tree.
+    ///
+    /// The `ietf-netconf-acm` module must be loaded in the context of the
+    /// data tree. Missing leaves take their default values, so an empty data
+    /// tree results in the default NACM behavior.
+    pub fn from_tree(dtree: &DataTree<'_>) -> Result<Nacm> {
+        let mut nacm = Nacm::default();
+
+        let dnode = match dtree.find_xpath(NACM_PATH)?.next() {
+            Some(dnode) => dnode,
+            None => return Ok(nacm),
+        };
+        for child in dnode.children() {
+            let value = child.value_canonical();
+            match (child.schema().name(), value.as_deref()) {
+                ("enable-nacm", Some(value)) => nacm.enabled = value == "true",
+                ("read-default", Some(value)) => {
+                    nacm.read_default = NacmAction::parse(value)
+                }
+                ("write-default", Some(value)) => {
+                    nacm.write_default = NacmAction::parse(value)
+                }
+                ("exec-default", Some(value)) => {
+                    nacm.exec_default = NacmAction::parse(value)
+                }
+                ("enable-external-groups", Some(value)) => {
+                    nacm.external_groups = value == "true"
+                }
+                ("groups", _) => {
+                    nacm.groups = child
+                        .children()
+                        .map(|group| read_group(&group))
+                        .collect();
+                }
+                ("rule-list", _) => {
+                    nacm.rule_lists.push(read_rule_list(&child))
+                }
+                _ => (),
+            }
+        }
+
+        Ok(nacm)
+    }
+
+    /// Returns whether access control is enforced.
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Create an access control session for the given user, who is a member
+    /// of the given groups in addition to the NACM groups listing it (e.g.
+    /// groups provided by the transport layer).
+    ///
+    /// The given groups are ignored if external groups are disabled.
+    pub fn session(
+        &self,
+        user: &str,
+        external_groups: &[&str],
+    ) -> NacmSession<'_> {
+        let mut groups = self
+            .groups
+            .iter()
+            .filter(|group| group.users.iter().any(|name| name == user))
+            .map(|group| group.name.clone())
+            .collect::<Vec<_>>();
+        if self.external_groups {
+            groups
+                .extend(external_groups.iter().map(|group| group.to_string()));
+        }
+
+        NacmSession {
+            nacm: self,
+            user: user.to_owned(),
+            groups,
+        }
+    }
+
+    // Returns the rule lists applicable to the given groups.
+    fn rule_lists<'b>(
+        &'b self,
+        groups: &'b [String],
+    ) -> impl Iterator<Item = &'b NacmRuleList> {
+        self.rule_lists.iter().filter(move |rule_list| {
+            rule_list
+                .groups
+                .iter()
+                .any(|group| group == "*" || groups.contains(group))
+        })
+    }
+}
+
+impl Default for Nacm {
+    fn default() -> Nacm {
+        Nacm {
+            enabled: true,
+            read_default: NacmAction::Permit,
+            write_default: NacmAction::Deny,
+            exec_default: NacmAction::Permit,
+            external_groups: true,
+            groups: Vec::new(),
+            rule_lists: Vec::new(),
+        }
+    }
+}
+
+// ===== impl NacmSession =====
+
+impl NacmSession<'_> {
+    /// Returns the name of the user.
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    /// Returns the groups the user is a member of.
+    pub fn groups(&self) -> &[String] {
+        &self.groups
+    }
+
+    /// Check the access to the given data node for the given operation.
+    pub fn check_data(
+        &self,
+        dnode: &DataNodeRef<'_>,
+        operation: NacmOperation,
+    ) -> Result<NacmAction> {
+        let mut cache = PathCache::new();
+        self.check_data_cached(dnode, operation, &mut cache)
+    }
+
+    /// Check the permission to invoke the given RPC or action.
+    ///
+    /// The `close-session` operation is always permitted, and the operations
+    /// marked with the `nacm:default-deny-all` extension are denied unless a
+    /// rule permits them (RFC 8341, section 3.4.4).
+    pub fn check_rpc(&self, snode: &SchemaNode<'_>) -> NacmAction {
+        if !self.nacm.enabled {
+            return NacmAction::Permit;
+        }
+        let module = snode.module();
+        if module.name() == "ietf-netconf" && snode.name() == "close-session" {
+            return NacmAction::Permit;
+        }
+
+        for rule_list in self.nacm.rule_lists(&self.groups) {
+            for rule in &rule_list.rules {
+                let matches = match &rule.rule_type {
+                    NacmRuleType::Any => true,
+                    NacmRuleType::ProtocolOperation(name) => {
+                        name == "*" || name == snode.name()
+                    }
+                    _ => false,
+                };
+                if matches
+                    && rule.matches_module(module.name())
+                    && rule.matches_operation(NacmOperation::Exec)
+                {
+                    return rule.action;
+                }
+            }
+        }
+
+        if has_extension(snode, "default-deny-all") {
+            return NacmAction::Deny;
+        }
+        self.nacm.exec_default
+    }
+
+    /// Check the permission to receive the given notification.
+    ///
+    /// The `replayComplete` and `notificationComplete` notifications are
+    /// always permitted, and the notifications marked with the
+    /// `nacm:default-deny-all` extension are denied unless a rule permits
+    /// them (RFC 8341, section 3.4.6).
+    pub fn check_notification(&self, snode: &SchemaNode<'_>) -> NacmAction {
+        if !self.nacm.enabled {
+            return NacmAction::Permit;
+        }
+        let module = snode.module();
+        if module.name() == "nc-notifications"
+            && matches!(snode.name(), "replayComplete" | "notificationComplete")
+        {
+            return NacmAction::Permit;
+        }
+
+        for rule_list in self.nacm.rule_lists(&self.groups) {
+            for rule in &rule_list.rules {
+                let matches = match &rule.rule_type {
+                    NacmRuleType::Any => true,
+                    NacmRuleType::Notification(name) => {
+                        name == "*" || name == snode.name()
+                    }
+                    _ => false,
+                };
+                if matches
+                    && rule.matches_module(module.name())
+                    && rule.matches_operation(NacmOperation::Read)
+                {
+                    return rule.action;
+                }
+            }
+        }
+
+        if has_extension(snode, "default-deny-all") {
+            return NacmAction::Deny;
+        }
+        self.nacm.read_default
+    }
+
+    /// Remove from the given data tree the nodes the user isn't allowed to
+    /// read (along with their descendants), as done for the replies to
+    /// retrieval operations.
+    ///
+    /// List keys are never removed on their own.
+    pub fn filter_read(&self, dtree: &mut DataTree<'_>) -> Result<()> {
+        if !self.nacm.enabled {
+            return Ok(());
+        }
+
+        let mut cache = PathCache::new();
+        let mut denied = Vec::new();
+        let mut denied_raws = HashSet::new();
+        for dnode in dtree.traverse() {
+            if dnode.is_opaque() || dnode.schema().is_list_key() {
+                continue;
+            }
+            if dnode
+                .parent()
+                .map(|parent| denied_raws.contains(&parent.as_raw()))
+                .unwrap_or(false)
+            {
+                denied_raws.insert(dnode.as_raw());
+                continue;
+            }
+            let action = self.check_data_cached(
+                &dnode,
+                NacmOperation::Read,
+                &mut cache,
+            )?;
+            if action == NacmAction::Deny {
+                denied_raws.insert(dnode.as_raw());
+                denied.push(dnode.path());
+            }
+        }
+
+        for path in denied.iter().rev() {
+            dtree.remove(path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Authorize the changes of the given diff (e.g. the result of an edit),
+    /// returning an access denied error for the first change the user isn't
+    /// allowed to make.
+    ///
+    /// All the nodes of created and deleted subtrees are checked, as each of
+    /// them is written (RFC 8341, section 3.4.5).
+    pub fn authorize_diff(&self, diff: &DataDiff<'_>) -> Result<()> {
+        if !self.nacm.enabled {
+            return Ok(());
+        }
+
+        let mut cache = PathCache::new();
+        let mut checked = HashSet::new();
+        for (op, dnode) in diff.iter() {
+            let (operation, dnodes) = match op {
+                DataDiffOp::Create => {
+                    (NacmOperation::Create, dnode.traverse().collect())
+                }
+                DataDiffOp::Delete => {
+                    (NacmOperation::Delete, dnode.traverse().collect())
+                }
+                DataDiffOp::Replace => (NacmOperation::Update, vec![dnode]),
+            };
+            for dnode in dnodes {
+                if dnode.is_opaque() || !checked.insert(dnode.as_raw()) {
+                    continue;
+                }
+                let action =
+                    self.check_data_cached(&dnode, operation, &mut cache)?;
+                if action == NacmAction::Deny {
+                    return Err(Error {
+                        errcode: ffi::LY_ERR::LY_EDENIED,
+                        msg: Some(format!(
+                            "Access denied to user \"{}\"",
+                            self.user
+                        )),
+                        path: Some(dnode.path()),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Check the access to the given data node, caching the nodes selected by
+    // the paths of the data node rules.
+    fn check_data_cached(
+        &self,
+        dnode: &DataNodeRef<'_>,
+        operation: NacmOperation,
+        cache: &mut PathCache,
+    ) -> Result<NacmAction> {
+        if !self.nacm.enabled {
+            return Ok(NacmAction::Permit);
+        }
+
+        let snode = dnode.schema();
+        let module = snode.module();
+        for rule_list in self.nacm.rule_lists(&self.groups) {
+            for rule in &rule_list.rules {
+                if !rule.matches_module(module.name())
+                    || !rule.matches_operation(operation)
+                {
+                    continue;
+                }
+                let matches = match &rule.rule_type {
+                    NacmRuleType::Any => true,
+                    NacmRuleType::DataNode(path) => {
+                        matches_path(dnode, path, cache)?
+                    }
+                    _ => false,
+                };
+                if matches {
+                    return Ok(rule.action);
+                }
+            }
+        }
+
+        // Apply the defaults set by the NACM extensions.
+        if has_extension(&snode, "default-deny-all")
+            || (operation.is_write()
+                && has_extension(&snode, "default-deny-write"))
+        {
+            return Ok(NacmAction::Deny);
+        }
+
+        Ok(match operation {
+            NacmOperation::Read => self.nacm.read_default,
+            NacmOperation::Exec => self.nacm.exec_default,
+            _ => self.nacm.write_default,
+        })
+    }
+}
+
+// ===== impl NacmRule =====
+
+impl NacmRule {
+    // Returns whether the rule applies to the given module.
+    fn matches_module(&self, module: &str) -> bool {
+        self.module_name == "*" || self.module_name == module
+    }
+
+    // Returns whether the rule applies to the given operation.
+    fn matches_operation(&self, operation: NacmOperation) -> bool {
+        self.operations
+            .as_ref()
+            .map(|operations| operations.contains(&operation))
+            .unwrap_or(true)
+    }
+}
+
+// ===== helper functions =====
+
+// Read a NACM group.
+fn read_group(dnode: &DataNodeRef<'_>) -> NacmGroup {
+    let mut group = NacmGroup {
+        name: String::new(),
+        users: Vec::new(),
+    };
+    for child in dnode.children() {
+        let value = child.value_canonical().unwrap_or_default();
+        match child.schema().name() {
+            "name" => group.name = value,
+            "user-name" => group.users.push(value),
+            _ => (),
+        }
+    }
+    group
+}
+
+// Read a NACM rule list.
+fn read_rule_list(dnode: &DataNodeRef<'_>) -> NacmRuleList {
+    let mut rule_list = NacmRuleList {
+        groups: Vec::new(),
+        rules: Vec::new(),
+    };
+    for child in dnode.children() {
+        match child.schema().name() {
+            "group" => rule_list
+                .groups
+                .push(child.value_canonical().unwrap_or_default()),
+            "rule" => rule_list.rules.push(read_rule(&child)),
+            _ => (),
+        }
+    }
+    rule_list
+}
+
+// Read a NACM rule.
+fn read_rule(dnode: &DataNodeRef<'_>) -> NacmRule {
+    let mut rule = NacmRule {
+        module_name: "*".to_owned(),
+        rule_type: NacmRuleType::Any,
+        operations: None,
+        action: NacmAction::Deny,
+    };
+    for child in dnode.children() {
+        let value = child.value_canonical().unwrap_or_default();
+        match child.schema().name() {
+            "module-name" => rule.module_name = value,
+            "rpc-name" => {
+                rule.rule_type = NacmRuleType::ProtocolOperation(value)
+            }
+            "notification-name" => {
+                rule.rule_type = NacmRuleType::Notification(value)
+            }
+            "path" => rule.rule_type = NacmRuleType::DataNode(value),
+            "access-operations" if value != "*" => {
+                rule.operations = Some(
+                    value
+                        .split_whitespace()
+                        .filter_map(NacmOperation::parse)
+                        .collect(),
+                );
+            }
+            "action" => rule.action = NacmAction::parse(&value),
+            _ => (),
+        }
+    }
+    rule
+}
+
+// Returns whether the given data node, or one of its ancestors, is selected
+// by the given path.
+fn matches_path(
+    dnode: &DataNodeRef<'_>,
+    path: &str,
+    cache: &mut PathCache,
+) -> Result<bool> {
+    if !cache.contains_key(path) {
+        let nodes = dnode
+            .tree()
+            .find_xpath(path)?
+            .map(|dnode| dnode.as_raw())
+            .collect();
+        cache.insert(path.to_owned(), nodes);
+    }
+    let nodes = &cache[path];
+
+    Ok(dnode
+        .inclusive_ancestors()
+        .any(|dnode| nodes.contains(&dnode.as_raw())))
+}
+
+// Returns whether the given schema node has the given NACM extension.
+fn has_extension(snode: &SchemaNode<'_>, name: &str) -> bool {
+    snode.extensions().any(|ext| {
+        ext.name() == name && ext.module().name() == "ietf-netconf-acm"
+    })
+}
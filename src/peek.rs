@@ -0,0 +1,358 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Context-less inspection of YANG and YIN module sources.
+
+use libyang3_sys as ffi;
+
+use crate::error::{Error, Result};
+
+/// Header information of a YANG module or submodule (see
+/// [`peek_module_info`]).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ModuleInfo {
+    /// Module or submodule name.
+    pub name: String,
+    /// Latest revision, if any.
+    pub revision: Option<String>,
+    /// Names of the imported modules.
+    pub imports: Vec<String>,
+    /// Name of the module the submodule belongs to (submodules only).
+    pub belongs_to: Option<String>,
+}
+
+// Token of the YANG syntax.
+#[derive(Debug, Eq, PartialEq)]
+enum Token<'s> {
+    String(&'s str),
+    Semicolon,
+    LeftBrace,
+    RightBrace,
+}
+
+// Tokenizer of the YANG syntax.
+struct Tokenizer<'s> {
+    rest: &'s str,
+}
+
+/// Extract the header information (name, latest revision and imports) of
+/// the given YANG or YIN module source, without building a context.
+///
+/// Only the module header is scanned, and the source isn't validated, so
+/// this is much faster than parsing the module. The format is detected
+/// from the source (YIN sources start with an XML element).
+pub fn peek_module_info(source: &str) -> Result<ModuleInfo> {
+    match source.trim_start().starts_with('<') {
+        true => peek_yin(source),
+        false => peek_yang(source),
+    }
+}
+
+/// Returns the line of the statement reached by following the given path of
+/// (keyword, argument) pairs from the top of the given YANG module source.
+pub(crate) fn find_statement(
+    source: &str,
+    path: &[(&str, Option<&str>)],
+) -> Option<usize> {
+    let mut tokens = Tokenizer::new(source);
+    match tokens.next_keyword().ok()? {
+        Some("module") | Some("submodule") => (),
+        _ => return None,
+    }
+    if !tokens.argument().ok()?.1 {
+        return None;
+    }
+
+    let mut index = 0;
+    while let Some(keyword) = tokens.next_keyword().ok()? {
+        let (argument, block) = tokens.concatenated_argument().ok()?;
+        let (expected_keyword, expected_argument) = *path.get(index)?;
+        if keyword == expected_keyword
+            && argument.as_deref() == expected_argument
+        {
+            if index + 1 == path.len() {
+                let offset =
+                    keyword.as_ptr() as usize - source.as_ptr() as usize;
+                return Some(source[..offset].matches('\n').count() + 1);
+            }
+            if !block {
+                return None;
+            }
+            index += 1;
+        } else if block {
+            tokens.skip_block().ok()?;
+        }
+    }
+
+    None
+}
+
+// ===== impl Tokenizer =====
+
+impl<'s> Tokenizer<'s> {
+    fn new(source: &'s str) -> Tokenizer<'s> {
+        Tokenizer { rest: source }
+    }
+
+    // Skip whitespace and comments.
+    fn skip_separators(&mut self) -> Result<()> {
+        loop {
+            self.rest = self.rest.trim_start();
+            if let Some(rest) = self.rest.strip_prefix("//") {
+                self.rest =
+                    rest.find('\n').map(|pos| &rest[pos..]).unwrap_or("");
+            } else if let Some(rest) = self.rest.strip_prefix("/*") {
+                let end = rest
+                    .find("*/")
+                    .ok_or_else(|| invalid_source("unterminated comment"))?;
+                self.rest = &rest[end + 2..];
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    // Returns the next token, if any.
+    fn next_token(&mut self) -> Result<Option<Token<'s>>> {
+        self.skip_separators()?;
+
+        let mut chars = self.rest.chars();
+        let token = match chars.next() {
+            None => return Ok(None),
+            Some(';') => Token::Semicolon,
+            Some('{') => Token::LeftBrace,
+            Some('}') => Token::RightBrace,
+            Some(quote @ ('"' | '\'')) => {
+                let rest = &self.rest[1..];
+                let mut escape = false;
+                let end = rest
+                    .char_indices()
+                    .find(|(_, c)| {
+                        if escape {
+                            escape = false;
+                            return false;
+                        }
+                        escape = quote == '"' && *c == '\\';
+                        *c == quote
+                    })
+                    .map(|(pos, _)| pos)
+                    .ok_or_else(|| invalid_source("unterminated string"))?;
+                self.rest = &rest[end + 1..];
+                return Ok(Some(Token::String(&rest[..end])));
+            }
+            Some(_) => {
+                let end = self
+                    .rest
+                    .find(|c: char| {
+                        c.is_whitespace() || matches!(c, ';' | '{' | '}')
+                    })
+                    .unwrap_or(self.rest.len());
+                let string = &self.rest[..end];
+                self.rest = &self.rest[end..];
+                return Ok(Some(Token::String(string)));
+            }
+        };
+        self.rest = &self.rest[1..];
+
+        Ok(Some(token))
+    }
+
+    // Returns the next statement keyword, or `None` at the end of the
+    // current block.
+    fn next_keyword(&mut self) -> Result<Option<&'s str>> {
+        match self.next_token()? {
+            Some(Token::String(keyword)) => Ok(Some(keyword)),
+            Some(Token::RightBrace) | None => Ok(None),
+            Some(_) => Err(invalid_source("unexpected token")),
+        }
+    }
+
+    // Returns the argument of the current statement (concatenated strings
+    // are reduced to the first one) and whether the statement has a block
+    // of substatements.
+    fn argument(&mut self) -> Result<(Option<&'s str>, bool)> {
+        let mut argument = None;
+        loop {
+            match self.next_token()? {
+                Some(Token::String(string)) => {
+                    argument.get_or_insert(string);
+                }
+                Some(Token::Semicolon) => return Ok((argument, false)),
+                Some(Token::LeftBrace) => return Ok((argument, true)),
+                _ => return Err(invalid_source("unexpected token")),
+            }
+        }
+    }
+
+    // Returns the argument of the current statement (with concatenated
+    // strings joined) and whether the statement has a block of
+    // substatements.
+    fn concatenated_argument(&mut self) -> Result<(Option<String>, bool)> {
+        let mut argument: Option<String> = None;
+        loop {
+            match self.next_token()? {
+                Some(Token::String("+")) if argument.is_some() => (),
+                Some(Token::String(string)) => {
+                    argument.get_or_insert_with(String::new).push_str(string);
+                }
+                Some(Token::Semicolon) => return Ok((argument, false)),
+                Some(Token::LeftBrace) => return Ok((argument, true)),
+                _ => return Err(invalid_source("unexpected token")),
+            }
+        }
+    }
+
+    // Skip the rest of the current block of substatements.
+    fn skip_block(&mut self) -> Result<()> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next_token()? {
+                Some(Token::LeftBrace) => depth += 1,
+                Some(Token::RightBrace) => depth -= 1,
+                Some(_) => (),
+                None => return Err(invalid_source("unterminated block")),
+            }
+        }
+        Ok(())
+    }
+}
+
+// ===== helper functions =====
+
+// Extract the header information of a YANG source.
+fn peek_yang(source: &str) -> Result<ModuleInfo> {
+    let mut tokens = Tokenizer::new(source);
+    let mut info = ModuleInfo::default();
+
+    match tokens.next_keyword()? {
+        Some("module") | Some("submodule") => (),
+        _ => return Err(invalid_source("missing module statement")),
+    }
+    match tokens.argument()? {
+        (Some(name), true) => info.name = name.to_owned(),
+        _ => return Err(invalid_source("invalid module statement")),
+    }
+
+    while let Some(keyword) = tokens.next_keyword()? {
+        let (argument, block) = tokens.argument()?;
+        match keyword {
+            "import" => info.imports.extend(argument.map(ToOwned::to_owned)),
+            "belongs-to" => info.belongs_to = argument.map(ToOwned::to_owned),
+            "revision" => {
+                if argument > info.revision.as_deref() {
+                    info.revision = argument.map(ToOwned::to_owned);
+                }
+            }
+            // Header, linkage and meta statements, and extensions.
+            "yang-version" | "namespace" | "prefix" | "include"
+            | "organization" | "contact" | "description" | "reference" => (),
+            _ if keyword.contains(':') => (),
+            // The body statements follow the revisions.
+            _ => break,
+        }
+        if block {
+            tokens.skip_block()?;
+        }
+    }
+
+    Ok(info)
+}
+
+// Extract the header information of a YIN source.
+fn peek_yin(source: &str) -> Result<ModuleInfo> {
+    let mut info = ModuleInfo::default();
+    let mut root = true;
+
+    let mut rest = source;
+    while let Some(pos) = rest.find('<') {
+        rest = &rest[pos + 1..];
+        if rest.starts_with('?')
+            || rest.starts_with('!')
+            || rest.starts_with('/')
+        {
+            continue;
+        }
+        let end = rest.find('>').unwrap_or(rest.len());
+        let tag = &rest[..end];
+        rest = &rest[end..];
+
+        // Ignore the namespace prefix of the element.
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or("");
+        let name = name.rsplit(':').next().unwrap_or(name);
+        if root {
+            if name != "module" && name != "submodule" {
+                return Err(invalid_source("missing module element"));
+            }
+            info.name = yin_attribute(tag, "name")
+                .ok_or_else(|| invalid_source("missing module name"))?
+                .to_owned();
+            root = false;
+            continue;
+        }
+        match name {
+            "import" => {
+                info.imports.extend(
+                    yin_attribute(tag, "module").map(ToOwned::to_owned),
+                );
+            }
+            "belongs-to" => {
+                info.belongs_to =
+                    yin_attribute(tag, "module").map(ToOwned::to_owned);
+            }
+            "revision" => {
+                let date = yin_attribute(tag, "date");
+                if date > info.revision.as_deref() {
+                    info.revision = date.map(ToOwned::to_owned);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    match root {
+        true => Err(invalid_source("missing module element")),
+        false => Ok(info),
+    }
+}
+
+// Returns the value of the given attribute of a YIN element.
+fn yin_attribute<'s>(tag: &'s str, attribute: &str) -> Option<&'s str> {
+    let mut rest = tag;
+    while let Some(pos) = rest.find(attribute) {
+        let preceded = rest[..pos]
+            .chars()
+            .next_back()
+            .map(char::is_whitespace)
+            .unwrap_or(false);
+        rest = rest[pos + attribute.len()..].trim_start();
+        if !preceded {
+            continue;
+        }
+        let value = match rest.strip_prefix('=') {
+            Some(value) => value.trim_start(),
+            None => continue,
+        };
+        let quote = value.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value = &value[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+// Returns an error for a malformed module source.
+fn invalid_source(reason: &str) -> Error {
+    Error {
+        errcode: ffi::LY_ERR::LY_EINVAL,
+        msg: Some(format!("Invalid module source: {}", reason)),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,341 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Repositories of YANG modules indexed by name and revision, and loading of
+//! vendor bundles laid out like the YangModels/yang repository.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::context::{Context, ContextFlags, ModuleCapability};
+use crate::error::{io_error, Result};
+use crate::peek::{peek_module_info, ModuleInfo};
+use crate::schema::SchemaInputFormat;
+
+/// Repository of YANG modules and submodules, indexed by their name and
+/// revision as declared in their sources (regardless of their file names).
+///
+/// A repository can be attached to a context (see
+/// [`Context::set_module_repository`]), so that imported and included
+/// modules are loaded from it.
+///
+/// [`Context::set_module_repository`]: crate::context::Context::set_module_repository
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModuleRepository {
+    modules: BTreeMap<String, BTreeMap<Option<String>, RepositoryModule>>,
+}
+
+/// Module or submodule stored in a repository.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepositoryModule {
+    info: ModuleInfo,
+    format: SchemaInputFormat,
+    source: RepositorySource,
+}
+
+// Location of the source of a module.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum RepositorySource {
+    File(PathBuf),
+    Memory(String),
+}
+
+// ===== impl ModuleRepository =====
+
+impl ModuleRepository {
+    /// Create a new empty repository.
+    pub fn new() -> ModuleRepository {
+        Default::default()
+    }
+
+    /// Index all the YANG and YIN files found in the given directory and
+    /// its subdirectories, returning the number of indexed modules.
+    /// Symbolic links to directories aren't followed.
+    ///
+    /// Files whose module header can't be parsed are skipped. When several
+    /// files declare the same module revision, the first one is kept.
+    pub fn index_dir<P: AsRef<Path>>(&mut self, dir: P) -> Result<usize> {
+        let mut count = 0;
+        let mut dirs = vec![dir.as_ref().to_owned()];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = std::fs::read_dir(&dir)
+                .and_then(|entries| {
+                    entries.collect::<std::io::Result<Vec<_>>>()
+                })
+                .map_err(io_error)?;
+            // Index the files in a deterministic order.
+            entries.sort_by_key(|entry| entry.file_name());
+
+            for entry in entries {
+                let path = entry.path();
+                // Symbolic links to directories aren't followed, as they
+                // could form cycles.
+                if entry.file_type().map_err(io_error)?.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                if !is_schema_file(&path) {
+                    continue;
+                }
+                if self.add_file(&path).is_ok() {
+                    count += 1;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Index the given YANG or YIN file.
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(io_error)?;
+        let info = peek_module_info(&source)?;
+        let format = source_format(&source);
+        self.insert(info, format, RepositorySource::File(path.to_owned()));
+        Ok(())
+    }
+
+    /// Index the given YANG or YIN source (e.g. extracted from an archive).
+    pub fn add_source(&mut self, source: &str) -> Result<()> {
+        let info = peek_module_info(source)?;
+        let format = source_format(source);
+        self.insert(info, format, RepositorySource::Memory(source.to_owned()));
+        Ok(())
+    }
+
+    /// Returns an iterator over the names of the indexed modules and
+    /// submodules.
+    pub fn modules(&self) -> impl Iterator<Item = &str> {
+        self.modules.keys().map(String::as_str)
+    }
+
+    /// Returns an iterator over the indexed revisions of the given module,
+    /// from the oldest to the latest.
+    pub fn revisions<'b>(
+        &'b self,
+        name: &str,
+    ) -> impl Iterator<Item = Option<&'b str>> {
+        self.modules
+            .get(name)
+            .into_iter()
+            .flat_map(|revisions| revisions.keys())
+            .map(Option::as_deref)
+    }
+
+    /// Returns the latest indexed revision of the given module.
+    pub fn latest(&self, name: &str) -> Option<&RepositoryModule> {
+        self.modules
+            .get(name)
+            .and_then(|revisions| revisions.values().next_back())
+    }
+
+    /// Returns the given revision of the given module, or its latest
+    /// revision if no revision is given.
+    pub fn get(
+        &self,
+        name: &str,
+        revision: Option<&str>,
+    ) -> Option<&RepositoryModule> {
+        match revision {
+            Some(revision) => self.modules.get(name).and_then(|revisions| {
+                revisions.get(&Some(revision.to_owned()))
+            }),
+            None => self.latest(name),
+        }
+    }
+
+    fn insert(
+        &mut self,
+        info: ModuleInfo,
+        format: SchemaInputFormat,
+        source: RepositorySource,
+    ) {
+        self.modules
+            .entry(info.name.clone())
+            .or_default()
+            .entry(info.revision.clone())
+            .or_insert(RepositoryModule {
+                info,
+                format,
+                source,
+            });
+    }
+}
+
+/// Find the platform descriptors (`*-capabilities.xml` files) of a vendor
+/// bundle laid out like the YangModels/yang repository, searching the given
+/// directory and its subdirectories (not following symbolic links to
+/// directories).
+pub fn find_platforms<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>> {
+    let mut platforms = Vec::new();
+    let mut dirs = vec![dir.as_ref().to_owned()];
+    while let Some(dir) = dirs.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .and_then(|entries| entries.collect::<std::io::Result<Vec<_>>>())
+            .map_err(io_error)?;
+        for entry in entries {
+            let path = entry.path();
+            // Symbolic links to directories aren't followed, as they could
+            // form cycles.
+            if entry.file_type().map_err(io_error)?.is_dir() {
+                dirs.push(path);
+            } else if is_platform_descriptor(&path) {
+                platforms.push(path);
+            }
+        }
+    }
+    platforms.sort();
+
+    Ok(platforms)
+}
+
+/// Parse a platform descriptor (the NETCONF `<hello>` message saved in a
+/// `*-capabilities.xml` file), returning the advertised modules.
+pub fn parse_platform(xml: &str) -> Result<Vec<ModuleCapability>> {
+    ModuleCapability::parse_hello(
+        xml_elements(xml, "capability").map(|uri| uri.trim()),
+    )
+}
+
+/// Create a context implementing the modules advertised by the given
+/// platform descriptor, with their features and deviations.
+///
+/// The modules are loaded from the directory of the descriptor and its
+/// subdirectories, which are indexed into a repository attached to the
+/// context. Advertised modules already implemented in the new context (e.g.
+/// the libyang internal modules) are left as they are.
+pub fn load_platform<P: AsRef<Path>>(
+    descriptor: P,
+    options: ContextFlags,
+) -> Result<Context> {
+    let descriptor = descriptor.as_ref();
+    let xml = std::fs::read_to_string(descriptor).map_err(io_error)?;
+    let capabilities = parse_platform(&xml)?;
+
+    let mut repository = ModuleRepository::new();
+    if let Some(dir) = descriptor.parent() {
+        let dir = match dir.as_os_str().is_empty() {
+            true => Path::new("."),
+            false => dir,
+        };
+        repository.index_dir(dir)?;
+    }
+
+    let mut context = Context::new(options)?;
+    context.set_module_repository(Arc::new(repository));
+    for capability in &capabilities {
+        if context.get_module_implemented(&capability.name).is_some() {
+            continue;
+        }
+        context.load_capability(capability)?;
+    }
+
+    Ok(context)
+}
+
+// ===== impl RepositoryModule =====
+
+impl RepositoryModule {
+    /// Returns the header information of the module.
+    pub fn info(&self) -> &ModuleInfo {
+        &self.info
+    }
+
+    /// Returns the name of the module.
+    pub fn name(&self) -> &str {
+        &self.info.name
+    }
+
+    /// Returns the revision of the module, if any.
+    pub fn revision(&self) -> Option<&str> {
+        self.info.revision.as_deref()
+    }
+
+    /// Returns whether this is a submodule.
+    pub fn is_submodule(&self) -> bool {
+        self.info.belongs_to.is_some()
+    }
+
+    /// Returns the format of the module source.
+    pub fn format(&self) -> SchemaInputFormat {
+        self.format
+    }
+
+    /// Returns the path of the module file, unless it was added from memory.
+    pub fn path(&self) -> Option<&Path> {
+        match &self.source {
+            RepositorySource::File(path) => Some(path),
+            RepositorySource::Memory(_) => None,
+        }
+    }
+
+    /// Returns the source of the module.
+    pub fn source(&self) -> Result<String> {
+        match &self.source {
+            RepositorySource::File(path) => {
+                std::fs::read_to_string(path).map_err(io_error)
+            }
+            RepositorySource::Memory(source) => Ok(source.clone()),
+        }
+    }
+}
+
+// ===== helper functions =====
+
+// Returns whether the given file is a YANG or YIN file, based on its
+// extension.
+fn is_schema_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("yang") | Some("yin")
+    )
+}
+
+// Returns whether the given file is a platform descriptor, based on its name.
+fn is_platform_descriptor(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.ends_with("-capabilities.xml"))
+        .unwrap_or(false)
+}
+
+// Returns an iterator over the text content of the XML elements of the given
+// name, ignoring their namespace prefix.
+fn xml_elements<'s>(
+    xml: &'s str,
+    name: &'s str,
+) -> impl Iterator<Item = &'s str> + 's {
+    let mut rest = xml;
+    std::iter::from_fn(move || {
+        while let Some(pos) = rest.find('<') {
+            rest = &rest[pos + 1..];
+            let end = rest.find('>')?;
+            let tag = &rest[..end];
+            rest = &rest[end + 1..];
+
+            let tag_name = tag.split_whitespace().next().unwrap_or("");
+            let tag_name = tag_name.rsplit(':').next().unwrap_or(tag_name);
+            if tag_name != name || tag.ends_with('/') {
+                continue;
+            }
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            rest = &rest[end..];
+            return Some(text);
+        }
+        None
+    })
+}
+
+// Returns the format of a schema source, based on its content.
+fn source_format(source: &str) -> SchemaInputFormat {
+    match source.trim_start().starts_with('<') {
+        true => SchemaInputFormat::YIN,
+        false => SchemaInputFormat::YANG,
+    }
+}
@@ -0,0 +1,172 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! YANG RPC/action dispatching.
+
+use libyang3_sys as ffi;
+use std::collections::HashMap;
+
+use crate::context::Context;
+use crate::data::{DataNodeRef, DataTree, DataTreeOwningRef};
+use crate::error::Error;
+use crate::schema::{SchemaNodeKind, SchemaPathFormat};
+
+/// RPC/action handler.
+///
+/// The handler receives the operation node containing the input parameters
+/// and the reply builder used to add the output parameters.
+pub type RpcHandler<'a, E> = Box<
+    dyn Fn(&DataNodeRef<'_>, &mut RpcOutput<'a, '_>) -> Result<(), E>
+        + Send
+        + Sync
+        + 'a,
+>;
+
+/// Dispatcher of RPCs and actions to their registered handlers.
+///
+/// Handlers are keyed by the schema path of their RPC/action nodes.
+pub struct RpcDispatcher<'a, E> {
+    context: &'a Context,
+    handlers: HashMap<String, RpcHandler<'a, E>>,
+}
+
+/// Builder of the output parameters of an RPC/action reply.
+#[derive(Debug)]
+pub struct RpcOutput<'a, 'b> {
+    reply: &'b mut DataTreeOwningRef<'a>,
+    op_path: String,
+}
+
+/// RPC/action dispatching error.
+#[derive(Debug)]
+pub enum RpcError<E> {
+    /// libyang error.
+    Yang(Error),
+    /// The operation tree doesn't contain any RPC/action node.
+    NoOperation,
+    /// No handler is registered for the RPC/action with the given schema path.
+    NoHandler(String),
+    /// Error returned by the RPC/action handler.
+    Handler(E),
+}
+
+// ===== impl RpcDispatcher =====
+
+impl<'a, E> RpcDispatcher<'a, E> {
+    /// Create a new dispatcher with no registered handlers.
+    pub fn new(context: &'a Context) -> RpcDispatcher<'a, E> {
+        RpcDispatcher {
+            context,
+            handlers: Default::default(),
+        }
+    }
+
+    /// Register a handler for the RPC/action identified by the given schema
+    /// path (e.g. `/ietf-routing:routing/ribs/rib/active-route`).
+    ///
+    /// Any handler previously registered for the same RPC/action is replaced.
+    pub fn register<F>(&mut self, path: &str, handler: F) -> Result<(), Error>
+    where
+        F: Fn(&DataNodeRef<'_>, &mut RpcOutput<'a, '_>) -> Result<(), E>
+            + Send
+            + Sync
+            + 'a,
+    {
+        let snode = self.context.find_path(path)?;
+        if !matches!(snode.kind(), SchemaNodeKind::Rpc | SchemaNodeKind::Action)
+        {
+            return Err(Error {
+                errcode: ffi::LY_ERR::LY_EINVAL,
+                msg: Some(format!("{} is not an RPC or action", path)),
+                ..Default::default()
+            });
+        }
+
+        let path = snode.path(SchemaPathFormat::DATA);
+        self.handlers.insert(path, Box::new(handler));
+        Ok(())
+    }
+
+    /// Dispatch the RPC/action contained in the given operation tree (e.g.
+    /// parsed using [`DataTree::parse_op_string`]) to its registered handler.
+    ///
+    /// Returns the reply, referencing the RPC/action node whose children are
+    /// the output parameters added by the handler.
+    pub fn dispatch(
+        &self,
+        op_tree: &DataTree<'a>,
+    ) -> Result<DataTreeOwningRef<'a>, RpcError<E>> {
+        let op = op_tree
+            .op_node()
+            .filter(|dnode| {
+                matches!(
+                    dnode.schema().kind(),
+                    SchemaNodeKind::Rpc | SchemaNodeKind::Action
+                )
+            })
+            .ok_or(RpcError::NoOperation)?;
+
+        let path = op.schema().path(SchemaPathFormat::DATA);
+        let handler =
+            self.handlers.get(&path).ok_or(RpcError::NoHandler(path))?;
+
+        let op_path = op.path();
+        let mut reply =
+            DataTreeOwningRef::new_path(self.context, &op_path, None, true)
+                .map_err(RpcError::Yang)?;
+        let mut output = RpcOutput {
+            reply: &mut reply,
+            op_path,
+        };
+        (handler)(&op, &mut output).map_err(RpcError::Handler)?;
+
+        Ok(reply)
+    }
+}
+
+impl<E> std::fmt::Debug for RpcDispatcher<'_, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RpcDispatcher")
+            .field("context", &self.context)
+            .field("handlers", &self.handlers.keys())
+            .finish()
+    }
+}
+
+// ===== impl RpcOutput =====
+
+impl RpcOutput<'_, '_> {
+    /// Create a new output node or modify an existing one, based on a path
+    /// relative to the RPC/action node.
+    pub fn new_path(
+        &mut self,
+        path: &str,
+        value: Option<&str>,
+    ) -> Result<(), Error> {
+        let path = format!("{}/{}", self.op_path, path);
+        // SAFETY: creating nodes never removes the referenced RPC/action
+        // node.
+        unsafe { self.reply.tree_mut() }.new_path(&path, value, true)?;
+        Ok(())
+    }
+}
+
+// ===== impl RpcError =====
+
+impl<E: std::fmt::Display> std::fmt::Display for RpcError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RpcError::Yang(error) => write!(f, "{}", error),
+            RpcError::NoOperation => write!(f, "No RPC or action found"),
+            RpcError::NoHandler(path) => {
+                write!(f, "No handler registered for {}", path)
+            }
+            RpcError::Handler(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug + std::fmt::Display> std::error::Error for RpcError<E> {}
@@ -9,18 +9,20 @@
 use bitflags::bitflags;
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
+use std::collections::HashSet;
 use std::ffi::CString;
 use std::mem;
 use std::os::raw::{c_char, c_void};
 use std::slice;
 
-use crate::context::Context;
-use crate::data::DataTree;
+use crate::context::{Context, ModuleCapability};
+use crate::data::{Data, DataNodeRef, DataTree};
 use crate::error::{Error, Result};
 use crate::iter::{
     Ancestors, Array, Getnext, IterSchemaFlags, NodeIterable, Set, Siblings,
     Traverse,
 };
+use crate::peek::find_statement;
 use crate::utils::*;
 use libyang3_sys as ffi;
 
@@ -38,6 +40,51 @@ pub struct SchemaSubmodule<'a> {
     pub(crate) raw: *mut ffi::lysp_submodule,
 }
 
+/// Parsed (uncompiled) YANG module, retaining the groupings and uses
+/// statements that are erased from compiled schema trees.
+#[derive(Clone, Debug)]
+pub struct SchemaModuleParsed<'a> {
+    context: &'a Context,
+    raw: *mut ffi::lysp_module,
+}
+
+/// Parsed (uncompiled) YANG schema node.
+#[derive(Clone, Debug)]
+pub struct SchemaParsedNode<'a> {
+    context: &'a Context,
+    raw: *mut ffi::lysp_node,
+}
+
+/// Parsed YANG schema node kind.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SchemaParsedNodeKind {
+    Container,
+    Case,
+    Choice,
+    Leaf,
+    LeafList,
+    List,
+    AnyXml,
+    AnyData,
+    Rpc,
+    Input,
+    Output,
+    Action,
+    Notification,
+    Uses,
+    Grouping,
+    Augment,
+}
+
+/// Location of a statement in a YANG source file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceLocation {
+    /// Path of the module or submodule file.
+    pub filepath: String,
+    /// Line of the statement (starting at 1).
+    pub line: usize,
+}
+
 /// Available YANG schema tree structures representing YANG import.
 #[derive(Clone, Debug)]
 pub struct SchemaImport<'a> {
@@ -55,11 +102,15 @@ pub enum SchemaInputFormat {
 }
 
 /// Schema output formats accepted by libyang.
-#[allow(clippy::upper_case_acronyms)]
+#[allow(clippy::upper_case_acronyms, non_camel_case_types)]
 #[repr(u32)]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum SchemaOutputFormat {
     YANG = ffi::LYS_OUTFORMAT::LYS_OUT_YANG,
+    /// YANG-like representation of the compiled module, as seen by the
+    /// context (disabled features pruned, deviations and augmentations
+    /// applied). Not supported for submodules.
+    YANG_COMPILED = ffi::LYS_OUTFORMAT::LYS_OUT_YANG_COMPILED,
     YIN = ffi::LYS_OUTFORMAT::LYS_OUT_YIN,
     TREE = ffi::LYS_OUTFORMAT::LYS_OUT_TREE,
 }
@@ -76,6 +127,24 @@ pub enum SchemaPathFormat {
     DATA = ffi::LYSC_PATH_TYPE::LYSC_PATH_DATA,
 }
 
+/// YANG language version of a module.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum YangVersion {
+    /// YANG 1.0 (RFC 6020).
+    V1_0,
+    /// YANG 1.1 (RFC 7950).
+    V1_1,
+}
+
+/// Conformance type of a module, as reported in the YANG library (RFC 8525).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Conformance {
+    /// The module is implemented.
+    Implement,
+    /// The module is only imported by other modules.
+    Import,
+}
+
 bitflags! {
     /// Schema printer flags.
     pub struct SchemaPrinterFlags: u32 {
@@ -87,6 +156,33 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Schema node lookup options.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct SchemaFindFlags: u32 {
+        /// Apply node access restrictions defined for 'when' and 'must'
+        /// evaluation.
+        const XP_SCHEMA = ffi::LYS_FIND_XP_SCHEMA;
+        /// Search RPC/action output nodes instead of input ones.
+        const XP_OUTPUT = ffi::LYS_FIND_XP_OUTPUT;
+        /// Return error if a path segment matches no nodes, otherwise only
+        /// warning is printed.
+        const NO_MATCH_ERROR = ffi::LYS_FIND_NO_MATCH_ERROR;
+    }
+}
+
+bitflags! {
+    /// Schema node statuses to leave out (along with the subtrees of the
+    /// nodes), e.g. when traversing schema trees or adding implicit nodes.
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+    pub struct StatusFilter: u32 {
+        /// Leave out deprecated nodes.
+        const DEPRECATED = ffi::LYS_STATUS_DEPRC;
+        /// Leave out obsolete nodes.
+        const OBSOLETE = ffi::LYS_STATUS_OBSLT;
+    }
+}
+
 /// Generic YANG schema node.
 #[derive(Clone, Debug)]
 pub struct SchemaNode<'a> {
@@ -113,6 +209,48 @@ pub enum SchemaNodeKind {
     Notification,
 }
 
+/// YANG container semantics (see [`SchemaNode::container_kind`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContainerKind {
+    /// Presence container, whose existence carries meaning.
+    Presence,
+    /// Non-presence container, used only to organize its child nodes.
+    NonPresence,
+}
+
+/// YANG list semantics (see [`SchemaNode::list_kind`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ListKind {
+    /// List whose instances are identified by their keys.
+    Keyed,
+    /// List without keys (only allowed for state data).
+    Keyless,
+}
+
+/// Ordering of list or leaf-list instances (see [`SchemaNode::ordering`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Ordering {
+    /// Instances are ordered by the system ("ordered-by system").
+    System,
+    /// Instances are ordered by the user ("ordered-by user").
+    User,
+}
+
+/// Kind of data a schema node belongs to (see [`SchemaNode::data_context`]).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DataContext {
+    /// Configuration data.
+    Config,
+    /// State data.
+    State,
+    /// RPC or action input (including the RPC or action node itself).
+    RpcInput,
+    /// RPC or action output.
+    RpcOutput,
+    /// Notification content (including the notification node itself).
+    Notification,
+}
+
 /// YANG must substatement.
 #[derive(Clone, Debug)]
 pub struct SchemaStmtMust<'a> {
@@ -127,11 +265,50 @@ pub struct SchemaStmtWhen<'a> {
     _marker: std::marker::PhantomData<&'a Context>,
 }
 
+/// YANG when substatement of an augment or uses statement, as parsed.
+#[derive(Clone, Debug)]
+pub struct SchemaParsedWhen<'a> {
+    raw: *mut ffi::lysp_when,
+    _marker: std::marker::PhantomData<&'a Context>,
+}
+
+/// Result of the evaluation of a must or when condition.
+#[derive(Clone, Debug)]
+pub struct XPathEvaluation<'a> {
+    /// Whether the condition is satisfied.
+    pub result: bool,
+    /// Data nodes instantiating the schema nodes referenced by the
+    /// condition, anywhere in the data tree.
+    ///
+    /// These are found from the atoms of the expression rather than from
+    /// its evaluation, so this is a superset of the nodes the condition
+    /// actually depends on. E.g. for `../type`, the `type` leaves of all
+    /// the list entries are returned, not only the one of the evaluated
+    /// entry.
+    pub referenced_nodes: Vec<DataNodeRef<'a>>,
+}
+
 /// YANG leaf(-list) type.
 #[derive(Clone, Debug)]
 pub struct SchemaLeafType<'a> {
     context: &'a Context,
     raw: *mut ffi::lysc_type,
+    // Leaf or leaf-list using the type, if known.
+    leaf: *mut ffi::lysc_node,
+}
+
+/// YANG identity.
+#[derive(Clone, Debug)]
+pub struct SchemaIdentity<'a> {
+    context: &'a Context,
+    raw: *mut ffi::lysc_ident,
+}
+
+/// YANG typedef.
+#[derive(Clone, Debug)]
+pub struct SchemaTypedef<'a> {
+    context: &'a Context,
+    raw: *mut ffi::lysp_tpdf,
 }
 
 /// YANG extension instance.
@@ -166,6 +343,100 @@ pub enum DataValueType {
     Int64 = 19,
 }
 
+/// Status of a YANG definition.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Status {
+    Current,
+    Deprecated,
+    Obsolete,
+}
+
+/// Item of an enumeration type (see [`SchemaLeafType::enum_items`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EnumItem {
+    /// Name of the item.
+    pub name: String,
+    /// Assigned value.
+    pub value: i32,
+    /// Status of the item.
+    pub status: Status,
+    /// description substatement.
+    pub description: Option<String>,
+    /// reference substatement.
+    pub reference: Option<String>,
+    /// if-feature expressions.
+    pub if_features: Vec<String>,
+}
+
+/// Item of a bits type (see [`SchemaLeafType::bits_items`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BitsItem {
+    /// Name of the item.
+    pub name: String,
+    /// Assigned position.
+    pub position: u32,
+    /// Status of the item.
+    pub status: Status,
+    /// description substatement.
+    pub description: Option<String>,
+    /// reference substatement.
+    pub reference: Option<String>,
+    /// if-feature expressions.
+    pub if_features: Vec<String>,
+}
+
+/// Compiled range or length restriction of a leaf type (see
+/// [`SchemaLeafType::range`] and [`SchemaLeafType::length`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypeRange {
+    /// Intervals of allowed values, in ascending order.
+    pub parts: Vec<RangePart>,
+    /// error-message substatement.
+    pub error_msg: Option<String>,
+    /// error-app-tag substatement.
+    pub error_apptag: Option<String>,
+    /// description substatement.
+    pub description: Option<String>,
+    /// reference substatement.
+    pub reference: Option<String>,
+}
+
+/// Interval of a range or length restriction, with inclusive bounds.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RangePart {
+    /// Interval of a signed integer range.
+    Signed { min: i64, max: i64 },
+    /// Interval of an unsigned integer range or of a length restriction.
+    Unsigned { min: u64, max: u64 },
+    /// Interval of a decimal64 range. The bounds are scaled by 10 to the
+    /// power of the fraction digits (e.g. 1.5 with 2 fraction digits is
+    /// 150).
+    Decimal64 {
+        min: i64,
+        max: i64,
+        fraction_digits: u8,
+    },
+}
+
+/// Compiled pattern restriction of a string leaf type (see
+/// [`SchemaLeafType::patterns`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TypePattern {
+    /// Regular expression (XML Schema syntax).
+    pub expr: String,
+    /// Whether the value must not match the expression (invert-match
+    /// modifier).
+    pub inverted: bool,
+    /// error-message substatement.
+    pub error_msg: Option<String>,
+    /// error-app-tag substatement.
+    pub error_apptag: Option<String>,
+    /// description substatement.
+    pub description: Option<String>,
+    /// reference substatement.
+    pub reference: Option<String>,
+}
+
 /// YANG data value.
 #[derive(Clone, Debug, PartialEq)]
 pub enum DataValue {
@@ -182,6 +453,18 @@ pub enum DataValue {
     Other(String),
 }
 
+// ===== impl StatusFilter =====
+
+impl StatusFilter {
+    // Returns whether the given schema node is left out by the filter.
+    pub(crate) fn excludes(&self, snode: &SchemaNode<'_>) -> bool {
+        (self.contains(StatusFilter::DEPRECATED)
+            && snode.is_status_deprecated())
+            || (self.contains(StatusFilter::OBSOLETE)
+                && snode.is_status_obsolete())
+    }
+}
+
 // ===== impl SchemaModule =====
 
 impl<'a> SchemaModule<'a> {
@@ -238,8 +521,10 @@ impl<'a> SchemaModule<'a> {
 
     /// Make the specific module implemented.
     pub fn set_implemented(&self) -> Result<()> {
+        let snapshot = self.context.snapshot();
         let ret =
             unsafe { ffi::lys_set_implemented(self.raw, std::ptr::null_mut()) };
+        self.context.notify_changes(snapshot);
         if ret != ffi::LY_ERR::LY_SUCCESS {
             return Err(Error::new(self.context));
         }
@@ -252,6 +537,27 @@ impl<'a> SchemaModule<'a> {
         unsafe { (*self.raw).implemented != 0 }
     }
 
+    /// Returns the YANG language version of the module, unless its parsed
+    /// representation was already freed.
+    pub fn yang_version(&self) -> Option<YangVersion> {
+        let parsed = unsafe { (*self.raw).parsed };
+        if parsed.is_null() {
+            return None;
+        }
+        match unsafe { (*parsed).version } as u32 {
+            ffi::LYS_VERSION::LYS_VERSION_1_1 => Some(YangVersion::V1_1),
+            _ => Some(YangVersion::V1_0),
+        }
+    }
+
+    /// Returns the conformance type of the module.
+    pub fn conformance(&self) -> Conformance {
+        match self.is_implemented() {
+            true => Conformance::Implement,
+            false => Conformance::Import,
+        }
+    }
+
     /// Get the current real status of the specified feature in the module.
     pub fn feature_value(&self, feature: &str) -> Result<bool> {
         let feature = CString::new(feature).unwrap();
@@ -292,6 +598,18 @@ impl<'a> SchemaModule<'a> {
         Some(unsafe { SchemaSubmodule::from_raw(self, module as _) })
     }
 
+    /// Returns an iterator over the submodules included by the module.
+    pub fn submodules(&self) -> impl Iterator<Item = SchemaSubmodule<'_>> {
+        let parsed = unsafe { (*self.raw).parsed };
+        let submodules = match parsed.is_null() {
+            true => vec![],
+            false => parsed_with_submodules(parsed).split_off(1),
+        };
+        submodules.into_iter().map(move |submodule| unsafe {
+            SchemaSubmodule::from_raw(self, submodule as _)
+        })
+    }
+
     /// Get the latest revision of the YANG submodule specified by its name.
     ///
     /// YANG modules with no revision are supposed to be the oldest one.
@@ -429,6 +747,25 @@ impl<'a> SchemaModule<'a> {
         Array::new(self.context, array as *mut _, ptr_size)
     }
 
+    /// Returns an iterator over the identities defined in the module.
+    pub fn identities(&self) -> impl Iterator<Item = SchemaIdentity<'a>> {
+        let array = unsafe { (*self.raw).identities };
+        let ptr_size = mem::size_of::<ffi::lysc_ident>();
+        Array::new(self.context, array, ptr_size)
+    }
+
+    /// Returns an iterator over the typedefs defined at the top level of the
+    /// module (excluding the ones of its submodules).
+    pub fn typedefs(&self) -> impl Iterator<Item = SchemaTypedef<'a>> {
+        let parsed = unsafe { (*self.raw).parsed };
+        if parsed.is_null() {
+            return Array::new(self.context, std::ptr::null_mut(), 0);
+        }
+        let array = unsafe { (*parsed).typedefs };
+        let ptr_size = mem::size_of::<ffi::lysp_tpdf>();
+        Array::new(self.context, array, ptr_size)
+    }
+
     /// Returns an iterator over the top-level data nodes. The iteration
     /// behavior is customizable using the provided `flags` option.
     pub fn top_level_nodes(
@@ -451,6 +788,95 @@ impl<'a> SchemaModule<'a> {
         data.chain(rpcs).chain(notifications)
     }
 
+    /// Returns an iterator over all data nodes in the schema module
+    /// (depth-first search algorithm), leaving out the nodes of the given
+    /// statuses. The iteration behavior is customizable using the provided
+    /// `flags` option, as in [`SchemaModule::top_level_nodes`].
+    pub fn traverse2(
+        &self,
+        flags: IterSchemaFlags,
+        status: StatusFilter,
+    ) -> impl Iterator<Item = SchemaNode<'a>> {
+        let top_flags = IterSchemaFlags::from_bits_retain(flags.bits());
+        self.top_level_nodes(top_flags).flat_map(move |snode| {
+            let flags = IterSchemaFlags::from_bits_retain(flags.bits());
+            snode.traverse2(flags, status)
+        })
+    }
+
+    /// Returns an iterator over the data nodes defined by the schema module
+    /// itself (depth-first search algorithm).
+    ///
+    /// Unlike [`SchemaModule::traverse`], nodes augmented in by other modules
+    /// are skipped, along with their whole subtrees.
+    pub fn traverse_own(&self) -> impl Iterator<Item = SchemaNode<'a>> {
+        let module = self.raw;
+        let mut stack = self
+            .data()
+            .chain(self.rpcs())
+            .chain(self.notifications())
+            .collect::<Vec<_>>();
+        stack.reverse();
+        std::iter::from_fn(move || {
+            let snode = stack.pop()?;
+            let children = snode
+                .children()
+                .filter(|child| unsafe { (*child.raw).module } == module)
+                .collect::<Vec<_>>();
+            stack.extend(children.into_iter().rev());
+            Some(snode)
+        })
+    }
+
+    /// Returns the parsed module, unless it was already freed.
+    pub fn parsed(&self) -> Option<SchemaModuleParsed<'a>> {
+        let parsed = unsafe { (*self.raw).parsed };
+        if parsed.is_null() {
+            return None;
+        }
+        Some(SchemaModuleParsed {
+            context: self.context,
+            raw: parsed,
+        })
+    }
+
+    /// Returns the names of the features enabled in the module.
+    pub fn enabled_features(&self) -> Vec<&'a str> {
+        let parsed = unsafe { (*self.raw).parsed };
+        if parsed.is_null() {
+            return Vec::new();
+        }
+
+        let mut features = Vec::new();
+        let mut idx = 0;
+        let mut feature = std::ptr::null_mut();
+        loop {
+            feature =
+                unsafe { ffi::lysp_feature_next(feature, parsed, &mut idx) };
+            if feature.is_null() {
+                break;
+            }
+            if unsafe { (*feature).flags } as u32 & ffi::LYS_FENABLED != 0 {
+                features.push(char_ptr_to_str(unsafe { (*feature).name }));
+            }
+        }
+        features
+    }
+
+    /// Returns the modules containing deviations of this module.
+    pub fn deviated_by(&self) -> Vec<SchemaModule<'a>> {
+        unsafe { ly_array_slice((*self.raw).deviated_by) }
+            .iter()
+            .map(|raw| unsafe { SchemaModule::from_raw(self.context, *raw) })
+            .collect()
+    }
+
+    /// Returns the module capability URI, as advertised in the NETCONF
+    /// `<hello>` message (RFC 6020, section 5.6.4).
+    pub fn capability_urn(&self) -> String {
+        ModuleCapability::from_module(self).urn()
+    }
+
     /// Returns an iterator over the list of imports.
     pub fn imports(&self) -> impl Iterator<Item = SchemaImport<'a>> {
         let parsed = unsafe { (*self.raw).parsed };
@@ -493,6 +919,26 @@ impl SchemaSubmodule<'_> {
         self.raw
     }
 
+    /// Name of the submodule.
+    pub fn name(&self) -> &str {
+        char_ptr_to_str(unsafe { (*self.raw).name })
+    }
+
+    /// Latest revision of the submodule.
+    pub fn revision(&self) -> Option<&str> {
+        let revs = unsafe { (*self.raw).revs };
+        if revs.is_null() {
+            return None;
+        }
+        // Revisions are sorted from the latest one.
+        Some(char_ptr_to_str(unsafe { (*revs).date.as_ptr() }))
+    }
+
+    /// File path, if the submodule was read from a file.
+    pub fn filepath(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).filepath })
+    }
+
     /// Print schema tree in the specified format into a string.
     pub fn print_string(
         &self,
@@ -547,6 +993,287 @@ impl PartialEq for SchemaSubmodule<'_> {
 unsafe impl Send for SchemaSubmodule<'_> {}
 unsafe impl Sync for SchemaSubmodule<'_> {}
 
+// ===== impl SchemaModuleParsed =====
+
+impl<'a> SchemaModuleParsed<'a> {
+    /// Returns a mutable raw pointer to the underlying C library representation
+    /// of the parsed module.
+    pub fn as_raw(&self) -> *mut ffi::lysp_module {
+        self.raw
+    }
+
+    /// Returns an iterator over all groupings defined in the module,
+    /// including the ones nested in other statements (depth-first search
+    /// algorithm).
+    pub fn groupings(&self) -> impl Iterator<Item = SchemaParsedNode<'a>> {
+        self.all_nodes()
+            .filter(|pnode| pnode.kind() == SchemaParsedNodeKind::Grouping)
+    }
+
+    /// Returns an iterator over all uses statements of the module, including
+    /// the ones inside groupings (depth-first search algorithm).
+    pub fn uses(&self) -> impl Iterator<Item = SchemaParsedNode<'a>> {
+        self.all_nodes()
+            .filter(|pnode| pnode.kind() == SchemaParsedNodeKind::Uses)
+    }
+
+    /// Returns an iterator over all augments of the module, including the
+    /// ones inside uses statements (depth-first search algorithm).
+    pub fn augments(&self) -> impl Iterator<Item = SchemaParsedNode<'a>> {
+        self.all_nodes()
+            .filter(|pnode| pnode.kind() == SchemaParsedNodeKind::Augment)
+    }
+
+    // Returns an iterator over all parsed nodes of the module, including
+    // groupings, augments, actions and notifications.
+    fn all_nodes(&self) -> impl Iterator<Item = SchemaParsedNode<'a>> {
+        let context = self.context;
+        let roots = unsafe {
+            [
+                (*self.raw).groupings as *mut ffi::lysp_node,
+                (*self.raw).data,
+                (*self.raw).augments as *mut ffi::lysp_node,
+                (*self.raw).rpcs as *mut ffi::lysp_node,
+                (*self.raw).notifs as *mut ffi::lysp_node,
+            ]
+        };
+        let mut stack = roots
+            .iter()
+            .flat_map(|raw| parsed_siblings(context, *raw))
+            .collect::<Vec<_>>();
+        stack.reverse();
+        std::iter::from_fn(move || {
+            let pnode = stack.pop()?;
+            stack.extend(pnode.all_children().into_iter().rev());
+            Some(pnode)
+        })
+    }
+}
+
+unsafe impl Send for SchemaModuleParsed<'_> {}
+unsafe impl Sync for SchemaModuleParsed<'_> {}
+
+// ===== impl SchemaParsedNode =====
+
+impl<'a> SchemaParsedNode<'a> {
+    /// Returns a mutable raw pointer to the underlying C library representation
+    /// of the parsed node.
+    pub fn as_raw(&self) -> *mut ffi::lysp_node {
+        self.raw
+    }
+
+    /// Returns the kind of the parsed node.
+    pub fn kind(&self) -> SchemaParsedNodeKind {
+        match unsafe { (*self.raw).nodetype } as u32 {
+            ffi::LYS_CONTAINER => SchemaParsedNodeKind::Container,
+            ffi::LYS_CASE => SchemaParsedNodeKind::Case,
+            ffi::LYS_CHOICE => SchemaParsedNodeKind::Choice,
+            ffi::LYS_LEAF => SchemaParsedNodeKind::Leaf,
+            ffi::LYS_LEAFLIST => SchemaParsedNodeKind::LeafList,
+            ffi::LYS_LIST => SchemaParsedNodeKind::List,
+            ffi::LYS_ANYXML => SchemaParsedNodeKind::AnyXml,
+            ffi::LYS_ANYDATA => SchemaParsedNodeKind::AnyData,
+            ffi::LYS_ACTION => SchemaParsedNodeKind::Action,
+            ffi::LYS_RPC => SchemaParsedNodeKind::Rpc,
+            ffi::LYS_INPUT => SchemaParsedNodeKind::Input,
+            ffi::LYS_OUTPUT => SchemaParsedNodeKind::Output,
+            ffi::LYS_NOTIF => SchemaParsedNodeKind::Notification,
+            ffi::LYS_USES => SchemaParsedNodeKind::Uses,
+            ffi::LYS_GROUPING => SchemaParsedNodeKind::Grouping,
+            ffi::LYS_AUGMENT => SchemaParsedNodeKind::Augment,
+            _ => panic!("unknown node type"),
+        }
+    }
+
+    /// Name of the node.
+    ///
+    /// For uses statements, this is the (possibly prefixed) name of the used
+    /// grouping. For augments, this is the target path.
+    pub fn name(&self) -> &str {
+        char_ptr_to_str(unsafe { (*self.raw).name })
+    }
+
+    /// Description of the node.
+    pub fn description(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).dsc })
+    }
+
+    /// Reference of the node.
+    pub fn reference(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).ref_ })
+    }
+
+    /// Returns the when substatement of an augment or uses statement, if any.
+    ///
+    /// Unlike [`SchemaNode::whens`], which also lists the conditions
+    /// inherited by compiled nodes, this allows to tell conditional augments
+    /// and uses statements apart from unconditional ones.
+    pub fn when(&self) -> Option<SchemaParsedWhen<'a>> {
+        let raw = match self.kind() {
+            SchemaParsedNodeKind::Augment => unsafe {
+                (*(self.raw as *mut ffi::lysp_node_augment)).when
+            },
+            SchemaParsedNodeKind::Uses => unsafe {
+                (*(self.raw as *mut ffi::lysp_node_uses)).when
+            },
+            _ => return None,
+        };
+        if raw.is_null() {
+            return None;
+        }
+        Some(SchemaParsedWhen {
+            raw,
+            _marker: std::marker::PhantomData,
+        })
+    }
+
+    /// Returns the location of the statement defining the node in the YANG
+    /// source of its module or submodule.
+    ///
+    /// libyang doesn't retain line numbers, so the statement is looked up in
+    /// the source file. Returns `None` if the module wasn't read from a YANG
+    /// file, or if the statement isn't written in it (e.g. implicit input
+    /// and output statements).
+    pub fn location(&self) -> Option<SourceLocation> {
+        let mut nodes = Ancestors::new(Some(self.clone())).collect::<Vec<_>>();
+        nodes.reverse();
+        let filepath = parsed_filepath(self.context, nodes[0].raw)?;
+        let source = std::fs::read_to_string(filepath).ok()?;
+
+        let path = nodes
+            .iter()
+            .map(|pnode| match pnode.kind() {
+                SchemaParsedNodeKind::Input | SchemaParsedNodeKind::Output => {
+                    (pnode.keyword(), None)
+                }
+                _ => (pnode.keyword(), Some(pnode.name())),
+            })
+            .collect::<Vec<_>>();
+        let line = find_statement(&source, &path)?;
+
+        Some(SourceLocation {
+            filepath: filepath.to_owned(),
+            line,
+        })
+    }
+
+    /// Returns an iterator over the ancestor parsed nodes.
+    pub fn ancestors(&self) -> Ancestors<'a, SchemaParsedNode<'a>> {
+        Ancestors::new(self.parent())
+    }
+
+    /// Returns an iterator over the child parsed nodes, excluding groupings,
+    /// actions and notifications.
+    pub fn children(&self) -> Siblings<'a, SchemaParsedNode<'a>> {
+        Siblings::new(self.first_child())
+    }
+
+    /// Returns an iterator over the groupings defined directly in this node.
+    pub fn groupings(&self) -> Siblings<'a, SchemaParsedNode<'a>> {
+        let rnode = unsafe { ffi::lysp_node_groupings(self.raw) };
+        parsed_siblings(self.context, rnode as *mut _)
+    }
+
+    /// Returns an iterator over all elements in the parsed tree (depth-first
+    /// search algorithm). For groupings, this is the grouping's node tree.
+    pub fn traverse(&self) -> Traverse<'a, SchemaParsedNode<'a>> {
+        Traverse::new(self.clone())
+    }
+
+    // Returns the YANG keyword of the statement defining the node.
+    fn keyword(&self) -> &'static str {
+        match self.kind() {
+            SchemaParsedNodeKind::Container => "container",
+            SchemaParsedNodeKind::Case => "case",
+            SchemaParsedNodeKind::Choice => "choice",
+            SchemaParsedNodeKind::Leaf => "leaf",
+            SchemaParsedNodeKind::LeafList => "leaf-list",
+            SchemaParsedNodeKind::List => "list",
+            SchemaParsedNodeKind::AnyXml => "anyxml",
+            SchemaParsedNodeKind::AnyData => "anydata",
+            SchemaParsedNodeKind::Rpc => "rpc",
+            SchemaParsedNodeKind::Input => "input",
+            SchemaParsedNodeKind::Output => "output",
+            SchemaParsedNodeKind::Action => "action",
+            SchemaParsedNodeKind::Notification => "notification",
+            SchemaParsedNodeKind::Uses => "uses",
+            SchemaParsedNodeKind::Grouping => "grouping",
+            SchemaParsedNodeKind::Augment => "augment",
+        }
+    }
+
+    // Returns all child nodes, including groupings, actions, notifications and
+    // augments of uses statements.
+    fn all_children(&self) -> Vec<SchemaParsedNode<'a>> {
+        let augments = if self.kind() == SchemaParsedNodeKind::Uses {
+            let uses = self.raw as *mut ffi::lysp_node_uses;
+            unsafe { (*uses).augments as *mut ffi::lysp_node }
+        } else {
+            std::ptr::null_mut()
+        };
+        let actions = unsafe { ffi::lysp_node_actions(self.raw) };
+        let notifs = unsafe { ffi::lysp_node_notifs(self.raw) };
+
+        self.groupings()
+            .chain(self.children())
+            .chain(parsed_siblings(self.context, actions as *mut _))
+            .chain(parsed_siblings(self.context, notifs as *mut _))
+            .chain(parsed_siblings(self.context, augments))
+            .collect()
+    }
+}
+
+unsafe impl<'a> Binding<'a> for SchemaParsedNode<'a> {
+    type CType = ffi::lysp_node;
+    type Container = Context;
+
+    unsafe fn from_raw(
+        context: &'a Context,
+        raw: *mut ffi::lysp_node,
+    ) -> SchemaParsedNode<'a> {
+        SchemaParsedNode { context, raw }
+    }
+}
+
+impl<'a> NodeIterable<'a> for SchemaParsedNode<'a> {
+    fn parent(&self) -> Option<SchemaParsedNode<'a>> {
+        let rparent = unsafe { (*self.raw).parent };
+        unsafe { SchemaParsedNode::from_raw_opt(self.context, rparent) }
+    }
+
+    fn next_sibling(&self) -> Option<SchemaParsedNode<'a>> {
+        let rnext = match self.kind() {
+            // The output of an RPC/action follows its input.
+            SchemaParsedNodeKind::Input => unsafe {
+                let action = (*self.raw).parent as *mut ffi::lysp_node_action;
+                &mut (*action).output as *mut _ as *mut ffi::lysp_node
+            },
+            _ => unsafe { (*self.raw).next },
+        };
+        unsafe { SchemaParsedNode::from_raw_opt(self.context, rnext) }
+    }
+
+    fn first_child(&self) -> Option<SchemaParsedNode<'a>> {
+        let rchild = match self.kind() {
+            SchemaParsedNodeKind::Rpc | SchemaParsedNodeKind::Action => unsafe {
+                let action = self.raw as *mut ffi::lysp_node_action;
+                &mut (*action).input as *mut _ as *mut ffi::lysp_node
+            },
+            _ => unsafe { ffi::lysp_node_child(self.raw) as *mut _ },
+        };
+        unsafe { SchemaParsedNode::from_raw_opt(self.context, rchild) }
+    }
+}
+
+impl PartialEq for SchemaParsedNode<'_> {
+    fn eq(&self, other: &SchemaParsedNode<'_>) -> bool {
+        self.raw == other.raw
+    }
+}
+
+unsafe impl Send for SchemaParsedNode<'_> {}
+unsafe impl Sync for SchemaParsedNode<'_> {}
+
 // ===== impl SchemaImport =====
 
 impl<'a> SchemaImport<'a> {
@@ -648,19 +1375,38 @@ impl<'a> SchemaNode<'a> {
         char_ptr_to_string(buf.as_ptr(), false)
     }
 
+    /// Write the path of the node to the given writer, in the same format as
+    /// [`SchemaNode::path`] but without any intermediate allocation.
+    pub fn write_path(
+        &self,
+        format: SchemaPathFormat,
+        writer: &mut impl std::fmt::Write,
+    ) -> std::fmt::Result {
+        write_path_segments(self.raw, format, writer)
+    }
+
     /// Evaluate an xpath expression on the node.
     pub fn find_xpath(&self, xpath: &str) -> Result<Set<'_, SchemaNode<'_>>> {
+        self.find_xpath_with(xpath, SchemaFindFlags::empty())
+    }
+
+    /// Evaluate an xpath expression on the node, using the given lookup
+    /// options.
+    pub fn find_xpath_with(
+        &self,
+        xpath: &str,
+        options: SchemaFindFlags,
+    ) -> Result<Set<'_, SchemaNode<'_>>> {
         let xpath = CString::new(xpath).unwrap();
         let mut set = std::ptr::null_mut();
         let set_ptr = &mut set;
-        let options = 0u32;
 
         let ret = unsafe {
             ffi::lys_find_xpath(
                 std::ptr::null(),
                 self.raw,
                 xpath.as_ptr(),
-                options,
+                options.bits(),
                 set_ptr,
             )
         };
@@ -693,14 +1439,62 @@ impl<'a> SchemaNode<'a> {
         Ok(unsafe { SchemaNode::from_raw(self.context, rnode as *mut _) })
     }
 
-    /// Returns whether the node is a configuration node.
-    pub fn is_config(&self) -> bool {
-        match self.kind {
-            SchemaNodeKind::Container
-            | SchemaNodeKind::Case
-            | SchemaNodeKind::Choice
-            | SchemaNodeKind::Leaf
-            | SchemaNodeKind::LeafList
+    /// Find the child data node (skipping choice and case nodes) with the
+    /// given name, defined in the given module or, if not specified, in the
+    /// module of this node.
+    ///
+    /// This is cheaper than [`SchemaNode::find_path`] when the parent node is
+    /// already at hand, since no path needs to be built and parsed.
+    pub fn child(
+        &self,
+        module: Option<&SchemaModule<'_>>,
+        name: &str,
+    ) -> Option<SchemaNode<'a>> {
+        let name_cstr = CString::new(name).unwrap();
+        let module = match module {
+            Some(module) => module.as_raw(),
+            None => unsafe { (*self.raw).module },
+        };
+
+        let rnode = unsafe {
+            ffi::lys_find_child(
+                self.raw,
+                module,
+                name_cstr.as_ptr(),
+                name.len(),
+                0,
+                0,
+            )
+        };
+        unsafe { SchemaNode::from_raw_opt(self.context, rnode as *mut _) }
+    }
+
+    /// Find a descendant data node based on the given relative path, with
+    /// optional module name prefixes (e.g. `statistics/in-octets` or
+    /// `ip:ipv4/ip:address`). Nodes without a prefix are looked up in the
+    /// module of their parent.
+    pub fn descendant(&self, path: &str) -> Option<SchemaNode<'a>> {
+        let mut snode = self.clone();
+        for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+            snode = match segment.split_once(':') {
+                Some((module, name)) => {
+                    let module = self.context.get_module_implemented(module)?;
+                    snode.child(Some(&module), name)?
+                }
+                None => snode.child(None, segment)?,
+            };
+        }
+        Some(snode)
+    }
+
+    /// Returns whether the node is a configuration node.
+    pub fn is_config(&self) -> bool {
+        match self.kind {
+            SchemaNodeKind::Container
+            | SchemaNodeKind::Case
+            | SchemaNodeKind::Choice
+            | SchemaNodeKind::Leaf
+            | SchemaNodeKind::LeafList
             | SchemaNodeKind::List
             | SchemaNodeKind::AnyData => self.check_flag(ffi::LYS_CONFIG_W),
             _ => false,
@@ -757,6 +1551,69 @@ impl<'a> SchemaNode<'a> {
         }
     }
 
+    /// Returns whether the node is a presence container.
+    pub fn is_presence_container(&self) -> bool {
+        self.container_kind() == Some(ContainerKind::Presence)
+    }
+
+    /// Returns the kind of container, or `None` if the node isn't a
+    /// container.
+    pub fn container_kind(&self) -> Option<ContainerKind> {
+        match self.kind {
+            SchemaNodeKind::Container if self.check_flag(ffi::LYS_PRESENCE) => {
+                Some(ContainerKind::Presence)
+            }
+            SchemaNodeKind::Container => Some(ContainerKind::NonPresence),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of list, or `None` if the node isn't a list.
+    pub fn list_kind(&self) -> Option<ListKind> {
+        match self.kind {
+            SchemaNodeKind::List if self.check_flag(ffi::LYS_KEYLESS) => {
+                Some(ListKind::Keyless)
+            }
+            SchemaNodeKind::List => Some(ListKind::Keyed),
+            _ => None,
+        }
+    }
+
+    /// Returns the ordering of the instances, or `None` if the node isn't a
+    /// list or leaf-list.
+    pub fn ordering(&self) -> Option<Ordering> {
+        match self.kind {
+            SchemaNodeKind::LeafList | SchemaNodeKind::List => {
+                match self.check_flag(ffi::LYS_ORDBY_USER) {
+                    true => Some(Ordering::User),
+                    false => Some(Ordering::System),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of data the node belongs to.
+    ///
+    /// Unlike [`SchemaNode::is_config`] and [`SchemaNode::is_state`], this
+    /// also classifies the nodes of RPCs, actions and notifications.
+    pub fn data_context(&self) -> DataContext {
+        match self.kind {
+            SchemaNodeKind::Rpc
+            | SchemaNodeKind::Action
+            | SchemaNodeKind::Input => DataContext::RpcInput,
+            SchemaNodeKind::Output => DataContext::RpcOutput,
+            SchemaNodeKind::Notification => DataContext::Notification,
+            _ if self.check_flag(ffi::LYS_IS_INPUT) => DataContext::RpcInput,
+            _ if self.check_flag(ffi::LYS_IS_OUTPUT) => DataContext::RpcOutput,
+            _ if self.check_flag(ffi::LYS_IS_NOTIF) => {
+                DataContext::Notification
+            }
+            _ if self.check_flag(ffi::LYS_CONFIG_W) => DataContext::Config,
+            _ => DataContext::State,
+        }
+    }
+
     /// Returns whether the node is a list's key.
     pub fn is_list_key(&self) -> bool {
         match self.kind {
@@ -911,8 +1768,11 @@ impl<'a> SchemaNode<'a> {
                 _ => return None,
             }
         };
-        let ltype = unsafe { SchemaLeafType::from_raw(self.context, raw) };
-        Some(ltype)
+        Some(SchemaLeafType {
+            context: self.context,
+            raw,
+            leaf: self.raw,
+        })
     }
 
     /// Units of the leaf(-list)'s type.
@@ -1097,6 +1957,28 @@ impl<'a> SchemaNode<'a> {
         Siblings::new(Some(self.clone()))
     }
 
+    /// Returns an iterator over the extension instances of the schema node.
+    pub fn extensions(&self) -> impl Iterator<Item = SchemaExtInstance<'a>> {
+        let array = unsafe { (*self.raw).exts };
+        let ptr_size = mem::size_of::<ffi::lysc_ext_instance>();
+        Array::new(self.context, array as *mut _, ptr_size)
+    }
+
+    /// Returns the `ietf-yang-schema-mount` mount point defined by the schema
+    /// node, if any.
+    pub fn mount_point(&self) -> Option<SchemaExtInstance<'a>> {
+        self.extensions().find(|ext| {
+            ext.name() == "mount-point"
+                && ext.module().name() == "ietf-yang-schema-mount"
+        })
+    }
+
+    /// Returns whether the schema node is an `ietf-yang-schema-mount` mount
+    /// point.
+    pub fn is_mount_point(&self) -> bool {
+        self.mount_point().is_some()
+    }
+
     /// Returns an iterator over the child schema nodes, excluding action and
     /// notification nodes.
     pub fn children(&self) -> Siblings<'a, SchemaNode<'a>> {
@@ -1128,6 +2010,31 @@ impl<'a> SchemaNode<'a> {
         Traverse::new(self.clone())
     }
 
+    /// Returns an iterator over all elements in the schema tree (depth-first
+    /// search algorithm), leaving out the nodes of the given statuses. The
+    /// iteration behavior is customizable using the provided `flags` option,
+    /// as in [`SchemaNode::children2`].
+    pub fn traverse2(
+        &self,
+        flags: IterSchemaFlags,
+        status: StatusFilter,
+    ) -> impl Iterator<Item = SchemaNode<'a>> {
+        let mut stack = Vec::new();
+        if !status.excludes(self) {
+            stack.push(self.clone());
+        }
+        std::iter::from_fn(move || {
+            let snode = stack.pop()?;
+            let flags = IterSchemaFlags::from_bits_retain(flags.bits());
+            let children = snode
+                .children2(flags)
+                .filter(|child| !status.excludes(child))
+                .collect::<Vec<_>>();
+            stack.extend(children.into_iter().rev());
+            Some(snode)
+        })
+    }
+
     /// Returns an iterator over the keys of the list.
     pub fn list_keys(&self) -> impl Iterator<Item = SchemaNode<'a>> {
         self.children().filter(|snode| snode.is_list_key())
@@ -1211,14 +2118,37 @@ unsafe impl Sync for SchemaNode<'_> {}
 // ===== impl SchemaStmtMust =====
 
 impl SchemaStmtMust<'_> {
-    // TODO: XPath condition
-
     /// Returns a mutable raw pointer to the underlying C library representation
     /// of the must statement.
     pub fn as_raw(&self) -> *mut ffi::lysc_must {
         self.raw
     }
 
+    /// XPath condition.
+    pub fn condition(&self) -> &str {
+        char_ptr_to_str(unsafe { ffi::lyxp_get_expr((*self.raw).cond) })
+    }
+
+    /// Evaluate the XPath condition against the given data node, which must
+    /// be an instance of the schema node containing the must statement.
+    ///
+    /// Besides the result, returns the data nodes referenced by the
+    /// condition, e.g. to explain why the constraint isn't satisfied.
+    pub fn evaluate<'b>(
+        &self,
+        dnode: &DataNodeRef<'b>,
+    ) -> Result<XPathEvaluation<'b>> {
+        let ctx_snode = unsafe { (*dnode.raw()).schema };
+        unsafe {
+            evaluate_condition(
+                dnode,
+                ctx_snode,
+                (*self.raw).cond,
+                (*self.raw).prefixes,
+            )
+        }
+    }
+
     /// description substatement.
     pub fn description(&self) -> Option<&str> {
         char_ptr_to_opt_str(unsafe { (*self.raw).dsc })
@@ -1261,14 +2191,37 @@ unsafe impl Sync for SchemaStmtMust<'_> {}
 // ===== impl SchemaStmtWhen =====
 
 impl SchemaStmtWhen<'_> {
-    // TODO: XPath condition
-
     /// Returns a mutable raw pointer to the underlying C library representation
     /// of the when statement.
     pub fn as_raw(&self) -> *mut ffi::lysc_when {
         self.raw
     }
 
+    /// XPath condition.
+    pub fn condition(&self) -> &str {
+        char_ptr_to_str(unsafe { ffi::lyxp_get_expr((*self.raw).cond) })
+    }
+
+    /// Evaluate the XPath condition against the given data node, which must
+    /// be an instance of the context node of the when statement (usually
+    /// the parent of the conditional node).
+    ///
+    /// Besides the result, returns the data nodes referenced by the
+    /// condition, e.g. to explain why the conditional node isn't valid.
+    pub fn evaluate<'b>(
+        &self,
+        dnode: &DataNodeRef<'b>,
+    ) -> Result<XPathEvaluation<'b>> {
+        unsafe {
+            evaluate_condition(
+                dnode,
+                (*self.raw).context,
+                (*self.raw).cond,
+                (*self.raw).prefixes,
+            )
+        }
+    }
+
     /// description substatement.
     pub fn description(&self) -> Option<&str> {
         char_ptr_to_opt_str(unsafe { (*self.raw).dsc })
@@ -1299,9 +2252,464 @@ unsafe impl<'a> Binding<'a> for SchemaStmtWhen<'a> {
 unsafe impl Send for SchemaStmtWhen<'_> {}
 unsafe impl Sync for SchemaStmtWhen<'_> {}
 
+// ===== impl SchemaParsedWhen =====
+
+impl SchemaParsedWhen<'_> {
+    /// Returns a mutable raw pointer to the underlying C library representation
+    /// of the when statement.
+    pub fn as_raw(&self) -> *mut ffi::lysp_when {
+        self.raw
+    }
+
+    /// XPath condition.
+    pub fn condition(&self) -> &str {
+        char_ptr_to_str(unsafe { (*self.raw).cond })
+    }
+
+    /// description substatement.
+    pub fn description(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).dsc })
+    }
+
+    /// reference substatement.
+    pub fn reference(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).ref_ })
+    }
+}
+
+unsafe impl Send for SchemaParsedWhen<'_> {}
+unsafe impl Sync for SchemaParsedWhen<'_> {}
+
+// Returns an iterator over the given parsed node and its siblings.
+fn parsed_siblings<'a>(
+    context: &'a Context,
+    raw: *mut ffi::lysp_node,
+) -> Siblings<'a, SchemaParsedNode<'a>> {
+    Siblings::new(unsafe { SchemaParsedNode::from_raw_opt(context, raw) })
+}
+
+// Kind of the bounds of a compiled range.
+#[derive(Clone, Copy)]
+enum RangeKind {
+    Signed,
+    Unsigned,
+    Decimal64(u8),
+}
+
+// Convert a compiled range or length restriction.
+fn type_range(
+    range: *mut ffi::lysc_range,
+    kind: RangeKind,
+) -> Option<TypeRange> {
+    if range.is_null() {
+        return None;
+    }
+
+    let range = unsafe { &*range };
+    let parts = unsafe { ly_array_slice(range.parts) }
+        .iter()
+        .map(|part| unsafe {
+            let min = &part.__bindgen_anon_1;
+            let max = &part.__bindgen_anon_2;
+            match kind {
+                RangeKind::Signed => RangePart::Signed {
+                    min: min.min_64,
+                    max: max.max_64,
+                },
+                RangeKind::Unsigned => RangePart::Unsigned {
+                    min: min.min_u64,
+                    max: max.max_u64,
+                },
+                RangeKind::Decimal64(fraction_digits) => RangePart::Decimal64 {
+                    min: min.min_64,
+                    max: max.max_64,
+                    fraction_digits,
+                },
+            }
+        })
+        .collect();
+
+    Some(TypeRange {
+        parts,
+        error_msg: char_ptr_to_opt_string(range.emsg, false),
+        error_apptag: char_ptr_to_opt_string(range.eapptag, false),
+        description: char_ptr_to_opt_string(range.dsc, false),
+        reference: char_ptr_to_opt_string(range.ref_, false),
+    })
+}
+
+// Returns all typedefs of the given parsed module and its submodules, the
+// top-level ones first, followed by the ones scoped in schema nodes.
+fn parsed_typedefs<'a>(
+    context: &'a Context,
+    module: *mut ffi::lysp_module,
+) -> Vec<SchemaTypedef<'a>> {
+    let pmods = parsed_with_submodules(module);
+    let ptr_size = mem::size_of::<ffi::lysp_tpdf>();
+    let mut typedefs = pmods
+        .iter()
+        .flat_map(|pmod| {
+            let array = unsafe { (**pmod).typedefs };
+            Array::new(context, array, ptr_size)
+        })
+        .collect::<Vec<_>>();
+    for pmod in pmods {
+        let parsed = SchemaModuleParsed { context, raw: pmod };
+        for pnode in parsed.all_nodes() {
+            let array = unsafe { ffi::lysp_node_typedefs(pnode.raw) };
+            typedefs.extend(Array::new(context, array as *mut _, ptr_size));
+        }
+    }
+
+    typedefs
+}
+
+// Returns the given parsed module, followed by its submodules.
+fn parsed_with_submodules(
+    module: *mut ffi::lysp_module,
+) -> Vec<*mut ffi::lysp_module> {
+    let mut pmods = vec![module];
+    for include in unsafe { ly_array_slice((*module).includes) } {
+        if !include.submodule.is_null() {
+            pmods.push(include.submodule as *mut ffi::lysp_module);
+        }
+    }
+    pmods
+}
+
+// Returns the parsed module of the given module, followed by the parsed
+// modules it imports.
+fn parsed_with_imports(
+    module: *mut ffi::lys_module,
+) -> Vec<*mut ffi::lysp_module> {
+    let parsed = unsafe { (*module).parsed };
+    if parsed.is_null() {
+        return vec![];
+    }
+    let mut pmods = vec![parsed];
+    for import in unsafe { ly_array_slice((*parsed).imports) } {
+        let module = import.module;
+        if !module.is_null() && !unsafe { (*module).parsed }.is_null() {
+            pmods.push(unsafe { (*module).parsed });
+        }
+    }
+    pmods
+}
+
+// Returns the parsed node defining the given compiled node, along with the
+// parsed module or submodule containing it.
+//
+// Compiled nodes don't reference their parsed nodes, so the parsed nodes of
+// the same name and kind are searched in the module of the node (augments
+// included) and in the modules it imports (groupings), picking the one whose
+// ancestors best match the compiled ones.
+fn parsed_node<'a>(
+    snode: &SchemaNode<'a>,
+) -> Option<(*mut ffi::lysp_module, SchemaParsedNode<'a>)> {
+    let context = snode.context;
+    let kind = match snode.kind() {
+        SchemaNodeKind::Leaf => SchemaParsedNodeKind::Leaf,
+        SchemaNodeKind::LeafList => SchemaParsedNodeKind::LeafList,
+        _ => return None,
+    };
+    let ancestors = snode
+        .ancestors()
+        .map(|snode| snode.name().to_owned())
+        .collect::<Vec<_>>();
+
+    let mut best: Option<(usize, *mut ffi::lysp_module, SchemaParsedNode<'a>)> =
+        None;
+    let module = unsafe { (*snode.raw).module };
+    for pmod in parsed_with_imports(module)
+        .into_iter()
+        .flat_map(parsed_with_submodules)
+    {
+        let parsed = SchemaModuleParsed { context, raw: pmod };
+        for pnode in parsed.all_nodes().filter(|pnode| {
+            pnode.kind() == kind && pnode.name() == snode.name()
+        }) {
+            // Count the matching ancestors, up to the first grouping, uses
+            // or augment.
+            let score = pnode
+                .ancestors()
+                .take_while(|pnode| {
+                    !matches!(
+                        pnode.kind(),
+                        SchemaParsedNodeKind::Uses
+                            | SchemaParsedNodeKind::Grouping
+                            | SchemaParsedNodeKind::Augment
+                    )
+                })
+                .zip(ancestors.iter())
+                .take_while(|(pnode, name)| pnode.name() == name.as_str())
+                .count();
+            if !matches!(best, Some((best, ..)) if best >= score) {
+                best = Some((score, pmod, pnode));
+            }
+        }
+    }
+
+    best.map(|(_, pmod, pnode)| (pmod, pnode))
+}
+
+// Returns the type of the given parsed leaf or leaf-list.
+fn parsed_leaf_type<'a>(
+    pnode: &SchemaParsedNode<'a>,
+) -> Option<&'a ffi::lysp_type> {
+    match pnode.kind() {
+        SchemaParsedNodeKind::Leaf => {
+            Some(unsafe { &(*(pnode.raw as *mut ffi::lysp_node_leaf)).type_ })
+        }
+        SchemaParsedNodeKind::LeafList => Some(unsafe {
+            &(*(pnode.raw as *mut ffi::lysp_node_leaflist)).type_
+        }),
+        _ => None,
+    }
+}
+
+// Resolve the typedef referenced by the given type name, as written in the
+// given parsed module or submodule.
+//
+// Prefixed names are resolved in the imported module of that prefix. Other
+// names are resolved in the typedefs scoped to the ancestors of the given
+// parsed node, if any, and then in the typedefs of the module.
+fn resolve_typedef<'a>(
+    context: &'a Context,
+    pmod: *const ffi::lysp_module,
+    scope: Option<&SchemaParsedNode<'a>>,
+    type_name: &str,
+) -> Option<SchemaTypedef<'a>> {
+    let (prefix, name) = match type_name.split_once(':') {
+        Some((prefix, name)) => (Some(prefix), name),
+        None => (None, type_name),
+    };
+    let ptr_size = mem::size_of::<ffi::lysp_tpdf>();
+
+    // Find the module of the typedef.
+    let module = match prefix {
+        Some(prefix) if prefix != parsed_prefix(pmod) => {
+            let imports = unsafe { (*pmod).imports };
+            let ptr_size = mem::size_of::<ffi::lysp_import>();
+            let mut imports: Array<'_, SchemaImport<'_>> =
+                Array::new(context, imports, ptr_size);
+            let import = imports.find(|import| import.prefix() == prefix)?;
+            unsafe { (*import.module().raw).parsed }
+        }
+        _ => {
+            // Search the typedefs scoped to the ancestors first.
+            let scoped = scope
+                .into_iter()
+                .flat_map(|pnode| pnode.ancestors())
+                .flat_map(|pnode| {
+                    let array = unsafe { ffi::lysp_node_typedefs(pnode.raw) };
+                    Array::new(context, array as *mut _, ptr_size)
+                })
+                .find(|tpdf: &SchemaTypedef<'_>| tpdf.name() == name);
+            if scoped.is_some() {
+                return scoped;
+            }
+            unsafe { (*(*pmod).mod_).parsed }
+        }
+    };
+    if module.is_null() {
+        return None;
+    }
+
+    parsed_typedefs(context, module)
+        .into_iter()
+        .find(|tpdf| tpdf.name() == name)
+}
+
+// Returns the items of a compiled enumeration or bits type.
+fn bitenum_items<'b>(
+    array: *mut ffi::lysc_type_bitenum_item,
+) -> &'b [ffi::lysc_type_bitenum_item] {
+    unsafe { ly_array_slice(array) }
+}
+
+// Returns the items of a parsed enumeration or bits type.
+fn parsed_bitenum_items<'b>(
+    array: *mut ffi::lysp_type_enum,
+) -> &'b [ffi::lysp_type_enum] {
+    unsafe { ly_array_slice(array) }
+}
+
+// Returns the status of a compiled item.
+fn item_status(flags: u16) -> Status {
+    let flags = flags as u32;
+    if flags & ffi::LYS_STATUS_OBSLT != 0 {
+        Status::Obsolete
+    } else if flags & ffi::LYS_STATUS_DEPRC != 0 {
+        Status::Deprecated
+    } else {
+        Status::Current
+    }
+}
+
+// Returns the prefix of the given parsed module or submodule.
+fn parsed_prefix<'a>(pmod: *const ffi::lysp_module) -> &'a str {
+    let prefix = unsafe {
+        match (*pmod).is_submod() {
+            0 => (*(*pmod).mod_).prefix,
+            _ => (*(pmod as *const ffi::lysp_submodule)).prefix,
+        }
+    };
+    char_ptr_to_str(prefix)
+}
+
+// Returns the file path of the module or submodule defining the given
+// top-level parsed node.
+fn parsed_filepath(
+    context: &Context,
+    root: *mut ffi::lysp_node,
+) -> Option<&str> {
+    // Returns whether the node is one of the given top-level siblings.
+    let contains = |roots: [*mut ffi::lysp_node; 5]| {
+        roots.iter().any(|raw| {
+            parsed_siblings(context, *raw).any(|pnode| pnode.raw == root)
+        })
+    };
+
+    for module in context.modules(false) {
+        let parsed = unsafe { (*module.raw).parsed };
+        if parsed.is_null() {
+            continue;
+        }
+        for pmod in parsed_with_submodules(parsed) {
+            let roots = unsafe {
+                [
+                    (*pmod).groupings as *mut ffi::lysp_node,
+                    (*pmod).data,
+                    (*pmod).augments as *mut ffi::lysp_node,
+                    (*pmod).rpcs as *mut ffi::lysp_node,
+                    (*pmod).notifs as *mut ffi::lysp_node,
+                ]
+            };
+            if !contains(roots) {
+                continue;
+            }
+            // Submodules keep their own file path.
+            let filepath = match pmod == parsed {
+                true => unsafe { (*module.raw).filepath },
+                false => unsafe {
+                    (*(pmod as *mut ffi::lysp_submodule)).filepath
+                },
+            };
+            return char_ptr_to_opt_str(filepath);
+        }
+    }
+
+    None
+}
+
+// Evaluate a compiled must/when condition, and find the instances of the
+// schema nodes it references.
+unsafe fn evaluate_condition<'b>(
+    dnode: &DataNodeRef<'b>,
+    ctx_snode: *const ffi::lysc_node,
+    cond: *mut ffi::lyxp_expr,
+    prefixes: *mut ffi::lysc_prefix,
+) -> Result<XPathEvaluation<'b>> {
+    let cur_mod = if ctx_snode.is_null() {
+        (*(*dnode.raw()).schema).module
+    } else {
+        (*ctx_snode).module
+    };
+
+    let mut result = 0;
+    let ret = ffi::lyd_eval_xpath3(
+        dnode.raw(),
+        cur_mod,
+        ffi::lyxp_get_expr(cond),
+        ffi::LY_VALUE_FORMAT::LY_VALUE_SCHEMA_RESOLVED,
+        prefixes as *mut c_void,
+        std::ptr::null(),
+        &mut result,
+    );
+    if ret != ffi::LY_ERR::LY_SUCCESS {
+        return Err(Error::new(dnode.context()));
+    }
+
+    // Find the schema nodes referenced by the condition.
+    let mut set = std::ptr::null_mut();
+    let ret = ffi::lys_find_expr_atoms(
+        ctx_snode, cur_mod, cond, prefixes, 0, &mut set,
+    );
+    if ret != ffi::LY_ERR::LY_SUCCESS {
+        return Err(Error::new(dnode.context()));
+    }
+    let count = (*set).count as usize;
+    let atoms = if count == 0 {
+        HashSet::new()
+    } else {
+        slice::from_raw_parts((*set).__bindgen_anon_1.snodes, count)
+            .iter()
+            .map(|snode| *snode as *const ffi::lysc_node)
+            .collect::<HashSet<_>>()
+    };
+    ffi::ly_set_free(set, None);
+
+    // Find their instances in the data tree.
+    let referenced_nodes = dnode
+        .owner()
+        .traverse()
+        .filter(|dnode| {
+            !dnode.is_opaque()
+                && atoms.contains(&((*dnode.raw()).schema as *const _))
+        })
+        .collect();
+
+    Ok(XPathEvaluation {
+        result: result != 0,
+        referenced_nodes,
+    })
+}
+
+// Write the path of the given schema node, starting from its top-level
+// ancestor. Mirrors `lysc_path()`.
+fn write_path_segments(
+    raw: *const ffi::lysc_node,
+    format: SchemaPathFormat,
+    writer: &mut impl std::fmt::Write,
+) -> std::fmt::Result {
+    // Nodes skipped in the data path format.
+    let schema_only = |raw: *const ffi::lysc_node| {
+        let nodetype = unsafe { (*raw).nodetype } as u32;
+        nodetype & (ffi::LYS_CHOICE | ffi::LYS_CASE) != 0
+    };
+
+    let parent = unsafe { (*raw).parent } as *const ffi::lysc_node;
+    if !parent.is_null() {
+        write_path_segments(parent, format, writer)?;
+    }
+    let nodetype = unsafe { (*raw).nodetype } as u32;
+    let io = ffi::LYS_INPUT | ffi::LYS_OUTPUT;
+    if format == SchemaPathFormat::DATA
+        && (schema_only(raw) || nodetype & io != 0)
+    {
+        return Ok(());
+    }
+
+    let mut parent = parent;
+    if format == SchemaPathFormat::DATA {
+        while !parent.is_null() && schema_only(parent) {
+            parent = unsafe { (*parent).parent };
+        }
+    }
+    if !(parent.is_null() && nodetype & io != 0) {
+        writer.write_char('/')?;
+    }
+    let module = unsafe { (*raw).module };
+    if parent.is_null() || unsafe { (*parent).module } != module {
+        let module_name = char_ptr_to_str(unsafe { (*module).name });
+        write!(writer, "{}:", module_name)?;
+    }
+    writer.write_str(char_ptr_to_str(unsafe { (*raw).name }))
+}
+
 // ===== impl SchemaLeafType =====
 
-impl SchemaLeafType<'_> {
+impl<'a> SchemaLeafType<'a> {
     /// Returns a mutable raw pointer to the underlying C library representation
     /// of the leaf type.
     pub fn as_raw(&self) -> *mut ffi::lysc_type {
@@ -1333,6 +2741,245 @@ impl SchemaLeafType<'_> {
             unsafe { SchemaLeafType::from_raw(self.context, real_type) };
         Some(ltype)
     }
+
+    /// Returns the range restriction of an integer or decimal64 type, if
+    /// any.
+    pub fn range(&self) -> Option<TypeRange> {
+        let (range, kind) = match self.base_type() {
+            DataValueType::Int8
+            | DataValueType::Int16
+            | DataValueType::Int32
+            | DataValueType::Int64 => {
+                let ltype = self.raw as *mut ffi::lysc_type_num;
+                (unsafe { (*ltype).range }, RangeKind::Signed)
+            }
+            DataValueType::Uint8
+            | DataValueType::Uint16
+            | DataValueType::Uint32
+            | DataValueType::Uint64 => {
+                let ltype = self.raw as *mut ffi::lysc_type_num;
+                (unsafe { (*ltype).range }, RangeKind::Unsigned)
+            }
+            DataValueType::Dec64 => {
+                let ltype = self.raw as *mut ffi::lysc_type_dec;
+                let fraction_digits = unsafe { (*ltype).fraction_digits };
+                (
+                    unsafe { (*ltype).range },
+                    RangeKind::Decimal64(fraction_digits),
+                )
+            }
+            _ => return None,
+        };
+        type_range(range, kind)
+    }
+
+    /// Returns the length restriction of a string or binary type, if any.
+    pub fn length(&self) -> Option<TypeRange> {
+        let length = match self.base_type() {
+            DataValueType::String => unsafe {
+                (*(self.raw as *mut ffi::lysc_type_str)).length
+            },
+            DataValueType::Binary => unsafe {
+                (*(self.raw as *mut ffi::lysc_type_bin)).length
+            },
+            _ => return None,
+        };
+        type_range(length, RangeKind::Unsigned)
+    }
+
+    /// Returns the pattern restrictions of a string type, including the ones
+    /// inherited from its typedefs.
+    pub fn patterns(&self) -> Vec<TypePattern> {
+        if self.base_type() != DataValueType::String {
+            return Vec::new();
+        }
+
+        let ltype = self.raw as *mut ffi::lysc_type_str;
+        unsafe { ly_array_slice((*ltype).patterns) }
+            .iter()
+            .map(|pattern| {
+                let pattern = unsafe { &**pattern };
+                TypePattern {
+                    expr: char_ptr_to_string(pattern.expr, false),
+                    inverted: pattern.inverted() != 0,
+                    error_msg: char_ptr_to_opt_string(pattern.emsg, false),
+                    error_apptag: char_ptr_to_opt_string(
+                        pattern.eapptag,
+                        false,
+                    ),
+                    description: char_ptr_to_opt_string(pattern.dsc, false),
+                    reference: char_ptr_to_opt_string(pattern.ref_, false),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the items of an enumeration type, excluding the ones disabled
+    /// by their if-feature expressions.
+    pub fn enum_items(&self) -> Vec<EnumItem> {
+        if self.base_type() != DataValueType::Enum {
+            return Vec::new();
+        }
+
+        let ltype = self.raw as *mut ffi::lysc_type_enum;
+        let items = bitenum_items(unsafe { (*ltype).enums });
+        let if_features = self.if_features(items, true);
+        items
+            .iter()
+            .zip(if_features)
+            .map(|(item, if_features)| EnumItem {
+                name: char_ptr_to_string(item.name, false),
+                value: unsafe { item.__bindgen_anon_1.value },
+                status: item_status(item.flags),
+                description: char_ptr_to_opt_string(item.dsc, false),
+                reference: char_ptr_to_opt_string(item.ref_, false),
+                if_features,
+            })
+            .collect()
+    }
+
+    /// Returns the items of a bits type, excluding the ones disabled by
+    /// their if-feature expressions.
+    pub fn bits_items(&self) -> Vec<BitsItem> {
+        if self.base_type() != DataValueType::Bits {
+            return Vec::new();
+        }
+
+        let ltype = self.raw as *mut ffi::lysc_type_bits;
+        let items = bitenum_items(unsafe { (*ltype).bits });
+        let if_features = self.if_features(items, false);
+        items
+            .iter()
+            .zip(if_features)
+            .map(|(item, if_features)| BitsItem {
+                name: char_ptr_to_string(item.name, false),
+                position: unsafe { item.__bindgen_anon_1.position },
+                status: item_status(item.flags),
+                description: char_ptr_to_opt_string(item.dsc, false),
+                reference: char_ptr_to_opt_string(item.ref_, false),
+                if_features,
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the base identities of an identityref type.
+    pub fn identity_bases(&self) -> impl Iterator<Item = SchemaIdentity<'_>> {
+        let bases = match self.base_type() {
+            DataValueType::IdentityRef => {
+                let ltype = self.raw as *mut ffi::lysc_type_identityref;
+                unsafe { (*ltype).bases }
+            }
+            _ => std::ptr::null_mut(),
+        };
+        let context = self.context;
+        unsafe { ly_array_slice(bases) }
+            .iter()
+            .map(move |raw| unsafe { SchemaIdentity::from_raw(context, *raw) })
+    }
+
+    /// Returns the number of fraction digits of a decimal64 type.
+    pub fn fraction_digits(&self) -> Option<u8> {
+        if self.base_type() != DataValueType::Dec64 {
+            return None;
+        }
+
+        let ltype = self.raw as *mut ffi::lysc_type_dec;
+        Some(unsafe { (*ltype).fraction_digits })
+    }
+
+    /// Returns the typedef the type is derived from, if any.
+    ///
+    /// The typedef is resolved from the type of the leaf or leaf-list as
+    /// written in the module defining it, so it's only available for types
+    /// obtained using [`SchemaNode::leaf_type`].
+    pub fn typedef(&self) -> Option<SchemaTypedef<'a>> {
+        let (pmod, pnode) = self.parsed_leaf()?;
+        let ptype = parsed_leaf_type(&pnode)?;
+        resolve_typedef(
+            self.context,
+            pmod,
+            Some(&pnode),
+            char_ptr_to_str(ptype.name),
+        )
+    }
+
+    // Returns the parsed leaf or leaf-list using the type, along with the
+    // parsed module or submodule defining it.
+    fn parsed_leaf(
+        &self,
+    ) -> Option<(*mut ffi::lysp_module, SchemaParsedNode<'a>)> {
+        if self.leaf.is_null() {
+            return None;
+        }
+        let snode = unsafe { SchemaNode::from_raw(self.context, self.leaf) };
+        parsed_node(&snode)
+    }
+
+    // Returns the if-feature expressions of the given enumeration or bits
+    // items.
+    //
+    // Compiled items don't retain their if-feature expressions, so they're
+    // taken from the parsed type defining all the items, searching the type
+    // of the leaf or leaf-list using the type and then its typedef
+    // derivation chain.
+    fn if_features(
+        &self,
+        items: &[ffi::lysc_type_bitenum_item],
+        enums: bool,
+    ) -> Vec<Vec<String>> {
+        let parsed_items = |ptype: &ffi::lysp_type| {
+            parsed_bitenum_items(match enums {
+                true => ptype.enums,
+                false => ptype.bits,
+            })
+        };
+        let defines_all = |ptype: &&ffi::lysp_type| {
+            let parsed = parsed_items(ptype);
+            !parsed.is_empty()
+                && items.iter().all(|item| {
+                    parsed.iter().any(|pitem| {
+                        char_ptr_to_str(pitem.name)
+                            == char_ptr_to_str(item.name)
+                    })
+                })
+        };
+
+        let leaf_type = self
+            .parsed_leaf()
+            .and_then(|(_, pnode)| parsed_leaf_type(&pnode));
+        let typedef_types = self
+            .typedef_chain()
+            .map(|tpdf| unsafe { &(*tpdf.raw).type_ });
+        let ptype =
+            leaf_type.into_iter().chain(typedef_types).find(defines_all);
+
+        items
+            .iter()
+            .map(|item| {
+                let pitem = ptype.and_then(|ptype| {
+                    parsed_items(ptype).iter().find(|pitem| {
+                        char_ptr_to_str(pitem.name)
+                            == char_ptr_to_str(item.name)
+                    })
+                });
+                let iffeatures = match pitem {
+                    Some(pitem) => pitem.iffeatures,
+                    None => return Vec::new(),
+                };
+                unsafe { ly_array_slice(iffeatures) }
+                    .iter()
+                    .map(|qname| char_ptr_to_string(qname.str_, false))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the typedef derivation chain of the type,
+    /// from the typedef it's derived from to the one derived from a built-in
+    /// type.
+    pub fn typedef_chain(&self) -> impl Iterator<Item = SchemaTypedef<'_>> {
+        std::iter::successors(self.typedef(), SchemaTypedef::base)
+    }
 }
 
 unsafe impl<'a> Binding<'a> for SchemaLeafType<'a> {
@@ -1343,13 +2990,212 @@ unsafe impl<'a> Binding<'a> for SchemaLeafType<'a> {
         context: &'a Context,
         raw: *mut ffi::lysc_type,
     ) -> SchemaLeafType<'a> {
-        SchemaLeafType { context, raw }
+        SchemaLeafType {
+            context,
+            raw,
+            leaf: std::ptr::null_mut(),
+        }
     }
 }
 
 unsafe impl Send for SchemaLeafType<'_> {}
 unsafe impl Sync for SchemaLeafType<'_> {}
 
+// ===== impl SchemaTypedef =====
+
+impl<'a> SchemaTypedef<'a> {
+    /// Returns a mutable raw pointer to the underlying C library representation
+    /// of the typedef.
+    pub fn as_raw(&self) -> *mut ffi::lysp_tpdf {
+        self.raw
+    }
+
+    /// Name of the typedef.
+    pub fn name(&self) -> &str {
+        char_ptr_to_str(unsafe { (*self.raw).name })
+    }
+
+    /// Module where the typedef is defined.
+    pub fn module(&self) -> SchemaModule<'a> {
+        let module = unsafe { (*(*self.raw).type_.pmod).mod_ };
+        unsafe { SchemaModule::from_raw(self.context, module) }
+    }
+
+    /// Name of the type the typedef is derived from, as written in the
+    /// module (e.g. `inet:ipv4-address` or `string`).
+    pub fn base_name(&self) -> &str {
+        char_ptr_to_str(unsafe { (*self.raw).type_.name })
+    }
+
+    /// Returns the typedef this typedef is derived from, or `None` if it's
+    /// derived from a built-in type.
+    pub fn base(&self) -> Option<SchemaTypedef<'a>> {
+        let pmod = unsafe { (*self.raw).type_.pmod };
+        resolve_typedef(self.context, pmod, None, self.base_name())
+    }
+
+    /// Returns the resolved base type, if the typedef is used by the
+    /// compiled schema.
+    pub fn base_type(&self) -> Option<DataValueType> {
+        let compiled = unsafe { (*self.raw).type_.compiled };
+        if compiled.is_null() {
+            return None;
+        }
+        let base_type = unsafe { (*compiled).basetype };
+        DataValueType::from_u32(base_type)
+    }
+
+    /// Units of the typedef.
+    pub fn units(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).units })
+    }
+
+    /// Default value of the typedef.
+    pub fn default(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).dflt.str_ })
+    }
+
+    /// Description of the typedef.
+    pub fn description(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).dsc })
+    }
+
+    /// Reference of the typedef.
+    pub fn reference(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).ref_ })
+    }
+}
+
+unsafe impl<'a> Binding<'a> for SchemaTypedef<'a> {
+    type CType = ffi::lysp_tpdf;
+    type Container = Context;
+
+    unsafe fn from_raw(
+        context: &'a Context,
+        raw: *mut ffi::lysp_tpdf,
+    ) -> SchemaTypedef<'a> {
+        SchemaTypedef { context, raw }
+    }
+}
+
+impl PartialEq for SchemaTypedef<'_> {
+    fn eq(&self, other: &SchemaTypedef<'_>) -> bool {
+        self.raw == other.raw
+    }
+}
+
+unsafe impl Send for SchemaTypedef<'_> {}
+unsafe impl Sync for SchemaTypedef<'_> {}
+
+// ===== impl SchemaIdentity =====
+
+impl<'a> SchemaIdentity<'a> {
+    /// Returns a mutable raw pointer to the underlying C library representation
+    /// of the identity.
+    pub fn as_raw(&self) -> *mut ffi::lysc_ident {
+        self.raw
+    }
+
+    /// Name of the identity.
+    pub fn name(&self) -> &str {
+        char_ptr_to_str(unsafe { (*self.raw).name })
+    }
+
+    /// Module where the identity is defined.
+    pub fn module(&self) -> SchemaModule<'a> {
+        let module = unsafe { (*self.raw).module };
+        unsafe { SchemaModule::from_raw(self.context, module) }
+    }
+
+    /// Description of the identity.
+    pub fn description(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).dsc })
+    }
+
+    /// Reference of the identity.
+    pub fn reference(&self) -> Option<&str> {
+        char_ptr_to_opt_str(unsafe { (*self.raw).ref_ })
+    }
+
+    /// Returns an iterator over the identities directly derived from this
+    /// one.
+    pub fn derived(&self) -> impl Iterator<Item = SchemaIdentity<'a>> {
+        let context = self.context;
+        unsafe { ly_array_slice((*self.raw).derived) }
+            .iter()
+            .map(move |raw| unsafe { SchemaIdentity::from_raw(context, *raw) })
+    }
+
+    /// Returns the identities derived from this one, directly or
+    /// transitively (depth-first search algorithm, without duplicates).
+    pub fn derived_all(&self) -> Vec<SchemaIdentity<'a>> {
+        let mut identities: Vec<SchemaIdentity<'a>> = Vec::new();
+        let mut stack = self.derived().collect::<Vec<_>>();
+        stack.reverse();
+        while let Some(ident) = stack.pop() {
+            if identities.contains(&ident) {
+                continue;
+            }
+            let mut derived = ident.derived().collect::<Vec<_>>();
+            derived.reverse();
+            stack.extend(derived);
+            identities.push(ident);
+        }
+        identities
+    }
+
+    /// Returns an iterator over the base identities of this identity.
+    ///
+    /// Compiled identities only link to their derived identities, so the
+    /// bases are found among the identities of all modules of the context.
+    pub fn bases(&self) -> impl Iterator<Item = SchemaIdentity<'a>> + '_ {
+        self.context
+            .modules(false)
+            .flat_map(|module| module.identities())
+            .filter(move |ident| {
+                ident.derived().any(|derived| derived == *self)
+            })
+    }
+
+    /// Returns whether the identity is derived from the given one, directly
+    /// or transitively.
+    pub fn is_derived_from(&self, base: &SchemaIdentity<'_>) -> bool {
+        base.derived_all().iter().any(|ident| ident.raw == self.raw)
+    }
+
+    /// Status of the identity.
+    pub fn status(&self) -> Status {
+        item_status(unsafe { (*self.raw).flags })
+    }
+
+    /// Returns the identity name qualified by the name of its module (e.g.
+    /// `iana-if-type:ethernetCsmacd`), as used in JSON-encoded values.
+    pub fn qualified_name(&self) -> String {
+        format!("{}:{}", self.module().name(), self.name())
+    }
+}
+
+unsafe impl<'a> Binding<'a> for SchemaIdentity<'a> {
+    type CType = ffi::lysc_ident;
+    type Container = Context;
+
+    unsafe fn from_raw(
+        context: &'a Context,
+        raw: *mut ffi::lysc_ident,
+    ) -> SchemaIdentity<'a> {
+        SchemaIdentity { context, raw }
+    }
+}
+
+impl PartialEq for SchemaIdentity<'_> {
+    fn eq(&self, other: &SchemaIdentity<'_>) -> bool {
+        self.raw == other.raw
+    }
+}
+
+unsafe impl Send for SchemaIdentity<'_> {}
+unsafe impl Sync for SchemaIdentity<'_> {}
+
 // ===== impl SchemaExtInstance =====
 
 impl<'a> SchemaExtInstance<'a> {
@@ -1359,6 +3205,17 @@ impl<'a> SchemaExtInstance<'a> {
         self.raw
     }
 
+    /// Returns the name of the extension.
+    pub fn name(&self) -> &str {
+        char_ptr_to_str(unsafe { (*(*self.raw).def).name })
+    }
+
+    /// Returns the module where the extension is defined.
+    pub fn module(&self) -> SchemaModule<'a> {
+        let module = unsafe { (*(*self.raw).def).module };
+        unsafe { SchemaModule::from_raw(self.context, module) }
+    }
+
     /// Returns the optional extension's argument.
     pub fn argument(&self) -> Option<String> {
         let argument = unsafe { (*self.raw).argument };
@@ -0,0 +1,329 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Session checkpoints (context and data trees saved to a single file).
+
+use libyang3_sys as ffi;
+use std::convert::TryInto;
+use std::path::Path;
+
+use crate::context::{Context, ContextBuilder, ContextFlags, ModuleCapability};
+use crate::data::{
+    Data, DataFormat, DataParserFlags, DataPrinterFlags, DataTree,
+    DataValidationFlags,
+};
+use crate::error::{io_error, Error, Result};
+
+/// Magic number identifying session files.
+const SESSION_MAGIC: &[u8; 8] = b"YANGSESS";
+
+/// Version of the session file format.
+const SESSION_VERSION: u32 = 2;
+
+/// Checkpoint of a YANG context and of one or more data trees, as saved by
+/// [`save_session`] and loaded by [`load_session`].
+///
+/// The modules of the context are saved as references (name, revision and
+/// enabled features), so their sources must be available in the search
+/// directory when the context is restored. The revisions of the modules that
+/// are only imported are saved as well, so that imports without a revision
+/// resolve to the same modules. The data trees are saved in the LYB format,
+/// which is much faster to load than the other formats.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Session {
+    /// Context options.
+    pub options: u16,
+    /// Implemented modules.
+    pub modules: Vec<ModuleCapability>,
+    /// Modules that are only imported.
+    pub import_only_modules: Vec<ModuleCapability>,
+    /// Named data trees, in the LYB format.
+    pub trees: Vec<(String, Vec<u8>)>,
+}
+
+// ===== impl Session =====
+
+impl Session {
+    /// Create a new session, referencing the modules of the given context.
+    pub fn new(context: &Context) -> Session {
+        let (modules, import_only_modules) = context
+            .modules(true)
+            .partition::<Vec<_>, _>(|module| module.is_implemented());
+        Session {
+            options: context.get_options().bits(),
+            modules: modules
+                .iter()
+                .map(ModuleCapability::from_module)
+                .collect(),
+            import_only_modules: import_only_modules
+                .iter()
+                .map(ModuleCapability::from_module)
+                .collect(),
+            trees: Vec::new(),
+        }
+    }
+
+    /// Add a named data tree to the session, replacing any previous tree of
+    /// the same name.
+    pub fn add_tree(&mut self, name: &str, dtree: &DataTree<'_>) -> Result<()> {
+        let data = match dtree.reference() {
+            Some(_) => dtree.print_bytes(
+                DataFormat::LYB,
+                DataPrinterFlags::WITH_SIBLINGS,
+            )?,
+            None => Vec::new(),
+        };
+        self.trees.retain(|(tree_name, _)| tree_name != name);
+        self.trees.push((name.to_owned(), data));
+        Ok(())
+    }
+
+    /// Returns an iterator over the names of the saved data trees.
+    pub fn tree_names(&self) -> impl Iterator<Item = &str> {
+        self.trees.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Restore the context of the session, loading its modules from the
+    /// given search directory.
+    ///
+    /// The context is created from YANG Library data listing the modules of
+    /// the session, so that the modules that are only imported are loaded
+    /// in their saved revisions.
+    pub fn context<P: AsRef<Path>>(&self, search_dir: P) -> Result<Context> {
+        ContextBuilder::new()
+            .options(ContextFlags::from_bits_truncate(self.options))
+            .search_dir(search_dir)
+            .yang_library(&self.yang_library(), DataFormat::JSON)
+            .build()
+    }
+
+    /// Restore the data tree of the given name, using the given context
+    /// (usually restored by [`Session::context`]).
+    pub fn tree<'a>(
+        &self,
+        context: &'a Context,
+        name: &str,
+    ) -> Result<DataTree<'a>> {
+        let data = self
+            .trees
+            .iter()
+            .find(|(tree_name, _)| tree_name == name)
+            .map(|(_, data)| data)
+            .ok_or_else(|| Error {
+                errcode: ffi::LY_ERR::LY_ENOTFOUND,
+                msg: Some(format!("Data tree \"{}\" not found", name)),
+                ..Default::default()
+            })?;
+        if data.is_empty() {
+            return Ok(DataTree::new(context));
+        }
+
+        DataTree::parse_string(
+            context,
+            data,
+            DataFormat::LYB,
+            DataParserFlags::NO_VALIDATION,
+            DataValidationFlags::empty(),
+        )
+    }
+
+    /// Encode the session into a single buffer.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(SESSION_MAGIC);
+        buf.extend_from_slice(&SESSION_VERSION.to_le_bytes());
+        buf.extend_from_slice(&self.options.to_le_bytes());
+        for modules in [&self.modules, &self.import_only_modules] {
+            buf.extend_from_slice(&(modules.len() as u32).to_le_bytes());
+            for module in modules {
+                put_bytes(&mut buf, module.urn().as_bytes());
+            }
+        }
+        buf.extend_from_slice(&(self.trees.len() as u32).to_le_bytes());
+        for (name, data) in &self.trees {
+            put_bytes(&mut buf, name.as_bytes());
+            put_bytes(&mut buf, data);
+        }
+        buf
+    }
+
+    /// Decode a session encoded by [`Session::to_bytes`].
+    pub fn from_bytes(mut buf: &[u8]) -> Result<Session> {
+        let magic = get_slice(&mut buf, SESSION_MAGIC.len())?;
+        if magic != SESSION_MAGIC {
+            return Err(invalid_session("bad magic number"));
+        }
+        // Version 1 files have no import-only modules.
+        let version = u32::from_le_bytes(get_array(&mut buf)?);
+        if version == 0 || version > SESSION_VERSION {
+            return Err(invalid_session("unsupported version"));
+        }
+
+        let mut session = Session {
+            options: u16::from_le_bytes(get_array(&mut buf)?),
+            ..Default::default()
+        };
+        session.modules = get_modules(&mut buf)?;
+        if version >= 2 {
+            session.import_only_modules = get_modules(&mut buf)?;
+        }
+        let count = u32::from_le_bytes(get_array(&mut buf)?);
+        for _ in 0..count {
+            let name = get_string(&mut buf)?;
+            let data = get_bytes(&mut buf)?.to_vec();
+            session.trees.push((name, data));
+        }
+
+        Ok(session)
+    }
+
+    // Returns YANG Library data (in the JSON format) listing the modules of
+    // the session.
+    fn yang_library(&self) -> String {
+        let module_entry = |module: &ModuleCapability, import_only: bool| {
+            let mut entry = format!(
+                "{{\"name\":{},\"namespace\":{}",
+                json_string(&module.name),
+                json_string(&module.namespace)
+            );
+            // The revision is a key of the import-only modules.
+            match &module.revision {
+                Some(revision) => entry.push_str(&format!(
+                    ",\"revision\":{}",
+                    json_string(revision)
+                )),
+                None if import_only => entry.push_str(",\"revision\":\"\""),
+                None => (),
+            }
+            if !import_only && !module.features.is_empty() {
+                let features = module
+                    .features
+                    .iter()
+                    .map(|feature| json_string(feature))
+                    .collect::<Vec<_>>();
+                entry.push_str(&format!(
+                    ",\"feature\":[{}]",
+                    features.join(",")
+                ));
+            }
+            entry.push('}');
+            entry
+        };
+        let modules = self
+            .modules
+            .iter()
+            .map(|module| module_entry(module, false))
+            .collect::<Vec<_>>();
+        let import_only_modules = self
+            .import_only_modules
+            .iter()
+            .map(|module| module_entry(module, true))
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\"ietf-yang-library:yang-library\":{{\
+             \"module-set\":[{{\"name\":\"complete\",\
+             \"module\":[{}],\"import-only-module\":[{}]}}],\
+             \"schema\":[{{\"name\":\"complete\",\
+             \"module-set\":[\"complete\"]}}],\
+             \"content-id\":\"1\"}}}}",
+            modules.join(","),
+            import_only_modules.join(",")
+        )
+    }
+}
+
+/// Save the modules implemented in the given context, along with the given
+/// named data trees, to a single session file.
+pub fn save_session<P: AsRef<Path>>(
+    path: P,
+    context: &Context,
+    trees: &[(&str, &DataTree<'_>)],
+) -> Result<()> {
+    let mut session = Session::new(context);
+    for (name, dtree) in trees {
+        session.add_tree(name, dtree)?;
+    }
+
+    std::fs::write(path, session.to_bytes()).map_err(io_error)
+}
+
+/// Load a session file saved by [`save_session`].
+pub fn load_session<P: AsRef<Path>>(path: P) -> Result<Session> {
+    let buf = std::fs::read(path).map_err(io_error)?;
+    Session::from_bytes(&buf)
+}
+
+// ===== helper functions =====
+
+// Returns the given string as a JSON string literal.
+fn json_string(value: &str) -> String {
+    let mut string = String::with_capacity(value.len() + 2);
+    string.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => string.push_str("\\\""),
+            '\\' => string.push_str("\\\\"),
+            c if c.is_control() => {
+                string.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => string.push(c),
+        }
+    }
+    string.push('"');
+    string
+}
+
+// Consume a list of module capability URIs.
+fn get_modules(buf: &mut &[u8]) -> Result<Vec<ModuleCapability>> {
+    let count = u32::from_le_bytes(get_array(buf)?);
+    (0..count)
+        .map(|_| ModuleCapability::parse(&get_string(buf)?))
+        .collect()
+}
+
+// Append a length-prefixed byte string.
+fn put_bytes(buf: &mut Vec<u8>, data: &[u8]) {
+    buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buf.extend_from_slice(data);
+}
+
+// Consume the given number of bytes.
+fn get_slice<'b>(buf: &mut &'b [u8], len: usize) -> Result<&'b [u8]> {
+    if buf.len() < len {
+        return Err(invalid_session("truncated file"));
+    }
+    let (data, rest) = buf.split_at(len);
+    *buf = rest;
+    Ok(data)
+}
+
+// Consume a fixed-size array.
+fn get_array<const N: usize>(buf: &mut &[u8]) -> Result<[u8; N]> {
+    Ok(get_slice(buf, N)?.try_into().unwrap())
+}
+
+// Consume a length-prefixed byte string.
+fn get_bytes<'b>(buf: &mut &'b [u8]) -> Result<&'b [u8]> {
+    let len = u32::from_le_bytes(get_array(buf)?) as usize;
+    get_slice(buf, len)
+}
+
+// Consume a length-prefixed UTF-8 string.
+fn get_string(buf: &mut &[u8]) -> Result<String> {
+    let data = get_bytes(buf)?;
+    String::from_utf8(data.to_vec())
+        .map_err(|_| invalid_session("invalid UTF-8 string"))
+}
+
+// Returns an error for a malformed session file.
+fn invalid_session(reason: &str) -> Error {
+    Error {
+        errcode: ffi::LY_ERR::LY_EINVAL,
+        msg: Some(format!("Invalid session file: {}", reason)),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,188 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! YANG module tags (RFC 8819).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::context::Context;
+use crate::data::{Data, DataTree};
+use crate::error::Result;
+use crate::schema::SchemaModule;
+
+/// Path of the module tags container defined by the `ietf-module-tags`
+/// module.
+const MODULE_TAGS_PATH: &str = "/ietf-module-tags:module-tags";
+
+/// Tags associated with YANG modules, as defined by the `ietf-module-tags`
+/// module (RFC 8819).
+///
+/// Tags listed as masked for a module aren't considered associated with it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModuleTags {
+    modules: BTreeMap<String, ModuleTagEntry>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct ModuleTagEntry {
+    tags: BTreeSet<String>,
+    masked: BTreeSet<String>,
+}
+
+// ===== impl ModuleTags =====
+
+impl ModuleTags {
+    /// Create a new empty set of module tags.
+    pub fn new() -> ModuleTags {
+        Default::default()
+    }
+
+    /// Read the module tags from the given `ietf-module-tags` data tree.
+    ///
+    /// The `ietf-module-tags` module must be loaded in the context of the
+    /// data tree.
+    pub fn from_tree(dtree: &DataTree<'_>) -> Result<ModuleTags> {
+        let mut module_tags = ModuleTags::new();
+
+        let xpath = format!("{}/module", MODULE_TAGS_PATH);
+        for dnode in dtree.find_xpath(&xpath)? {
+            let mut name = None;
+            let mut entry = ModuleTagEntry::default();
+            for child in dnode.children() {
+                let value = match child.value_canonical() {
+                    Some(value) => value,
+                    None => continue,
+                };
+                match child.schema().name() {
+                    "name" => name = Some(value),
+                    "tag" => {
+                        entry.tags.insert(value);
+                    }
+                    "masked-tag" => {
+                        entry.masked.insert(value);
+                    }
+                    _ => (),
+                }
+            }
+            if let Some(name) = name {
+                module_tags.modules.insert(name, entry);
+            }
+        }
+
+        Ok(module_tags)
+    }
+
+    /// Create a new `ietf-module-tags` data tree containing these module
+    /// tags.
+    ///
+    /// The `ietf-module-tags` module must be loaded in the given context.
+    pub fn to_tree<'a>(&self, context: &'a Context) -> Result<DataTree<'a>> {
+        let mut dtree = DataTree::new(context);
+
+        for (name, entry) in &self.modules {
+            let path = format!("{}/module[name='{}']", MODULE_TAGS_PATH, name);
+            dtree.new_path(&path, None, false)?;
+            for tag in &entry.tags {
+                dtree.new_path(&format!("{}/tag", path), Some(tag), false)?;
+            }
+            for tag in &entry.masked {
+                dtree.new_path(
+                    &format!("{}/masked-tag", path),
+                    Some(tag),
+                    false,
+                )?;
+            }
+        }
+
+        Ok(dtree)
+    }
+
+    /// Associate a tag with the given module.
+    pub fn add_tag(&mut self, module: &str, tag: &str) {
+        self.entry(module).tags.insert(tag.to_owned());
+    }
+
+    /// Remove a tag from the given module. Returns whether the tag was
+    /// present.
+    pub fn remove_tag(&mut self, module: &str, tag: &str) -> bool {
+        self.modules
+            .get_mut(module)
+            .map(|entry| entry.tags.remove(tag))
+            .unwrap_or(false)
+    }
+
+    /// Mask a tag of the given module, so that it's no longer associated with
+    /// it.
+    pub fn mask_tag(&mut self, module: &str, tag: &str) {
+        self.entry(module).masked.insert(tag.to_owned());
+    }
+
+    /// Returns an iterator over the names of the modules with tags.
+    pub fn modules(&self) -> impl Iterator<Item = &str> {
+        self.modules.keys().map(String::as_str)
+    }
+
+    /// Returns an iterator over the tags associated with the given module,
+    /// excluding the masked ones.
+    pub fn tags<'b>(&'b self, module: &str) -> impl Iterator<Item = &'b str> {
+        self.modules
+            .get(module)
+            .into_iter()
+            .flat_map(|entry| entry.tags.difference(&entry.masked))
+            .map(String::as_str)
+    }
+
+    /// Returns an iterator over the masked tags of the given module.
+    pub fn masked_tags<'b>(
+        &'b self,
+        module: &str,
+    ) -> impl Iterator<Item = &'b str> {
+        self.modules
+            .get(module)
+            .into_iter()
+            .flat_map(|entry| entry.masked.iter())
+            .map(String::as_str)
+    }
+
+    /// Returns whether the given tag is associated with the given module.
+    pub fn has_tag(&self, module: &str, tag: &str) -> bool {
+        self.modules
+            .get(module)
+            .map(|entry| {
+                entry.tags.contains(tag) && !entry.masked.contains(tag)
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns an iterator over the names of the modules associated with the
+    /// given tag.
+    pub fn modules_with_tag<'b>(
+        &'b self,
+        tag: &'b str,
+    ) -> impl Iterator<Item = &'b str> {
+        self.modules
+            .keys()
+            .filter(move |module| self.has_tag(module, tag))
+            .map(String::as_str)
+    }
+
+    /// Returns the modules loaded in the given context that are associated
+    /// with the given tag.
+    pub fn filter_modules<'a>(
+        &self,
+        context: &'a Context,
+        tag: &str,
+    ) -> Vec<SchemaModule<'a>> {
+        context
+            .modules(false)
+            .filter(|module| self.has_tag(module.name(), tag))
+            .collect()
+    }
+
+    fn entry(&mut self, module: &str) -> &mut ModuleTagEntry {
+        self.modules.entry(module.to_owned()).or_default()
+    }
+}
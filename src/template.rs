@@ -0,0 +1,288 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Configuration templates.
+
+use libyang3_sys as ffi;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::data::{Data, DataNodeRef, DataTree};
+use crate::error::{Error, Result};
+use crate::iter::NodeIterable;
+
+/// Configuration template, consisting of a set of data nodes whose paths and
+/// values can contain variables in the `${name}` form (e.g.
+/// `/ietf-interfaces:interfaces/interface[name='${ifname}']/enabled`).
+#[derive(Clone, Debug)]
+pub struct ConfigTemplate {
+    name: String,
+    nodes: Vec<(String, Option<String>)>,
+}
+
+/// Effective (intended) configuration, obtained by expanding configuration
+/// templates on top of the running configuration.
+///
+/// Nodes present in the running configuration take precedence over the nodes
+/// produced by templates, and templates applied first take precedence over
+/// the ones applied later.
+#[derive(Debug)]
+pub struct EffectiveConfig<'a> {
+    tree: DataTree<'a>,
+    // Nodes present before the templates were applied.
+    running: HashSet<*mut ffi::lyd_node>,
+    // Template that produced each node.
+    origins: HashMap<*mut ffi::lyd_node, String>,
+}
+
+// ===== impl ConfigTemplate =====
+
+impl ConfigTemplate {
+    /// Create a new empty template.
+    pub fn new(name: &str) -> ConfigTemplate {
+        ConfigTemplate {
+            name: name.to_owned(),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Returns the name of the template.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Add a node to the template. Both the path and the value can contain
+    /// variables, substituted when the template is applied.
+    ///
+    /// Variables used in path predicates are quoted as needed, and fail to
+    /// be substituted when their value can't be quoted safely.
+    pub fn add_node(&mut self, path: &str, value: Option<&str>) {
+        self.nodes
+            .push((path.to_owned(), value.map(ToOwned::to_owned)));
+    }
+}
+
+// ===== impl EffectiveConfig =====
+
+impl<'a> EffectiveConfig<'a> {
+    /// Start computing the effective configuration from a copy of the given
+    /// running configuration.
+    pub fn new(running: &DataTree<'a>) -> Result<EffectiveConfig<'a>> {
+        let tree = running.duplicate()?;
+        let running = tree.traverse().map(|dnode| dnode.as_raw()).collect();
+        Ok(EffectiveConfig {
+            tree,
+            running,
+            origins: Default::default(),
+        })
+    }
+
+    /// Expand the given template, substituting its variables with the given
+    /// values.
+    ///
+    /// Template nodes that already exist in the effective configuration are
+    /// skipped. The resulting data tree isn't validated.
+    pub fn apply(
+        &mut self,
+        template: &ConfigTemplate,
+        variables: &[(&str, &str)],
+    ) -> Result<()> {
+        for (path, value) in &template.nodes {
+            let path = substitute_path(path, variables)?;
+            let value = value
+                .as_ref()
+                .map(|value| substitute(value, variables))
+                .transpose()?;
+
+            if self.tree.find_path(&path).is_ok() {
+                continue;
+            }
+            let dnode =
+                match self.tree.new_path(&path, value.as_deref(), false)? {
+                    Some(dnode) => dnode,
+                    None => continue,
+                };
+
+            // Record the origin of all created nodes.
+            for dnode in dnode.inclusive_ancestors() {
+                let raw = dnode.as_raw();
+                if self.running.contains(&raw)
+                    || self.origins.contains_key(&raw)
+                {
+                    break;
+                }
+                self.origins.insert(raw, template.name.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the effective configuration data tree.
+    pub fn tree(&self) -> &DataTree<'a> {
+        &self.tree
+    }
+
+    /// Consumes the effective configuration, returning its data tree.
+    pub fn into_tree(self) -> DataTree<'a> {
+        self.tree
+    }
+
+    /// Returns the name of the template that produced the given node (or one
+    /// of its ancestors), or `None` if the node comes from the running
+    /// configuration.
+    pub fn origin(&self, dnode: &DataNodeRef<'_>) -> Option<&str> {
+        dnode
+            .inclusive_ancestors()
+            .find_map(|dnode| self.origins.get(&dnode.as_raw()))
+            .map(String::as_str)
+    }
+
+    /// Returns the provenance map of the nodes produced by templates, mapping
+    /// their data paths to the names of the templates that produced them.
+    ///
+    /// Only the topmost node of each produced subtree is listed.
+    pub fn provenance(&self) -> BTreeMap<String, &str> {
+        self.tree
+            .traverse()
+            .filter_map(|dnode| {
+                let origin = self.origins.get(&dnode.as_raw())?;
+                let parent_origin = dnode
+                    .parent()
+                    .and_then(|parent| self.origins.get(&parent.as_raw()));
+                if parent_origin.is_some() {
+                    return None;
+                }
+                Some((dnode.path(), origin.as_str()))
+            })
+            .collect()
+    }
+}
+
+unsafe impl Send for EffectiveConfig<'_> {}
+unsafe impl Sync for EffectiveConfig<'_> {}
+
+// ===== helper functions =====
+
+// Substitute the `${name}` variables of the given text.
+fn substitute(text: &str, variables: &[(&str, &str)]) -> Result<String> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        output.push_str(&rest[..start]);
+        let (_, value, len) = variable(text, &rest[start..], variables)?;
+        output.push_str(value);
+        rest = &rest[start + len..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+// Substitute the `${name}` variables of the given data path.
+//
+// Values that land inside predicates are quoted, unless already inside a
+// quoted literal. A literal consisting of a single variable is quoted again
+// with the other quote character if needed, so that no value can end the
+// literal it's part of and inject extra predicates.
+fn substitute_path(path: &str, variables: &[(&str, &str)]) -> Result<String> {
+    let mut output = String::with_capacity(path.len());
+    let mut depth = 0;
+    // Quote character and output position of the current literal, if any.
+    let mut literal: Option<(char, usize)> = None;
+    let mut rest = path;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("${") {
+            let (name, value, len) = variable(path, rest, variables)?;
+            rest = &rest[len..];
+            match literal {
+                _ if depth == 0 => output.push_str(value),
+                None => {
+                    let quote = quote_char(name, value)?;
+                    output.push(quote);
+                    output.push_str(value);
+                    output.push(quote);
+                }
+                Some((quote, _)) if !value.contains(quote) => {
+                    output.push_str(value)
+                }
+                Some((quote, start))
+                    if start + 1 == output.len() && rest.starts_with(quote) =>
+                {
+                    let quote = quote_char(name, value)?;
+                    output.truncate(start);
+                    output.push(quote);
+                    output.push_str(value);
+                    output.push(quote);
+                    rest = &rest[1..];
+                    literal = None;
+                }
+                Some(_) => return Err(unquotable_error(name)),
+            }
+            continue;
+        }
+
+        match (literal, c) {
+            (Some((quote, _)), _) if c == quote => literal = None,
+            (Some(_), _) => (),
+            (None, '\'' | '"') if depth > 0 => {
+                literal = Some((c, output.len()))
+            }
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            _ => (),
+        }
+        output.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    Ok(output)
+}
+
+// Returns the name and value of the variable at the start of the given text,
+// along with its length.
+fn variable<'r, 'v>(
+    text: &str,
+    rest: &'r str,
+    variables: &[(&str, &'v str)],
+) -> Result<(&'r str, &'v str, usize)> {
+    let end = rest.find('}').ok_or_else(|| Error {
+        errcode: ffi::LY_ERR::LY_EINVAL,
+        msg: Some(format!("Unterminated variable in \"{}\"", text)),
+        ..Default::default()
+    })?;
+    let name = &rest[2..end];
+    let value = variables
+        .iter()
+        .find(|(var, _)| *var == name)
+        .map(|(_, value)| *value)
+        .ok_or_else(|| Error {
+            errcode: ffi::LY_ERR::LY_EINVAL,
+            msg: Some(format!("Undefined variable \"{}\"", name)),
+            ..Default::default()
+        })?;
+
+    Ok((name, value, end + 1))
+}
+
+// Returns a quote character that doesn't occur in the given variable value.
+fn quote_char(name: &str, value: &str) -> Result<char> {
+    if !value.contains('\'') {
+        Ok('\'')
+    } else if !value.contains('"') {
+        Ok('"')
+    } else {
+        Err(unquotable_error(name))
+    }
+}
+
+// Returns the error for a variable value that can't be quoted safely.
+fn unquotable_error(name: &str) -> Error {
+    Error {
+        errcode: ffi::LY_ERR::LY_EINVAL,
+        msg: Some(format!("Value of variable \"{}\" can't be quoted", name)),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,823 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Schema-driven generation of example YANG data.
+
+use bitflags::bitflags;
+use std::ffi::CString;
+
+use crate::context::Context;
+use crate::data::DataTree;
+#[cfg(feature = "proptest")]
+use crate::data::DataValidationFlags;
+use crate::error::Result;
+use crate::schema::{
+    DataValueType, RangePart, SchemaLeafType, SchemaModule, SchemaNode,
+    SchemaNodeKind, TypeRange,
+};
+use crate::utils::*;
+use libyang3_sys as ffi;
+#[cfg(feature = "proptest")]
+use proptest::prelude::Rng as _;
+#[cfg(feature = "proptest")]
+use proptest::strategy::{NewTree, Strategy, ValueTree};
+#[cfg(feature = "proptest")]
+use proptest::test_runner::TestRunner;
+
+/// Generator of example YANG data.
+///
+/// The generated data is valid on a best-effort basis: values are chosen
+/// according to their types (ranges, lengths, patterns, enumerations,
+/// identities, etc), list keys are unique and mandatory nodes are present.
+/// Nodes subject to "when" or "must" conditions, as well as leafrefs and
+/// instance-identifiers requiring an existing instance, are only generated
+/// when mandatory.
+#[derive(Clone, Debug)]
+pub struct DataGenerator<'a> {
+    context: &'a Context,
+    options: DataGeneratorFlags,
+    list_entries: u32,
+    seed: u64,
+}
+
+bitflags! {
+    /// Data generation options.
+    ///
+    /// Default behavior:
+    /// - only mandatory leaves (and list keys) are generated.
+    /// - state data is not generated.
+    #[derive(Clone, Copy, Debug)]
+    pub struct DataGeneratorFlags: u32 {
+        /// Generate optional nodes too (leaves, leaf-lists, presence
+        /// containers and non-mandatory choices).
+        const OPTIONAL = 0x01;
+        /// Generate state data too.
+        const STATE = 0x02;
+    }
+}
+
+// Simple xorshift pseudo-random number generator.
+#[derive(Debug)]
+struct Rng(u64);
+
+/// Proptest strategy generating random data trees for a module (see
+/// [`DataGenerator::strategy`]).
+#[cfg(feature = "proptest")]
+#[derive(Clone, Debug)]
+pub struct DataTreeStrategy<'a> {
+    generator: DataGenerator<'a>,
+    module: SchemaModule<'a>,
+    invalid: bool,
+}
+
+/// Proptest value tree of a generated data tree, shrinking it by removing one
+/// subtree at a time.
+#[cfg(feature = "proptest")]
+#[derive(Debug)]
+pub struct DataTreeValueTree<'a> {
+    tree: DataTree<'a>,
+    prev: Option<DataTree<'a>>,
+    paths: Vec<String>,
+    next: usize,
+    keep_valid: bool,
+    state: bool,
+}
+
+// ===== impl DataGenerator =====
+
+impl<'a> DataGenerator<'a> {
+    /// Create a new data generator.
+    pub fn new(
+        context: &'a Context,
+        options: DataGeneratorFlags,
+    ) -> DataGenerator<'a> {
+        DataGenerator {
+            context,
+            options,
+            list_entries: 1,
+            seed: 0,
+        }
+    }
+
+    /// Set the number of instances generated for each list and leaf-list
+    /// (default: 1). The min-elements and max-elements constraints take
+    /// precedence.
+    pub fn set_list_entries(&mut self, list_entries: u32) {
+        self.list_entries = list_entries;
+    }
+
+    /// Set the seed used to pick values, list keys and choice cases
+    /// (default: 0). The same seed always generates the same data.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Generate example data for the given schema subtree.
+    ///
+    /// The ancestors of the schema node are also generated, including the
+    /// keys of the ancestor lists.
+    pub fn generate(&self, snode: &SchemaNode<'_>) -> Result<DataTree<'a>> {
+        let mut rng = Rng::new(self.seed);
+        let mut tree = DataTree::new(self.context);
+
+        // Build the path of the parent data node.
+        let mut parent_path = String::new();
+        let mut ancestors = snode
+            .ancestors()
+            .filter(|snode| !snode.is_schema_only())
+            .collect::<Vec<_>>();
+        ancestors.reverse();
+        for ancestor in ancestors {
+            parent_path = node_path(&parent_path, &ancestor);
+            if ancestor.kind() == SchemaNodeKind::List {
+                let keys = self.list_keys(&ancestor, &mut rng, &[]);
+                let keys = keys.unwrap_or_default();
+                parent_path.push_str(&key_predicates(&ancestor, &keys));
+            }
+        }
+
+        self.generate_node(&mut tree, &mut rng, &parent_path, snode)?;
+        Ok(tree)
+    }
+
+    /// Generate example data for all top-level data nodes of the given
+    /// module.
+    pub fn generate_module(
+        &self,
+        module: &SchemaModule<'_>,
+    ) -> Result<DataTree<'a>> {
+        let mut rng = Rng::new(self.seed);
+        let mut tree = DataTree::new(self.context);
+        for snode in module.data() {
+            self.generate_node(&mut tree, &mut rng, "", &snode)?;
+        }
+        Ok(tree)
+    }
+
+    /// Returns valid example values for the given leaf or leaf-list, in
+    /// canonical or lexical form.
+    pub fn values(&self, snode: &SchemaNode<'_>) -> Vec<String> {
+        let mut rng = Rng::new(self.seed);
+        self.candidates(snode, 0, &mut rng)
+            .into_iter()
+            .filter(|value| self.validate(snode, value))
+            .collect()
+    }
+
+    /// Returns a proptest strategy generating random data trees for the given
+    /// module, using a different seed for each test case.
+    ///
+    /// When `invalid` is set, a mandatory node (or the last instance of a list
+    /// with a min-elements constraint) is removed from each generated data
+    /// tree when possible, making it slightly invalid.
+    ///
+    /// Generated data trees are shrunk by removing one subtree at a time. The
+    /// shrunk data trees remain valid unless `invalid` is set.
+    #[cfg(feature = "proptest")]
+    pub fn strategy(
+        &self,
+        module: &SchemaModule<'a>,
+        invalid: bool,
+    ) -> DataTreeStrategy<'a> {
+        DataTreeStrategy {
+            generator: self.clone(),
+            module: module.clone(),
+            invalid,
+        }
+    }
+
+    fn generate_node(
+        &self,
+        tree: &mut DataTree<'a>,
+        rng: &mut Rng,
+        parent_path: &str,
+        snode: &SchemaNode<'_>,
+    ) -> Result<()> {
+        let mandatory = snode.is_mandatory()
+            || snode.min_elements().unwrap_or(0) > 0
+            || snode.is_list_key();
+        let optional = self.options.contains(DataGeneratorFlags::OPTIONAL);
+
+        // Skip nodes that can't be generated.
+        if !snode.is_config()
+            && !snode.is_schema_only()
+            && !self.options.contains(DataGeneratorFlags::STATE)
+        {
+            return Ok(());
+        }
+        if !mandatory
+            && (snode.whens().next().is_some()
+                || snode
+                    .musts()
+                    .is_some_and(|mut musts| musts.next().is_some()))
+        {
+            return Ok(());
+        }
+
+        match snode.kind() {
+            SchemaNodeKind::Container => {
+                let path = node_path(parent_path, snode);
+                if !snode.is_np_container() {
+                    if !optional && !mandatory {
+                        return Ok(());
+                    }
+                    tree.new_path(&path, None, false)?;
+                }
+                for child in snode.children() {
+                    self.generate_node(tree, rng, &path, &child)?;
+                }
+            }
+            SchemaNodeKind::Choice => {
+                if !optional && !mandatory {
+                    return Ok(());
+                }
+                let case = snode.default_case().or_else(|| {
+                    let cases = snode.children().collect::<Vec<_>>();
+                    rng.choose(&cases).cloned()
+                });
+                if let Some(case) = case {
+                    self.generate_node(tree, rng, parent_path, &case)?;
+                }
+            }
+            SchemaNodeKind::Case => {
+                for child in snode.children() {
+                    self.generate_node(tree, rng, parent_path, &child)?;
+                }
+            }
+            SchemaNodeKind::Leaf => {
+                if snode.is_list_key() || (!optional && !mandatory) {
+                    return Ok(());
+                }
+                let path = node_path(parent_path, snode);
+                if let Some(value) = self.value(snode, 0, rng, &[]) {
+                    let value = leaf_value(snode, &value);
+                    tree.new_path(&path, value, false)?;
+                }
+            }
+            SchemaNodeKind::LeafList => {
+                if !optional && !mandatory {
+                    return Ok(());
+                }
+                let path = node_path(parent_path, snode);
+                let mut values = vec![];
+                for index in 0..self.instances(snode) {
+                    let Some(value) = self.value(snode, index, rng, &values)
+                    else {
+                        break;
+                    };
+                    let path = format!("{}[.={}]", path, quote(&value));
+                    tree.new_path(&path, None, false)?;
+                    values.push(value);
+                }
+            }
+            SchemaNodeKind::List => {
+                let path = node_path(parent_path, snode);
+                let mut instances = vec![];
+                for _ in 0..self.instances(snode) {
+                    let Some(keys) = self.list_keys(snode, rng, &instances)
+                    else {
+                        break;
+                    };
+                    let list_path =
+                        format!("{}{}", path, key_predicates(snode, &keys));
+                    let instance_path = tree
+                        .new_path(&list_path, None, false)?
+                        .map(|dnode| dnode.path())
+                        .unwrap_or(list_path);
+                    for child in snode.children() {
+                        self.generate_node(tree, rng, &instance_path, &child)?;
+                    }
+                    instances.push(keys);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    // Number of instances to generate for the given list or leaf-list.
+    fn instances(&self, snode: &SchemaNode<'_>) -> u32 {
+        let min = snode.min_elements().unwrap_or(0);
+        let max = snode.max_elements().unwrap_or(u32::MAX);
+        self.list_entries.max(min).min(max)
+    }
+
+    // Generate the key values of a new list instance, unique among the given
+    // existing instances.
+    fn list_keys(
+        &self,
+        snode: &SchemaNode<'_>,
+        rng: &mut Rng,
+        instances: &[Vec<String>],
+    ) -> Option<Vec<String>> {
+        let index = instances.len() as u32;
+        snode
+            .list_keys()
+            .enumerate()
+            .map(|(i, key)| {
+                let used = instances
+                    .iter()
+                    .map(|keys| keys[i].clone())
+                    .collect::<Vec<_>>();
+                self.value(&key, index, rng, &used)
+            })
+            .collect()
+    }
+
+    // Pick a valid value for the given leaf or leaf-list, different from the
+    // given used values.
+    fn value(
+        &self,
+        snode: &SchemaNode<'_>,
+        index: u32,
+        rng: &mut Rng,
+        used: &[String],
+    ) -> Option<String> {
+        self.candidates(snode, index, rng)
+            .into_iter()
+            .filter(|value| !used.contains(value))
+            .find(|value| self.validate(snode, value))
+    }
+
+    fn candidates(
+        &self,
+        snode: &SchemaNode<'_>,
+        index: u32,
+        rng: &mut Rng,
+    ) -> Vec<String> {
+        match snode.leaf_type() {
+            Some(ltype) => type_candidates(snode.context, &ltype, index, rng),
+            None => vec![],
+        }
+    }
+
+    fn validate(&self, snode: &SchemaNode<'_>, value: &str) -> bool {
+        let cvalue = CString::new(value).unwrap();
+        let ret = unsafe {
+            ffi::lyd_value_validate(
+                self.context.raw,
+                snode.raw,
+                cvalue.as_ptr(),
+                value.len(),
+                std::ptr::null(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        ret == ffi::LY_ERR::LY_SUCCESS
+    }
+}
+
+// ===== impl DataTreeStrategy =====
+
+#[cfg(feature = "proptest")]
+impl<'a> Strategy for DataTreeStrategy<'a> {
+    type Tree = DataTreeValueTree<'a>;
+    type Value = DataTree<'a>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let mut generator = self.generator.clone();
+        generator.set_seed(runner.rng().next_u64());
+        let mut tree = generator
+            .generate_module(&self.module)
+            .map_err(|error| error.to_string())?;
+
+        // Make the data tree invalid by removing a mandatory node.
+        if self.invalid {
+            let paths = tree
+                .traverse()
+                .filter(|dnode| !dnode.is_opaque())
+                .filter(|dnode| {
+                    let snode = dnode.schema();
+                    (snode.is_mandatory() && !snode.is_list_key())
+                        || (snode.min_elements().unwrap_or(0) > 0
+                            && dnode.list_instances().count() as u32
+                                <= snode.min_elements().unwrap_or(0))
+                })
+                .map(|dnode| dnode.path())
+                .collect::<Vec<_>>();
+            if !paths.is_empty() {
+                let index = runner.rng().next_u64() as usize % paths.len();
+                tree.remove(&paths[index])
+                    .map_err(|error| error.to_string())?;
+            }
+        }
+
+        let paths = tree
+            .traverse()
+            .filter(|dnode| dnode.is_opaque() || !dnode.schema().is_list_key())
+            .map(|dnode| dnode.path())
+            .collect();
+        Ok(DataTreeValueTree {
+            tree,
+            prev: None,
+            paths,
+            next: 0,
+            keep_valid: !self.invalid,
+            state: self.generator.options.contains(DataGeneratorFlags::STATE),
+        })
+    }
+}
+
+// ===== impl DataTreeValueTree =====
+
+#[cfg(feature = "proptest")]
+impl<'a> ValueTree for DataTreeValueTree<'a> {
+    type Value = DataTree<'a>;
+
+    fn current(&self) -> DataTree<'a> {
+        self.tree
+            .duplicate()
+            .expect("Failed to duplicate data tree")
+    }
+
+    fn simplify(&mut self) -> bool {
+        while self.next < self.paths.len() {
+            let path = &self.paths[self.next];
+            self.next += 1;
+
+            // Skip subtrees that were already removed along with an ancestor.
+            let Ok(mut tree) = self.tree.duplicate() else {
+                continue;
+            };
+            if tree.remove(path).is_err() {
+                continue;
+            }
+
+            // Skip removals that would make the data tree invalid.
+            if self.keep_valid {
+                let options = if self.state {
+                    DataValidationFlags::empty()
+                } else {
+                    DataValidationFlags::NO_STATE
+                };
+                let valid = tree
+                    .duplicate()
+                    .and_then(|mut tree| tree.validate(options));
+                if valid.is_err() {
+                    continue;
+                }
+            }
+
+            self.prev = Some(std::mem::replace(&mut self.tree, tree));
+            return true;
+        }
+
+        false
+    }
+
+    fn complicate(&mut self) -> bool {
+        match self.prev.take() {
+            Some(prev) => {
+                self.tree = prev;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// ===== impl Rng =====
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        Rng(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            0
+        } else {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    fn choose<'b, T>(&mut self, items: &'b [T]) -> Option<&'b T> {
+        items.get(self.below(items.len()))
+    }
+
+    fn rotate<T>(&mut self, items: &mut [T]) {
+        let n = self.below(items.len());
+        items.rotate_left(n);
+    }
+}
+
+// ===== helper functions =====
+
+fn node_path(parent_path: &str, snode: &SchemaNode<'_>) -> String {
+    format!("{}/{}:{}", parent_path, snode.module().name(), snode.name())
+}
+
+fn leaf_value<'b>(snode: &SchemaNode<'_>, value: &'b str) -> Option<&'b str> {
+    let empty = snode
+        .leaf_type()
+        .is_some_and(|ltype| ltype.base_type() == DataValueType::Empty);
+    if empty {
+        None
+    } else {
+        Some(value)
+    }
+}
+
+fn quote(value: &str) -> String {
+    if value.contains('\'') {
+        format!("\"{}\"", value)
+    } else {
+        format!("'{}'", value)
+    }
+}
+
+fn key_predicates(snode: &SchemaNode<'_>, keys: &[String]) -> String {
+    snode
+        .list_keys()
+        .zip(keys)
+        .map(|(key, value)| format!("[{}={}]", key.name(), quote(value)))
+        .collect()
+}
+
+fn type_candidates(
+    context: &Context,
+    ltype: &SchemaLeafType<'_>,
+    index: u32,
+    rng: &mut Rng,
+) -> Vec<String> {
+    let rtype = ltype.as_raw();
+    let base_type = unsafe { (*rtype).basetype };
+    let mut candidates = match base_type {
+        ffi::LY_DATA_TYPE::LY_TYPE_INT8
+        | ffi::LY_DATA_TYPE::LY_TYPE_INT16
+        | ffi::LY_DATA_TYPE::LY_TYPE_INT32
+        | ffi::LY_DATA_TYPE::LY_TYPE_INT64 => {
+            let (min, max) = signed_bounds(base_type);
+            signed_candidates(signed_parts(ltype.range()), min, max, index, rng)
+                .into_iter()
+                .map(|value| value.to_string())
+                .collect()
+        }
+        ffi::LY_DATA_TYPE::LY_TYPE_UINT8
+        | ffi::LY_DATA_TYPE::LY_TYPE_UINT16
+        | ffi::LY_DATA_TYPE::LY_TYPE_UINT32
+        | ffi::LY_DATA_TYPE::LY_TYPE_UINT64 => {
+            let max = unsigned_max(base_type);
+            unsigned_candidates(unsigned_parts(ltype.range()), max, index, rng)
+                .into_iter()
+                .map(|value| value.to_string())
+                .collect()
+        }
+        ffi::LY_DATA_TYPE::LY_TYPE_DEC64 => {
+            let digits = ltype.fraction_digits().unwrap_or(0) as u32;
+            let scale = 10i64.pow(digits);
+            let parts = signed_parts(ltype.range());
+            let (min, max) = match (parts.first(), parts.last()) {
+                (Some(first), Some(last)) => (first.0, last.1),
+                _ => (i64::MIN, i64::MAX),
+            };
+            let mut values = signed_candidates(parts, min, max, index, rng);
+            // Prefer integer values when possible.
+            values.insert(0, (index as i64).saturating_mul(scale));
+            values
+                .into_iter()
+                .map(|value| {
+                    let sign = if value < 0 { "-" } else { "" };
+                    let value = value.unsigned_abs();
+                    let scale = scale as u64;
+                    format!(
+                        "{}{}.{:0width$}",
+                        sign,
+                        value / scale,
+                        value % scale,
+                        width = digits as usize
+                    )
+                })
+                .collect()
+        }
+        ffi::LY_DATA_TYPE::LY_TYPE_STRING => {
+            let min_len = min_length(ltype);
+            string_candidates(index)
+                .into_iter()
+                .map(|mut value| {
+                    while (value.len() as u64) < min_len {
+                        value.push('x');
+                    }
+                    value
+                })
+                .collect()
+        }
+        ffi::LY_DATA_TYPE::LY_TYPE_BINARY => [min_length(ltype), 1, 3]
+            .iter()
+            .map(|len| base64_zeros(*len as usize))
+            .collect(),
+        ffi::LY_DATA_TYPE::LY_TYPE_BOOL => {
+            vec!["true".to_owned(), "false".to_owned()]
+        }
+        ffi::LY_DATA_TYPE::LY_TYPE_EMPTY => vec![String::new()],
+        ffi::LY_DATA_TYPE::LY_TYPE_ENUM => {
+            let rtype = rtype as *const ffi::lysc_type_enum;
+            bitenum_names(unsafe { (*rtype).enums })
+        }
+        ffi::LY_DATA_TYPE::LY_TYPE_BITS => {
+            let rtype = rtype as *const ffi::lysc_type_bits;
+            let mut values = bitenum_names(unsafe { (*rtype).bits });
+            values.push(String::new());
+            values
+        }
+        ffi::LY_DATA_TYPE::LY_TYPE_IDENT => {
+            let rtype = rtype as *const ffi::lysc_type_identityref;
+            let mut values = vec![];
+            for base in unsafe { ly_array_slice((*rtype).bases) } {
+                derived_identities(*base, &mut values);
+            }
+            values
+        }
+        ffi::LY_DATA_TYPE::LY_TYPE_UNION => {
+            let rtype = rtype as *const ffi::lysc_type_union;
+            unsafe { ly_array_slice((*rtype).types) }
+                .iter()
+                .flat_map(|member| {
+                    let member =
+                        unsafe { SchemaLeafType::from_raw(context, *member) };
+                    type_candidates(context, &member, index, rng)
+                })
+                .collect()
+        }
+        ffi::LY_DATA_TYPE::LY_TYPE_LEAFREF => {
+            let rtype = rtype as *const ffi::lysc_type_leafref;
+            if unsafe { (*rtype).require_instance } != 0 {
+                return vec![];
+            }
+            match ltype.leafref_real_type() {
+                Some(rtype) => type_candidates(context, &rtype, index, rng),
+                None => vec![],
+            }
+        }
+        _ => vec![],
+    };
+
+    // Vary the values picked for enumerated types.
+    if matches!(
+        base_type,
+        ffi::LY_DATA_TYPE::LY_TYPE_BOOL
+            | ffi::LY_DATA_TYPE::LY_TYPE_ENUM
+            | ffi::LY_DATA_TYPE::LY_TYPE_IDENT
+    ) {
+        rng.rotate(&mut candidates);
+    }
+    candidates
+}
+
+fn signed_bounds(base_type: ffi::LY_DATA_TYPE::Type) -> (i64, i64) {
+    match base_type {
+        ffi::LY_DATA_TYPE::LY_TYPE_INT8 => (i8::MIN as i64, i8::MAX as i64),
+        ffi::LY_DATA_TYPE::LY_TYPE_INT16 => (i16::MIN as i64, i16::MAX as i64),
+        ffi::LY_DATA_TYPE::LY_TYPE_INT32 => (i32::MIN as i64, i32::MAX as i64),
+        ffi::LY_DATA_TYPE::LY_TYPE_INT64 => (i64::MIN, i64::MAX),
+        _ => (0, 0),
+    }
+}
+
+fn unsigned_max(base_type: ffi::LY_DATA_TYPE::Type) -> u64 {
+    match base_type {
+        ffi::LY_DATA_TYPE::LY_TYPE_UINT8 => u8::MAX as u64,
+        ffi::LY_DATA_TYPE::LY_TYPE_UINT16 => u16::MAX as u64,
+        ffi::LY_DATA_TYPE::LY_TYPE_UINT32 => u32::MAX as u64,
+        ffi::LY_DATA_TYPE::LY_TYPE_UINT64 => u64::MAX,
+        _ => 0,
+    }
+}
+
+fn signed_parts(range: Option<TypeRange>) -> Vec<(i64, i64)> {
+    range
+        .into_iter()
+        .flat_map(|range| range.parts)
+        .filter_map(|part| match part {
+            RangePart::Signed { min, max }
+            | RangePart::Decimal64 { min, max, .. } => Some((min, max)),
+            RangePart::Unsigned { .. } => None,
+        })
+        .collect()
+}
+
+fn unsigned_parts(range: Option<TypeRange>) -> Vec<(u64, u64)> {
+    range
+        .into_iter()
+        .flat_map(|range| range.parts)
+        .filter_map(|part| match part {
+            RangePart::Unsigned { min, max } => Some((min, max)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn min_length(ltype: &SchemaLeafType<'_>) -> u64 {
+    unsigned_parts(ltype.length())
+        .first()
+        .map(|(min, _)| *min)
+        .unwrap_or(0)
+}
+
+fn signed_candidates(
+    mut parts: Vec<(i64, i64)>,
+    min: i64,
+    max: i64,
+    index: u32,
+    rng: &mut Rng,
+) -> Vec<i64> {
+    if parts.is_empty() {
+        parts.push((min.max(0), max));
+    }
+
+    let offset = index as i64 + rng.below(16) as i64;
+    let mut values = vec![];
+    for (min, max) in parts {
+        values.push(min.saturating_add(offset).min(max));
+        values.push(min.saturating_add(index as i64).min(max));
+        values.push(min);
+        values.push(max);
+    }
+    values
+}
+
+fn unsigned_candidates(
+    mut parts: Vec<(u64, u64)>,
+    max: u64,
+    index: u32,
+    rng: &mut Rng,
+) -> Vec<u64> {
+    if parts.is_empty() {
+        parts.push((0, max));
+    }
+
+    let offset = index as u64 + rng.below(16) as u64;
+    let mut values = vec![];
+    for (min, max) in parts {
+        values.push(min.saturating_add(offset).min(max));
+        values.push(min.saturating_add(index as u64).min(max));
+        values.push(min);
+        values.push(max);
+    }
+    values
+}
+
+fn string_candidates(index: u32) -> Vec<String> {
+    let octet = index % 256;
+    vec![
+        format!("example{}", index),
+        format!("{}", index),
+        format!("192.0.2.{}", octet),
+        format!("192.0.2.{}/32", octet),
+        format!("2001:db8::{:x}", index),
+        format!("2001:db8::{:x}/128", index),
+        format!("00:00:5e:00:53:{:02x}", octet),
+        format!("0000.0000.{:04}", index % 10000),
+        format!("0000.0000.{:04}.00-00", index % 10000),
+        format!("2024-01-01T00:00:{:02}Z", index % 60),
+        format!("example{}.com", index),
+        format!("{}", index + 1),
+    ]
+}
+
+fn base64_zeros(len: usize) -> String {
+    let mut value = "AAAA".repeat(len / 3);
+    match len % 3 {
+        1 => value.push_str("AA=="),
+        2 => value.push_str("AAA="),
+        _ => {}
+    }
+    value
+}
+
+fn bitenum_names(items: *const ffi::lysc_type_bitenum_item) -> Vec<String> {
+    unsafe { ly_array_slice(items) }
+        .iter()
+        .map(|item| char_ptr_to_string(item.name, false))
+        .collect()
+}
+
+fn derived_identities(ident: *mut ffi::lysc_ident, values: &mut Vec<String>) {
+    for derived in unsafe { ly_array_slice((*ident).derived) } {
+        let derived = *derived;
+        let module = unsafe { (*derived).module };
+        let value = format!(
+            "{}:{}",
+            char_ptr_to_string(unsafe { (*module).name }, false),
+            char_ptr_to_string(unsafe { (*derived).name }, false)
+        );
+        if !values.contains(&value) {
+            values.push(value);
+            derived_identities(derived, values);
+        }
+    }
+}
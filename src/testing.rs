@@ -0,0 +1,108 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Test helpers for comparing YANG data trees.
+
+use std::path::Path;
+
+use crate::data::{
+    Data, DataDiffFlags, DataDiffTextFlags, DataFormat, DataParserFlags,
+    DataPrinterFlags, DataTree, DataValidationFlags,
+};
+use crate::error::Result;
+
+/// Environment variable that, when set, makes [`assert_snapshot`] overwrite
+/// the stored snapshots instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "YANG_UPDATE_SNAPSHOTS";
+
+/// Print the data tree in a canonical form suitable for comparisons: JSON,
+/// with the nodes equal to their default values trimmed.
+pub fn canonicalize(dtree: &DataTree<'_>) -> Result<String> {
+    if dtree.reference().is_none() {
+        return Ok(String::new());
+    }
+
+    dtree.print_string(
+        DataFormat::JSON,
+        DataPrinterFlags::WITH_SIBLINGS | DataPrinterFlags::WD_TRIM,
+    )
+}
+
+/// Assert that two data trees are equal, once canonicalized.
+///
+/// On mismatch, panics with a human-readable summary of the differences (see
+/// [`DataDiff::to_text`]).
+///
+/// [`DataDiff::to_text`]: crate::data::DataDiff::to_text
+#[track_caller]
+pub fn assert_data_eq<'a>(left: &DataTree<'a>, right: &DataTree<'a>) {
+    let left_str = canonicalize(left).expect("Failed to print data tree");
+    let right_str = canonicalize(right).expect("Failed to print data tree");
+    if left_str != right_str {
+        panic!(
+            "data trees differ (- left, + right):\n{}",
+            diff_text(left, right)
+        );
+    }
+}
+
+/// Assert that the data tree matches the snapshot stored in the given JSON
+/// file.
+///
+/// The snapshot is created when the file doesn't exist, or overwritten when
+/// the [`UPDATE_SNAPSHOTS_ENV`] environment variable is set. On mismatch,
+/// panics with a human-readable summary of the differences (see
+/// [`DataDiff::to_text`]).
+///
+/// [`DataDiff::to_text`]: crate::data::DataDiff::to_text
+#[track_caller]
+pub fn assert_snapshot(dtree: &DataTree<'_>, path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    let actual = canonicalize(dtree).expect("Failed to print data tree");
+
+    if !path.exists() || std::env::var_os(UPDATE_SNAPSHOTS_ENV).is_some() {
+        std::fs::write(path, &actual).unwrap_or_else(|error| {
+            panic!("Failed to write snapshot {}: {}", path.display(), error)
+        });
+        return;
+    }
+
+    let expected = std::fs::read_to_string(path).unwrap_or_else(|error| {
+        panic!("Failed to read snapshot {}: {}", path.display(), error)
+    });
+    if expected == actual {
+        return;
+    }
+
+    let snapshot = DataTree::parse_string(
+        dtree.context(),
+        &expected,
+        DataFormat::JSON,
+        DataParserFlags::NO_VALIDATION,
+        DataValidationFlags::empty(),
+    )
+    .unwrap_or_else(|error| {
+        panic!("Failed to parse snapshot {}: {}", path.display(), error)
+    });
+    // Ignore formatting differences in hand-edited snapshots.
+    if canonicalize(&snapshot).ok().as_ref() == Some(&actual) {
+        return;
+    }
+    panic!(
+        "data tree doesn't match snapshot {} (- snapshot, + actual):\n{}\n\
+         (set {} to update the snapshot)",
+        path.display(),
+        diff_text(&snapshot, dtree),
+        UPDATE_SNAPSHOTS_ENV
+    );
+}
+
+fn diff_text<'a>(left: &DataTree<'a>, right: &DataTree<'a>) -> String {
+    match left.diff(right, DataDiffFlags::DEFAULTS) {
+        Ok(diff) => diff.to_text(DataDiffTextFlags::empty()),
+        Err(error) => format!("<failed to compute diff: {}>", error),
+    }
+}
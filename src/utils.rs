@@ -44,6 +44,33 @@ pub(crate) fn char_ptr_to_opt_str<'a>(c_str: *const c_char) -> Option<&'a str> {
     }
 }
 
+/// Returns the number of records in a libyang sized array (equivalent to
+/// LY_ARRAY_COUNT).
+///
+/// # Safety
+///
+/// The pointer must be either null or point to a libyang sized array.
+pub(crate) unsafe fn ly_array_count<T>(array: *const T) -> usize {
+    if array.is_null() {
+        0
+    } else {
+        (array as *const usize).offset(-1).read()
+    }
+}
+
+/// Returns the records of a libyang sized array.
+///
+/// # Safety
+///
+/// The pointer must be either null or point to a libyang sized array that
+/// outlives the returned slice.
+pub(crate) unsafe fn ly_array_slice<'a, T>(array: *const T) -> &'a [T] {
+    match ly_array_count(array) {
+        0 => &[],
+        count => std::slice::from_raw_parts(array, count),
+    }
+}
+
 /// A trait implemented by all types that can be created from a raw C pointer
 /// and a generic container type.
 pub unsafe trait Binding<'a>
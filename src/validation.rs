@@ -0,0 +1,282 @@
+//
+// Copyright (c) The yang-rs Core Contributors
+//
+// SPDX-License-Identifier: MIT
+//
+
+//! Structured reports of data validation failures.
+
+use libyang3_sys as ffi;
+
+use crate::context::Context;
+use crate::data::{Data, DataNodeRef, DataTree, DataValidationFlags};
+use crate::error::Result;
+use crate::schema::SchemaNode;
+use crate::utils::*;
+
+/// Report of all the errors and warnings found while validating a data tree (see
+/// [`DataTree::validate_report`]).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ValidationReport {
+    /// Validation issues, in the order they were found.
+    pub issues: Vec<ValidationIssue>,
+}
+
+/// Single validation error or warning, along with the constraint that
+/// caused it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidationIssue {
+    /// Severity of the issue.
+    pub severity: ValidationSeverity,
+    /// libyang error code.
+    pub errcode: ffi::LY_ERR::Type,
+    /// libyang validation error code.
+    pub vecode: ffi::LY_VECODE::Type,
+    /// Error message.
+    pub msg: String,
+    /// Path of the offending data node, if any.
+    pub data_path: Option<String>,
+    /// Path of the schema node of the offending data node, if any.
+    pub schema_path: Option<String>,
+    /// Error application tag, if any.
+    pub apptag: Option<String>,
+    /// Constraint that isn't satisfied, if any.
+    pub constraint: Option<ValidationConstraint>,
+}
+
+/// Severity of a validation issue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ValidationSeverity {
+    /// The data tree is invalid.
+    Error,
+    /// The data tree is valid, but likely not as intended.
+    Warning,
+}
+
+/// Reference whose target couldn't be found (see [`resolve_references`]).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnresolvedReference {
+    /// Path of the referencing data node.
+    pub path: String,
+    /// Value of the reference (leafref or instance-identifier).
+    pub value: Option<String>,
+    /// Error message.
+    pub msg: String,
+}
+
+/// YANG constraint causing a validation error.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ValidationConstraint {
+    /// Must statement.
+    Must {
+        /// XPath condition.
+        condition: String,
+        /// error-message substatement.
+        error_msg: Option<String>,
+        /// error-app-tag substatement.
+        error_apptag: Option<String>,
+        /// description substatement.
+        description: Option<String>,
+    },
+    /// When statement.
+    When {
+        /// XPath condition.
+        condition: String,
+        /// description substatement.
+        description: Option<String>,
+    },
+}
+
+// ===== impl ValidationReport =====
+
+impl ValidationReport {
+    /// Collect the errors and warnings stored in the context, resolving their
+    /// constraints using the given data tree.
+    pub(crate) fn collect(dtree: &DataTree<'_>) -> ValidationReport {
+        let context = dtree.context();
+        let mut issues = Vec::new();
+        let mut error = unsafe { ffi::ly_err_first(context.raw) };
+        while !error.is_null() {
+            let item = unsafe { &*error };
+            error = item.next;
+            let severity = match item.level {
+                ffi::LY_LOG_LEVEL::LY_LLERR => ValidationSeverity::Error,
+                ffi::LY_LOG_LEVEL::LY_LLWRN => ValidationSeverity::Warning,
+                _ => continue,
+            };
+
+            issues.push(ValidationIssue {
+                severity,
+                errcode: item.err,
+                vecode: item.vecode,
+                msg: char_ptr_to_opt_string(item.msg, false)
+                    .unwrap_or_default(),
+                data_path: char_ptr_to_opt_string(item.data_path, false),
+                schema_path: char_ptr_to_opt_string(item.schema_path, false),
+                apptag: char_ptr_to_opt_string(item.apptag, false),
+                constraint: None,
+            });
+        }
+
+        // Resolve the constraints only once all errors are collected, since
+        // the lookups below might log errors of their own.
+        for issue in &mut issues {
+            let dnode =
+                issue.data_node(dtree).filter(|dnode| !dnode.is_opaque());
+            let snode = match &dnode {
+                Some(dnode) => Some(dnode.schema()),
+                None => issue.schema_node(context),
+            };
+            issue.constraint =
+                snode.and_then(|snode| find_constraint(&snode, &issue.msg));
+        }
+
+        ValidationReport { issues }
+    }
+
+    /// Returns whether no issues were found.
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns whether any of the issues is an error.
+    pub fn has_errors(&self) -> bool {
+        self.issues
+            .iter()
+            .any(|issue| issue.severity == ValidationSeverity::Error)
+    }
+
+    /// Returns an iterator over the issues of the given severity.
+    pub fn issues_with(
+        &self,
+        severity: ValidationSeverity,
+    ) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(move |issue| issue.severity == severity)
+    }
+}
+
+impl std::fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+// ===== impl ValidationIssue =====
+
+impl ValidationIssue {
+    /// Returns whether the issue is an unresolved reference, that is, a
+    /// leafref or instance-identifier value whose target doesn't exist.
+    pub fn is_unresolved_reference(&self) -> bool {
+        // Reported using the "instance-required" error-app-tag (RFC 7950,
+        // section 15.5).
+        self.apptag.as_deref() == Some("instance-required")
+    }
+
+    /// Returns the offending data node in the given data tree, if any.
+    pub fn data_node<'a>(
+        &self,
+        dtree: &'a DataTree<'a>,
+    ) -> Option<DataNodeRef<'a>> {
+        let path = self.data_path.as_ref()?;
+        dtree.find_path(path).ok()
+    }
+
+    /// Returns the schema node of the offending data node, if any.
+    pub fn schema_node<'a>(
+        &self,
+        context: &'a Context,
+    ) -> Option<SchemaNode<'a>> {
+        let path = self.schema_path.as_ref()?;
+        context.find_path(path).ok()
+    }
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.severity == ValidationSeverity::Warning {
+            write!(f, "warning: ")?;
+        }
+        write!(f, "{}", self.msg)?;
+        if let Some(path) = &self.data_path {
+            write!(f, " (path: {})", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolve the references (leafref and instance-identifier values) of the
+/// given data tree against the data tree itself and the given other data
+/// trees (e.g. the other shards of a larger data tree).
+///
+/// Unlike a full validation, which fails at the first unresolved reference
+/// and takes all the constraints into account, this returns the list of all
+/// the references of the data tree whose target couldn't be found.
+pub fn resolve_references(
+    dtree: &DataTree<'_>,
+    shards: &[&DataTree<'_>],
+) -> Result<Vec<UnresolvedReference>> {
+    let mut assembled = dtree.duplicate()?;
+    for shard in shards {
+        assembled.merge(shard)?;
+    }
+
+    let report = match assembled.validate_report(DataValidationFlags::empty()) {
+        Ok(_) => return Ok(Vec::new()),
+        Err(report) => report,
+    };
+    let unresolved = report
+        .issues
+        .into_iter()
+        .filter(ValidationIssue::is_unresolved_reference)
+        .filter_map(|issue| {
+            // Skip the references of the other data trees.
+            let dnode = issue.data_node(dtree)?;
+            Some(UnresolvedReference {
+                path: dnode.path(),
+                value: dnode.value_canonical(),
+                msg: issue.msg,
+            })
+        })
+        .collect();
+
+    Ok(unresolved)
+}
+
+// ===== helper functions =====
+
+// Find the must or when statement of the schema node that caused the given
+// error message.
+pub(crate) fn find_constraint(
+    snode: &SchemaNode<'_>,
+    msg: &str,
+) -> Option<ValidationConstraint> {
+    let must = snode.musts().into_iter().flatten().find(|must| {
+        msg.contains(must.condition()) || must.error_msg() == Some(msg)
+    });
+    if let Some(must) = must {
+        return Some(ValidationConstraint::Must {
+            condition: must.condition().to_owned(),
+            error_msg: must.error_msg().map(ToOwned::to_owned),
+            error_apptag: must.error_apptag().map(ToOwned::to_owned),
+            description: must.description().map(ToOwned::to_owned),
+        });
+    }
+
+    snode
+        .whens()
+        .find(|when| msg.contains(when.condition()))
+        .map(|when| ValidationConstraint::When {
+            condition: when.condition().to_owned(),
+            description: when.description().map(ToOwned::to_owned),
+        })
+}
@@ -1,10 +1,36 @@
 use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+use yang3::anonymize::{Anonymizer, IdentifierKind};
+use yang3::archive::ConfigArchive;
+use yang3::cache::CachedTreeView;
+use yang3::commit::ConfirmedCommit;
 use yang3::context::{Context, ContextFlags};
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use yang3::data::CompressionCodec;
 use yang3::data::{
-    Data, DataDiff, DataDiffFlags, DataFormat, DataImplicitFlags,
-    DataOperation, DataParserFlags, DataPrinterFlags, DataTree,
-    DataTreeOwningRef, DataValidationFlags,
+    CompiledPath, Data, DataDiff, DataDiffFlags, DataDiffOp, DataDiffTextFlags,
+    DataEdit, DataFormat, DataImplicitFlags, DataOperation, DataParserFlags,
+    DataPrinterFlags, DataTree, DataTreeOwningRef, DataValidationFlags,
+    MergeConflict, UnknownElement, XPathProfile, REDACTED_VALUE,
 };
+use yang3::input::{InputProvider, ReadInput};
+use yang3::journal::EditJournal;
+use yang3::limits::{LimitedParseError, ParserLimits};
+use yang3::nacm::{Nacm, NacmAction, NacmOperation};
+use yang3::rpc::{RpcDispatcher, RpcError};
+use yang3::schema::{
+    DataValue, SchemaInputFormat, SchemaNodeKind, StatusFilter,
+};
+use yang3::session::{load_session, save_session};
+use yang3::tags::ModuleTags;
+use yang3::template::{ConfigTemplate, EffectiveConfig};
+use yang3::testdata::{DataGenerator, DataGeneratorFlags};
+use yang3::testing;
+use yang3::validation::{
+    resolve_references, ValidationConstraint, ValidationSeverity,
+};
+use yang3::{Error, ErrorKind, ValidationErrorKind};
 
 static SEARCH_DIR: &str = "./assets/yang/";
 static YANG_LIBRARY_FILE: &str = "./assets/data/lib.json";
@@ -69,6 +95,21 @@ static JSON_TREE3: &str = r###"
       }
     }"###;
 
+static JSON_UNKNOWN: &str = r###"
+    {
+        "ietf-interfaces:interfaces":{
+            "interface": [
+                {
+                    "name": "eth/0/0",
+                    "type": "iana-if-type:ethernetCsmacd"
+                }
+            ]
+        },
+        "acme-vendor:system":{
+            "hostname": "router1"
+        }
+    }"###;
+
 static JSON_MERGE: &str = r###"
     {
         "ietf-interfaces:interfaces":{
@@ -190,6 +231,12 @@ static JSON_RPC2_OUTPUT: &str = r###"
         }
     }"###;
 
+static JSON_RPC2_INPUT: &str = r###"
+    {
+        "ietf-mpls-ldp:mpls-ldp-clear-peer":{
+        }
+    }"###;
+
 static JSON_ACTION1: &str = r###"
     {
         "ietf-routing:routing": {
@@ -309,172 +356,303 @@ fn parse_json_rpc_reply<'a>(ctx: &'a Context, string: &str) -> DataTree<'a> {
 }
 
 #[test]
-fn data_find_xpath() {
+fn data_parse_unknown_elements() {
     let ctx = create_context();
-    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
-
-    assert_eq!(
-        dtree1
-            .find_xpath("/ietf-interfaces:interfaces/interface")
-            .expect("Failed to lookup data")
-            .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/1']"
-        ]
-    );
+    let dtree = DataTree::parse_string(
+        &ctx,
+        JSON_UNKNOWN,
+        DataFormat::JSON,
+        DataParserFlags::NO_VALIDATION | DataParserFlags::OPAQ,
+        DataValidationFlags::empty(),
+    )
+    .expect("Failed to parse data tree");
 
     assert_eq!(
-        dtree1
-            .find_xpath(
-                "/ietf-interfaces:interfaces/interface[name='eth/0/0']/*"
-            )
-            .expect("Failed to lookup data")
-            .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/name",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/description",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled",
-        ]
+        dtree.unknown_elements(),
+        vec![UnknownElement {
+            path: "/acme-vendor:system".to_owned(),
+            name: "system".to_owned(),
+            module: Some("acme-vendor".to_owned()),
+        }]
     );
 }
 
 #[test]
-fn data_find_path() {
+fn data_parse_input() {
     let ctx = create_context();
-    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let expected = parse_json_data(&ctx, JSON_TREE1);
 
-    assert!(dtree1
-        .find_path("/ietf-interfaces:interfaces/interface")
-        .is_err());
-    assert!(dtree1
-        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
-        .is_ok());
+    let mut input = ReadInput::with_chunk_size(JSON_TREE1.as_bytes(), 7);
+    let dtree = DataTree::parse_input(
+        &ctx,
+        &mut input,
+        DataFormat::JSON,
+        DataParserFlags::NO_VALIDATION,
+        DataValidationFlags::empty(),
+    )
+    .expect("Failed to parse data tree");
+    let diff = expected
+        .diff(&dtree, DataDiffFlags::empty())
+        .expect("Failed to compare data trees");
+    assert_eq!(diff.iter().count(), 0);
 }
 
 #[test]
-fn data_find_action_output_path() {
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+fn data_print_compressed() {
     let ctx = create_context();
-    let dtree1 = parse_json_rpc_reply(&ctx, JSON_ACTION1);
-    let dtree2 = parse_json_rpc_reply(&ctx, JSON_RPC2_OUTPUT);
+    let dtree = parse_json_data(&ctx, JSON_TREE1);
 
-    assert!(dtree1
-        .find_output_path(
-            "/ietf-routing:routing/ribs/rib[name='default']/active-route"
+    let codecs = [
+        #[cfg(feature = "gzip")]
+        CompressionCodec::Gzip,
+        #[cfg(feature = "zstd")]
+        CompressionCodec::Zstd,
+    ];
+    for codec in codecs {
+        for format in [DataFormat::JSON, DataFormat::LYB] {
+            let path = std::env::temp_dir().join(format!(
+                "yang3-compressed-{}-{:?}-{:?}",
+                std::process::id(),
+                codec,
+                format
+            ));
+            dtree
+                .print_compressed(&path, format, codec)
+                .expect("Failed to print data");
+            let parsed = DataTree::parse_compressed(
+                &ctx,
+                &path,
+                format,
+                codec,
+                DataParserFlags::NO_VALIDATION,
+                DataValidationFlags::empty(),
+            )
+            .expect("Failed to parse data");
+            std::fs::remove_file(&path).expect("Failed to remove file");
+
+            let diff = dtree
+                .diff(&parsed, DataDiffFlags::empty())
+                .expect("Failed to compare data trees");
+            assert_eq!(diff.iter().count(), 0);
+        }
+    }
+}
+
+#[test]
+fn data_parse_on_demand() {
+    let mut ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+    ctx.set_searchdir(SEARCH_DIR)
+        .expect("Failed to set YANG search directory");
+    for module_name in &["iana-if-type", "ietf-interfaces"] {
+        ctx.load_module(module_name, None, &[])
+            .expect("Failed to load module");
+    }
+
+    let json = r###"
+    {
+        "ietf-interfaces:interfaces":{
+            "interface": [
+                {
+                    "name": "eth/0/0",
+                    "type": "iana-if-type:ethernetCsmacd",
+                    "ietf-ip:ipv4": {
+                        "enabled": false
+                    }
+                }
+            ]
+        }
+    }"###;
+    let mut requested = Vec::new();
+    let loaded = ctx
+        .load_missing_modules(json, DataFormat::JSON, |module| {
+            requested.push(module.to_owned());
+            let path = format!("{}/ietf-ip@2018-02-22.yang", SEARCH_DIR);
+            (module == "ietf-ip")
+                .then(|| std::fs::read_to_string(path).unwrap())
+        })
+        .expect("Failed to load missing modules");
+    assert!(loaded);
+    let dtree = DataTree::parse_string(
+        &ctx,
+        json,
+        DataFormat::JSON,
+        DataParserFlags::NO_VALIDATION,
+        DataValidationFlags::empty(),
+    )
+    .expect("Failed to parse data tree");
+    assert_eq!(requested, vec!["ietf-ip"]);
+    assert!(dtree.unknown_elements().is_empty());
+    assert!(dtree
+        .find_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/ietf-ip:ipv4/enabled"
         )
         .is_ok());
-    assert!(dtree2
-        .find_output_path("/ietf-mpls-ldp:mpls-ldp-clear-peer/protocol-name")
+}
+
+#[test]
+fn data_convert() {
+    let ctx = create_context();
+    let xml = DataTree::convert(
+        &ctx,
+        JSON_UNKNOWN,
+        DataFormat::JSON,
+        DataFormat::XML,
+    )
+    .expect("Failed to convert data to XML");
+    let json = DataTree::convert(&ctx, xml, DataFormat::XML, DataFormat::JSON)
+        .expect("Failed to convert data to JSON");
+
+    let dtree = DataTree::parse_string(
+        &ctx,
+        json,
+        DataFormat::JSON,
+        DataParserFlags::NO_VALIDATION | DataParserFlags::OPAQ,
+        DataValidationFlags::empty(),
+    )
+    .expect("Failed to parse converted data");
+    assert_eq!(dtree.unknown_elements().len(), 1);
+    assert!(dtree
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
         .is_ok());
 }
 
 #[test]
-fn data_edit() {
+fn data_print_wd_tagged() {
     let ctx = create_context();
-    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
-    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+    let dtree = parse_json_data(&ctx, JSON_TREE1);
 
-    enum Operation {
-        MODIFY(&'static str, Option<&'static str>),
-        DELETE(&'static str),
+    // The ietf-netconf-with-defaults module isn't loaded.
+    for options in [DataPrinterFlags::WD_ALL_TAG, DataPrinterFlags::WD_IMPL_TAG]
+    {
+        assert!(dtree
+            .print_string(
+                DataFormat::JSON,
+                options | DataPrinterFlags::WITH_SIBLINGS
+            )
+            .is_err());
     }
+    assert!(dtree
+        .print_string(DataFormat::JSON, DataPrinterFlags::WD_ALL)
+        .is_ok());
+}
 
-    let changes = [
-        Operation::MODIFY(
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled",
-            Some("false"),
-        ),
-        Operation::DELETE(
-            "/ietf-interfaces:interfaces/interface[name='eth/0/1']",
-        ),
-        Operation::MODIFY(
-            "/ietf-interfaces:interfaces/interface[name='eth/0/2']/description",
-            Some("MGMT"),
-        ),
-        Operation::MODIFY(
-            "/ietf-interfaces:interfaces/interface[name='eth/0/2']/type",
+#[test]
+fn data_print_tree_string() {
+    let ctx = create_context();
+    let path = "/ietf-interfaces:interfaces/interface[name='eth0']";
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .new_path(
+            &format!("{}/type", path),
             Some("iana-if-type:ethernetCsmacd"),
-        ),
-        Operation::MODIFY(
-            "/ietf-interfaces:interfaces/interface[name='eth/0/2']/enabled",
-            Some("true"),
-        ),
-    ];
-    for change in &changes {
-        match change {
-            Operation::MODIFY(xpath, value) => {
-                dtree1
-                    .new_path(xpath, *value, false)
-                    .expect("Failed to edit data tree");
-            }
-            Operation::DELETE(xpath) => {
-                dtree1.remove(xpath).expect("Failed to edit data tree")
-            }
-        };
-    }
+            false,
+        )
+        .expect("Failed to edit data tree");
+    let module = ctx.get_module_latest("ietf-interfaces").unwrap();
+    dtree
+        .add_implicit_module(
+            &module,
+            DataImplicitFlags::NO_STATE,
+            StatusFilter::empty(),
+        )
+        .expect("Failed to add implicit nodes");
 
-    assert_data_eq!(&dtree1, &dtree2);
+    assert_eq!(
+        dtree.print_tree_string(),
+        "ietf-interfaces:interfaces\n\
+         └── interface[name='eth0']\n\
+         \x20   ├── name: eth0\n\
+         \x20   ├── type: iana-if-type:ethernetCsmacd\n\
+         \x20   └── enabled: true (default)\n"
+    );
 }
 
 #[test]
-fn data_validate() {
+fn data_print_list_entries() {
     let ctx = create_context();
-    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dnode = dtree1
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/1']")
+        .expect("Failed to find list entry");
+
+    let mut output = vec![];
+    dnode
+        .print_list_entries(&mut output, DataFormat::JSON)
+        .expect("Failed to print list entries");
+    let output = String::from_utf8(output).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("eth/0/0"));
+    assert!(lines[1].contains("eth/0/1"));
+    assert!(dnode.print_list_entries(vec![], DataFormat::LYB).is_err());
 
-    // Mandatory node "oper-status" instance does not exist.
-    // (path: /ietf-interfaces:interfaces/interface/oper-status)
-    assert!(dtree1.validate(DataValidationFlags::PRESENT).is_err());
+    let dnode = dtree1
+        .find_path("/ietf-interfaces:interfaces")
+        .expect("Failed to find container");
+    assert!(dnode.print_list_entries(vec![], DataFormat::JSON).is_err());
 }
 
 #[test]
-fn data_duplicate_tree() {
+fn data_list_page() {
     let ctx = create_context();
     let dtree1 = parse_json_data(&ctx, JSON_TREE1);
-    let dup = dtree1.duplicate().expect("Failed to duplicate data tree");
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces/interface")
+        .expect("Failed to find list schema");
+    let dnode = dtree1
+        .find_path("/ietf-interfaces:interfaces")
+        .expect("Failed to find container");
 
-    assert_data_eq!(&dtree1, &dup);
+    let page = dnode
+        .list_page(&snode, 1, Some(1), Some("description"))
+        .expect("Failed to get list page");
+    assert_eq!(
+        page.traverse()
+            .filter(|dnode| dnode.schema().name() == "name")
+            .filter_map(|dnode| dnode.value_canonical())
+            .collect::<Vec<_>>(),
+        vec!["eth/0/1"]
+    );
+
+    let page = dnode
+        .list_page(&snode, 0, None, None)
+        .expect("Failed to get list page");
+    assert_eq!(
+        page.find_xpath("//ietf-interfaces:interface")
+            .unwrap()
+            .count(),
+        2
+    );
 }
 
 #[test]
-fn data_duplicate_subtree() {
+fn data_find_xpath() {
     let ctx = create_context();
     let dtree1 = parse_json_data(&ctx, JSON_TREE1);
 
-    let dnode = dtree1
-        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
-        .expect("Failed to lookup data");
-
-    // Duplicate without parents.
-    let dup = dnode
-        .duplicate(false)
-        .expect("Failed to duplicate data subtree");
     assert_eq!(
-        dup.traverse()
+        dtree1
+            .find_xpath("/ietf-interfaces:interfaces/interface")
+            .expect("Failed to lookup data")
             .map(|dnode| dnode.path())
             .collect::<Vec<String>>(),
         vec![
-            "/ietf-interfaces:interface[name='eth/0/0']",
-            "/ietf-interfaces:interface[name='eth/0/0']/name",
-            "/ietf-interfaces:interface[name='eth/0/0']/description",
-            "/ietf-interfaces:interface[name='eth/0/0']/type",
-            "/ietf-interfaces:interface[name='eth/0/0']/enabled",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']"
         ]
     );
 
-    // Duplicate with parents.
-    let dup = dnode
-        .duplicate(true)
-        .expect("Failed to duplicate data subtree");
     assert_eq!(
-        dup.traverse()
+        dtree1
+            .find_xpath(
+                "/ietf-interfaces:interfaces/interface[name='eth/0/0']/*"
+            )
+            .expect("Failed to lookup data")
             .map(|dnode| dnode.path())
             .collect::<Vec<String>>(),
         vec![
-            "/ietf-interfaces:interfaces",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
             "/ietf-interfaces:interfaces/interface[name='eth/0/0']/name",
             "/ietf-interfaces:interfaces/interface[name='eth/0/0']/description",
             "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
@@ -484,364 +662,3206 @@ fn data_duplicate_subtree() {
 }
 
 #[test]
-fn data_merge() {
+fn data_write_path() {
     let ctx = create_context();
-    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
-    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
-    let dtree_merge = parse_json_data(&ctx, JSON_MERGE);
+    let mut dtree = parse_json_data(&ctx, JSON_TREE1);
+    dtree
+        .new_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/ietf-ip:ipv4/address[ip='10.0.0.1']/prefix-length",
+            Some("24"),
+            false,
+        )
+        .expect("Failed to edit data tree");
 
-    dtree1.merge(&dtree2).expect("Failed to merge data trees");
-    assert_data_eq!(&dtree1, &dtree_merge);
+    let mut path = String::new();
+    for dnode in dtree.traverse() {
+        path.clear();
+        dnode.write_path(&mut path).expect("Failed to write path");
+        assert_eq!(path, dnode.path());
+    }
 }
 
 #[test]
-fn data_add_implicit() {
+fn data_module_tags() {
+    let mut ctx = create_context();
+    ctx.load_module("ietf-module-tags", None, &[])
+        .expect("Failed to load module");
+
+    let mut tags = ModuleTags::new();
+    tags.add_tag("ietf-isis", "ietf:routing");
+    tags.add_tag("ietf-isis", "ietf:protocol");
+    tags.add_tag("ietf-interfaces", "ietf:network-element-class");
+    tags.mask_tag("ietf-isis", "ietf:protocol");
+
+    // Write the tags and read them back.
+    let dtree = tags.to_tree(&ctx).expect("Failed to write module tags");
+    let tags2 =
+        ModuleTags::from_tree(&dtree).expect("Failed to read module tags");
+    assert_eq!(tags, tags2);
+
+    assert!(tags2.has_tag("ietf-isis", "ietf:routing"));
+    assert!(!tags2.has_tag("ietf-isis", "ietf:protocol"));
+    assert_eq!(
+        tags2.tags("ietf-isis").collect::<Vec<_>>(),
+        vec!["ietf:routing"]
+    );
+    assert_eq!(
+        tags2.masked_tags("ietf-isis").collect::<Vec<_>>(),
+        vec!["ietf:protocol"]
+    );
+    assert_eq!(
+        tags2
+            .filter_modules(&ctx, "ietf:routing")
+            .iter()
+            .map(|module| module.name())
+            .collect::<Vec<_>>(),
+        vec!["ietf-isis"]
+    );
+    assert!(tags2.modules_with_tag("ietf:protocol").next().is_none());
+}
+
+#[test]
+fn data_cached_tree_view() {
     let ctx = create_context();
+    let dtree = parse_json_data(&ctx, JSON_TREE1);
+    let mut view = CachedTreeView::new(dtree);
+    let xpath = "/ietf-interfaces:interfaces/interface[enabled='true']";
+    let find_xpath = |view: &CachedTreeView<'_>| {
+        view.find_xpath(xpath)
+            .expect("Failed to lookup data")
+            .iter()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>()
+    };
 
-    // Original data tree.
-    let xpath = "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/area-address";
-    let mut dtree1 = DataTree::new(&ctx);
-    dtree1
-        .new_path(xpath, Some("00"), false)
+    assert_eq!(find_xpath(&view).len(), 2);
+    assert_eq!(find_xpath(&view).len(), 2);
+    let path = "/ietf-interfaces:interfaces/interface[name='eth/0/1']";
+    assert_eq!(
+        view.find_path(path).expect("Failed to lookup data").path(),
+        path
+    );
+    assert!(view
+        .find_path("/ietf-interfaces:interfaces/interface[name='x']")
+        .is_err());
+    assert_eq!(view.cached_count(), 2);
+
+    // Edits invalidate the cache.
+    view.new_path(&format!("{}/enabled", path), Some("false"), false)
         .expect("Failed to edit data tree");
+    assert_eq!(view.cached_count(), 0);
+    assert_eq!(
+        find_xpath(&view),
+        vec!["/ietf-interfaces:interfaces/interface[name='eth/0/0']"]
+    );
+    view.remove(path).expect("Failed to edit data tree");
+    assert!(view.find_path(path).is_err());
+}
 
-    // Original data tree with implicit configuration nodes added.
-    let mut dtree2 = dtree1.duplicate().expect("Failed to duplicate data");
-    dtree2
-        .add_implicit(DataImplicitFlags::NO_STATE)
-        .expect("Failed to add implicit nodes");
+#[test]
+fn data_find_path() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
 
-    // Test implicit config nodes.
-    let dtree1_nodes = dtree1
-        .traverse()
-        .map(|dnode| dnode.path())
-        .collect::<BTreeSet<String>>();
-    let dtree2_nodes = dtree2
-        .traverse()
-        .map(|dnode| dnode.path())
-        .collect::<BTreeSet<String>>();
+    assert!(dtree1
+        .find_path("/ietf-interfaces:interfaces/interface")
+        .is_err());
+    assert!(dtree1
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
+        .is_ok());
+}
+
+#[test]
+fn data_collect_values() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+
+    let paths = [
+        "/ietf-interfaces:interfaces/interface[name='eth/0/1']/description",
+        "/ietf-interfaces:interfaces/interface[name='eth/0/0']/description",
+        "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled",
+        "/ietf-interfaces:interfaces/interface[name='eth/0/9']/description",
+    ]
+    .iter()
+    .map(|path| CompiledPath::new(&ctx, path).expect("Failed to compile path"))
+    .collect::<Vec<_>>();
     assert_eq!(
-        dtree2_nodes
-            .symmetric_difference(&dtree1_nodes)
-            .collect::<Vec<&String>>(),
+        dtree1.collect_values(&paths),
         vec![
-            "/ietf-interfaces:interfaces",
-            "/ietf-key-chain:key-chains",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/authentication",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/authentication/level-1",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/authentication/level-2",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/default-metric",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/default-metric/level-1",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/default-metric/level-2",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/default-metric/value",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/interfaces",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/level-type",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/lsp-mtu",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/metric-type",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/metric-type/level-1",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/metric-type/level-2",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/metric-type/value",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/mpls",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/mpls/ldp",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/overload",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/overload/status",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/preference",
-            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/spf-control",
-            "/ietf-routing:routing/ribs",
+            Some(DataValue::Other("MKT".to_owned())),
+            Some(DataValue::Other("ENG".to_owned())),
+            Some(DataValue::Bool(true)),
+            None,
         ]
     );
+
+    assert!(
+        CompiledPath::new(&ctx, "/ietf-interfaces:interfaces/interface")
+            .is_err()
+    );
 }
 
 #[test]
-fn data_diff() {
+fn data_counter_deltas() {
     let ctx = create_context();
+    let snapshot = |in_octets: &str, out_octets: &str| {
+        let mut dtree = DataTree::new(&ctx);
+        for (leaf, value) in
+            [("in-octets", in_octets), ("out-octets", out_octets)].iter()
+        {
+            dtree
+                .new_path(
+                    &format!("/ietf-interfaces:interfaces/interface[name='eth0']/statistics/{}", leaf),
+                    Some(value),
+                    false,
+                )
+                .expect("Failed to edit data tree");
+        }
+        dtree
+    };
+    let dtree1 = snapshot("100", "18446744073709551610");
+    let dtree2 = snapshot("250", "4");
+
+    let deltas = dtree1.counter_deltas(&dtree2);
+    let deltas = deltas
+        .iter()
+        .filter(|delta| delta.path.contains("/statistics/"))
+        .map(|delta| (delta.path.as_str(), delta.delta))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        deltas,
+        vec![
+            (
+                "/ietf-interfaces:interfaces/interface[name='eth0']/statistics/in-octets",
+                150
+            ),
+            (
+                "/ietf-interfaces:interfaces/interface[name='eth0']/statistics/out-octets",
+                10
+            ),
+        ]
+    );
+}
+
+#[test]
+fn data_annotate_last_modified() {
+    let mut ctx = create_context();
+    ctx.load_module("example-last-modified", None, &[])
+        .expect("Failed to load module");
     let dtree1 = parse_json_data(&ctx, JSON_TREE1);
-    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
-    let dtree_diff = parse_json_diff(&ctx, JSON_DIFF);
 
-    let diff = dtree1
-        .diff(&dtree2, DataDiffFlags::DEFAULTS)
-        .expect("Failed to compare data trees");
-    assert_data_eq!(&diff, &dtree_diff);
+    let mut dnode = dtree1
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
+        .expect("Failed to find data node");
+    for secs in [1_000_000_000, 1_700_000_000].iter() {
+        dnode
+            .annotate_last_modified(
+                "example-last-modified",
+                UNIX_EPOCH + Duration::from_secs(*secs),
+            )
+            .expect("Failed to annotate data node");
+    }
+    assert_eq!(
+        dnode.last_modified("example-last-modified").as_deref(),
+        Some("2023-11-14T22:13:20Z")
+    );
+    assert_eq!(dnode.meta().count(), 1);
+    assert!(dnode
+        .annotate_last_modified("ietf-interfaces", UNIX_EPOCH)
+        .is_err());
 }
 
 #[test]
-fn data_diff_apply() {
-    let ctx = create_context();
-    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
-    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+fn data_default_flags() {
+    let mut ctx = create_context();
+    ctx.set_default_printer_flags(DataPrinterFlags::WITH_SIBLINGS);
+    ctx.set_default_parser_flags(DataParserFlags::STRICT);
 
-    let diff = dtree1
-        .diff(&dtree2, DataDiffFlags::DEFAULTS)
-        .expect("Failed to compare data trees");
-    dtree1.diff_apply(&diff).expect("Failed to apply diff");
+    assert!(DataTree::parse_string_default(
+        &ctx,
+        JSON_UNKNOWN,
+        DataFormat::JSON,
+        DataValidationFlags::NO_STATE,
+    )
+    .is_err());
+    // Explicit options are used as given, even if empty.
+    assert!(DataTree::parse_string(
+        &ctx,
+        JSON_UNKNOWN,
+        DataFormat::JSON,
+        DataParserFlags::empty(),
+        DataValidationFlags::NO_STATE,
+    )
+    .is_ok());
 
-    assert_data_eq!(&dtree1, &dtree2);
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    assert_eq!(
+        dtree1
+            .print_string_default(DataFormat::JSON)
+            .expect("Failed to print data"),
+        dtree1
+            .print_string(DataFormat::JSON, DataPrinterFlags::WITH_SIBLINGS)
+            .expect("Failed to print data"),
+    );
+    let dnode = dtree1
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
+        .expect("Failed to lookup data");
+    assert_ne!(
+        dnode
+            .print_string(DataFormat::JSON, DataPrinterFlags::empty())
+            .expect("Failed to print data"),
+        dnode
+            .print_string_default(DataFormat::JSON)
+            .expect("Failed to print data"),
+    );
 }
 
 #[test]
-fn data_diff_reverse() {
+fn data_config_archive() {
     let ctx = create_context();
     let dtree1 = parse_json_data(&ctx, JSON_TREE1);
     let dtree2 = parse_json_data(&ctx, JSON_TREE2);
-    let dtree_rdiff = parse_json_data(&ctx, JSON_RDIFF);
 
-    let diff = dtree1
+    let mut archive = ConfigArchive::new(2);
+    let id0 = archive
+        .push(&DataTree::new(&ctx), None)
+        .expect("Failed to archive configuration");
+    let id1 = archive
+        .push(&dtree1, Some("first"))
+        .expect("Failed to archive configuration");
+    let id2 = archive
+        .push(&dtree2, Some("second"))
+        .expect("Failed to archive configuration");
+
+    // The oldest configuration was pruned.
+    assert_eq!(
+        archive.entries().map(|entry| entry.id).collect::<Vec<_>>(),
+        vec![id1, id2]
+    );
+    assert!(archive.get(&ctx, id0).is_err());
+
+    let dtree = archive.get(&ctx, id1).expect("Failed to get configuration");
+    assert_data_eq!(&dtree, &dtree1);
+    let diff = archive
+        .diff(&ctx, id1, id2)
+        .expect("Failed to compare configurations");
+    let expected = dtree1
         .diff(&dtree2, DataDiffFlags::DEFAULTS)
         .expect("Failed to compare data trees");
-    let rdiff = diff.reverse().expect("Failed to reverse diff");
-    assert_data_eq!(&rdiff, &dtree_rdiff);
+    assert_data_eq!(&diff, &expected);
+
+    // Age-based pruning always keeps the most recent configuration.
+    archive.set_max_age(Some(Duration::from_secs(0)));
+    std::thread::sleep(Duration::from_millis(10));
+    archive.prune();
+    assert_eq!(
+        archive.entries().map(|entry| entry.id).collect::<Vec<_>>(),
+        vec![id2]
+    );
+
+    // No revision can follow the highest possible one.
+    let mut entry = archive.entries().next().unwrap().clone();
+    entry.id = u64::MAX;
+    assert!(archive.restore(entry).is_err());
 }
 
 #[test]
-fn data_iterator_traverse() {
+fn data_confirmed_commit() {
     let ctx = create_context();
     let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
 
-    assert_eq!(
-        dtree1
-            .traverse()
-            .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-interfaces:interfaces",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/name",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/description",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/1']",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/name",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/description",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/type",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/enabled"
-        ]
-    );
+    // Cancel the commit.
+    let mut running = dtree2.duplicate().expect("Failed to duplicate data");
+    let commit = ConfirmedCommit::new(
+        &dtree1,
+        &dtree2,
+        Duration::from_secs(600),
+        Some("id1"),
+    )
+    .expect("Failed to start confirmed commit");
+    assert!(!commit.is_expired());
+    assert_eq!(commit.persist(), Some("id1"));
+    assert!(commit.cancel(&mut running, Some("id2")).is_err());
+    assert_data_eq!(&running, &dtree2);
+    commit
+        .cancel(&mut running, Some("id1"))
+        .expect("Failed to cancel commit");
+    assert_data_eq!(&running, &dtree1);
+
+    // Persist identifier mismatch.
+    let commit =
+        ConfirmedCommit::new(&dtree1, &dtree2, Duration::from_secs(0), None)
+            .expect("Failed to start confirmed commit");
+    assert!(commit.is_expired());
+    assert!(commit.confirm(Some("id2")).is_err());
+    assert!(commit.confirm(None).is_ok());
+
+    // Follow-up commit, rolled back to the original configuration.
+    let mut running = dtree2.duplicate().expect("Failed to duplicate data");
+    let mut commit =
+        ConfirmedCommit::new(&dtree1, &dtree2, Duration::from_secs(0), None)
+            .expect("Failed to start confirmed commit");
+    let dtree3 = parse_json_data(&ctx, JSON_TREE1);
+    commit
+        .extend(&running, &dtree3, Duration::from_secs(600), None)
+        .expect("Failed to extend commit");
+    running = dtree3;
+    assert!(!commit.is_expired());
+    assert_eq!(commit.diff().iter().count(), 0);
+    commit.rollback(&mut running).expect("Failed to roll back");
+    assert_data_eq!(&running, &dtree1);
 }
 
 #[test]
-fn data_iterator_traverse_notification() {
+fn data_effective_config() {
     let ctx = create_context();
-    let dtree1 = parse_json_notification(&ctx, JSON_NOTIF1);
+    let running = parse_json_data(&ctx, JSON_TREE1);
+
+    let mut template = ConfigTemplate::new("ethernet");
+    template.add_node(
+        "/ietf-interfaces:interfaces/interface[name='${ifname}']/type",
+        Some("iana-if-type:ethernetCsmacd"),
+    );
+    template.add_node(
+        "/ietf-interfaces:interfaces/interface[name='${ifname}']/description",
+        Some("${descr}"),
+    );
+
+    let mut effective =
+        EffectiveConfig::new(&running).expect("Failed to duplicate data");
+    for (ifname, descr) in [("eth/0/0", "LAB"), ("eth/0/5", "WAN")].iter() {
+        effective
+            .apply(&template, &[("ifname", ifname), ("descr", descr)])
+            .expect("Failed to apply template");
+    }
+    assert!(effective.apply(&template, &[]).is_err());
+
+    // Running configuration takes precedence.
+    let dtree = effective.tree();
+    let dnode = dtree
+        .find_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/description",
+        )
+        .expect("Failed to find data node");
+    assert_eq!(dnode.value_canonical().as_deref(), Some("ENG"));
+    assert_eq!(effective.origin(&dnode), None);
 
+    let dnode = dtree
+        .find_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/5']/description",
+        )
+        .expect("Failed to find data node");
+    assert_eq!(dnode.value_canonical().as_deref(), Some("WAN"));
+    assert_eq!(effective.origin(&dnode), Some("ethernet"));
     assert_eq!(
-        dtree1
-            .traverse()
-            .map(|dnode| dnode.path().to_owned())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-isis:attempt-to-exceed-max-sequence",
-            "/ietf-isis:attempt-to-exceed-max-sequence/routing-protocol-name",
-            "/ietf-isis:attempt-to-exceed-max-sequence/isis-level",
-            "/ietf-isis:attempt-to-exceed-max-sequence/lsp-id"
-        ]
+        effective.provenance().into_iter().collect::<Vec<_>>(),
+        vec![(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/5']".to_owned(),
+            "ethernet"
+        )]
     );
+
+    // Values are quoted so that they can't inject predicates.
+    let mut effective =
+        EffectiveConfig::new(&running).expect("Failed to duplicate data");
+    effective
+        .apply(&template, &[("ifname", "eth'0"), ("descr", "LAB")])
+        .expect("Failed to apply template");
+    assert!(effective
+        .tree()
+        .find_path("/ietf-interfaces:interfaces/interface[name=\"eth'0\"]")
+        .is_ok());
+    assert!(effective
+        .apply(&template, &[("ifname", "eth'0\"]"), ("descr", "LAB")])
+        .is_err());
 }
 
 #[test]
-fn data_iterator_traverse_rpc() {
+fn data_redact() {
     let ctx = create_context();
-    let dtree1 = parse_json_rpc(&ctx, JSON_RPC1);
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
 
-    assert_eq!(
-        dtree1
-            .traverse()
-            .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-isis:clear-adjacency",
-            "/ietf-isis:clear-adjacency/routing-protocol-instance-name"
-        ]
-    );
+    dtree1
+        .redact(|snode| snode.name() == "description" || snode.name() == "name")
+        .expect("Failed to redact data");
+    let output = dtree1
+        .print_string(DataFormat::JSON, DataPrinterFlags::WITH_SIBLINGS)
+        .expect("Failed to print data");
+    assert!(!output.contains("ENG") && !output.contains("MKT"));
+    assert_eq!(output.matches(REDACTED_VALUE).count(), 2);
+    assert!(output.contains("eth/0/0") && output.contains("eth/0/1"));
 }
 
 #[test]
-fn data_iterator_traverse_action() {
+fn data_evaluate_when() {
     let ctx = create_context();
-    let mut tree1 = DataTreeOwningRef::new_path(
-        &ctx,
-        "/ietf-routing:routing/ribs/rib[name=\"default\"]/active-route",
-        None,
-        false,
-    )
-    .expect("Failed to create OP node");
-
-    tree1
-        .parse_restconf_reply_op(
-            r###"
-{
-    "ietf-routing:output": {
-        "route": {
-            "source-protocol": "direct"
-        }
+    let mut dtree = DataTree::new(&ctx);
+    for (ptype, name) in
+        [("ietf-routing:static", "main"), ("ietf-isis:isis", "core")].iter()
+    {
+        dtree
+            .new_path(
+                &format!("/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='{}'][name='{}']", ptype, name),
+                None,
+                false,
+            )
+            .expect("Failed to edit data tree");
     }
-}"###,
-            DataFormat::JSON,
-        )
-        .expect("Failed to parse YANG ACTION REPLY");
 
-    assert_eq!(
-        tree1
-            .tree()
-            .traverse()
+    let snode = ctx
+        .find_path("/ietf-routing:routing/control-plane-protocols/control-plane-protocol/ietf-isis:isis")
+        .expect("Failed to find schema node");
+    let when = snode.whens().next().expect("Failed to find when statement");
+    assert_eq!(when.condition(), "rt:type = 'isis:isis'");
+
+    for (ptype, name, expected) in [
+        ("ietf-routing:static", "main", false),
+        ("ietf-isis:isis", "core", true),
+    ]
+    .iter()
+    {
+        let dnode = dtree
+            .find_path(&format!("/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='{}'][name='{}']", ptype, name))
+            .expect("Failed to find data node");
+        let evaluation = when.evaluate(&dnode).expect("Failed to evaluate");
+        assert_eq!(evaluation.result, *expected);
+
+        // The type leaves of all protocol instances are referenced.
+        let mut referenced_nodes = evaluation
+            .referenced_nodes
+            .iter()
             .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-routing:routing",
-            "/ietf-routing:routing/ribs",
-            "/ietf-routing:routing/ribs/rib[name='default']",
-            "/ietf-routing:routing/ribs/rib[name='default']/name",
-            "/ietf-routing:routing/ribs/rib[name='default']/active-route",
-            "/ietf-routing:routing/ribs/rib[name='default']/active-route/route",
-            "/ietf-routing:routing/ribs/rib[name='default']/active-route/route/source-protocol",
-        ]
-    );
+            .collect::<Vec<_>>();
+        referenced_nodes.sort();
+        assert_eq!(
+            referenced_nodes,
+            vec![
+                "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='core']/type",
+                "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-routing:static'][name='main']/type",
+            ]
+        );
+    }
+}
 
-    //
-    // Check generic interface
-    //
+#[test]
+fn data_validate_report() {
+    let ctx = create_context();
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .new_path(
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='core']/static-routes",
+            None,
+            false,
+        )
+        .expect("Failed to edit data tree");
 
-    let tree1 = parse_json_rpc_reply(&ctx, JSON_ACTION1);
+    let report = dtree
+        .validate_report(DataValidationFlags::NO_STATE)
+        .expect_err("Validation should fail");
+    assert!(report.has_errors());
+    let issue = report
+        .issues
+        .iter()
+        .find(|issue| issue.constraint.is_some())
+        .expect("Failed to find constraint");
+    assert_eq!(issue.severity, ValidationSeverity::Error);
     assert_eq!(
-        tree1
-            .tree()
-            .traverse()
-            .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-routing:routing",
-            "/ietf-routing:routing/ribs",
-            "/ietf-routing:routing/ribs/rib[name='default']",
-            "/ietf-routing:routing/ribs/rib[name='default']/name",
-            "/ietf-routing:routing/ribs/rib[name='default']/active-route",
-            "/ietf-routing:routing/ribs/rib[name='default']/active-route/route",
-            "/ietf-routing:routing/ribs/rib[name='default']/active-route/route/source-protocol",
-        ]
+        issue.constraint,
+        Some(ValidationConstraint::When {
+            condition: "derived-from-or-self(../type, 'rt:static')".to_owned(),
+            description: Some(
+                "This container is only valid for the 'static' routing\n\
+                 protocol."
+                    .to_owned()
+            ),
+        })
     );
+    assert!(issue.data_node(&dtree).is_some());
 }
 
 #[test]
-fn data_iterator_ancestors() {
+fn data_instances_of() {
     let ctx = create_context();
     let dtree1 = parse_json_data(&ctx, JSON_TREE1);
 
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces/interface/description")
+        .expect("Failed to find schema node");
     assert_eq!(
         dtree1
-            .find_path(
-                "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
-            )
-            .expect("Failed to lookup data")
-            .ancestors()
+            .instances_of(&snode)
+            .iter()
             .map(|dnode| dnode.path())
             .collect::<Vec<String>>(),
         vec![
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
-            "/ietf-interfaces:interfaces",
-        ]
-    );
-    assert_eq!(
-        dtree1
-            .find_path(
-                "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
-            )
-            .expect("Failed to lookup data")
-            .inclusive_ancestors()
-            .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
-            "/ietf-interfaces:interfaces",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/description",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/description",
         ]
     );
+
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces/interface/statistics/in-octets")
+        .expect("Failed to find schema node");
+    assert!(dtree1.instances_of(&snode).is_empty());
+
+    // Choices and cases have no instances of their own.
+    let snode = ctx
+        .find_path(
+            "/ietf-interfaces:interfaces/interface/ietf-ip:ipv4/address/prefix-length",
+        )
+        .expect("Failed to find schema node");
+    for snode in snode.ancestors().filter(|snode| {
+        matches!(snode.kind(), SchemaNodeKind::Choice | SchemaNodeKind::Case)
+    }) {
+        assert!(dtree1.instances_of(&snode).is_empty());
+    }
 }
 
 #[test]
-fn data_iterator_siblings() {
+fn data_find_action_output_path() {
     let ctx = create_context();
-    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree1 = parse_json_rpc_reply(&ctx, JSON_ACTION1);
+    let dtree2 = parse_json_rpc_reply(&ctx, JSON_RPC2_OUTPUT);
+
+    assert!(dtree1
+        .find_output_path(
+            "/ietf-routing:routing/ribs/rib[name='default']/active-route"
+        )
+        .is_ok());
+    assert!(dtree2
+        .find_output_path("/ietf-mpls-ldp:mpls-ldp-clear-peer/protocol-name")
+        .is_ok());
+}
 
+#[test]
+fn data_action_op_node() {
+    let ctx = create_context();
+    let path = "/ietf-routing:routing/ribs/rib[name='default']/active-route";
+
+    let action = DataTreeOwningRef::new_action_path(&ctx, path)
+        .expect("Failed to create action node");
+    assert_eq!(action.noderef().path(), path);
+    assert!(DataTreeOwningRef::new_action_path(
+        &ctx,
+        "/ietf-routing:routing/ribs/rib[name='default']"
+    )
+    .is_err());
+
+    let dtree1 = parse_json_rpc_reply(&ctx, JSON_ACTION1);
     assert_eq!(
-        dtree1
-            .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
-            .expect("Failed to lookup data")
-            .siblings()
-            .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec!["/ietf-interfaces:interfaces/interface[name='eth/0/1']",]
+        dtree1.op_node().map(|dnode| dnode.path()).as_deref(),
+        Some(path)
     );
+
+    let reply = DataTreeOwningRef::parse_op_string(
+        &ctx,
+        JSON_ACTION1,
+        DataFormat::JSON,
+        DataOperation::ReplyYang,
+    )
+    .expect("Failed to parse YANG action reply");
+    assert_eq!(reply.noderef().path(), path);
+
+    // Data without an operation node can't be referenced.
+    assert!(DataTreeOwningRef::parse_op_string(
+        &ctx,
+        "{}",
+        DataFormat::JSON,
+        DataOperation::ReplyYang,
+    )
+    .is_err());
+}
+
+#[test]
+fn data_reply_template() {
+    let ctx = create_context();
+    let path = "/ietf-routing:routing/ribs/rib[name='default']/active-route";
+
+    let action = DataTreeOwningRef::new_action_path(&ctx, path)
+        .expect("Failed to create action node");
+    let reply = action
+        .reply_template()
+        .expect("Failed to create reply template");
+    assert_eq!(reply.path(), path);
+    assert_eq!(reply.children().count(), 0);
     assert_eq!(
-        dtree1
-            .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
-            .expect("Failed to lookup data")
-            .inclusive_siblings()
+        reply
+            .tree()
+            .reference()
             .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/1']"
-        ]
+            .as_deref(),
+        Some("/ietf-routing:routing")
     );
+
+    let dtree = DataTreeOwningRef::from(parse_json_data(&ctx, JSON_TREE1));
+    assert!(dtree.reply_template().is_err());
 }
 
 #[test]
-fn data_iterator_children() {
+fn data_validate_op() {
     let ctx = create_context();
-    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let running = parse_json_data(&ctx, JSON_TREE1);
+    let path = "/ietf-routing:routing/ribs/rib[name='default']/active-route";
+
+    let mut action = DataTreeOwningRef::new_action_path(&ctx, path)
+        .expect("Failed to create action node");
+    assert!(action
+        .validate_op(Some(&running), DataOperation::RpcYang)
+        .is_ok());
+    assert!(action
+        .validate_op(Some(&running), DataOperation::NotificationYang)
+        .is_err());
+}
+
+#[test]
+fn data_owning_ref_accessors() {
+    let ctx = create_context();
+    let path = "/ietf-routing:routing/ribs/rib[name='default']/active-route";
 
+    let mut action = DataTreeOwningRef::new_action_path(&ctx, path)
+        .expect("Failed to create action node");
+    assert_eq!(action.path(), path);
+    assert_eq!(action.schema().kind(), SchemaNodeKind::Action);
     assert_eq!(
-        dtree1
-            .find_path("/ietf-interfaces:interfaces")
-            .expect("Failed to lookup data")
-            .children()
-            .map(|dnode| dnode.path())
-            .collect::<Vec<String>>(),
-        vec![
-            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
-            "/ietf-interfaces:interfaces/interface[name='eth/0/1']",
-        ]
+        action.parent().map(|dnode| dnode.path()).as_deref(),
+        Some("/ietf-routing:routing/ribs/rib[name='default']")
     );
+    assert_eq!(action.children().count(), 0);
+
+    unsafe { action.tree_mut() }
+        .new_path(
+            "/ietf-routing:routing/ribs/rib[name='default']/description",
+            Some("default RIB"),
+            false,
+        )
+        .expect("Failed to create data node");
+    assert_eq!(action.path(), path);
+
+    let dtree = action.into_tree();
+    assert!(dtree
+        .find_path("/ietf-routing:routing/ribs/rib[name='default']/description")
+        .is_ok());
 }
 
 #[test]
-fn data_is_default() {
+fn data_parse_netconf_envelope() {
+    let ctx = create_context();
+
+    let (rpc, envelope) = DataTreeOwningRef::parse_netconf_rpc_op_envelope(
+        &ctx,
+        r###"<rpc message-id="101" xmlns="urn:ietf:params:xml:ns:netconf:base:1.0">
+  <clear-adjacency xmlns="urn:ietf:params:xml:ns:yang:ietf-isis">
+    <routing-protocol-instance-name>main</routing-protocol-instance-name>
+  </clear-adjacency>
+</rpc>"###,
+    )
+    .expect("Failed to parse NETCONF RPC");
+    assert_eq!(rpc.noderef().path(), "/ietf-isis:clear-adjacency");
+    assert_eq!(envelope.message_id().as_deref(), Some("101"));
+    assert_eq!(envelope.event_time(), None);
+
+    let (notif, envelope) = DataTreeOwningRef::parse_netconf_notif_op_envelope(
+        &ctx,
+        r###"<notification xmlns="urn:ietf:params:xml:ns:netconf:notification:1.0">
+  <eventTime>2024-01-01T00:00:00Z</eventTime>
+  <attempt-to-exceed-max-sequence xmlns="urn:ietf:params:xml:ns:yang:ietf-isis">
+    <routing-protocol-name>main</routing-protocol-name>
+    <isis-level>level-1</isis-level>
+    <lsp-id>0000.0000.0000.00-00</lsp-id>
+  </attempt-to-exceed-max-sequence>
+</notification>"###,
+    )
+    .expect("Failed to parse NETCONF notification");
+    assert_eq!(
+        notif.noderef().path(),
+        "/ietf-isis:attempt-to-exceed-max-sequence"
+    );
+    assert_eq!(
+        envelope.event_time().as_deref(),
+        Some("2024-01-01T00:00:00Z")
+    );
+}
+
+#[test]
+fn data_parse_op_file_reader() {
+    let ctx = create_context();
+    let path = "/ietf-routing:routing/ribs/rib[name='default']/active-route";
+
+    let file_path = std::env::temp_dir()
+        .join(format!("yang3-action-{}.json", std::process::id()));
+    std::fs::write(&file_path, JSON_ACTION1).expect("Failed to write file");
+    let file = std::fs::File::open(&file_path).expect("Failed to open file");
+    let reply = DataTreeOwningRef::parse_op_file(
+        &ctx,
+        file,
+        DataFormat::JSON,
+        DataOperation::ReplyYang,
+    );
+    let _ = std::fs::remove_file(&file_path);
+    let reply = reply.expect("Failed to parse YANG action reply");
+    assert_eq!(reply.path(), path);
+
+    let rpc = DataTreeOwningRef::parse_netconf_rpc_op_reader(
+        &ctx,
+        r###"<rpc message-id="101" xmlns="urn:ietf:params:xml:ns:netconf:base:1.0">
+  <clear-adjacency xmlns="urn:ietf:params:xml:ns:yang:ietf-isis">
+    <routing-protocol-instance-name>main</routing-protocol-instance-name>
+  </clear-adjacency>
+</rpc>"###
+            .as_bytes(),
+    )
+    .expect("Failed to parse NETCONF RPC");
+    assert_eq!(rpc.path(), "/ietf-isis:clear-adjacency");
+}
+
+#[test]
+fn data_edit() {
     let ctx = create_context();
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
     let dtree2 = parse_json_data(&ctx, JSON_TREE2);
 
+    enum Operation {
+        MODIFY(&'static str, Option<&'static str>),
+        DELETE(&'static str),
+    }
+
+    let changes = [
+        Operation::MODIFY(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled",
+            Some("false"),
+        ),
+        Operation::DELETE(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']",
+        ),
+        Operation::MODIFY(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/2']/description",
+            Some("MGMT"),
+        ),
+        Operation::MODIFY(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/2']/type",
+            Some("iana-if-type:ethernetCsmacd"),
+        ),
+        Operation::MODIFY(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/2']/enabled",
+            Some("true"),
+        ),
+    ];
+    for change in &changes {
+        match change {
+            Operation::MODIFY(xpath, value) => {
+                dtree1
+                    .new_path(xpath, *value, false)
+                    .expect("Failed to edit data tree");
+            }
+            Operation::DELETE(xpath) => {
+                dtree1.remove(xpath).expect("Failed to edit data tree")
+            }
+        };
+    }
+
+    assert_data_eq!(&dtree1, &dtree2);
+}
+
+#[test]
+fn data_clear() {
+    let ctx = create_context();
+    let mut dtree = parse_json_data(&ctx, JSON_TREE1);
+    let leaf_path = "/ietf-interfaces:interfaces/interface/description";
+    dtree.add_index(leaf_path).expect("Failed to add index");
+
+    dtree.clear();
+    assert!(dtree.reference().is_none());
     assert_eq!(
-        dtree2
-            .find_path(
-                "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled"
-            )
-            .expect("Failed to lookup data")
-            .is_default(),
-        false,
+        dtree
+            .find_indexed(leaf_path, "ENG")
+            .expect("Failed to look up index")
+            .count(),
+        0
     );
+
+    // The data tree and its indexes remain usable.
+    dtree
+        .new_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/9']/description",
+            Some("ENG"),
+            false,
+        )
+        .expect("Failed to edit data tree");
     assert_eq!(
-        dtree2
-            .find_path(
-                "/ietf-interfaces:interfaces/interface[name='eth/0/2']/enabled"
-            )
-            .expect("Failed to lookup data")
-            .is_default(),
-        true,
+        dtree
+            .find_indexed(leaf_path, "ENG")
+            .expect("Failed to look up index")
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec!["/ietf-interfaces:interfaces/interface[name='eth/0/9']"]
     );
 }
 
 #[test]
-fn data_validate_using_yang_library() {
-    let ctx = create_yang_library_context();
+fn data_secondary_index() {
+    let ctx = create_context();
     let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
-    let dtree3 = DataTree::parse_string(
+    let leaf_path = "/ietf-interfaces:interfaces/interface/description";
+    let find_indexed = |dtree: &DataTree<'_>, value: &str| {
+        dtree
+            .find_indexed(leaf_path, value)
+            .expect("Failed to look up index")
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>()
+    };
+
+    dtree1.add_index(leaf_path).expect("Failed to add index");
+    assert_eq!(
+        find_indexed(&dtree1, "ENG"),
+        vec!["/ietf-interfaces:interfaces/interface[name='eth/0/0']"]
+    );
+
+    dtree1
+        .new_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/description",
+            Some("ENG"),
+            false,
+        )
+        .expect("Failed to edit data tree");
+    assert_eq!(find_indexed(&dtree1, "ENG").len(), 2);
+    assert!(find_indexed(&dtree1, "MKT").is_empty());
+
+    dtree1
+        .remove("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
+        .expect("Failed to edit data tree");
+    assert_eq!(
+        find_indexed(&dtree1, "ENG"),
+        vec!["/ietf-interfaces:interfaces/interface[name='eth/0/1']"]
+    );
+
+    assert!(dtree1
+        .find_indexed("/ietf-interfaces:interfaces/interface/type", "x")
+        .is_err());
+    assert!(dtree1.add_index("/ietf-interfaces:interfaces").is_err());
+}
+
+#[test]
+fn data_leafref_referrers() {
+    let mut ctx = create_context();
+    ctx.set_options(ContextFlags::LEAFREF_LINKING)
+        .expect("Failed to set context options");
+    let dtree = DataTree::parse_string(
         &ctx,
-        JSON_TREE3,
+        r###"
+    {
+        "ietf-interfaces:interfaces":{
+            "interface": [
+                {
+                    "name": "eth/0/0",
+                    "type": "iana-if-type:ethernetCsmacd",
+                    "higher-layer-if": ["eth/0/1"]
+                },
+                {
+                    "name": "eth/0/1",
+                    "type": "iana-if-type:ethernetCsmacd"
+                }
+            ]
+        }
+    }"###,
         DataFormat::JSON,
-        DataParserFlags::STRICT,
+        DataParserFlags::empty(),
         DataValidationFlags::PRESENT,
+    )
+    .expect("Failed to parse data tree");
+
+    let dnode = dtree
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/1']/name")
+        .expect("Failed to find data node");
+    assert_eq!(
+        dnode
+            .leafref_referrers()
+            .iter()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec!["/ietf-interfaces:interfaces/interface[name='eth/0/0']/higher-layer-if[.='eth/0/1']"]
     );
 
-    // Mandatory node "oper-status" instance does not exist.
-    // (path: /ietf-interfaces:interfaces/interface/oper-status)
-    assert!(dtree1.validate(DataValidationFlags::PRESENT).is_err());
-    assert!(dtree3.is_ok());
+    let dangling = dtree
+        .dangling_on_remove(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']",
+        )
+        .expect("Failed to find dangling references");
+    assert_eq!(dangling.len(), 1);
+    let dangling = dtree
+        .dangling_on_remove(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
+        )
+        .expect("Failed to find dangling references");
+    assert!(dangling.is_empty());
+}
+
+#[test]
+fn data_leafref_targets() {
+    let mut ctx = create_context();
+    ctx.set_options(ContextFlags::LEAFREF_LINKING)
+        .expect("Failed to set context options");
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .new_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/higher-layer-if",
+            Some("eth/0/1"),
+            false,
+        )
+        .expect("Failed to edit data tree");
+    dtree
+        .new_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']",
+            None,
+            false,
+        )
+        .expect("Failed to edit data tree");
+
+    let interfaces = dtree
+        .find_path("/ietf-interfaces:interfaces")
+        .expect("Failed to find data node");
+    interfaces.link_leafrefs().expect("Failed to link leafrefs");
+
+    let dnode = dtree
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']/higher-layer-if[.='eth/0/1']")
+        .expect("Failed to find data node");
+    assert_eq!(
+        dnode.leafref_target().map(|target| target.path()),
+        Some(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/name"
+                .to_owned()
+        )
+    );
+}
+
+#[test]
+fn data_generate_example() {
+    let ctx = create_context();
+    let module = ctx
+        .get_module_latest("ietf-interfaces")
+        .expect("Failed to find module");
+    let mut generator = DataGenerator::new(&ctx, DataGeneratorFlags::empty());
+    generator.set_list_entries(3);
+
+    let mut dtree = generator
+        .generate_module(&module)
+        .expect("Failed to generate data");
+    dtree
+        .validate(DataValidationFlags::NO_STATE)
+        .expect("Failed to validate generated data");
+    assert_eq!(
+        dtree
+            .find_xpath("/ietf-interfaces:interfaces/interface")
+            .expect("Failed to lookup data")
+            .count(),
+        3
+    );
+
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces/interface/enabled")
+        .expect("Failed to find schema node");
+    assert_eq!(generator.values(&snode).len(), 2);
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn data_generate_proptest() {
+    use proptest::strategy::{Strategy, ValueTree};
+    use proptest::test_runner::TestRunner;
+
+    let ctx = create_context();
+    let module = ctx
+        .get_module_latest("ietf-interfaces")
+        .expect("Failed to find module");
+    let mut generator = DataGenerator::new(&ctx, DataGeneratorFlags::OPTIONAL);
+    generator.set_list_entries(2);
+    let strategy = generator.strategy(&module, false);
+
+    let mut runner = TestRunner::deterministic();
+    let mut value_tree = strategy
+        .new_tree(&mut runner)
+        .expect("Failed to generate data");
+    let count = |dtree: &DataTree<'_>| dtree.traverse().count();
+    let mut prev_count = count(&value_tree.current());
+    while value_tree.simplify() {
+        let mut dtree = value_tree.current();
+        assert!(count(&dtree) < prev_count);
+        prev_count = count(&dtree);
+        dtree
+            .validate(DataValidationFlags::NO_STATE)
+            .expect("Failed to validate shrunk data");
+    }
+}
+
+#[test]
+fn data_snapshot() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+    testing::assert_data_eq(&dtree1, &parse_json_data(&ctx, JSON_TREE1));
+
+    let path = std::env::temp_dir()
+        .join(format!("yang3-snapshot-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    testing::assert_snapshot(&dtree1, &path);
+    testing::assert_snapshot(&dtree1, &path);
+    let result = std::panic::catch_unwind(|| {
+        testing::assert_snapshot(&dtree2, &path);
+    });
+    let _ = std::fs::remove_file(&path);
+
+    let error = result.expect_err("Snapshot mismatch not detected");
+    let msg = error
+        .downcast_ref::<String>()
+        .expect("Unexpected panic payload");
+    assert!(msg.contains("eth/0/2"));
+}
+
+#[test]
+fn data_validate() {
+    let ctx = create_context();
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
+
+    // Mandatory node "oper-status" instance does not exist.
+    // (path: /ietf-interfaces:interfaces/interface/oper-status)
+    assert!(dtree1.validate(DataValidationFlags::PRESENT).is_err());
+}
+
+#[test]
+fn data_validate_diff() {
+    let ctx = create_context();
+    let path = "/ietf-interfaces:interfaces/interface[name='eth0']";
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .new_path(
+            &format!("{}/type", path),
+            Some("iana-if-type:ethernetCsmacd"),
+            false,
+        )
+        .expect("Failed to edit data tree");
+
+    let diff = dtree
+        .validate_diff(
+            DataValidationFlags::NO_STATE | DataValidationFlags::PRESENT,
+        )
+        .expect("Failed to validate data tree");
+    let enabled = format!("{}/enabled", path);
+    assert!(
+        diff.iter()
+            .any(|(op, dnode)| op == DataDiffOp::Create
+                && dnode.path() == enabled)
+    );
+    assert!(dtree.find_path(&enabled).is_ok());
+
+    dtree
+        .strip_defaults(&diff)
+        .expect("Failed to strip defaults");
+    assert!(dtree.find_path(&enabled).is_err());
+    assert!(dtree.find_path(&format!("{}/type", path)).is_ok());
+}
+
+#[test]
+fn data_duplicate_tree() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dup = dtree1.duplicate().expect("Failed to duplicate data tree");
+
+    assert_data_eq!(&dtree1, &dup);
+}
+
+#[test]
+fn data_duplicate_subtree() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+
+    let dnode = dtree1
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
+        .expect("Failed to lookup data");
+
+    // Duplicate without parents.
+    let dup = dnode
+        .duplicate(false)
+        .expect("Failed to duplicate data subtree");
+    assert_eq!(
+        dup.traverse()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-interfaces:interface[name='eth/0/0']",
+            "/ietf-interfaces:interface[name='eth/0/0']/name",
+            "/ietf-interfaces:interface[name='eth/0/0']/description",
+            "/ietf-interfaces:interface[name='eth/0/0']/type",
+            "/ietf-interfaces:interface[name='eth/0/0']/enabled",
+        ]
+    );
+
+    // Duplicate with parents.
+    let dup = dnode
+        .duplicate(true)
+        .expect("Failed to duplicate data subtree");
+    assert_eq!(
+        dup.traverse()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-interfaces:interfaces",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/name",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/description",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled",
+        ]
+    );
+}
+
+#[test]
+fn data_merge() {
+    let ctx = create_context();
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+    let dtree_merge = parse_json_data(&ctx, JSON_MERGE);
+
+    dtree1.merge(&dtree2).expect("Failed to merge data trees");
+    assert_data_eq!(&dtree1, &dtree_merge);
+}
+
+#[test]
+fn data_nacm() {
+    let mut ctx = create_context();
+    ctx.load_module("ietf-netconf-acm", None, &[])
+        .expect("Failed to load module");
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+    let rules = parse_json_data(
+        &ctx,
+        r###"
+        {
+            "ietf-netconf-acm:nacm": {
+                "groups": {
+                    "group": [
+                        {
+                            "name": "admin",
+                            "user-name": ["alice"]
+                        },
+                        {
+                            "name": "limited",
+                            "user-name": ["bob"]
+                        }
+                    ]
+                },
+                "rule-list": [
+                    {
+                        "name": "admin",
+                        "group": ["admin"],
+                        "rule": [
+                            {
+                                "name": "permit-all",
+                                "module-name": "*",
+                                "access-operations": "*",
+                                "action": "permit"
+                            }
+                        ]
+                    },
+                    {
+                        "name": "limited",
+                        "group": ["limited"],
+                        "rule": [
+                            {
+                                "name": "deny-eth0",
+                                "module-name": "ietf-interfaces",
+                                "path": "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
+                                "access-operations": "read",
+                                "action": "deny"
+                            }
+                        ]
+                    }
+                ]
+            }
+        }"###,
+    );
+    let nacm = Nacm::from_tree(&rules).expect("Failed to read NACM rules");
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+
+    // Administrator.
+    let session = nacm.session("alice", &[]);
+    assert_eq!(session.groups(), ["admin"]);
+    let mut dtree = dtree1.duplicate().expect("Failed to duplicate data");
+    session
+        .filter_read(&mut dtree)
+        .expect("Failed to filter data");
+    assert_data_eq!(&dtree, &dtree1);
+    assert!(session.authorize_diff(&diff).is_ok());
+
+    // Limited user.
+    let session = nacm.session("bob", &[]);
+    let mut dtree = dtree1.duplicate().expect("Failed to duplicate data");
+    session
+        .filter_read(&mut dtree)
+        .expect("Failed to filter data");
+    assert!(dtree
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
+        .is_err());
+    assert!(dtree
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/1']")
+        .is_ok());
+    assert!(session.authorize_diff(&diff).is_err());
+    let snode = ctx
+        .find_path("/ietf-isis:clear-adjacency")
+        .expect("Failed to find schema node");
+    assert_eq!(session.check_rpc(&snode), NacmAction::Permit);
+
+    // The NACM rules themselves are protected by "default-deny-all".
+    let dnode = rules
+        .find_path("/ietf-netconf-acm:nacm")
+        .expect("Failed to find data node");
+    assert_eq!(
+        session.check_data(&dnode, NacmOperation::Read),
+        Ok(NacmAction::Deny)
+    );
+}
+
+#[test]
+fn data_session() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    let path = std::env::temp_dir()
+        .join(format!("yang3-session-{}.bin", std::process::id()));
+    save_session(&path, &ctx, &[("running", &dtree1), ("candidate", &dtree2)])
+        .expect("Failed to save session");
+    let session = load_session(&path).expect("Failed to load session");
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        session.tree_names().collect::<Vec<_>>(),
+        vec!["running", "candidate"]
+    );
+    let ctx2 = session
+        .context(SEARCH_DIR)
+        .expect("Failed to restore context");
+    assert!(ctx2.get_module_implemented("ietf-isis").is_some());
+
+    // Import-only modules are restored in their saved revisions.
+    assert!(!session.import_only_modules.is_empty());
+    for module in &session.import_only_modules {
+        let module = ctx2
+            .get_module(&module.name, module.revision.as_deref())
+            .expect("Failed to find import-only module");
+        assert!(!module.is_implemented());
+    }
+    let running = session
+        .tree(&ctx2, "running")
+        .expect("Failed to restore data tree");
+    let candidate = session
+        .tree(&ctx2, "candidate")
+        .expect("Failed to restore data tree");
+    assert_data_eq!(&running, &dtree1);
+    assert_data_eq!(&candidate, &dtree2);
+    assert!(session.tree(&ctx2, "startup").is_err());
+}
+
+#[test]
+fn data_edit_journal() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    let dir = std::env::temp_dir();
+    let journal_path =
+        dir.join(format!("yang3-journal-{}.wal", std::process::id()));
+    let snapshot_path =
+        dir.join(format!("yang3-journal-{}.lyb", std::process::id()));
+    let _ = std::fs::remove_file(&journal_path);
+    let _ = std::fs::remove_file(&snapshot_path);
+
+    // Save the base snapshot.
+    let mut journal =
+        EditJournal::open(&journal_path).expect("Failed to open journal");
+    assert_eq!(journal.generation(), None);
+    journal
+        .recover(&ctx, &snapshot_path)
+        .expect("Failed to recover data tree");
+    journal
+        .checkpoint(&dtree1, &snapshot_path)
+        .expect("Failed to save snapshot");
+    assert_eq!(journal.generation(), Some(1));
+    drop(journal);
+
+    // Diffs can only be journaled once the snapshot generation is known.
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    let mut journal =
+        EditJournal::open(&journal_path).expect("Failed to open journal");
+    assert!(journal.append(&diff).is_err());
+    let dtree = journal
+        .recover(&ctx, &snapshot_path)
+        .expect("Failed to recover data tree");
+    assert_data_eq!(&dtree, &dtree1);
+    assert_eq!(journal.generation(), Some(1));
+    journal.append(&diff).expect("Failed to append diff");
+    drop(journal);
+
+    // Simulate a crash in the middle of appending another record.
+    let mut data = std::fs::read(&journal_path).unwrap();
+    let len = data.len();
+    data.extend_from_slice(&[0xff; 6]);
+    std::fs::write(&journal_path, &data).unwrap();
+
+    let mut journal =
+        EditJournal::open(&journal_path).expect("Failed to open journal");
+    let dtree = journal
+        .recover(&ctx, &snapshot_path)
+        .expect("Failed to recover data tree");
+    assert_data_eq!(&dtree, &dtree2);
+    assert_eq!(
+        std::fs::metadata(&journal_path).unwrap().len() as usize,
+        len
+    );
+
+    let _ = std::fs::remove_file(&journal_path);
+    let _ = std::fs::remove_file(&snapshot_path);
+}
+
+#[test]
+fn data_observers() {
+    let ctx = create_context();
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    let edits = Arc::new(Mutex::new(Vec::new()));
+    let observer_edits = edits.clone();
+    let id = dtree1.add_observer(move |edit| {
+        let edit = match edit {
+            DataEdit::Created(dnode) => format!("created {}", dnode.path()),
+            DataEdit::Modified(dnode) => format!("modified {}", dnode.path()),
+            DataEdit::Removed(dnode) => format!("removed {}", dnode.path()),
+            DataEdit::Merged(dnode) => format!("merged {}", dnode.path()),
+            DataEdit::DiffApplied(_, dnode) => {
+                format!("applied {}", dnode.path())
+            }
+        };
+        observer_edits.lock().unwrap().push(edit);
+    });
+
+    let path = "/ietf-interfaces:interfaces/interface[name='eth/0/0']";
+    dtree1
+        .new_path(&format!("{}/description", path), Some("HR"), false)
+        .expect("Failed to edit data tree");
+    let new_path = "/ietf-interfaces:interfaces/interface[name='eth/0/5']";
+    dtree1
+        .new_path(&format!("{}/description", new_path), Some("NEW"), false)
+        .expect("Failed to edit data tree");
+    dtree1
+        .remove(&format!("{}/description", path))
+        .expect("Failed to remove data node");
+    assert_eq!(
+        *edits.lock().unwrap(),
+        vec![
+            format!("modified {}/description", path),
+            format!("created {}", new_path),
+            format!("removed {}/description", path),
+        ]
+    );
+
+    edits.lock().unwrap().clear();
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    dtree1.diff_apply(&diff).expect("Failed to apply diff");
+    assert_eq!(edits.lock().unwrap().len(), diff.iter().count());
+
+    edits.lock().unwrap().clear();
+    dtree1.merge(&dtree2).expect("Failed to merge data trees");
+    assert!(edits
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|edit| edit.starts_with("merged ")));
+
+    // No edits are reported once the observer is removed.
+    assert!(dtree1.remove_observer(id));
+    edits.lock().unwrap().clear();
+    dtree1.remove(path).expect("Failed to remove data node");
+    assert!(edits.lock().unwrap().is_empty());
+}
+
+#[test]
+fn data_split_by_module() {
+    let ctx = create_context();
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    dtree1
+        .new_path(
+            "/ietf-routing:routing/ribs/rib[name='default']",
+            None,
+            false,
+        )
+        .expect("Failed to edit data tree");
+
+    let trees = dtree1.split_by_module().expect("Failed to split data tree");
+    assert_eq!(
+        trees.keys().collect::<Vec<_>>(),
+        vec!["ietf-interfaces", "ietf-routing"]
+    );
+
+    let mut dtree2 = DataTree::new(&ctx);
+    dtree2
+        .extend(trees.into_values())
+        .expect("Failed to join data trees");
+    assert_data_eq!(&dtree1, &dtree2);
+}
+
+#[test]
+fn data_add_implicit() {
+    let ctx = create_context();
+
+    // Original data tree.
+    let xpath = "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/area-address";
+    let mut dtree1 = DataTree::new(&ctx);
+    dtree1
+        .new_path(xpath, Some("00"), false)
+        .expect("Failed to edit data tree");
+
+    // Original data tree with implicit configuration nodes added.
+    let mut dtree2 = dtree1.duplicate().expect("Failed to duplicate data");
+    dtree2
+        .add_implicit(DataImplicitFlags::NO_STATE)
+        .expect("Failed to add implicit nodes");
+
+    // Test implicit config nodes.
+    let dtree1_nodes = dtree1
+        .traverse()
+        .map(|dnode| dnode.path())
+        .collect::<BTreeSet<String>>();
+    let dtree2_nodes = dtree2
+        .traverse()
+        .map(|dnode| dnode.path())
+        .collect::<BTreeSet<String>>();
+    assert_eq!(
+        dtree2_nodes
+            .symmetric_difference(&dtree1_nodes)
+            .collect::<Vec<&String>>(),
+        vec![
+            "/ietf-interfaces:interfaces",
+            "/ietf-key-chain:key-chains",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/authentication",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/authentication/level-1",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/authentication/level-2",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/default-metric",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/default-metric/level-1",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/default-metric/level-2",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/default-metric/value",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/interfaces",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/level-type",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/lsp-mtu",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/metric-type",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/metric-type/level-1",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/metric-type/level-2",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/metric-type/value",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/mpls",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/mpls/ldp",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/overload",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/overload/status",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/preference",
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis/spf-control",
+            "/ietf-routing:routing/ribs",
+        ]
+    );
+}
+
+#[test]
+fn data_add_implicit_scoped() {
+    let ctx = create_context();
+    let isis = "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='main']/ietf-isis:isis";
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .new_path(&format!("{}/area-address", isis), Some("00"), false)
+        .expect("Failed to edit data tree");
+
+    // Implicit nodes of a single module.
+    let module = ctx.get_module_latest("ietf-interfaces").unwrap();
+    dtree
+        .add_implicit_module(
+            &module,
+            DataImplicitFlags::NO_STATE,
+            StatusFilter::empty(),
+        )
+        .expect("Failed to add implicit nodes");
+    assert!(dtree.find_path("/ietf-interfaces:interfaces").is_ok());
+    assert!(dtree.find_path("/ietf-key-chain:key-chains").is_err());
+
+    // Implicit nodes of a single subtree, reported to the observers.
+    let created = Arc::new(Mutex::new(Vec::new()));
+    let observer_created = created.clone();
+    dtree.add_observer(move |edit| {
+        if let DataEdit::Created(dnode) = edit {
+            observer_created.lock().unwrap().push(dnode.path());
+        }
+    });
+    dtree
+        .add_implicit_subtree(
+            isis,
+            DataImplicitFlags::NO_STATE,
+            StatusFilter::empty(),
+        )
+        .expect("Failed to add implicit nodes");
+    assert!(dtree.find_path(&format!("{}/lsp-mtu", isis)).is_ok());
+    assert!(dtree.find_path("/ietf-routing:routing/ribs").is_err());
+    let created = created.lock().unwrap();
+    assert!(created.contains(&format!("{}/lsp-mtu", isis)));
+    assert!(created.iter().all(|path| path.starts_with(isis)));
+    drop(created);
+
+    // Indexes cover the added implicit nodes.
+    let interface = "/ietf-interfaces:interfaces/interface[name='eth/0/9']";
+    dtree
+        .new_path(
+            &format!("{}/type", interface),
+            Some("iana-if-type:ethernetCsmacd"),
+            false,
+        )
+        .expect("Failed to edit data tree");
+    let enabled = "/ietf-interfaces:interfaces/interface/enabled";
+    dtree.add_index(enabled).expect("Failed to add index");
+    dtree
+        .add_implicit_subtree(
+            interface,
+            DataImplicitFlags::NO_STATE,
+            StatusFilter::empty(),
+        )
+        .expect("Failed to add implicit nodes");
+    assert_eq!(
+        dtree
+            .find_indexed(enabled, "true")
+            .expect("Failed to find indexed nodes")
+            .map(|dnode| dnode.path())
+            .collect::<Vec<_>>(),
+        vec![interface]
+    );
+}
+
+#[test]
+fn data_add_implicit_status() {
+    let ctx = create_context();
+    let module = ctx.get_module_latest("ietf-interfaces").unwrap();
+
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .add_implicit_module(
+            &module,
+            DataImplicitFlags::empty(),
+            StatusFilter::empty(),
+        )
+        .expect("Failed to add implicit nodes");
+    assert!(dtree.find_path("/ietf-interfaces:interfaces").is_ok());
+    assert!(dtree.find_path("/ietf-interfaces:interfaces-state").is_ok());
+
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .add_implicit_module(
+            &module,
+            DataImplicitFlags::empty(),
+            StatusFilter::DEPRECATED,
+        )
+        .expect("Failed to add implicit nodes");
+    assert!(dtree.find_path("/ietf-interfaces:interfaces").is_ok());
+    assert!(dtree
+        .find_path("/ietf-interfaces:interfaces-state")
+        .is_err());
+
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .add_implicit2(DataImplicitFlags::empty(), StatusFilter::DEPRECATED)
+        .expect("Failed to add implicit nodes");
+    assert!(dtree.find_path("/ietf-interfaces:interfaces").is_ok());
+    assert!(dtree
+        .find_path("/ietf-interfaces:interfaces-state")
+        .is_err());
+}
+
+#[test]
+fn data_required_modules() {
+    let ctx = create_context();
+    let mut dtree = parse_json_data(&ctx, JSON_TREE1);
+    dtree
+        .new_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/ietf-ip:ipv4/enabled",
+            Some("true"),
+            false,
+        )
+        .expect("Failed to edit data tree");
+
+    let modules = dtree.required_modules();
+    assert_eq!(
+        modules
+            .iter()
+            .map(|module| module.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["iana-if-type", "ietf-interfaces", "ietf-ip"]
+    );
+    for module in &modules {
+        let schema = ctx
+            .get_module_implemented(&module.name)
+            .expect("Failed to find module");
+        assert_eq!(module.revision.as_deref(), schema.revision());
+    }
+}
+
+#[test]
+fn data_merge_check() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    assert_eq!(
+        dtree1.merge_check(&dtree2),
+        vec![MergeConflict {
+            path:
+                "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled"
+                    .to_owned(),
+            ours: Some("true".to_owned()),
+            theirs: Some("false".to_owned()),
+        }]
+    );
+    assert!(dtree1.merge_check(&dtree1).is_empty());
+    assert!(dtree1.merge_check(&DataTree::new(&ctx)).is_empty());
+}
+
+#[test]
+fn data_merge3() {
+    let ctx = create_context();
+    let base = parse_json_data(&ctx, JSON_TREE1);
+    let theirs = parse_json_data(&ctx, JSON_TREE2);
+    let eth0 = "/ietf-interfaces:interfaces/interface[name='eth/0/0']";
+    let eth1 = "/ietf-interfaces:interfaces/interface[name='eth/0/1']";
+    let eth2 = "/ietf-interfaces:interfaces/interface[name='eth/0/2']";
+
+    // Non-overlapping and identical changes.
+    let mut ours = base.duplicate().expect("Failed to duplicate data");
+    ours.new_path(&format!("{}/description", eth0), Some("CORE"), false)
+        .expect("Failed to edit data tree");
+    ours.new_path(&format!("{}/enabled", eth0), Some("false"), false)
+        .expect("Failed to edit data tree");
+    let (merged, conflicts) = DataTree::merge3(&base, &ours, &theirs)
+        .expect("Failed to merge data trees");
+    assert!(conflicts.is_empty());
+    let mut expected = theirs.duplicate().expect("Failed to duplicate data");
+    expected
+        .new_path(&format!("{}/description", eth0), Some("CORE"), false)
+        .expect("Failed to edit data tree");
+    assert_data_eq!(&merged, &expected);
+
+    // Change to a node deleted by the other side.
+    let mut ours = base.duplicate().expect("Failed to duplicate data");
+    ours.new_path(&format!("{}/description", eth1), Some("SALES"), false)
+        .expect("Failed to edit data tree");
+    let (merged, conflicts) = DataTree::merge3(&base, &ours, &theirs)
+        .expect("Failed to merge data trees");
+    assert_eq!(
+        conflicts,
+        vec![MergeConflict {
+            path: eth1.to_owned(),
+            ours: None,
+            theirs: None,
+        }]
+    );
+    assert!(merged.find_path(eth1).is_ok());
+    assert!(merged.find_path(eth2).is_ok());
+}
+
+#[test]
+fn data_diff() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+    let dtree_diff = parse_json_diff(&ctx, JSON_DIFF);
+
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    assert_data_eq!(&diff, &dtree_diff);
+}
+
+#[test]
+fn data_diff_apply() {
+    let ctx = create_context();
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    dtree1.diff_apply(&diff).expect("Failed to apply diff");
+
+    assert_data_eq!(&dtree1, &dtree2);
+}
+
+#[test]
+fn data_diff_reverse() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+    let dtree_rdiff = parse_json_data(&ctx, JSON_RDIFF);
+
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    let rdiff = diff.reverse().expect("Failed to reverse diff");
+    assert_data_eq!(&rdiff, &dtree_rdiff);
+}
+
+#[test]
+fn data_diff_to_text() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    assert_eq!(
+        diff.to_text(DataDiffTextFlags::empty()),
+        "~ /ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled: true -> false\n\
+         - /ietf-interfaces:interfaces/interface[name='eth/0/1']\n\
+         + /ietf-interfaces:interfaces/interface[name='eth/0/2']\n"
+    );
+}
+
+#[test]
+fn data_diff_unified_text() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    let text = diff
+        .unified_text(&dtree1, DataFormat::JSON)
+        .expect("Failed to render diff");
+    assert!(text.starts_with("@@ -"));
+    let has_line = |prefix: char, content: &str| {
+        text.lines()
+            .any(|line| line.starts_with(prefix) && line.contains(content))
+    };
+    assert!(has_line('-', "\"enabled\": true"));
+    assert!(has_line('+', "\"enabled\": false"));
+    assert!(has_line('-', "eth/0/1"));
+    assert!(has_line('+', "eth/0/2"));
+    assert!(!has_line('-', "eth/0/0"));
+}
+
+#[test]
+fn data_diff_filter() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+
+    let filtered = diff
+        .filter("/ietf-interfaces:interfaces/interface[name='eth/0/2']")
+        .expect("Failed to filter diff");
+    assert_eq!(
+        filtered
+            .iter()
+            .map(|(op, dnode)| (op, dnode.path()))
+            .collect::<Vec<_>>(),
+        vec![(
+            DataDiffOp::Create,
+            "/ietf-interfaces:interfaces/interface[name='eth/0/2']".to_owned()
+        )]
+    );
+
+    let module = ctx.get_module_latest("ietf-interfaces").unwrap();
+    let filtered = diff.filter_module(&module).expect("Failed to filter diff");
+    assert_data_eq!(&filtered, &diff);
+
+    let module = ctx.get_module_latest("ietf-routing").unwrap();
+    let filtered = diff.filter_module(&module).expect("Failed to filter diff");
+    assert_eq!(filtered.iter().count(), 0);
+}
+
+#[test]
+fn data_diff_revert() {
+    let ctx = create_context();
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree1_orig = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    dtree1.diff_apply(&diff).expect("Failed to apply diff");
+    assert_data_eq!(&dtree1, &dtree2);
+    dtree1.revert(&diff).expect("Failed to revert diff");
+    assert_data_eq!(&dtree1, &dtree1_orig);
+}
+
+#[test]
+fn data_rpc_dispatch() {
+    let ctx = create_context();
+    let mut dispatcher = RpcDispatcher::<String>::new(&ctx);
+    dispatcher
+        .register("/ietf-mpls-ldp:mpls-ldp-clear-peer", |_input, output| {
+            output
+                .new_path("protocol-name", Some("test"))
+                .map_err(|error| error.to_string())
+        })
+        .expect("Failed to register RPC handler");
+    dispatcher
+        .register("/ietf-isis:clear-adjacency", |input, _output| {
+            Err(input
+                .find_path("routing-protocol-instance-name")
+                .ok()
+                .and_then(|dnode| dnode.value_canonical())
+                .unwrap_or_default())
+        })
+        .expect("Failed to register RPC handler");
+    assert!(dispatcher
+        .register("/ietf-interfaces:interfaces", |_, _| Ok(()))
+        .is_err());
+
+    let rpc = parse_json_rpc(&ctx, JSON_RPC2_INPUT);
+    let reply = dispatcher.dispatch(&rpc).expect("Failed to dispatch RPC");
+    let expected = parse_json_rpc_reply(&ctx, JSON_RPC2_OUTPUT);
+    assert_data_eq!(reply.tree(), &expected);
+
+    let rpc = parse_json_rpc(&ctx, JSON_RPC1);
+    let result = dispatcher.dispatch(&rpc);
+    assert!(
+        matches!(result, Err(RpcError::Handler(ref error)) if error == "main")
+    );
+}
+
+#[test]
+fn data_iterator_traverse() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+
+    assert_eq!(
+        dtree1
+            .traverse()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-interfaces:interfaces",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/name",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/description",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/name",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/description",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/type",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']/enabled"
+        ]
+    );
+}
+
+#[test]
+fn data_iterator_traverse_notification() {
+    let ctx = create_context();
+    let dtree1 = parse_json_notification(&ctx, JSON_NOTIF1);
+
+    assert_eq!(
+        dtree1
+            .traverse()
+            .map(|dnode| dnode.path().to_owned())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-isis:attempt-to-exceed-max-sequence",
+            "/ietf-isis:attempt-to-exceed-max-sequence/routing-protocol-name",
+            "/ietf-isis:attempt-to-exceed-max-sequence/isis-level",
+            "/ietf-isis:attempt-to-exceed-max-sequence/lsp-id"
+        ]
+    );
+}
+
+#[test]
+fn data_iterator_traverse_rpc() {
+    let ctx = create_context();
+    let dtree1 = parse_json_rpc(&ctx, JSON_RPC1);
+
+    assert_eq!(
+        dtree1
+            .traverse()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-isis:clear-adjacency",
+            "/ietf-isis:clear-adjacency/routing-protocol-instance-name"
+        ]
+    );
+}
+
+#[test]
+fn data_iterator_traverse_action() {
+    let ctx = create_context();
+    let mut tree1 = DataTreeOwningRef::new_path(
+        &ctx,
+        "/ietf-routing:routing/ribs/rib[name=\"default\"]/active-route",
+        None,
+        false,
+    )
+    .expect("Failed to create OP node");
+
+    tree1
+        .parse_restconf_reply_op(
+            r###"
+{
+    "ietf-routing:output": {
+        "route": {
+            "source-protocol": "direct"
+        }
+    }
+}"###,
+            DataFormat::JSON,
+        )
+        .expect("Failed to parse YANG ACTION REPLY");
+
+    assert_eq!(
+        tree1
+            .tree()
+            .traverse()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-routing:routing",
+            "/ietf-routing:routing/ribs",
+            "/ietf-routing:routing/ribs/rib[name='default']",
+            "/ietf-routing:routing/ribs/rib[name='default']/name",
+            "/ietf-routing:routing/ribs/rib[name='default']/active-route",
+            "/ietf-routing:routing/ribs/rib[name='default']/active-route/route",
+            "/ietf-routing:routing/ribs/rib[name='default']/active-route/route/source-protocol",
+        ]
+    );
+
+    //
+    // Check generic interface
+    //
+
+    let tree1 = parse_json_rpc_reply(&ctx, JSON_ACTION1);
+    assert_eq!(
+        tree1
+            .tree()
+            .traverse()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-routing:routing",
+            "/ietf-routing:routing/ribs",
+            "/ietf-routing:routing/ribs/rib[name='default']",
+            "/ietf-routing:routing/ribs/rib[name='default']/name",
+            "/ietf-routing:routing/ribs/rib[name='default']/active-route",
+            "/ietf-routing:routing/ribs/rib[name='default']/active-route/route",
+            "/ietf-routing:routing/ribs/rib[name='default']/active-route/route/source-protocol",
+        ]
+    );
+}
+
+#[test]
+fn data_iterator_ancestors() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+
+    assert_eq!(
+        dtree1
+            .find_path(
+                "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
+            )
+            .expect("Failed to lookup data")
+            .ancestors()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
+            "/ietf-interfaces:interfaces",
+        ]
+    );
+    assert_eq!(
+        dtree1
+            .find_path(
+                "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
+            )
+            .expect("Failed to lookup data")
+            .inclusive_ancestors()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
+            "/ietf-interfaces:interfaces",
+        ]
+    );
+}
+
+#[test]
+fn data_iterator_siblings() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+
+    assert_eq!(
+        dtree1
+            .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
+            .expect("Failed to lookup data")
+            .siblings()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec!["/ietf-interfaces:interfaces/interface[name='eth/0/1']",]
+    );
+    assert_eq!(
+        dtree1
+            .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
+            .expect("Failed to lookup data")
+            .inclusive_siblings()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']"
+        ]
+    );
+}
+
+#[test]
+fn data_iterator_children() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+
+    assert_eq!(
+        dtree1
+            .find_path("/ietf-interfaces:interfaces")
+            .expect("Failed to lookup data")
+            .children()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']",
+            "/ietf-interfaces:interfaces/interface[name='eth/0/1']",
+        ]
+    );
+}
+
+#[test]
+fn data_is_default() {
+    let ctx = create_context();
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    assert_eq!(
+        dtree2
+            .find_path(
+                "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled"
+            )
+            .expect("Failed to lookup data")
+            .is_default(),
+        false,
+    );
+    assert_eq!(
+        dtree2
+            .find_path(
+                "/ietf-interfaces:interfaces/interface[name='eth/0/2']/enabled"
+            )
+            .expect("Failed to lookup data")
+            .is_default(),
+        true,
+    );
+}
+
+#[test]
+fn data_identity() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let path = "/ietf-interfaces:interfaces/interface[name='eth/0/0']/type";
+
+    let mut dnode = dtree1.find_path(path).expect("Failed to lookup data");
+    let identity = dnode.identity().expect("Failed to get identity");
+    assert_eq!(identity.name(), "ethernetCsmacd");
+    assert_eq!(identity.module().name(), "iana-if-type");
+
+    let loopback = ctx
+        .get_module_latest("iana-if-type")
+        .expect("Failed to get module")
+        .identities()
+        .find(|identity| identity.name() == "softwareLoopback")
+        .expect("Failed to find identity");
+    dnode
+        .set_identity(&loopback)
+        .expect("Failed to set identity");
+    assert_eq!(
+        dnode.value_canonical().as_deref(),
+        Some("iana-if-type:softwareLoopback")
+    );
+    assert!(dnode.identity() == Some(loopback));
+
+    assert!(dtree1
+        .find_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/description"
+        )
+        .expect("Failed to lookup data")
+        .identity()
+        .is_none());
+}
+
+#[test]
+fn data_bits() {
+    let mut ctx = create_context();
+    ctx.load_module("example-types", None, &[])
+        .expect("Failed to load module");
+    let mut dtree = DataTree::new(&ctx);
+    let path = "/example-types:types/flags";
+
+    dtree
+        .new_path(path, Some("loopback up"), false)
+        .expect("Failed to create data node");
+    let mut dnode = dtree.find_path(path).expect("Failed to lookup data");
+    assert_eq!(dnode.bits_set(), Some(vec!["up", "loopback"]));
+
+    dnode
+        .set_bits(&["running", "up"])
+        .expect("Failed to set bits");
+    assert_eq!(dnode.bits_set(), Some(vec!["up", "running"]));
+    assert_eq!(dnode.value_canonical().as_deref(), Some("up running"));
+
+    dnode.set_bits(&[]).expect("Failed to set bits");
+    assert_eq!(dnode.bits_set(), Some(vec![]));
+    assert!(dnode.set_bits(&["down"]).is_err());
+}
+
+#[test]
+fn data_binary() {
+    let mut ctx = create_context();
+    ctx.load_module("example-types", None, &[])
+        .expect("Failed to load module");
+    let mut dtree = DataTree::new(&ctx);
+
+    let mut dnode = dtree
+        .new_path("/example-types:types", None, false)
+        .expect("Failed to create data node")
+        .expect("Data node not created");
+    dnode
+        .new_term_bytes(None, "data", &[0x00, 0x01, 0x02, 0xff])
+        .expect("Failed to create binary node");
+
+    let dnode = dtree
+        .find_path("/example-types:types/data")
+        .expect("Failed to lookup data");
+    assert_eq!(dnode.value_canonical().as_deref(), Some("AAEC/w=="));
+    assert_eq!(dnode.value_bytes(), Some(&[0x00, 0x01, 0x02, 0xff][..]));
+}
+
+#[test]
+fn data_empty_nodes() {
+    let mut ctx = create_context();
+    ctx.load_module("example-types", None, &[])
+        .expect("Failed to load module");
+    let mut dtree = DataTree::new(&ctx);
+
+    dtree
+        .touch("/example-types:types/enabled")
+        .expect("Failed to create empty leaf");
+    dtree
+        .touch("/example-types:types/enabled")
+        .expect("Failed to create empty leaf");
+    assert!(dtree.touch("/example-types:types/data").is_err());
+    assert!(dtree.find_path("/example-types:types/enabled").is_ok());
+
+    let mut dnode = dtree
+        .find_path("/example-types:types")
+        .expect("Failed to lookup data");
+    dnode
+        .set_empty(None, "options")
+        .expect("Failed to create presence container");
+    dnode
+        .set_empty(None, "options")
+        .expect("Failed to create presence container");
+    assert!(dnode.set_empty(None, "flags").is_err());
+    assert_eq!(dnode.children().count(), 2);
+}
+
+#[test]
+fn data_typed_getters() {
+    let ctx = create_context();
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let path = "/ietf-interfaces:interfaces/interface[name='eth/0/0']";
+
+    dtree1
+        .new_path(&format!("{}/ietf-ip:ipv4/mtu", path), Some("1500"), false)
+        .expect("Failed to create data node");
+    let find = |subpath: &str| {
+        dtree1
+            .find_path(&format!("{}/{}", path, subpath))
+            .expect("Failed to lookup data")
+    };
+
+    assert_eq!(find("enabled").as_bool(), Ok(true));
+    assert!(find("enabled").as_str().is_err());
+    assert_eq!(find("description").as_str(), Ok("ENG"));
+    assert!(find("description").as_u64().is_err());
+    assert_eq!(find("ietf-ip:ipv4/mtu").as_u64(), Ok(1500));
+    assert_eq!(find("ietf-ip:ipv4/mtu").as_i64(), Ok(1500));
+    assert!(find("ietf-ip:ipv4/mtu").as_bool().is_err());
+    assert!(find("ietf-ip:ipv4").as_u64().is_err());
+}
+
+#[test]
+fn data_validate_using_yang_library() {
+    let ctx = create_yang_library_context();
+    let mut dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree3 = DataTree::parse_string(
+        &ctx,
+        JSON_TREE3,
+        DataFormat::JSON,
+        DataParserFlags::STRICT,
+        DataValidationFlags::PRESENT,
+    );
+
+    // Mandatory node "oper-status" instance does not exist.
+    // (path: /ietf-interfaces:interfaces/interface/oper-status)
+    assert!(dtree1.validate(DataValidationFlags::PRESENT).is_err());
+    assert!(dtree3.is_ok());
+}
+
+#[test]
+fn data_explain_xpath() {
+    let ctx = create_context();
+    let mut dtree = parse_json_data(&ctx, JSON_TREE1);
+
+    let xpath = "/ietf-interfaces:interfaces/interface[name='eth/0/0']";
+    let profile = dtree.explain_xpath(xpath).expect("Failed to explain xpath");
+    assert_eq!(profile.xpath, xpath);
+    assert_eq!(profile.matches, 1);
+    assert!(profile.max_nodes_visited >= 1);
+    assert!(profile.key_hash_lookup_possible);
+
+    let xpath = "/ietf-interfaces:interfaces/interface[enabled='true']";
+    let profile = dtree.explain_xpath(xpath).expect("Failed to explain xpath");
+    assert!(!profile.key_hash_lookup_possible);
+
+    let profiles = Arc::new(Mutex::new(Vec::new()));
+    let profiler_profiles = profiles.clone();
+    dtree.set_xpath_profiler(Some(move |profile: &XPathProfile| {
+        profiler_profiles
+            .lock()
+            .unwrap()
+            .push(profile.xpath.clone());
+    }));
+    let xpath = "/ietf-interfaces:interfaces/interface";
+    assert_eq!(dtree.find_xpath(xpath).unwrap().count(), 2);
+    assert_eq!(*profiles.lock().unwrap(), vec![xpath.to_owned()]);
+
+    dtree.set_xpath_profiler(None::<fn(&XPathProfile)>);
+    dtree.find_xpath(xpath).unwrap();
+    assert_eq!(profiles.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn data_schema_mount() {
+    let mut ctx =
+        Context::new(ContextFlags::empty()).expect("Failed to create context");
+    ctx.set_searchdir(SEARCH_DIR)
+        .expect("Failed to set YANG search directory");
+    for module_name in &[
+        "ietf-yang-schema-mount",
+        "iana-if-type",
+        "ietf-interfaces",
+        "example-schema-mount",
+    ] {
+        ctx.load_module(module_name, None, &[])
+            .expect("Failed to load module");
+    }
+
+    let snode = ctx
+        .find_path("/example-schema-mount:root")
+        .expect("Failed to find schema node");
+    let ext = snode.mount_point().expect("Failed to find mount point");
+    assert_eq!(ext.argument().as_deref(), Some("root"));
+    assert!(!ctx
+        .find_path("/ietf-interfaces:interfaces")
+        .unwrap()
+        .is_mount_point());
+
+    // Mount all the modules of the context inline, recording the default
+    // printer options of the context given to the callback.
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let callback_calls = calls.clone();
+    ctx.set_default_printer_flags(DataPrinterFlags::SHRINK);
+    ctx.set_ext_data_callback(move |ctx, _ext| {
+        callback_calls
+            .lock()
+            .unwrap()
+            .push(ctx.get_default_printer_flags());
+        let mut dtree = ctx.yang_library_data()?;
+        let mounts = DataTree::parse_string(
+            ctx,
+            r#"{
+                "ietf-yang-schema-mount:schema-mounts": {
+                    "mount-point": [
+                        {
+                            "module": "example-schema-mount",
+                            "label": "root",
+                            "inline": {}
+                        }
+                    ]
+                }
+            }"#,
+            DataFormat::JSON,
+            DataParserFlags::NO_VALIDATION,
+            DataValidationFlags::empty(),
+        )?;
+        dtree.merge(&mounts)?;
+        Ok(dtree)
+    });
+    // Move the context after setting the callback.
+    let ctx = Box::new(ctx);
+
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .new_path("/example-schema-mount:root", None, false)
+        .expect("Failed to edit data tree");
+    dtree
+        .parse_mounted_string(
+            "/example-schema-mount:root",
+            r#"{
+                "ietf-interfaces:interfaces": {
+                    "interface": [
+                        {
+                            "name": "eth/0/0",
+                            "type": "iana-if-type:ethernetCsmacd"
+                        }
+                    ]
+                }
+            }"#,
+            DataFormat::JSON,
+            DataParserFlags::NO_VALIDATION,
+            DataValidationFlags::empty(),
+        )
+        .expect("Failed to parse mounted data");
+    let calls = calls.lock().unwrap().clone();
+    assert!(!calls.is_empty());
+    assert!(calls.iter().all(|flags| *flags == DataPrinterFlags::SHRINK));
+
+    let mount_points = dtree.mount_points();
+    assert_eq!(mount_points.len(), 1);
+    assert_eq!(mount_points[0].path(), "/example-schema-mount:root");
+    assert!(dtree
+        .find_path(
+            "/example-schema-mount:root/ietf-interfaces:interfaces\
+             /interface[name='eth/0/0']"
+        )
+        .is_ok());
+    assert!(dtree
+        .parse_mounted_string(
+            "/example-schema-mount:root/ietf-interfaces:interfaces",
+            "{}",
+            DataFormat::JSON,
+            DataParserFlags::NO_VALIDATION,
+            DataValidationFlags::empty(),
+        )
+        .is_err());
+}
+
+#[test]
+fn data_parser_limits() {
+    let ctx = create_context();
+    let parse = |data: &str, format, limits: &ParserLimits| {
+        DataTree::parse_string_limited(
+            &ctx,
+            data,
+            format,
+            DataParserFlags::NO_VALIDATION,
+            DataValidationFlags::empty(),
+            limits,
+        )
+    };
+
+    // JSON_TREE1 has 11 nodes and a depth of 3.
+    let limits = ParserLimits::new()
+        .max_depth(3)
+        .max_nodes(11)
+        .max_input_size(JSON_TREE1.len());
+    assert!(parse(JSON_TREE1, DataFormat::JSON, &limits).is_ok());
+
+    let limits = ParserLimits::new().max_input_size(16);
+    assert_eq!(
+        parse(JSON_TREE1, DataFormat::JSON, &limits).unwrap_err(),
+        LimitedParseError::InputSize {
+            size: JSON_TREE1.len(),
+            limit: 16
+        }
+    );
+    let limits = ParserLimits::new().max_depth(2);
+    assert_eq!(
+        parse(JSON_TREE1, DataFormat::JSON, &limits).unwrap_err(),
+        LimitedParseError::Depth { limit: 2 }
+    );
+    let limits = ParserLimits::new().max_nodes(10);
+    assert_eq!(
+        parse(JSON_TREE1, DataFormat::JSON, &limits).unwrap_err(),
+        LimitedParseError::NodeCount { limit: 10 }
+    );
+
+    // Deeply nested input is rejected before being parsed.
+    let limits = ParserLimits::new().max_depth(32);
+    let xml = format!("{}{}", "<a>".repeat(1000), "</a>".repeat(1000));
+    assert_eq!(
+        parse(&xml, DataFormat::XML, &limits).unwrap_err(),
+        LimitedParseError::Depth { limit: 32 }
+    );
+    let json = format!("{}{}", "{\"a\":".repeat(1000), "}".repeat(1000));
+    assert_eq!(
+        parse(&json, DataFormat::JSON, &limits).unwrap_err(),
+        LimitedParseError::Depth { limit: 32 }
+    );
+    let json = format!("{{\"a\":{}{}}}", "[".repeat(1000), "]".repeat(1000));
+    assert_eq!(
+        parse(&json, DataFormat::JSON, &limits).unwrap_err(),
+        LimitedParseError::Depth { limit: 32 }
+    );
+
+    // Parse errors are still reported.
+    assert!(matches!(
+        parse("{", DataFormat::JSON, &ParserLimits::new()).unwrap_err(),
+        LimitedParseError::Parse(_)
+    ));
+}
+
+#[test]
+fn data_parse_untrusted() {
+    let ctx = create_context();
+    let parse = |data: &str, format, limits: &ParserLimits| {
+        DataTree::parse_untrusted(
+            &ctx,
+            data,
+            format,
+            DataParserFlags::NO_VALIDATION,
+            DataValidationFlags::empty(),
+            limits,
+        )
+    };
+    let limits = ParserLimits::new().max_attributes(2);
+
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <interfaces xmlns="urn:ietf:params:xml:ns:yang:ietf-interfaces">
+          <interface>
+            <name>eth/0/0</name>
+          </interface>
+        </interfaces>"#;
+    assert!(parse(xml, DataFormat::XML, &limits).is_ok());
+    assert!(parse(JSON_TREE1, DataFormat::JSON, &limits).is_ok());
+
+    // External entities.
+    let xml = r#"<!DOCTYPE foo [<!ENTITY xxe SYSTEM "file:///etc/passwd">]>
+        <interfaces xmlns="urn:ietf:params:xml:ns:yang:ietf-interfaces">
+          <interface>
+            <name>&xxe;</name>
+          </interface>
+        </interfaces>"#;
+    assert_eq!(
+        parse(xml, DataFormat::XML, &limits).unwrap_err(),
+        LimitedParseError::Doctype
+    );
+
+    let xml = r#"<interfaces xmlns="urn:ietf:params:xml:ns:yang:ietf-interfaces">
+          <?foo bar?>
+        </interfaces>"#;
+    assert_eq!(
+        parse(xml, DataFormat::XML, &limits).unwrap_err(),
+        LimitedParseError::ProcessingInstruction
+    );
+
+    let xml = r#"<interfaces xmlns="urn:ietf:params:xml:ns:yang:ietf-interfaces"
+          xmlns:a="urn:a" xmlns:b="urn:b"/>"#;
+    assert_eq!(
+        parse(xml, DataFormat::XML, &limits).unwrap_err(),
+        LimitedParseError::AttributeCount { limit: 2 }
+    );
+
+    // Unknown data is rejected.
+    let json = r#"{"ietf-interfaces:interfaces": {"foo": "bar"}}"#;
+    assert!(matches!(
+        parse(json, DataFormat::JSON, &limits).unwrap_err(),
+        LimitedParseError::Parse(_)
+    ));
+
+    // LYB input is rejected.
+    let lyb = parse_json_data(&ctx, JSON_TREE1)
+        .print_bytes(DataFormat::LYB, DataPrinterFlags::WITH_SIBLINGS)
+        .expect("Failed to print data");
+    assert!(matches!(
+        DataTree::parse_untrusted(
+            &ctx,
+            &lyb,
+            DataFormat::LYB,
+            DataParserFlags::empty(),
+            DataValidationFlags::empty(),
+            &limits,
+        )
+        .unwrap_err(),
+        LimitedParseError::Parse(_)
+    ));
+}
+
+#[test]
+fn data_validate_partial() {
+    let ctx = create_context();
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .new_path(
+            "/ietf-routing:routing/interfaces/interface",
+            Some("eth/0/9"),
+            false,
+        )
+        .expect("Failed to edit data tree");
+
+    // The referenced interface doesn't exist.
+    let report = dtree
+        .validate_report(DataValidationFlags::empty())
+        .expect_err("Validation should fail");
+    assert!(report
+        .issues
+        .iter()
+        .any(|issue| issue.apptag.as_deref() == Some("instance-required")));
+    assert!(dtree.validate_partial(DataValidationFlags::empty()).is_ok());
+
+    // Other errors are still reported.
+    dtree
+        .new_path(
+            "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='core']/static-routes",
+            None,
+            false,
+        )
+        .expect("Failed to edit data tree");
+    let report = dtree
+        .validate_partial(DataValidationFlags::empty())
+        .expect_err("Validation should fail");
+    assert!(report
+        .issues
+        .iter()
+        .all(|issue| issue.apptag.as_deref() != Some("instance-required")));
+}
+
+#[test]
+fn data_resolve_references() {
+    let ctx = create_context();
+    let shard = parse_json_data(&ctx, JSON_TREE1);
+    let mut dtree = DataTree::new(&ctx);
+    for name in &["eth/0/0", "eth/0/9"] {
+        dtree
+            .new_path(
+                "/ietf-routing:routing/interfaces/interface",
+                Some(name),
+                false,
+            )
+            .expect("Failed to edit data tree");
+    }
+
+    let unresolved = resolve_references(&dtree, &[&shard])
+        .expect("Failed to resolve references");
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(unresolved[0].value.as_deref(), Some("eth/0/9"));
+    assert_eq!(
+        unresolved[0].path,
+        "/ietf-routing:routing/interfaces/interface[.='eth/0/9']"
+    );
+
+    let unresolved =
+        resolve_references(&dtree, &[]).expect("Failed to resolve references");
+    assert_eq!(unresolved.len(), 2);
+}
+
+#[test]
+fn data_error_kinds() {
+    let ctx = create_context();
+
+    let error = DataTree::parse_string(
+        &ctx,
+        "{",
+        DataFormat::JSON,
+        DataParserFlags::NO_VALIDATION,
+        DataValidationFlags::empty(),
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Validation);
+    assert_eq!(
+        error.validation_kind(),
+        Some(ValidationErrorKind::SyntaxJson)
+    );
+    assert!(error.is_validation());
+
+    let mut dtree = DataTree::new(&ctx);
+    let error = dtree
+        .new_path(
+            "/ietf-interfaces:interfaces/interface[name='eth/0/0']/enabled",
+            Some("maybe"),
+            false,
+        )
+        .unwrap_err();
+    assert!(error.is_validation());
+    assert_eq!(error.validation_kind(), Some(ValidationErrorKind::Data));
+
+    let mut error = Error::default();
+    error.errcode = yang3::ffi::LY_ERR::LY_ENOTFOUND;
+    assert!(error.is_not_found());
+    assert!(!error.is_validation());
+    assert_eq!(error.validation_kind(), None);
+    assert_eq!(Error::default().kind(), ErrorKind::Other);
+
+    let error =
+        Error::with_message(yang3::ffi::LY_ERR::LY_EEXIST, "Already there");
+    assert!(error.is_exists());
+    assert_eq!(error.to_string(), "Already there");
+}
+
+#[test]
+fn data_failed_constraints() {
+    let ctx = create_context();
+    let mut dtree = DataTree::new(&ctx);
+    let path = "/ietf-routing:routing/control-plane-protocols/control-plane-protocol[type='ietf-isis:isis'][name='core']";
+    dtree
+        .new_path(&format!("{}/static-routes", path), None, false)
+        .expect("Failed to edit data tree");
+
+    dtree
+        .validate(DataValidationFlags::NO_STATE)
+        .expect_err("Validation should fail");
+    let dnode = dtree
+        .find_path(&format!("{}/static-routes", path))
+        .expect("Failed to find data node");
+    assert_eq!(
+        dnode.failed_constraints(),
+        vec![ValidationConstraint::When {
+            condition: "derived-from-or-self(../type, 'rt:static')".to_owned(),
+            description: Some(
+                "This container is only valid for the 'static' routing\n\
+                 protocol."
+                    .to_owned()
+            ),
+        }]
+    );
+    let dnode = dtree.find_path(path).expect("Failed to find data node");
+    assert!(dnode.failed_constraints().is_empty());
+}
+
+#[test]
+fn data_anonymize() {
+    static JSON_ADDRESSES: &str = r###"
+    {
+      "ietf-interfaces:interfaces": {
+        "interface": [
+          {
+            "name": "eth/0/0",
+            "type": "iana-if-type:ethernetCsmacd",
+            "ietf-ip:ipv4": {
+              "address": [
+                { "ip": "192.0.2.1", "prefix-length": 24 },
+                { "ip": "192.0.2.2", "prefix-length": 24 }
+              ],
+              "neighbor": [
+                {
+                  "ip": "192.0.2.3",
+                  "link-layer-address": "00:11:22:33:44:55"
+                }
+              ]
+            },
+            "ietf-ip:ipv6": {
+              "address": [
+                { "ip": "2001:db8:1::1", "prefix-length": 64 }
+              ]
+            }
+          },
+          {
+            "name": "lo",
+            "type": "iana-if-type:softwareLoopback",
+            "ietf-ip:ipv4": {
+              "address": [
+                { "ip": "127.0.0.1", "prefix-length": 8 }
+              ],
+              "neighbor": [
+                { "ip": "192.0.2.1", "link-layer-address": "00:11:22" }
+              ]
+            }
+          }
+        ]
+      }
+    }
+    "###;
+
+    let ctx = create_context();
+    let mut dtree = parse_json_data(&ctx, JSON_ADDRESSES);
+    let mut anonymizer = Anonymizer::new(7);
+    assert_eq!(dtree.anonymize(&mut anonymizer), Ok(7));
+    dtree
+        .validate(DataValidationFlags::NO_STATE)
+        .expect("Failed to validate anonymized data");
+
+    let output = dtree
+        .print_string(DataFormat::JSON, DataPrinterFlags::WITH_SIBLINGS)
+        .expect("Failed to print data");
+    assert!(!output.contains("192.0.2."));
+    assert!(!output.contains("2001:db8:1::1"));
+    assert!(!output.contains("00:11:22"));
+    assert!(output.contains("127.0.0.1") && output.contains("eth/0/0"));
+
+    // The same value is always replaced with the same pseudonym.
+    let pseudonym =
+        anonymizer.pseudonym(IdentifierKind::Ipv4Address, "192.0.2.1");
+    assert!(pseudonym.starts_with("10."));
+    assert_eq!(output.matches(&format!("\"{}\"", pseudonym)).count(), 2);
+    assert_eq!(
+        Anonymizer::new(7).pseudonym(IdentifierKind::Ipv4Address, "192.0.2.1"),
+        pseudonym
+    );
+    assert_eq!(
+        anonymizer
+            .mappings()
+            .filter(|(kind, _, _)| *kind == IdentifierKind::MacAddress)
+            .map(|(_, _, pseudonym)| pseudonym.len())
+            .collect::<Vec<_>>(),
+        vec![8, 17]
+    );
+}
+
+#[test]
+fn data_nacm_subtrees() {
+    let mut ctx = create_context();
+    ctx.load_module("ietf-netconf-acm", None, &[])
+        .expect("Failed to load module");
+    ctx.parse_module_string(
+        "module example-nacm {
+           yang-version 1.1;
+           namespace \"urn:example:nacm\";
+           prefix ex;
+           import ietf-netconf-acm { prefix nacm; }
+           container system {
+             container users {
+               list user {
+                 key name;
+                 leaf name { type string; }
+                 leaf password { type string; }
+               }
+             }
+             container secrets {
+               leaf key { type string; nacm:default-deny-write; }
+               leaf note { type string; }
+             }
+           }
+         }",
+        SchemaInputFormat::YANG,
+        &[],
+    )
+    .expect("Failed to parse module");
+    let rules = parse_json_data(
+        &ctx,
+        r###"
+        {
+            "ietf-netconf-acm:nacm": {
+                "write-default": "permit",
+                "rule-list": [
+                    {
+                        "name": "all",
+                        "group": ["*"],
+                        "rule": [
+                            {
+                                "name": "deny-password",
+                                "module-name": "example-nacm",
+                                "path": "/example-nacm:system/users/user/password",
+                                "access-operations": "create update",
+                                "action": "deny"
+                            }
+                        ]
+                    }
+                ]
+            }
+        }"###,
+    );
+    let nacm = Nacm::from_tree(&rules).expect("Failed to read NACM rules");
+    let session = nacm.session("bob", &[]);
+    let empty = DataTree::new(&ctx);
+    let authorize = |from: &DataTree<'_>, to: &DataTree<'_>| {
+        let diff = from
+            .diff(to, DataDiffFlags::DEFAULTS)
+            .expect("Failed to compare data trees");
+        session.authorize_diff(&diff)
+    };
+
+    // Denied leaf inside a created subtree.
+    let users = parse_json_data(
+        &ctx,
+        r###"{"example-nacm:system": {"users": {"user": [{"name": "bob"}]}}}"###,
+    );
+    assert!(authorize(&empty, &users).is_ok());
+    let passwords = parse_json_data(
+        &ctx,
+        r###"
+        {
+            "example-nacm:system": {
+                "users": {"user": [{"name": "bob", "password": "secret"}]}
+            }
+        }"###,
+    );
+    let error = authorize(&empty, &passwords).unwrap_err();
+    assert_eq!(
+        error.path.as_deref(),
+        Some("/example-nacm:system/users/user[name='bob']/password")
+    );
+
+    // "default-deny-write" leaf inside a deleted subtree.
+    let notes = parse_json_data(
+        &ctx,
+        r###"{"example-nacm:system": {"secrets": {"note": "hello"}}}"###,
+    );
+    assert!(authorize(&notes, &empty).is_ok());
+    let keys = parse_json_data(
+        &ctx,
+        r###"
+        {
+            "example-nacm:system": {"secrets": {"note": "hello", "key": "k"}}
+        }"###,
+    );
+    let error = authorize(&keys, &empty).unwrap_err();
+    assert_eq!(
+        error.path.as_deref(),
+        Some("/example-nacm:system/secrets/key")
+    );
+}
+
+#[test]
+fn data_nacm_operations() {
+    let mut ctx = create_context();
+    ctx.load_module("ietf-netconf-acm", None, &[])
+        .expect("Failed to load module");
+    ctx.parse_module_string(
+        "module ietf-netconf {
+           namespace \"urn:ietf:params:xml:ns:netconf:base:1.0\";
+           prefix nc;
+           import ietf-netconf-acm { prefix nacm; }
+           rpc close-session;
+           rpc kill-session { nacm:default-deny-all; }
+           rpc get-config;
+         }",
+        SchemaInputFormat::YANG,
+        &[],
+    )
+    .expect("Failed to parse module");
+    let rules = parse_json_data(
+        &ctx,
+        r###"
+        {
+            "ietf-netconf-acm:nacm": {
+                "groups": {
+                    "group": [
+                        { "name": "admin", "user-name": ["alice"] }
+                    ]
+                },
+                "rule-list": [
+                    {
+                        "name": "admin",
+                        "group": ["admin"],
+                        "rule": [
+                            {
+                                "name": "permit-kill-session",
+                                "module-name": "ietf-netconf",
+                                "rpc-name": "kill-session",
+                                "action": "permit"
+                            }
+                        ]
+                    },
+                    {
+                        "name": "all",
+                        "group": ["*"],
+                        "rule": [
+                            {
+                                "name": "deny-netconf",
+                                "module-name": "ietf-netconf",
+                                "rpc-name": "*",
+                                "access-operations": "exec",
+                                "action": "deny"
+                            },
+                            {
+                                "name": "deny-isis-notifications",
+                                "module-name": "ietf-isis",
+                                "notification-name": "*",
+                                "action": "deny"
+                            }
+                        ]
+                    }
+                ]
+            }
+        }"###,
+    );
+    let nacm = Nacm::from_tree(&rules).expect("Failed to read NACM rules");
+    let find = |path: &str| ctx.find_path(path).expect("Failed to find node");
+
+    // "close-session" is always permitted, even when a rule denies it.
+    let bob = nacm.session("bob", &[]);
+    let alice = nacm.session("alice", &[]);
+    let close_session = find("/ietf-netconf:close-session");
+    assert_eq!(bob.check_rpc(&close_session), NacmAction::Permit);
+
+    // Operations denied by a rule.
+    let get_config = find("/ietf-netconf:get-config");
+    assert_eq!(bob.check_rpc(&get_config), NacmAction::Deny);
+    let notification = find("/ietf-isis:database-overload");
+    assert_eq!(bob.check_notification(&notification), NacmAction::Deny);
+
+    // Operations denied by "default-deny-all", unless a rule permits them.
+    let kill_session = find("/ietf-netconf:kill-session");
+    assert_eq!(alice.check_rpc(&kill_session), NacmAction::Permit);
+    let default_nacm = Nacm::default();
+    let session = default_nacm.session("bob", &[]);
+    assert_eq!(session.check_rpc(&kill_session), NacmAction::Deny);
+    assert_eq!(session.check_rpc(&get_config), NacmAction::Permit);
+    assert_eq!(
+        session.check_notification(&notification),
+        NacmAction::Permit
+    );
+}
+
+#[test]
+fn data_anonymize_short_prefixes() {
+    let mut anonymizer = Anonymizer::new(7);
+    let mut pseudonyms = BTreeSet::new();
+    for (kind, value) in
+        (1..=20)
+            .map(|i| (IdentifierKind::Ipv4Prefix, format!("{}.0.0.0/8", i)))
+            .chain((1..=20).map(|i| {
+                (IdentifierKind::Ipv4Prefix, format!("{}.0.0.0/9", i))
+            }))
+            .chain((1..=20).map(|i| {
+                (IdentifierKind::Ipv6Prefix, format!("2001:{:x}::/32", i))
+            }))
+    {
+        let pseudonym = anonymizer.pseudonym(kind, &value);
+        assert_ne!(pseudonym, value);
+        assert_eq!(pseudonym.split('/').nth(1), value.split('/').nth(1));
+        assert!(pseudonyms.insert(pseudonym));
+    }
+    assert_eq!(anonymizer.kept().count(), 0);
+
+    // Exhausting the pseudonyms terminates, keeping the remaining values.
+    let mut anonymizer = Anonymizer::new(7);
+    for i in 0..=255 {
+        anonymizer
+            .pseudonym(IdentifierKind::Ipv4Prefix, &format!("{}.0.0.0/8", i));
+    }
+    assert_eq!(anonymizer.mappings().count(), 256);
+    assert!(anonymizer
+        .kept()
+        .all(|(kind, _)| kind == IdentifierKind::Ipv4Prefix));
+}
+
+#[test]
+fn data_secondary_index_stale() {
+    let ctx = create_context();
+    let mut dtree = parse_json_data(&ctx, JSON_TREE1);
+    let leaf_path = "/ietf-interfaces:interfaces/interface/description";
+    dtree.add_index(leaf_path).expect("Failed to add index");
+
+    // Instances removed through a node reference are skipped, even before
+    // the indexes are rebuilt.
+    dtree
+        .find_path("/ietf-interfaces:interfaces/interface[name='eth/0/0']")
+        .expect("Failed to lookup data")
+        .remove();
+    assert_eq!(
+        dtree
+            .find_indexed(leaf_path, "ENG")
+            .expect("Failed to look up index")
+            .count(),
+        0
+    );
+
+    dtree.reindex();
+    assert_eq!(
+        dtree
+            .find_indexed(leaf_path, "ENG")
+            .expect("Failed to look up index")
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn data_cached_tree_view_stale() {
+    let ctx = create_context();
+    let dtree = parse_json_data(&ctx, JSON_TREE1);
+    let view = CachedTreeView::new(dtree);
+    let xpath = "/ietf-interfaces:interfaces/interface[enabled='true']";
+    let path = "/ietf-interfaces:interfaces/interface[name='eth/0/1']";
+    assert_eq!(
+        view.find_xpath(xpath).expect("Failed to lookup data").len(),
+        2
+    );
+    view.find_path(path).expect("Failed to lookup data");
+
+    // Nodes removed behind the view's back are never returned.
+    view.find_path(path)
+        .expect("Failed to lookup data")
+        .remove();
+    assert!(view.find_path(path).is_err());
+    assert_eq!(
+        view.find_xpath(xpath)
+            .expect("Failed to lookup data")
+            .iter()
+            .map(|dnode| dnode.path())
+            .collect::<Vec<String>>(),
+        vec!["/ietf-interfaces:interfaces/interface[name='eth/0/0']"]
+    );
+}
+
+#[test]
+fn data_edit_journal_stale_records() {
+    let ctx = create_context();
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+
+    let dir = std::env::temp_dir();
+    let journal_path =
+        dir.join(format!("yang3-journal-stale-{}.wal", std::process::id()));
+    let snapshot_path =
+        dir.join(format!("yang3-journal-stale-{}.lyb", std::process::id()));
+    let _ = std::fs::remove_file(&journal_path);
+    let _ = std::fs::remove_file(&snapshot_path);
+
+    let mut journal =
+        EditJournal::open(&journal_path).expect("Failed to open journal");
+    journal
+        .recover(&ctx, &snapshot_path)
+        .expect("Failed to recover data tree");
+    journal
+        .checkpoint(&dtree1, &snapshot_path)
+        .expect("Failed to save snapshot");
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    journal.append(&diff).expect("Failed to append diff");
+    let records = std::fs::read(&journal_path).unwrap();
+    journal
+        .checkpoint(&dtree2, &snapshot_path)
+        .expect("Failed to save snapshot");
+    assert_eq!(journal.generation(), Some(2));
+    drop(journal);
+
+    // Simulate a crash between saving the snapshot and clearing the journal.
+    std::fs::write(&journal_path, &records).unwrap();
+
+    let mut journal =
+        EditJournal::open(&journal_path).expect("Failed to open journal");
+    let dtree = journal
+        .recover(&ctx, &snapshot_path)
+        .expect("Failed to recover data tree");
+    assert_eq!(journal.generation(), Some(2));
+    assert_data_eq!(&dtree, &dtree2);
+    assert_eq!(std::fs::metadata(&journal_path).unwrap().len(), 0);
+
+    let _ = std::fs::remove_file(&journal_path);
+    let _ = std::fs::remove_file(&snapshot_path);
+}
+
+#[test]
+fn data_parse_input_error() {
+    struct FailingReader;
+
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("read failed"))
+        }
+    }
+
+    let ctx = create_context();
+    let mut input = ReadInput::new(FailingReader);
+    assert!(DataTree::parse_input(
+        &ctx,
+        &mut input,
+        DataFormat::JSON,
+        DataParserFlags::NO_VALIDATION,
+        DataValidationFlags::empty(),
+    )
+    .is_err());
+
+    // Input providers work with any parse function.
+    let dtree1 = parse_json_data(&ctx, JSON_TREE1);
+    let dtree2 = parse_json_data(&ctx, JSON_TREE2);
+    let diff = dtree1
+        .diff(&dtree2, DataDiffFlags::DEFAULTS)
+        .expect("Failed to compare data trees");
+    let data = diff
+        .print_bytes(DataFormat::LYB, DataPrinterFlags::WITH_SIBLINGS)
+        .expect("Failed to print diff");
+    let mut input = ReadInput::with_chunk_size(&data[..], 7);
+    let diff = DataDiff::parse_string(
+        &ctx,
+        input.read_all().expect("Failed to read input"),
+        DataFormat::LYB,
+        DataParserFlags::NO_VALIDATION,
+        DataValidationFlags::empty(),
+    )
+    .expect("Failed to parse diff");
+    assert!(diff.iter().count() > 0);
+}
+
+#[test]
+fn data_validate_report_warnings() {
+    let ctx = create_context();
+    let mut dtree = DataTree::new(&ctx);
+    dtree
+        .new_path(
+            "/ietf-routing:routing/interfaces/interface",
+            Some("eth/0/9"),
+            false,
+        )
+        .expect("Failed to edit data tree");
+
+    // Semantic constraint violations are only warnings in operational data.
+    let report = dtree
+        .validate_report(DataValidationFlags::OPERATIONAL)
+        .expect("Validation should succeed");
+    assert!(!report.has_errors());
+    assert!(report
+        .issues
+        .iter()
+        .any(|issue| issue.severity == ValidationSeverity::Warning));
+
+    // Valid data trees yield an empty report.
+    let mut dtree = parse_json_data(&ctx, JSON_TREE1);
+    let report = dtree
+        .validate_report(DataValidationFlags::NO_STATE)
+        .expect("Validation should succeed");
+    assert!(report.is_empty());
+}
+
+#[test]
+fn data_remove_first_top_level() {
+    let ctx = create_context();
+    let mut dtree = parse_json_data(&ctx, JSON_TREE1);
+    dtree
+        .new_path(
+            "/ietf-routing:routing/ribs/rib[name='default']",
+            None,
+            false,
+        )
+        .expect("Failed to edit data tree");
+
+    // Removing the first top-level node leaves its siblings in place.
+    let first = dtree.reference().expect("Empty data tree").path();
+    dtree.remove(&first).expect("Failed to remove data node");
+    let top_level = dtree
+        .reference()
+        .expect("Empty data tree")
+        .inclusive_siblings()
+        .map(|dnode| dnode.path())
+        .collect::<Vec<_>>();
+    assert_eq!(top_level.len(), 1);
+    assert_ne!(top_level[0], first);
+
+    let last = top_level[0].clone();
+    dtree.remove(&last).expect("Failed to remove data node");
+    assert!(dtree.reference().is_none());
 }
@@ -1,9 +1,22 @@
 use std::collections::BTreeSet;
-use yang3::context::{Context, ContextFlags};
-use yang3::data::DataFormat;
+use std::sync::{Arc, Mutex};
+use yang3::cli::{command_tree, module_commands, CliCommandKind, CliFlags};
+use yang3::context::{
+    CompletionFlags, CompletionKind, Context, ContextBuilder, ContextEvent,
+    ContextFlags, ModuleCapability,
+};
+use yang3::data::{Data, DataFormat};
 use yang3::iter::IterSchemaFlags;
+use yang3::peek::peek_module_info;
+use yang3::repository::{
+    find_platforms, load_platform, parse_platform, ModuleRepository,
+};
 use yang3::schema::{
-    DataValue, DataValueType, SchemaNodeKind, SchemaPathFormat,
+    BitsItem, Conformance, ContainerKind, DataContext, DataValue,
+    DataValueType, EnumItem, ListKind, Ordering, RangePart, SchemaFindFlags,
+    SchemaInputFormat, SchemaModule, SchemaNodeKind, SchemaOutputFormat,
+    SchemaParsedNodeKind, SchemaPathFormat, SchemaPrinterFlags, SchemaTypedef,
+    SourceLocation, Status, StatusFilter, TypePattern, TypeRange, YangVersion,
 };
 
 static SEARCH_DIR: &str = "./assets/yang/";
@@ -100,6 +113,309 @@ fn schema_feature_value() {
     assert!(module.feature_value("blabla").is_err());
 }
 
+#[test]
+fn schema_module_version_conformance() {
+    let ctx = create_context();
+
+    let module = ctx.get_module_latest("ietf-interfaces").unwrap();
+    assert_eq!(module.yang_version(), Some(YangVersion::V1_1));
+    assert_eq!(module.conformance(), Conformance::Implement);
+
+    let module = ctx.get_module_latest("ietf-routing-types").unwrap();
+    assert_eq!(module.yang_version(), Some(YangVersion::V1_0));
+    assert_eq!(module.conformance(), Conformance::Import);
+}
+
+#[test]
+fn schema_capability_urn() {
+    let ctx = create_context();
+    let module = ctx.get_module_latest("ietf-interfaces").unwrap();
+    let urn = "urn:ietf:params:xml:ns:yang:ietf-interfaces\
+               ?module=ietf-interfaces&revision=2018-02-20\
+               &features=pre-provisioning";
+    assert_eq!(module.capability_urn(), urn);
+    assert_eq!(
+        ModuleCapability::parse(urn)
+            .expect("Failed to parse capability")
+            .urn(),
+        urn
+    );
+    assert!(ctx.hello_capabilities().iter().any(|cap| cap == urn));
+    assert!(!ctx
+        .hello_capabilities()
+        .iter()
+        .any(|cap| cap.contains("?module=ietf-routing-types&")));
+}
+
+#[test]
+fn schema_parse_capabilities() {
+    let hello = [
+        "urn:ietf:params:netconf:base:1.1",
+        "urn:ietf:params:xml:ns:yang:ietf-interfaces\
+         ?module=ietf-interfaces&amp;revision=2018-02-20\
+         &amp;features=pre-provisioning,if-mib",
+        "urn:ietf:params:xml:ns:yang:ietf-key-chain?module=ietf-key-chain",
+    ];
+    let capabilities = ModuleCapability::parse_hello(hello.iter().copied())
+        .expect("Failed to parse capabilities");
+    assert_eq!(capabilities.len(), 2);
+    assert_eq!(
+        capabilities[0],
+        ModuleCapability {
+            namespace: "urn:ietf:params:xml:ns:yang:ietf-interfaces".to_owned(),
+            name: "ietf-interfaces".to_owned(),
+            revision: Some("2018-02-20".to_owned()),
+            features: vec!["pre-provisioning".to_owned(), "if-mib".to_owned()],
+            deviations: vec![],
+        }
+    );
+    assert!(
+        ModuleCapability::parse("urn:ietf:params:netconf:base:1.1").is_err()
+    );
+
+    let mut ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+    ctx.set_searchdir(SEARCH_DIR)
+        .expect("Failed to set YANG search directory");
+    let module = ctx
+        .load_capability(&capabilities[0])
+        .expect("Failed to load module");
+    assert_eq!(module.feature_value("if-mib"), Ok(true));
+    assert_eq!(module.feature_value("arbitrary-names"), Ok(false));
+}
+
+#[test]
+fn schema_context_hooks() {
+    let mut ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+    ctx.set_searchdir(SEARCH_DIR)
+        .expect("Failed to set YANG search directory");
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let hook_events = events.clone();
+    let id = ctx.add_hook(move |_, event| {
+        hook_events.lock().unwrap().push(event.clone());
+    });
+
+    ctx.load_module("ietf-interfaces", None, &[])
+        .expect("Failed to load module");
+    {
+        let events = events.lock().unwrap();
+        assert!(events.contains(&ContextEvent::ModuleLoaded {
+            name: "ietf-interfaces".to_owned(),
+            revision: Some("2018-02-20".to_owned()),
+        }));
+        assert!(events.contains(&ContextEvent::ModuleImplemented {
+            name: "ietf-interfaces".to_owned(),
+            revision: Some("2018-02-20".to_owned()),
+        }));
+        assert_eq!(events.last(), Some(&ContextEvent::Recompiled));
+    }
+
+    // No events are reported once the hook is removed.
+    assert!(ctx.remove_hook(id));
+    assert!(!ctx.remove_hook(id));
+    events.lock().unwrap().clear();
+    ctx.load_module("ietf-ip", None, &[])
+        .expect("Failed to load module");
+    assert!(events.lock().unwrap().is_empty());
+}
+
+#[test]
+fn schema_parse_module_file() {
+    let mut ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+
+    let module = ctx
+        .parse_module_string(
+            "module example-mem { namespace urn:example:mem; prefix mem; \
+             leaf value { type string; } }",
+            SchemaInputFormat::YANG,
+            &[],
+        )
+        .expect("Failed to parse module");
+    assert_eq!(module.name(), "example-mem");
+    assert!(ctx
+        .parse_module_string("module {", SchemaInputFormat::YANG, &[])
+        .is_err());
+
+    let file = std::fs::File::open("./assets/yang/example-types.yang")
+        .expect("Failed to open file");
+    let module = ctx
+        .parse_module_file(file, SchemaInputFormat::YANG, &[])
+        .expect("Failed to parse module");
+    assert_eq!(module.name(), "example-types");
+    assert!(ctx.find_path("/example-types:types").is_ok());
+}
+
+#[test]
+fn schema_context_builder() {
+    let ctx = ContextBuilder::new()
+        .options(ContextFlags::NO_YANGLIBRARY)
+        .search_dir(SEARCH_DIR)
+        .module("ietf-routing", None, &[])
+        .module("ietf-interfaces", None, &["pre-provisioning"])
+        .build()
+        .expect("Failed to create context");
+    let module = ctx
+        .get_module_implemented("ietf-interfaces")
+        .expect("Failed to find module");
+    assert_eq!(module.feature_value("pre-provisioning"), Ok(true));
+    assert!(ctx.find_path("/ietf-routing:routing/ribs").is_ok());
+
+    let ctx = ContextBuilder::new()
+        .search_dir(SEARCH_DIR)
+        .yang_library(JSON_YANG_LIBRARY, DataFormat::JSON)
+        .module("ietf-routing", None, &[])
+        .build()
+        .expect("Failed to create context");
+    assert!(ctx.get_module_implemented("ietf-interfaces").is_some());
+    assert!(ctx.get_module_implemented("ietf-routing").is_some());
+
+    assert!(ContextBuilder::new()
+        .search_dir(SEARCH_DIR)
+        .module("ietf-missing", None, &[])
+        .build()
+        .is_err());
+}
+
+#[test]
+fn schema_yang_library_data() {
+    let mut ctx =
+        Context::new(ContextFlags::empty()).expect("Failed to create context");
+    ctx.set_searchdir(SEARCH_DIR)
+        .expect("Failed to set YANG search directory");
+    ctx.load_module("ietf-interfaces", None, &[])
+        .expect("Failed to load module");
+
+    let dtree = ctx
+        .yang_library_data()
+        .expect("Failed to generate YANG Library data");
+    let dnode = dtree
+        .find_path(
+            "/ietf-yang-library:yang-library/module-set[name='complete']\
+             /module[name='ietf-interfaces']/revision",
+        )
+        .expect("Failed to find module");
+    assert_eq!(dnode.value_canonical().as_deref(), Some("2018-02-20"));
+    assert!(dtree
+        .find_path("/ietf-yang-library:yang-library/content-id")
+        .is_ok());
+
+    let ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+    assert!(ctx.yang_library_data().is_err());
+}
+
+#[test]
+fn schema_load_modules_batch() {
+    let mut ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+    ctx.set_searchdir(SEARCH_DIR)
+        .expect("Failed to set YANG search directory");
+
+    let failures = ctx
+        .load_modules_batch(&[
+            ("ietf-routing", None, &[]),
+            ("ietf-interfaces", None, &["pre-provisioning"]),
+            ("ietf-missing", None, &[]),
+        ])
+        .expect("Failed to compile modules");
+    assert_eq!(
+        failures
+            .iter()
+            .map(|failure| failure.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["ietf-missing"]
+    );
+    let module = ctx
+        .get_module_implemented("ietf-interfaces")
+        .expect("Failed to find module");
+    assert_eq!(module.feature_value("pre-provisioning"), Ok(true));
+    assert!(ctx.find_path("/ietf-routing:routing/ribs").is_ok());
+}
+
+#[test]
+fn schema_intern() {
+    let ctx = create_context();
+
+    let value = ctx
+        .intern("iana-if-type:ethernetCsmacd")
+        .expect("Failed to intern string");
+    let other = ctx
+        .intern("iana-if-type:ethernetCsmacd")
+        .expect("Failed to intern string");
+    assert_eq!(&*value, "iana-if-type:ethernetCsmacd");
+    assert_eq!(value.as_ptr(), other.as_ptr());
+    assert_eq!(value.clone(), other);
+    drop(value);
+    assert_eq!(other.as_str(), "iana-if-type:ethernetCsmacd");
+
+    let empty = ctx.intern("").expect("Failed to intern string");
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn schema_write_path() {
+    let ctx = create_context();
+
+    let mut path = String::new();
+    for snode in ctx.traverse() {
+        for format in [SchemaPathFormat::LOG, SchemaPathFormat::DATA] {
+            path.clear();
+            snode
+                .write_path(format, &mut path)
+                .expect("Failed to write path");
+            assert_eq!(path, snode.path(format));
+        }
+    }
+}
+
+#[test]
+fn schema_print_compiled() {
+    let ctx = create_context();
+    let module = ctx.get_module_latest("ietf-interfaces").unwrap();
+
+    let yang = module
+        .print_string(SchemaOutputFormat::YANG, SchemaPrinterFlags::empty())
+        .expect("Failed to print module");
+    assert!(yang.contains("if-index"));
+
+    let compiled = module
+        .print_string(
+            SchemaOutputFormat::YANG_COMPILED,
+            SchemaPrinterFlags::empty(),
+        )
+        .expect("Failed to print module");
+    assert!(!compiled.contains("if-index"));
+}
+
+#[test]
+fn schema_child_descendant() {
+    let ctx = create_context();
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces-state")
+        .expect("Failed to lookup schema data");
+
+    let child = snode
+        .child(None, "interface")
+        .expect("Failed to find child");
+    assert_eq!(
+        child.path(SchemaPathFormat::DATA),
+        "/ietf-interfaces:interfaces-state/interface"
+    );
+    assert!(snode.child(None, "blabla").is_none());
+
+    let descendant = snode
+        .descendant("interface/ietf-interfaces:statistics/in-octets")
+        .expect("Failed to find descendant");
+    assert_eq!(
+        descendant.path(SchemaPathFormat::DATA),
+        "/ietf-interfaces:interfaces-state/interface/statistics/in-octets"
+    );
+    assert!(snode.descendant("interface/blabla:statistics").is_none());
+}
+
 #[test]
 fn schema_find_xpath() {
     let ctx = create_context();
@@ -145,6 +461,34 @@ fn schema_find_path() {
         .is_ok());
 }
 
+#[test]
+fn schema_find_qualified() {
+    let ctx = create_context();
+
+    assert_eq!(
+        ctx.find_qualified("ietf-interfaces:name")
+            .expect("Failed to lookup schema nodes")
+            .iter()
+            .map(|snode| snode.path(SchemaPathFormat::DATA))
+            .collect::<Vec<String>>(),
+        vec![
+            "/ietf-interfaces:interfaces/interface/name",
+            "/ietf-interfaces:interfaces-state/interface/name",
+        ]
+    );
+    assert!(ctx
+        .find_qualified("ietf-routing:route")
+        .expect("Failed to lookup schema nodes")
+        .iter()
+        .any(|snode| snode.path(SchemaPathFormat::DATA)
+            == "/ietf-routing:routing/ribs/rib/active-route/route"));
+    assert!(ctx
+        .find_qualified("ietf-interfaces:blabla")
+        .expect("Failed to lookup schema nodes")
+        .is_empty());
+    assert!(ctx.find_qualified("name").is_err());
+}
+
 #[test]
 fn schema_iterator_traverse() {
     let ctx = create_context();
@@ -212,6 +556,98 @@ fn schema_iterator_traverse() {
     );
 }
 
+#[test]
+fn schema_iterator_traverse_status() {
+    let ctx = create_context();
+    let module = ctx.get_module_latest("ietf-interfaces").unwrap();
+    let state = "/ietf-interfaces:interfaces-state";
+
+    let paths = module
+        .traverse2(IterSchemaFlags::empty(), StatusFilter::empty())
+        .map(|snode| snode.path(SchemaPathFormat::DATA))
+        .collect::<Vec<_>>();
+    assert!(paths.iter().any(|path| path.starts_with(state)));
+
+    let paths = module
+        .traverse2(IterSchemaFlags::empty(), StatusFilter::DEPRECATED)
+        .map(|snode| snode.path(SchemaPathFormat::DATA))
+        .collect::<Vec<_>>();
+    assert!(paths
+        .contains(&"/ietf-interfaces:interfaces/interface/name".to_owned()));
+    assert!(!paths.iter().any(|path| path.starts_with(state)));
+}
+
+#[test]
+fn schema_iterator_traverse_own() {
+    let ctx = create_context();
+    let module = ctx.get_module_latest("ietf-routing").unwrap();
+
+    assert!(module
+        .traverse()
+        .any(|snode| snode.module().name() == "ietf-mpls-ldp"));
+    assert!(module
+        .traverse_own()
+        .all(|snode| snode.module().name() == "ietf-routing"));
+    let path = "/ietf-routing:routing/control-plane-protocols/\
+                control-plane-protocol/name";
+    assert!(module
+        .traverse_own()
+        .any(|snode| snode.path(SchemaPathFormat::DATA) == path));
+}
+
+#[test]
+fn schema_parsed_groupings_uses() {
+    let ctx = create_context();
+    let module = ctx.get_module_latest("ietf-routing").unwrap();
+    let parsed = module.parsed().expect("Missing parsed module");
+
+    let grouping = parsed
+        .groupings()
+        .find(|grp| grp.name() == "router-id")
+        .expect("Failed to find grouping");
+    assert_eq!(grouping.kind(), SchemaParsedNodeKind::Grouping);
+    assert_eq!(
+        grouping
+            .children()
+            .map(|pnode| pnode.name().to_owned())
+            .collect::<Vec<String>>(),
+        vec!["router-id"]
+    );
+    assert!(parsed
+        .uses()
+        .all(|pnode| pnode.kind() == SchemaParsedNodeKind::Uses));
+    assert!(parsed.uses().any(|pnode| pnode.name() == "router-id"));
+}
+
+#[test]
+fn schema_parsed_augment_when() {
+    let mut ctx = create_context();
+    ctx.load_module("ietf-ipv4-unicast-routing", None, &[])
+        .expect("Failed to load module");
+    let module = ctx.get_module_latest("ietf-ipv4-unicast-routing").unwrap();
+    let parsed = module.parsed().expect("Missing parsed module");
+
+    let augment = parsed
+        .augments()
+        .find(|pnode| {
+            pnode.name() == "/rt:routing/rt:ribs/rt:rib/rt:routes/rt:route"
+        })
+        .expect("Failed to find augment");
+    let when = augment.when().expect("Missing when statement");
+    assert_eq!(
+        when.condition(),
+        "derived-from-or-self(../../rt:address-family, 'v4ur:ipv4-unicast')"
+    );
+    assert_eq!(
+        when.description(),
+        Some("This augment is valid only for IPv4 unicast.")
+    );
+
+    let module = ctx.get_module_latest("ietf-routing").unwrap();
+    let parsed = module.parsed().expect("Missing parsed module");
+    assert!(parsed.uses().all(|pnode| pnode.when().is_none()));
+}
+
 #[test]
 fn schema_iterator_ancestors() {
     let ctx = create_context();
@@ -460,6 +896,72 @@ fn schema_iterator_top_level_nodes() {
     );
 }
 
+#[test]
+fn schema_node_semantics() {
+    let ctx = create_context();
+
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces/interface/ietf-ip:ipv4")
+        .expect("Failed to lookup schema node");
+    assert!(snode.is_presence_container());
+    assert_eq!(snode.container_kind(), Some(ContainerKind::Presence));
+    assert_eq!(snode.list_kind(), None);
+    assert_eq!(snode.ordering(), None);
+
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces")
+        .expect("Failed to lookup schema node");
+    assert!(!snode.is_presence_container());
+    assert_eq!(snode.container_kind(), Some(ContainerKind::NonPresence));
+
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces/interface")
+        .expect("Failed to lookup schema node");
+    assert_eq!(snode.container_kind(), None);
+    assert_eq!(snode.list_kind(), Some(ListKind::Keyed));
+    assert_eq!(snode.ordering(), Some(Ordering::System));
+
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces/interface/name")
+        .expect("Failed to lookup schema node");
+    assert_eq!(snode.list_kind(), None);
+    assert_eq!(snode.ordering(), None);
+}
+
+#[test]
+fn schema_node_data_context() {
+    let ctx = create_context();
+
+    for (path, data_context) in [
+        (
+            "/ietf-interfaces:interfaces/interface/name",
+            DataContext::Config,
+        ),
+        (
+            "/ietf-interfaces:interfaces-state/interface/name",
+            DataContext::State,
+        ),
+        (
+            "/ietf-routing:routing/ribs/rib/active-route",
+            DataContext::RpcInput,
+        ),
+    ] {
+        let snode = ctx.find_path(path).expect("Failed to lookup schema node");
+        assert_eq!(snode.data_context(), data_context);
+    }
+
+    let snode = ctx
+        .find_xpath_with(
+            "/ietf-routing:routing/ribs/rib/active-route/route",
+            SchemaFindFlags::XP_OUTPUT,
+        )
+        .expect("Failed to lookup schema node")
+        .next()
+        .expect("Failed to lookup schema node");
+    assert_eq!(snode.data_context(), DataContext::RpcOutput);
+    assert!(!snode.is_config() && !snode.is_state());
+}
+
 #[test]
 fn schema_node_attributes() {
     let ctx = create_context();
@@ -668,3 +1170,1011 @@ fn schema_module_imports() {
     let module_imports: Vec<_> = module.imports().collect();
     assert_eq!(module_imports.len(), 0);
 }
+
+#[test]
+fn schema_searchdirs() {
+    let mut ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+    assert!(ctx.searchdirs().is_empty());
+
+    // libyang stores the absolute search paths.
+    ctx.set_searchdir(SEARCH_DIR)
+        .expect("Failed to set YANG search directory");
+    ctx.set_searchdir("./assets/data/")
+        .expect("Failed to set YANG search directory");
+    assert_eq!(
+        ctx.searchdirs(),
+        vec![
+            std::fs::canonicalize(SEARCH_DIR).unwrap(),
+            std::fs::canonicalize("./assets/data/").unwrap(),
+        ]
+    );
+
+    ctx.unset_searchdir_last(1)
+        .expect("Failed to unset YANG search directory");
+    assert_eq!(ctx.searchdirs().len(), 1);
+}
+
+#[test]
+fn schema_peek_module_info() {
+    let source =
+        std::fs::read_to_string("./assets/yang/ietf-routing@2018-01-25.yang")
+            .expect("Failed to read module");
+    let info = peek_module_info(&source).expect("Failed to peek module");
+    assert_eq!(info.name, "ietf-routing");
+    assert_eq!(info.revision.as_deref(), Some("2018-01-25"));
+    assert_eq!(info.imports, vec!["ietf-yang-types", "ietf-interfaces"]);
+    assert_eq!(info.belongs_to, None);
+
+    let source = r#"
+        // Comment.
+        submodule "example-sub" {
+          yang-version 1.1;
+          belongs-to example { prefix ex; }
+          description "Example " + "submodule; {";
+          revision 2020-01-01;
+          revision 2021-01-01 { description "Update."; }
+          container foo;
+          import ietf-yang-types { prefix yang; }
+        }"#;
+    let info = peek_module_info(source).expect("Failed to peek module");
+    assert_eq!(info.name, "example-sub");
+    assert_eq!(info.revision.as_deref(), Some("2021-01-01"));
+    assert!(info.imports.is_empty());
+    assert_eq!(info.belongs_to.as_deref(), Some("example"));
+
+    let source = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <module name="example"
+                xmlns="urn:ietf:params:xml:ns:yang:yin:1">
+          <namespace uri="urn:example"/>
+          <prefix value="ex"/>
+          <import module="ietf-inet-types">
+            <prefix value="inet"/>
+            <revision-date date="2013-07-15"/>
+          </import>
+          <revision date="2022-02-02"/>
+        </module>"#;
+    let info = peek_module_info(source).expect("Failed to peek module");
+    assert_eq!(info.name, "example");
+    assert_eq!(info.revision.as_deref(), Some("2022-02-02"));
+    assert_eq!(info.imports, vec!["ietf-inet-types"]);
+
+    assert!(peek_module_info("container foo;").is_err());
+    assert!(peek_module_info("module foo { description \"bar").is_err());
+}
+
+#[test]
+fn schema_module_repository() {
+    let dir = std::env::temp_dir()
+        .join(format!("yang3-schema-repository-{}", std::process::id()));
+    let module = |revision: &str| {
+        format!(
+            "module example-a {{
+               yang-version 1.1;
+               namespace \"urn:example:a\";
+               prefix a;
+               revision {};
+               leaf foo {{ type string; }}
+             }}",
+            revision
+        )
+    };
+    // Conflicting file names in different directories.
+    for (subdir, source) in &[
+        ("old", module("2020-01-01")),
+        ("new", module("2021-01-01")),
+        (
+            "other",
+            "module example-b {
+               yang-version 1.1;
+               namespace \"urn:example:b\";
+               prefix b;
+               import example-a { prefix a; }
+             }"
+            .to_owned(),
+        ),
+    ] {
+        std::fs::create_dir_all(dir.join(subdir)).unwrap();
+        std::fs::write(dir.join(subdir).join("example.yang"), source).unwrap();
+    }
+    std::fs::write(dir.join("README"), "Not a module").unwrap();
+    // Symbolic link cycle, which isn't followed.
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&dir, dir.join("other").join("loop")).unwrap();
+
+    let mut repository = ModuleRepository::new();
+    assert_eq!(repository.index_dir(&dir).expect("Failed to index"), 3);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(
+        repository.modules().collect::<Vec<_>>(),
+        vec!["example-a", "example-b"]
+    );
+    assert_eq!(
+        repository.revisions("example-a").collect::<Vec<_>>(),
+        vec![Some("2020-01-01"), Some("2021-01-01")]
+    );
+    let latest = repository.latest("example-a").expect("Failed to find");
+    assert_eq!(latest.revision(), Some("2021-01-01"));
+    assert!(repository.get("example-a", Some("2019-01-01")).is_none());
+
+    // The files were removed, so load the modules from memory.
+    let mut repository = ModuleRepository::new();
+    for source in &[module("2020-01-01"), module("2021-01-01")] {
+        repository.add_source(source).expect("Failed to add module");
+    }
+    repository
+        .add_source(
+            "module example-b {
+               yang-version 1.1;
+               namespace \"urn:example:b\";
+               prefix b;
+               import example-a { prefix a; }
+             }",
+        )
+        .expect("Failed to add module");
+
+    let mut ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+    ctx.set_module_repository(Arc::new(repository));
+    ctx.load_module("example-b", None, &[])
+        .expect("Failed to load module");
+    let module = ctx.get_module_latest("example-a").expect("Failed to find");
+    assert_eq!(module.revision(), Some("2021-01-01"));
+}
+
+#[test]
+fn schema_vendor_bundle() {
+    let dir = std::env::temp_dir()
+        .join(format!("yang3-schema-bundle-{}", std::process::id()));
+    let platform = dir.join("vendor").join("example").join("1.0");
+    std::fs::create_dir_all(platform.join("common")).unwrap();
+    std::fs::write(
+        platform.join("example-a.yang"),
+        "module example-a {
+           yang-version 1.1;
+           namespace \"urn:example:a\";
+           prefix a;
+           import example-common { prefix c; }
+           revision 2021-01-01;
+           feature fast;
+           leaf foo { type c:name; }
+           leaf bar { if-feature fast; type string; }
+         }",
+    )
+    .unwrap();
+    std::fs::write(
+        platform.join("common").join("example-common.yang"),
+        "module example-common {
+           yang-version 1.1;
+           namespace \"urn:example:common\";
+           prefix c;
+           typedef name { type string; }
+         }",
+    )
+    .unwrap();
+    std::fs::write(
+        platform.join("example-a-deviations.yang"),
+        "module example-a-deviations {
+           yang-version 1.1;
+           namespace \"urn:example:a-deviations\";
+           prefix ad;
+           import example-a { prefix a; }
+           deviation /a:foo { deviate not-supported; }
+         }",
+    )
+    .unwrap();
+    std::fs::write(
+        platform.join("example-1.0-capabilities.xml"),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+         <hello xmlns=\"urn:ietf:params:xml:ns:netconf:base:1.0\">
+           <capabilities>
+             <capability>urn:ietf:params:netconf:base:1.1</capability>
+             <capability>
+               urn:example:a?module=example-a&amp;revision=2021-01-01&amp;features=fast&amp;deviations=example-a-deviations
+             </capability>
+           </capabilities>
+         </hello>",
+    )
+    .unwrap();
+
+    let platforms = find_platforms(&dir).expect("Failed to find platforms");
+    assert_eq!(
+        platforms,
+        vec![platform.join("example-1.0-capabilities.xml")]
+    );
+    let xml = std::fs::read_to_string(&platforms[0]).unwrap();
+    let capabilities = parse_platform(&xml).expect("Failed to parse");
+    assert_eq!(capabilities.len(), 1);
+    assert_eq!(capabilities[0].name, "example-a");
+    assert_eq!(capabilities[0].deviations, vec!["example-a-deviations"]);
+
+    let ctx = load_platform(&platforms[0], ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to load platform");
+    std::fs::remove_dir_all(&dir).unwrap();
+    let module = ctx
+        .get_module_implemented("example-a")
+        .expect("Failed to find module");
+    assert_eq!(module.revision(), Some("2021-01-01"));
+    assert_eq!(module.feature_value("fast"), Ok(true));
+    assert!(ctx.find_path("/example-a:bar").is_ok());
+    assert!(ctx.find_path("/example-a:foo").is_err());
+}
+
+#[test]
+fn schema_parsed_location() {
+    let mut ctx = create_context();
+    ctx.load_module("ietf-ipv4-unicast-routing", None, &[])
+        .expect("Failed to load module");
+
+    let module = ctx.get_module_latest("ietf-routing").unwrap();
+    let filepath = module.filepath().expect("Missing file path").to_owned();
+    let parsed = module.parsed().expect("Missing parsed module");
+    let grouping = parsed
+        .groupings()
+        .find(|grp| grp.name() == "router-id")
+        .expect("Failed to find grouping");
+    assert_eq!(
+        grouping.location(),
+        Some(SourceLocation {
+            filepath: filepath.clone(),
+            line: 158,
+        })
+    );
+    let leaf = grouping.children().next().expect("Missing leaf");
+    assert_eq!(leaf.location().map(|location| location.line), Some(161));
+
+    // Augment target path split into concatenated strings.
+    let module = ctx.get_module_latest("ietf-ipv4-unicast-routing").unwrap();
+    let parsed = module.parsed().expect("Missing parsed module");
+    let augment = parsed
+        .augments()
+        .find(|pnode| {
+            pnode.name()
+                == "/rt:routing/rt:ribs/rt:rib/rt:active-route/rt:output/rt:route"
+        })
+        .expect("Failed to find augment");
+    assert_eq!(augment.location().map(|location| location.line), Some(138));
+
+    // Modules parsed from memory have no source location.
+    let module = ctx
+        .parse_module_string(
+            "module example-mem {
+               yang-version 1.1;
+               namespace \"urn:example:mem\";
+               prefix m;
+               grouping bar { leaf baz { type string; } }
+             }",
+            SchemaInputFormat::YANG,
+            &[],
+        )
+        .expect("Failed to parse module");
+    let parsed = module.parsed().expect("Missing parsed module");
+    let grouping = parsed.groupings().next().expect("Missing grouping");
+    assert_eq!(grouping.location(), None);
+}
+
+#[test]
+fn schema_typedefs() {
+    let mut ctx = create_context();
+    ctx.parse_module_string(
+        "module example-typedefs {
+           yang-version 1.1;
+           namespace \"urn:example:typedefs\";
+           prefix td;
+           import ietf-inet-types { prefix inet; }
+           typedef percent {
+             type uint8 { range 0..100; }
+             units \"%\";
+             default 50;
+             description \"Percentage.\";
+           }
+           typedef small-percent {
+             type td:percent { range 0..10; }
+           }
+           typedef address {
+             type inet:ipv4-address-no-zone;
+           }
+           leaf load { type small-percent; }
+           leaf address { type address; }
+           leaf name { type string; }
+         }",
+        SchemaInputFormat::YANG,
+        &[],
+    )
+    .expect("Failed to parse module");
+
+    let module = ctx.get_module_latest("example-typedefs").unwrap();
+    assert_eq!(
+        module
+            .typedefs()
+            .map(|tpdf| tpdf.name().to_owned())
+            .collect::<Vec<String>>(),
+        vec!["percent", "small-percent", "address"]
+    );
+
+    let snode = ctx.find_path("/example-typedefs:load").unwrap();
+    let ltype = snode.leaf_type().unwrap();
+    let chain = ltype.typedef_chain().collect::<Vec<SchemaTypedef<'_>>>();
+    assert_eq!(
+        chain.iter().map(|tpdf| tpdf.name()).collect::<Vec<&str>>(),
+        vec!["small-percent", "percent"]
+    );
+    assert_eq!(chain[0].base_name(), "td:percent");
+    assert_eq!(chain[1].base_name(), "uint8");
+    assert_eq!(chain[1].units(), Some("%"));
+    assert_eq!(chain[1].default(), Some("50"));
+    assert_eq!(chain[1].description(), Some("Percentage."));
+    assert_eq!(chain[1].module().name(), "example-typedefs");
+
+    let snode = ctx.find_path("/example-typedefs:address").unwrap();
+    let ltype = snode.leaf_type().unwrap();
+    assert_eq!(
+        ltype
+            .typedef_chain()
+            .map(|tpdf| format!("{}:{}", tpdf.module().name(), tpdf.name()))
+            .collect::<Vec<String>>(),
+        vec![
+            "example-typedefs:address",
+            "ietf-inet-types:ipv4-address-no-zone",
+            "ietf-inet-types:ipv4-address",
+        ]
+    );
+
+    let snode = ctx.find_path("/example-typedefs:name").unwrap();
+    assert!(snode.leaf_type().unwrap().typedef().is_none());
+}
+
+#[test]
+fn schema_complete_path() {
+    let ctx = create_context();
+    let complete = |path: &str, flags: CompletionFlags| {
+        ctx.complete_path(path, flags)
+            .into_iter()
+            .map(|completion| completion.text)
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(
+        complete("/ietf-interfaces:inter", CompletionFlags::empty()),
+        vec![
+            "ietf-interfaces:interfaces",
+            "ietf-interfaces:interfaces-state"
+        ]
+    );
+    assert_eq!(
+        complete("/ietf-interfaces:inter", CompletionFlags::NO_STATE),
+        vec!["ietf-interfaces:interfaces"]
+    );
+
+    let path = "/ietf-interfaces:interfaces/interface[name='eth/0'][";
+    let completions = ctx.complete_path(path, CompletionFlags::empty());
+    assert!(completions.is_empty());
+    let path = "/ietf-interfaces:interfaces/interface[";
+    let completions = ctx.complete_path(path, CompletionFlags::empty());
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].text, "name");
+    assert_eq!(completions[0].kind, CompletionKind::Key);
+
+    let path = "/ietf-interfaces:interfaces/interface[name='eth/0']/";
+    assert_eq!(
+        complete(&format!("{}en", path), CompletionFlags::empty()),
+        vec!["enabled"]
+    );
+    assert_eq!(
+        complete(&format!("{}oper", path), CompletionFlags::empty()),
+        vec!["oper-status"]
+    );
+    assert!(
+        complete(&format!("{}oper", path), CompletionFlags::NO_STATE)
+            .is_empty()
+    );
+
+    assert!(
+        complete("/ietf-interfaces:foo/", CompletionFlags::empty()).is_empty()
+    );
+}
+
+#[test]
+fn schema_type_restrictions() {
+    let mut ctx = create_context();
+    ctx.parse_module_string(
+        "module example-restrictions {
+           yang-version 1.1;
+           namespace \"urn:example:restrictions\";
+           prefix r;
+           leaf mtu {
+             type uint16 {
+               range \"68..1500 | 9000\" {
+                 error-message \"Invalid MTU\";
+               }
+             }
+           }
+           leaf offset { type int8 { range \"-10..10\"; } }
+           leaf ratio { type decimal64 { fraction-digits 2; range 0..1.5; } }
+           leaf name {
+             type string {
+               length 1..16;
+               pattern '[a-z]+';
+               pattern 'x.*' {
+                 modifier invert-match;
+                 error-app-tag reserved-name;
+               }
+             }
+           }
+           leaf data { type binary { length 4; } }
+           leaf flag { type boolean; }
+         }",
+        SchemaInputFormat::YANG,
+        &[],
+    )
+    .expect("Failed to parse module");
+    let snode = |name: &str| {
+        ctx.find_path(&format!("/example-restrictions:{}", name))
+            .expect("Failed to find schema node")
+    };
+
+    assert_eq!(
+        snode("mtu").leaf_type().unwrap().range(),
+        Some(TypeRange {
+            parts: vec![
+                RangePart::Unsigned { min: 68, max: 1500 },
+                RangePart::Unsigned {
+                    min: 9000,
+                    max: 9000
+                },
+            ],
+            error_msg: Some("Invalid MTU".to_owned()),
+            error_apptag: None,
+            description: None,
+            reference: None,
+        })
+    );
+    assert_eq!(
+        snode("offset").leaf_type().unwrap().range().unwrap().parts,
+        vec![RangePart::Signed { min: -10, max: 10 }]
+    );
+    let ratio = snode("ratio");
+    let ratio = ratio.leaf_type().unwrap();
+    assert_eq!(ratio.fraction_digits(), Some(2));
+    assert_eq!(
+        ratio.range().unwrap().parts,
+        vec![RangePart::Decimal64 {
+            min: 0,
+            max: 150,
+            fraction_digits: 2,
+        }]
+    );
+
+    let name = snode("name");
+    let name = name.leaf_type().unwrap();
+    assert!(name.range().is_none());
+    assert_eq!(
+        name.length().unwrap().parts,
+        vec![RangePart::Unsigned { min: 1, max: 16 }]
+    );
+    assert_eq!(
+        name.patterns(),
+        vec![
+            TypePattern {
+                expr: "[a-z]+".to_owned(),
+                inverted: false,
+                error_msg: None,
+                error_apptag: None,
+                description: None,
+                reference: None,
+            },
+            TypePattern {
+                expr: "x.*".to_owned(),
+                inverted: true,
+                error_msg: None,
+                error_apptag: Some("reserved-name".to_owned()),
+                description: None,
+                reference: None,
+            },
+        ]
+    );
+    assert_eq!(
+        snode("data").leaf_type().unwrap().length().unwrap().parts,
+        vec![RangePart::Unsigned { min: 4, max: 4 }]
+    );
+    let flag = snode("flag");
+    let flag = flag.leaf_type().unwrap();
+    assert!(flag.range().is_none());
+    assert!(flag.length().is_none());
+    assert!(flag.patterns().is_empty());
+}
+
+#[test]
+fn schema_enum_bits_items() {
+    let mut ctx = create_context();
+    ctx.parse_module_string(
+        "module example-items {
+           yang-version 1.1;
+           namespace \"urn:example:items\";
+           prefix i;
+           feature fast;
+           feature slow;
+           typedef speed {
+             type enumeration {
+               enum low { value 1; description \"Low speed.\"; }
+               enum high { if-feature fast; }
+               enum crawl { if-feature slow; }
+               enum legacy { value 10; status deprecated; }
+             }
+           }
+           leaf speed { type speed; }
+           leaf flags {
+             type bits {
+               bit up { position 0; }
+               bit down { position 3; if-feature \"fast or slow\"; }
+             }
+           }
+         }",
+        SchemaInputFormat::YANG,
+        &["fast"],
+    )
+    .expect("Failed to parse module");
+
+    let snode = ctx.find_path("/example-items:speed").unwrap();
+    let ltype = snode.leaf_type().unwrap();
+    assert_eq!(
+        ltype.enum_items(),
+        vec![
+            EnumItem {
+                name: "low".to_owned(),
+                value: 1,
+                status: Status::Current,
+                description: Some("Low speed.".to_owned()),
+                reference: None,
+                if_features: vec![],
+            },
+            EnumItem {
+                name: "high".to_owned(),
+                value: 2,
+                status: Status::Current,
+                description: None,
+                reference: None,
+                if_features: vec!["fast".to_owned()],
+            },
+            EnumItem {
+                name: "legacy".to_owned(),
+                value: 10,
+                status: Status::Deprecated,
+                description: None,
+                reference: None,
+                if_features: vec![],
+            },
+        ]
+    );
+    assert!(ltype.bits_items().is_empty());
+
+    let snode = ctx.find_path("/example-items:flags").unwrap();
+    let ltype = snode.leaf_type().unwrap();
+    assert_eq!(
+        ltype.bits_items(),
+        vec![
+            BitsItem {
+                name: "up".to_owned(),
+                position: 0,
+                status: Status::Current,
+                description: None,
+                reference: None,
+                if_features: vec![],
+            },
+            BitsItem {
+                name: "down".to_owned(),
+                position: 3,
+                status: Status::Current,
+                description: None,
+                reference: None,
+                if_features: vec!["fast or slow".to_owned()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn schema_cli_commands() {
+    let ctx = create_context();
+    let module = ctx.get_module_latest("ietf-interfaces").unwrap();
+
+    let commands = module_commands(&module, CliFlags::empty());
+    assert_eq!(
+        commands.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+        vec!["interfaces"]
+    );
+
+    let snode = ctx.find_path("/ietf-interfaces:interfaces").unwrap();
+    let interfaces = command_tree(&snode, CliFlags::empty()).unwrap();
+    assert_eq!(interfaces.kind, CliCommandKind::Container);
+    let interface = interfaces.subcommand("interface").unwrap();
+    assert_eq!(interface.kind, CliCommandKind::List);
+    assert_eq!(
+        interface
+            .arguments
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["name"]
+    );
+    assert!(interface.subcommand("name").is_none());
+    assert!(interface.subcommand("oper-status").is_none());
+
+    let enabled = interface.subcommand("enabled").unwrap();
+    assert_eq!(enabled.kind, CliCommandKind::Leaf);
+    let argument = &enabled.arguments[0];
+    assert_eq!(argument.value_type, DataValueType::Bool);
+    assert_eq!(argument.candidates, vec!["true", "false"]);
+    assert_eq!(argument.default.as_deref(), Some("true"));
+    assert!(argument.validate("false").is_ok());
+    assert!(argument.validate("maybe").is_err());
+
+    let argument = &interface.subcommand("type").unwrap().arguments[0];
+    assert_eq!(argument.value_type, DataValueType::IdentityRef);
+    assert!(argument
+        .candidates
+        .contains(&"iana-if-type:ethernetCsmacd".to_owned()));
+
+    let interfaces = command_tree(&snode, CliFlags::STATE).unwrap();
+    let interface = interfaces.subcommand("interface").unwrap();
+    let argument = &interface.subcommand("oper-status").unwrap().arguments[0];
+    assert_eq!(argument.value_type, DataValueType::Enum);
+    assert!(argument.candidates.contains(&"up".to_owned()));
+    assert!(interface
+        .traverse()
+        .any(|(path, _)| path == ["interface", "statistics", "in-octets"]));
+}
+
+#[test]
+fn schema_identities() {
+    let mut ctx = create_context();
+    ctx.parse_module_string(
+        "module example-identities {
+           yang-version 1.1;
+           namespace \"urn:example:identities\";
+           prefix i;
+           identity transport;
+           identity tcp { base transport; description \"TCP.\"; }
+           identity udp { base transport; }
+           identity mptcp { base tcp; base udp; status deprecated; }
+           leaf protocol { type identityref { base transport; } }
+         }",
+        SchemaInputFormat::YANG,
+        &[],
+    )
+    .expect("Failed to parse module");
+
+    let module = ctx.get_module_latest("example-identities").unwrap();
+    let find = |name: &str| {
+        module
+            .identities()
+            .find(|ident| ident.name() == name)
+            .unwrap()
+    };
+    assert_eq!(
+        module
+            .identities()
+            .map(|i| i.name().to_owned())
+            .collect::<Vec<_>>(),
+        vec!["transport", "tcp", "udp", "mptcp"]
+    );
+
+    let transport = find("transport");
+    assert_eq!(
+        transport
+            .derived()
+            .map(|i| i.name().to_owned())
+            .collect::<Vec<_>>(),
+        vec!["tcp", "udp"]
+    );
+    assert_eq!(
+        transport
+            .derived_all()
+            .iter()
+            .map(|i| i.name())
+            .collect::<Vec<_>>(),
+        vec!["tcp", "mptcp", "udp"]
+    );
+    assert_eq!(transport.bases().count(), 0);
+
+    let mptcp = find("mptcp");
+    assert_eq!(mptcp.status(), Status::Deprecated);
+    assert_eq!(
+        mptcp
+            .bases()
+            .map(|i| i.name().to_owned())
+            .collect::<Vec<_>>(),
+        vec!["tcp", "udp"]
+    );
+    assert!(mptcp.is_derived_from(&transport));
+    assert!(!transport.is_derived_from(&mptcp));
+    assert_eq!(find("tcp").status(), Status::Current);
+    assert_eq!(find("tcp").description(), Some("TCP."));
+
+    let snode = ctx.find_path("/example-identities:protocol").unwrap();
+    let ltype = snode.leaf_type().unwrap();
+    assert_eq!(
+        ltype
+            .identity_bases()
+            .map(|i| i.qualified_name())
+            .collect::<Vec<_>>(),
+        vec!["example-identities:transport"]
+    );
+
+    let snode = ctx
+        .find_path("/ietf-interfaces:interfaces/interface/type")
+        .unwrap();
+    let ltype = snode.leaf_type().unwrap();
+    let base = ltype.identity_bases().next().unwrap();
+    assert!(base
+        .derived_all()
+        .iter()
+        .any(|i| i.qualified_name() == "iana-if-type:ethernetCsmacd"));
+}
+
+#[test]
+fn schema_export_modules() {
+    let dir = std::env::temp_dir()
+        .join(format!("yang3-schema-export-{}", std::process::id()));
+    let src = dir.join("src");
+    let out = dir.join("out");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(
+        src.join("example-main.yang"),
+        "module example-main {
+           yang-version 1.1;
+           namespace \"urn:example:main\";
+           prefix m;
+           import example-lib { prefix lib; }
+           include example-sub;
+           revision 2022-01-01;
+           leaf foo { type lib:name; }
+         }",
+    )
+    .unwrap();
+    std::fs::write(
+        src.join("example-lib.yang"),
+        "module example-lib {
+           yang-version 1.1;
+           namespace \"urn:example:lib\";
+           prefix lib;
+           typedef name { type string; }
+         }",
+    )
+    .unwrap();
+    std::fs::write(
+        src.join("example-sub.yang"),
+        "submodule example-sub {
+           yang-version 1.1;
+           belongs-to example-main { prefix m; }
+           revision 2022-02-01;
+           revision 2022-01-01;
+           leaf bar { type string; }
+         }",
+    )
+    .unwrap();
+
+    let mut ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+    ctx.set_searchdir(&src)
+        .expect("Failed to set YANG search directory");
+    ctx.load_module("example-main", None, &[])
+        .expect("Failed to load module");
+
+    let module = ctx.get_module_latest("example-main").unwrap();
+    let submodule = module.submodules().next().unwrap();
+    assert_eq!(submodule.name(), "example-sub");
+    assert_eq!(submodule.revision(), Some("2022-02-01"));
+
+    let count = ctx
+        .export_modules(&out, SchemaOutputFormat::YANG)
+        .expect("Failed to export modules");
+    let mut files = std::fs::read_dir(&out)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    files.sort();
+    assert_eq!(count, files.len());
+    assert_eq!(
+        files,
+        vec![
+            "example-lib.yang",
+            "example-main@2022-01-01.yang",
+            "example-sub@2022-02-01.yang"
+        ]
+    );
+    let sub = std::fs::read_to_string(out.join("example-sub@2022-02-01.yang"))
+        .unwrap();
+    assert!(sub.starts_with("submodule example-sub"));
+
+    // Only the implemented modules have a compiled form.
+    let compiled = dir.join("compiled");
+    let count = ctx
+        .export_modules(&compiled, SchemaOutputFormat::YANG_COMPILED)
+        .expect("Failed to export modules");
+    let files = std::fs::read_dir(&compiled)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(count, 1);
+    assert_eq!(files, vec!["example-main@2022-01-01.yang"]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn schema_modules_snapshot() {
+    let mut ctx = Context::new(ContextFlags::NO_YANGLIBRARY)
+        .expect("Failed to create context");
+    ctx.set_searchdir(SEARCH_DIR)
+        .expect("Failed to set YANG search directory");
+    ctx.load_module("ietf-interfaces", None, &[])
+        .expect("Failed to load module");
+
+    let names = |modules: &[SchemaModule<'_>]| {
+        modules
+            .iter()
+            .map(|m| m.name().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let snapshot = names(&ctx.modules_vec(true));
+    assert!(snapshot.contains(&"ietf-interfaces".to_owned()));
+    assert_eq!(
+        ctx.modules_vec(false).len() as u32,
+        snapshot.len() as u32 + ctx.internal_module_count()
+    );
+
+    let count = ctx.modules_vec(false).len() as u32;
+    let index = ctx
+        .modules_vec(false)
+        .iter()
+        .position(|m| m.name() == "ietf-interfaces")
+        .unwrap() as u32;
+    assert_eq!(ctx.module_at(index).unwrap().name(), "ietf-interfaces");
+    assert!(ctx.module_at(count).is_none());
+
+    // Indexes remain valid when new modules are loaded.
+    ctx.load_module("ietf-ip", None, &[])
+        .expect("Failed to load module");
+    assert_eq!(ctx.module_at(index).unwrap().name(), "ietf-interfaces");
+    assert_eq!(ctx.module_at(count).unwrap().name(), "ietf-ip");
+    assert_eq!(
+        names(&ctx.modules_vec(true))[..snapshot.len()],
+        snapshot[..]
+    );
+}
+
+#[test]
+fn schema_find_xpath_with() {
+    let ctx = create_context();
+
+    assert_eq!(
+        ctx.find_xpath_with(
+            "/ietf-routing:routing/ribs/rib/active-route/*",
+            SchemaFindFlags::XP_OUTPUT
+        )
+        .expect("Failed to lookup schema data")
+        .map(|dnode| dnode.path(SchemaPathFormat::DATA))
+        .collect::<Vec<String>>(),
+        vec!["/ietf-routing:routing/ribs/rib/active-route/route"]
+    );
+
+    let snode = ctx
+        .find_path("/ietf-routing:routing/ribs/rib")
+        .expect("Failed to lookup schema node");
+    assert_eq!(
+        snode
+            .find_xpath_with("active-route/*", SchemaFindFlags::XP_OUTPUT)
+            .expect("Failed to lookup schema data")
+            .map(|dnode| dnode.path(SchemaPathFormat::DATA))
+            .collect::<Vec<String>>(),
+        vec!["/ietf-routing:routing/ribs/rib/active-route/route"]
+    );
+}
+
+#[test]
+fn schema_typedefs_scoped() {
+    let mut ctx = create_context();
+    ctx.parse_module_string(
+        "module example-counters {
+           yang-version 1.1;
+           namespace \"urn:example:counters\";
+           prefix c;
+           typedef counter { type uint8; }
+           grouping stats {
+             leaf total { type counter; }
+           }
+         }",
+        SchemaInputFormat::YANG,
+        &[],
+    )
+    .expect("Failed to parse module");
+    ctx.parse_module_string(
+        "module example-scoped {
+           yang-version 1.1;
+           namespace \"urn:example:scoped\";
+           prefix s;
+           import example-counters { prefix c; }
+           typedef counter { type string; }
+           leaf local { type counter; }
+           leaf imported { type c:counter; }
+           container stats {
+             uses c:stats;
+           }
+           container nested {
+             typedef counter { type int16; }
+             leaf value { type counter; }
+           }
+         }",
+        SchemaInputFormat::YANG,
+        &[],
+    )
+    .expect("Failed to parse module");
+
+    let typedef = |path: &str| {
+        let snode = ctx.find_path(path).unwrap();
+        let tpdf = snode.leaf_type().unwrap().typedef().unwrap();
+        format!(
+            "{}:{}:{}",
+            tpdf.module().name(),
+            tpdf.name(),
+            tpdf.base_name()
+        )
+    };
+    assert_eq!(
+        typedef("/example-scoped:local"),
+        "example-scoped:counter:string"
+    );
+    assert_eq!(
+        typedef("/example-scoped:imported"),
+        "example-counters:counter:uint8"
+    );
+    assert_eq!(
+        typedef("/example-scoped:stats/total"),
+        "example-counters:counter:uint8"
+    );
+    assert_eq!(
+        typedef("/example-scoped:nested/value"),
+        "example-scoped:counter:int16"
+    );
+}
+
+#[test]
+fn schema_enum_items_defining_leaf() {
+    let mut ctx = create_context();
+    for (name, feature) in [("example-mode-a", "a"), ("example-mode-b", "b")] {
+        ctx.parse_module_string(
+            &format!(
+                "module {name} {{
+                   yang-version 1.1;
+                   namespace \"urn:example:{name}\";
+                   prefix m;
+                   feature {feature};
+                   leaf mode {{
+                     type enumeration {{
+                       enum on {{ if-feature {feature}; }}
+                       enum off;
+                     }}
+                   }}
+                 }}"
+            ),
+            SchemaInputFormat::YANG,
+            &[feature],
+        )
+        .expect("Failed to parse module");
+    }
+
+    for (name, feature) in [("example-mode-a", "a"), ("example-mode-b", "b")] {
+        let snode = ctx.find_path(&format!("/{}:mode", name)).unwrap();
+        assert_eq!(
+            snode
+                .leaf_type()
+                .unwrap()
+                .enum_items()
+                .into_iter()
+                .map(|item| item.if_features)
+                .collect::<Vec<_>>(),
+            vec![vec![feature.to_owned()], vec![]]
+        );
+    }
+}